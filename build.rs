@@ -1,7 +1,8 @@
 mod gen;
-use gen::{generate_nodes, generate_parser_y};
+use gen::{generate_arena_nodes, generate_nodes, generate_parser_y};
 
 fn main() {
     generate_parser_y();
     generate_nodes();
+    generate_arena_nodes();
 }
@@ -0,0 +1,19 @@
+extern crate cbindgen;
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    std::fs::create_dir_all("include").unwrap();
+
+    match cbindgen::generate(&crate_dir) {
+        Ok(bindings) => {
+            bindings.write_to_file("include/lib-ruby-parser.h");
+        }
+        Err(err) => {
+            eprintln!("Failed to generate C header.\n{:#?}", err);
+            std::process::exit(1);
+        }
+    }
+}
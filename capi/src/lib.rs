@@ -0,0 +1,580 @@
+//! C API of lib-ruby-parser.
+//!
+//! All functions take/return raw pointers, a result of parsing
+//! is returned as an opaque `LibRubyParserResult` that must be released
+//! with `lib_ruby_parser_free_result`. All strings, nodes and locations
+//! returned by accessors are borrowed from the result and live
+//! as long as the result itself.
+//!
+//! Accessors accept `NULL` and return an empty value
+//! (`NULL`, `0`, `false` or an empty location) for it.
+//!
+//! C header is generated by `build.rs` into `include/lib-ruby-parser.h`.
+
+use lib_ruby_parser::nodes::FieldValue;
+use lib_ruby_parser::source::{CommentType, Range};
+use lib_ruby_parser::{token_name, Node, Parser, ParserOptions, ParserResult, TokenValue};
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::ptr;
+
+/// Result of parsing, opaque for C
+pub struct LibRubyParserResult {
+    result: ParserResult,
+    nodes: Vec<LibRubyParserNode>,
+    token_names: Vec<String>,
+    diagnostic_messages: Vec<String>,
+}
+
+/// Node of the AST, opaque for C
+pub struct LibRubyParserNode {
+    node: *const Node,
+    // children are stored next to each other in `LibRubyParserResult.nodes`
+    children: *const LibRubyParserNode,
+    children_count: usize,
+}
+
+impl LibRubyParserNode {
+    fn node(&self) -> &Node {
+        // points to the AST owned by the same result
+        unsafe { &*self.node }
+    }
+}
+
+/// Options of parsing, `buffer_name` can be `NULL`
+#[repr(C)]
+pub struct LibRubyParserOptions {
+    pub buffer_name: *const c_char,
+    pub record_tokens: bool,
+    pub record_comments: bool,
+}
+
+/// Borrowed byte string, not NULL-terminated.
+/// `ptr` is `NULL` if there's no value
+#[repr(C)]
+pub struct LibRubyParserStr {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+impl LibRubyParserStr {
+    fn new(bytes: &[u8]) -> Self {
+        Self {
+            ptr: bytes.as_ptr(),
+            len: bytes.len(),
+        }
+    }
+
+    fn null() -> Self {
+        Self {
+            ptr: ptr::null(),
+            len: 0,
+        }
+    }
+}
+
+/// Location of the node/token/diagnostic/comment, byte offsets in the input
+#[repr(C)]
+pub struct LibRubyParserLoc {
+    pub begin: usize,
+    pub end: usize,
+}
+
+impl LibRubyParserLoc {
+    fn empty() -> Self {
+        Self { begin: 0, end: 0 }
+    }
+}
+
+impl From<&Range> for LibRubyParserLoc {
+    fn from(range: &Range) -> Self {
+        Self {
+            begin: range.begin_pos,
+            end: range.end_pos,
+        }
+    }
+}
+
+#[repr(C)]
+pub enum LibRubyParserCommentKind {
+    Inline,
+    Document,
+    Unknown,
+}
+
+unsafe fn options_from_c(options: *const LibRubyParserOptions) -> ParserOptions {
+    let mut result = ParserOptions::default();
+    if let Some(options) = options.as_ref() {
+        if !options.buffer_name.is_null() {
+            result.buffer_name = CStr::from_ptr(options.buffer_name)
+                .to_string_lossy()
+                .into_owned();
+        }
+        result.record_tokens = options.record_tokens;
+        result.record_comments = options.record_comments;
+    }
+    result
+}
+
+// Lays out all nodes of the AST in a single vector in breadth-first order,
+// so direct children of every node are stored next to each other
+// and can be accessed by index in constant time.
+fn flatten_ast(ast: &Node) -> Vec<LibRubyParserNode> {
+    // (node, index of the first child, number of children)
+    let mut queue: Vec<(&Node, usize, usize)> = vec![(ast, 0, 0)];
+    let mut idx = 0;
+    while idx < queue.len() {
+        let children = queue[idx].0.children();
+        queue[idx].1 = queue.len();
+        queue[idx].2 = children.len();
+        queue.extend(children.into_iter().map(|child| (child, 0, 0)));
+        idx += 1;
+    }
+
+    // the vector is never re-allocated, so pointers to its items stay valid
+    let mut nodes = Vec::with_capacity(queue.len());
+    let first_node: *const LibRubyParserNode = nodes.as_ptr();
+    for (node, first_child, children_count) in queue {
+        nodes.push(LibRubyParserNode {
+            node,
+            children: first_node.wrapping_add(first_child),
+            children_count,
+        });
+    }
+    nodes
+}
+
+/// Parses `len` bytes starting at `bytes`.
+///
+/// `options` can be `NULL`, then default options are used.
+///
+/// # Safety
+///
+/// `bytes` must be `NULL` or point to `len` readable bytes,
+/// `options` must be `NULL` or point to valid options
+/// with `buffer_name` that is `NULL` or a NULL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_parse(
+    bytes: *const u8,
+    len: usize,
+    options: *const LibRubyParserOptions,
+) -> *mut LibRubyParserResult {
+    let input: &[u8] = if bytes.is_null() {
+        &[]
+    } else {
+        std::slice::from_raw_parts(bytes, len)
+    };
+    let options = options_from_c(options);
+
+    let result = Parser::new(input, options).do_parse();
+    let token_names = result
+        .tokens
+        .iter()
+        .map(|token| token_name(token.token_type))
+        .collect();
+    let diagnostic_messages = result
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.render_message())
+        .collect();
+
+    // nodes point to the AST, so they are built once it's boxed
+    let mut result = Box::new(LibRubyParserResult {
+        result,
+        nodes: vec![],
+        token_names,
+        diagnostic_messages,
+    });
+    if let Some(ast) = &result.result.ast {
+        result.nodes = flatten_ast(ast);
+    }
+    Box::into_raw(result)
+}
+
+/// Releases the result of `lib_ruby_parser_parse`
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+/// All data borrowed from the result becomes invalid.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_free_result(result: *mut LibRubyParserResult) {
+    if !result.is_null() {
+        drop(Box::from_raw(result))
+    }
+}
+
+unsafe fn result_ref<'a>(result: *const LibRubyParserResult) -> Option<&'a LibRubyParserResult> {
+    result.as_ref()
+}
+
+unsafe fn node_ref<'a>(node: *const LibRubyParserNode) -> Option<&'a LibRubyParserNode> {
+    node.as_ref()
+}
+
+/// Returns (decoded) input that has been parsed
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_input(
+    result: *const LibRubyParserResult,
+) -> LibRubyParserStr {
+    match result_ref(result) {
+        Some(result) => LibRubyParserStr::new(&result.result.input.bytes),
+        None => LibRubyParserStr::null(),
+    }
+}
+
+//
+// AST
+//
+
+/// Returns a root node of the AST, `NULL` if there's no AST
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_ast(
+    result: *const LibRubyParserResult,
+) -> *const LibRubyParserNode {
+    match result_ref(result).and_then(|result| result.nodes.first()) {
+        Some(node) => node,
+        None => ptr::null(),
+    }
+}
+
+/// Returns a whitequark/parser -like type of the node (like "send" or "int")
+///
+/// # Safety
+///
+/// `node` must be `NULL` or a node of a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_node_type(
+    node: *const LibRubyParserNode,
+) -> LibRubyParserStr {
+    match node_ref(node) {
+        Some(node) => LibRubyParserStr::new(node.node().str_type().as_bytes()),
+        None => LibRubyParserStr::null(),
+    }
+}
+
+/// Returns location of the full node expression
+///
+/// # Safety
+///
+/// `node` must be `NULL` or a node of a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_node_expression(
+    node: *const LibRubyParserNode,
+) -> LibRubyParserLoc {
+    match node_ref(node) {
+        Some(node) => node.node().expression().into(),
+        None => LibRubyParserLoc::empty(),
+    }
+}
+
+/// Writes location of the node field with a given name
+/// (like "expression_l", "name_l" or "begin_l") to `loc`.
+///
+/// Returns `false` (and leaves `loc` untouched) if the node
+/// has no such location or if it's absent (like `begin_l` of `if` without `then`).
+///
+/// # Safety
+///
+/// `node` must be `NULL` or a node of a result that hasn't been released yet,
+/// `name` must be `NULL` or a NULL-terminated string,
+/// `loc` must be `NULL` or point to a writable location.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_node_loc(
+    node: *const LibRubyParserNode,
+    name: *const c_char,
+    loc: *mut LibRubyParserLoc,
+) -> bool {
+    let (node, loc) = match (node_ref(node), loc.as_mut()) {
+        (Some(node), Some(loc)) if !name.is_null() => (node, loc),
+        _ => return false,
+    };
+    let name = CStr::from_ptr(name).to_bytes();
+
+    let range = node
+        .node()
+        .fields()
+        .into_iter()
+        .find(|(field_name, _)| field_name.as_bytes() == name)
+        .and_then(|(_, value)| match value {
+            FieldValue::Range(range) => Some(range),
+            FieldValue::MaybeRange(range) => range,
+            _ => None,
+        });
+
+    match range {
+        Some(range) => {
+            *loc = range.into();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns number of direct children of the node
+///
+/// # Safety
+///
+/// `node` must be `NULL` or a node of a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_node_children_count(
+    node: *const LibRubyParserNode,
+) -> usize {
+    match node_ref(node) {
+        Some(node) => node.children_count,
+        None => 0,
+    }
+}
+
+/// Returns a direct child of the node at a given index, `NULL` if there's no such child
+///
+/// # Safety
+///
+/// `node` must be `NULL` or a node of a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_node_child(
+    node: *const LibRubyParserNode,
+    idx: usize,
+) -> *const LibRubyParserNode {
+    match node_ref(node) {
+        Some(node) if idx < node.children_count => node.children.add(idx),
+        _ => ptr::null(),
+    }
+}
+
+/// Returns a literal value of the node:
+/// + numeric value for numeric literals (e.g. "42" for `int`)
+/// + string value for `str`/`sym`
+/// + name for variables, constants, arguments, method calls and definitions
+///
+/// Returns a string with `NULL` pointer for all other nodes
+///
+/// # Safety
+///
+/// `node` must be `NULL` or a node of a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_node_value(
+    node: *const LibRubyParserNode,
+) -> LibRubyParserStr {
+    match node_ref(node).and_then(|node| node.node().value()) {
+        Some(value) => LibRubyParserStr::new(value),
+        None => LibRubyParserStr::null(),
+    }
+}
+
+//
+// Tokens
+//
+
+/// Returns number of recorded tokens
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_tokens_count(result: *const LibRubyParserResult) -> usize {
+    match result_ref(result) {
+        Some(result) => result.result.tokens.len(),
+        None => 0,
+    }
+}
+
+/// Returns a numeric type of the token at a given index, -1 if there's no such token
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_token_type(
+    result: *const LibRubyParserResult,
+    idx: usize,
+) -> i32 {
+    match result_ref(result).and_then(|result| result.result.tokens.get(idx)) {
+        Some(token) => token.token_type,
+        None => -1,
+    }
+}
+
+/// Returns a name of the token at a given index (like "tIDENTIFIER")
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_token_name(
+    result: *const LibRubyParserResult,
+    idx: usize,
+) -> LibRubyParserStr {
+    match result_ref(result).and_then(|result| result.token_names.get(idx)) {
+        Some(name) => LibRubyParserStr::new(name.as_bytes()),
+        None => LibRubyParserStr::null(),
+    }
+}
+
+/// Returns a value of the token at a given index
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_token_value(
+    result: *const LibRubyParserResult,
+    idx: usize,
+) -> LibRubyParserStr {
+    match result_ref(result).and_then(|result| result.result.tokens.get(idx)) {
+        Some(token) => match &token.token_value {
+            TokenValue::String(s) => LibRubyParserStr::new(s.as_bytes()),
+            TokenValue::InvalidString(bytes) => LibRubyParserStr::new(bytes),
+        },
+        None => LibRubyParserStr::null(),
+    }
+}
+
+/// Returns location of the token at a given index
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_token_loc(
+    result: *const LibRubyParserResult,
+    idx: usize,
+) -> LibRubyParserLoc {
+    match result_ref(result).and_then(|result| result.result.tokens.get(idx)) {
+        Some(token) => LibRubyParserLoc {
+            begin: token.loc.begin,
+            end: token.loc.end,
+        },
+        None => LibRubyParserLoc::empty(),
+    }
+}
+
+//
+// Diagnostics
+//
+
+/// Returns number of diagnostics (errors and warnings)
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_diagnostics_count(
+    result: *const LibRubyParserResult,
+) -> usize {
+    match result_ref(result) {
+        Some(result) => result.result.diagnostics.len(),
+        None => 0,
+    }
+}
+
+/// Returns `true` if the diagnostic at a given index is an error
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_diagnostic_is_error(
+    result: *const LibRubyParserResult,
+    idx: usize,
+) -> bool {
+    match result_ref(result).and_then(|result| result.result.diagnostics.get(idx)) {
+        Some(diagnostic) => diagnostic.is_error(),
+        None => false,
+    }
+}
+
+/// Returns a rendered message of the diagnostic at a given index
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_diagnostic_message(
+    result: *const LibRubyParserResult,
+    idx: usize,
+) -> LibRubyParserStr {
+    match result_ref(result).and_then(|result| result.diagnostic_messages.get(idx)) {
+        Some(message) => LibRubyParserStr::new(message.as_bytes()),
+        None => LibRubyParserStr::null(),
+    }
+}
+
+/// Returns location of the diagnostic at a given index
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_diagnostic_loc(
+    result: *const LibRubyParserResult,
+    idx: usize,
+) -> LibRubyParserLoc {
+    match result_ref(result).and_then(|result| result.result.diagnostics.get(idx)) {
+        Some(diagnostic) => (&diagnostic.range).into(),
+        None => LibRubyParserLoc::empty(),
+    }
+}
+
+//
+// Comments
+//
+
+/// Returns number of recorded comments
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_comments_count(
+    result: *const LibRubyParserResult,
+) -> usize {
+    match result_ref(result) {
+        Some(result) => result.result.comments.len(),
+        None => 0,
+    }
+}
+
+/// Returns kind of the comment at a given index
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_comment_kind(
+    result: *const LibRubyParserResult,
+    idx: usize,
+) -> LibRubyParserCommentKind {
+    match result_ref(result)
+        .and_then(|result| result.result.comments.get(idx))
+        .map(|comment| &comment.kind)
+    {
+        Some(CommentType::Inline) => LibRubyParserCommentKind::Inline,
+        Some(CommentType::Document) => LibRubyParserCommentKind::Document,
+        _ => LibRubyParserCommentKind::Unknown,
+    }
+}
+
+/// Returns location of the comment at a given index
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_comment_loc(
+    result: *const LibRubyParserResult,
+    idx: usize,
+) -> LibRubyParserLoc {
+    match result_ref(result).and_then(|result| result.result.comments.get(idx)) {
+        Some(comment) => (&comment.location).into(),
+        None => LibRubyParserLoc::empty(),
+    }
+}
@@ -0,0 +1,92 @@
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "lib-ruby-parser.h"
+
+static bool str_eq(LibRubyParserStr s, const char *expected)
+{
+    return s.ptr != NULL && s.len == strlen(expected) && memcmp(s.ptr, expected, s.len) == 0;
+}
+
+static void test_ast(void)
+{
+    const char *code = "foo(42) # comment\n";
+    LibRubyParserOptions options = {"(capi_test)", true, true};
+    LibRubyParserResult *result = lib_ruby_parser_parse((const uint8_t *)code, strlen(code), &options);
+
+    const LibRubyParserNode *ast = lib_ruby_parser_ast(result);
+    assert(ast != NULL);
+    assert(str_eq(lib_ruby_parser_node_type(ast), "send"));
+    assert(str_eq(lib_ruby_parser_node_value(ast), "foo"));
+    assert(lib_ruby_parser_node_children_count(ast) == 1);
+    assert(lib_ruby_parser_node_child(ast, 1) == NULL);
+
+    const LibRubyParserNode *arg = lib_ruby_parser_node_child(ast, 0);
+    assert(str_eq(lib_ruby_parser_node_type(arg), "int"));
+    assert(str_eq(lib_ruby_parser_node_value(arg), "42"));
+
+    LibRubyParserLoc loc = lib_ruby_parser_node_expression(arg);
+    assert(loc.begin == 4 && loc.end == 6);
+
+    assert(lib_ruby_parser_node_loc(ast, "selector_l", &loc));
+    assert(loc.begin == 0 && loc.end == 3);
+    assert(lib_ruby_parser_node_loc(ast, "begin_l", &loc));
+    assert(loc.begin == 3 && loc.end == 4);
+    assert(!lib_ruby_parser_node_loc(ast, "dot_l", &loc));
+    assert(!lib_ruby_parser_node_loc(ast, "unknown_l", &loc));
+
+    lib_ruby_parser_free_result(result);
+}
+
+static void test_tokens_and_comments(void)
+{
+    const char *code = "foo(42) # comment\n";
+    LibRubyParserResult *result = lib_ruby_parser_parse((const uint8_t *)code, strlen(code), NULL);
+
+    assert(lib_ruby_parser_tokens_count(result) > 0);
+    assert(str_eq(lib_ruby_parser_token_name(result, 0), "tIDENTIFIER"));
+    assert(str_eq(lib_ruby_parser_token_value(result, 0), "foo"));
+    LibRubyParserLoc loc = lib_ruby_parser_token_loc(result, 0);
+    assert(loc.begin == 0 && loc.end == 3);
+
+    assert(lib_ruby_parser_comments_count(result) == 1);
+    assert(lib_ruby_parser_comment_kind(result, 0) == LibRubyParserCommentKind_Inline);
+    loc = lib_ruby_parser_comment_loc(result, 0);
+    assert(loc.begin == 8 && loc.end == 17);
+
+    lib_ruby_parser_free_result(result);
+}
+
+static void test_diagnostics(void)
+{
+    const char *code = "def";
+    LibRubyParserResult *result = lib_ruby_parser_parse((const uint8_t *)code, strlen(code), NULL);
+
+    assert(lib_ruby_parser_ast(result) == NULL);
+    assert(lib_ruby_parser_diagnostics_count(result) > 0);
+    assert(lib_ruby_parser_diagnostic_is_error(result, 0));
+    assert(lib_ruby_parser_diagnostic_message(result, 0).len > 0);
+
+    lib_ruby_parser_free_result(result);
+}
+
+static void test_null(void)
+{
+    assert(lib_ruby_parser_ast(NULL) == NULL);
+    assert(lib_ruby_parser_tokens_count(NULL) == 0);
+    assert(lib_ruby_parser_node_children_count(NULL) == 0);
+    assert(lib_ruby_parser_node_child(NULL, 0) == NULL);
+    assert(lib_ruby_parser_node_type(NULL).ptr == NULL);
+    lib_ruby_parser_free_result(NULL);
+}
+
+int main(void)
+{
+    test_ast();
+    test_tokens_and_comments();
+    test_diagnostics();
+    test_null();
+    printf("OK\n");
+    return 0;
+}
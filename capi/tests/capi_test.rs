@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+fn target_dir() -> PathBuf {
+    let mut path = std::env::current_exe().expect("failed to get current exe");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path
+}
+
+#[test]
+fn test_c_bindings() {
+    let target_dir = target_dir();
+    let exe = target_dir.join("capi_test_c");
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+
+    let status = Command::new(cc)
+        .arg("tests/capi/test.c")
+        .arg("-Iinclude")
+        .arg(target_dir.join("liblib_ruby_parser_capi.a"))
+        .args(&["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&exe)
+        .status()
+        .expect("failed to run C compiler");
+    assert!(status.success(), "failed to compile C test");
+
+    let output = Command::new(&exe).output().expect("failed to run C test");
+    assert!(
+        output.status.success(),
+        "C test failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,113 @@
+extern crate clap;
+use clap::Clap;
+
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::{Parser, ParserOptions, ParserResult};
+use std::fs;
+use std::time::Instant;
+
+#[derive(Debug, Clap)]
+struct Args {
+    #[clap(about = "file to use (by default a multi-megabyte file is generated)")]
+    path: Option<String>,
+
+    #[clap(
+        long,
+        default_value = "5",
+        about = "size of the generated file in megabytes"
+    )]
+    size: usize,
+}
+
+const SNIPPET: &str = "class Foo
+  # Returns a greeting
+  def greet(name, greeting: \"Привет\")
+    puts \"#{greeting}, #{name}!\" if name
+  end
+end
+
+";
+
+fn generate(megabytes: usize) -> Vec<u8> {
+    let size = megabytes * 1024 * 1024;
+    SNIPPET.repeat(size / SNIPPET.len() + 1).into_bytes()
+}
+
+// `f` returns the number of lookups and a checksum of their results
+// (so they can't be optimized away)
+fn measure<F: FnMut() -> (usize, usize)>(name: &str, mut f: F) {
+    let started_at = Instant::now();
+    let (count, checksum) = f();
+    let elapsed = started_at.elapsed();
+    println!(
+        "{:<25} {:>10} lookups in {:?} ({:?} per lookup, checksum {})",
+        name,
+        count,
+        elapsed,
+        elapsed / std::cmp::max(count, 1) as u32,
+        checksum
+    );
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Args = Args::parse();
+
+    let source = match &args.path {
+        Some(path) => fs::read(path)?,
+        None => generate(args.size),
+    };
+
+    let ParserResult { tokens, input, .. } =
+        Parser::new(&source, ParserOptions::default()).do_parse();
+    println!(
+        "{} bytes, {} lines, {} tokens",
+        input.len(),
+        input.lines.len(),
+        tokens.len()
+    );
+
+    measure("line_col_for_pos", || {
+        let checksum = tokens
+            .iter()
+            .filter_map(|token| input.line_col_for_pos(token.loc.begin))
+            .map(|(line, col)| line + col)
+            .sum();
+        (tokens.len(), checksum)
+    });
+
+    measure("line_char_col_for_pos", || {
+        let checksum = tokens
+            .iter()
+            .filter_map(|token| input.line_char_col_for_pos(token.loc.begin))
+            .map(|(line, col)| line + col)
+            .sum();
+        (tokens.len(), checksum)
+    });
+
+    measure("line_utf16_col_for_pos", || {
+        let checksum = tokens
+            .iter()
+            .filter_map(|token| input.line_utf16_col_for_pos(token.loc.begin))
+            .map(|(line, col)| line + col)
+            .sum();
+        (tokens.len(), checksum)
+    });
+
+    measure("pos_for_line_col", || {
+        let checksum = (0..input.lines.len())
+            .filter_map(|line| input.pos_for_line_col(line, 0))
+            .sum();
+        (input.lines.len(), checksum)
+    });
+
+    measure("Range::lines", || {
+        let checksum = tokens
+            .iter()
+            .filter_map(|token| Range::new(token.loc.begin, token.loc.end).lines(&input))
+            .map(|(first, last)| first + last)
+            .sum();
+        (tokens.len(), checksum)
+    });
+
+    Ok(())
+}
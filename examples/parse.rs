@@ -3,11 +3,45 @@ use clap::Clap;
 
 extern crate jemallocator;
 #[global_allocator]
-static GLOBAL: jemallocator::Jemalloc = jemallocator::Jemalloc;
+static GLOBAL: CountingAllocator = CountingAllocator;
 
-use lib_ruby_parser::ParserResult;
+use lib_ruby_parser::arena::{Arena, NodeId};
+use lib_ruby_parser::{Interner, Node, Parser, ParserOptions, ParserResult, SourceToken, Token};
+use std::alloc::{GlobalAlloc, Layout};
+use std::cell::RefCell;
 use std::fs;
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+// jemalloc that keeps track of currently allocated bytes (for `--memory`)
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = jemallocator::Jemalloc.alloc(layout);
+        if !ptr.is_null() {
+            ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        jemallocator::Jemalloc.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = jemallocator::Jemalloc.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+            ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+        new_ptr
+    }
+}
 
 mod helpers;
 use helpers::*;
@@ -37,6 +71,34 @@ struct Args {
 
     #[clap(short, long, about = "Run profiles")]
     profile: bool,
+
+    #[clap(long, about = "don't record tokens")]
+    no_tokens: bool,
+
+    #[clap(long, about = "don't record comments")]
+    no_comments: bool,
+
+    #[clap(long, about = "record range-backed tokens instead of owned ones")]
+    source_tokens: bool,
+
+    #[clap(long, about = "share an identifiers interner between all files")]
+    shared_interner: bool,
+
+    #[clap(long, about = "keep ASTs and tokens of all files and print their size")]
+    memory: bool,
+
+    #[clap(long, about = "with --memory, keep ASTs in a single arena")]
+    arena: bool,
+}
+
+// ASTs and tokens that are kept until the end of the run (for `--memory`)
+#[derive(Default)]
+struct Retained {
+    nodes: Vec<Node>,
+    arena: Arena,
+    roots: Vec<NodeId>,
+    tokens: Vec<Token>,
+    source_tokens: Vec<SourceToken>,
 }
 
 fn print_diagnostics(result: &ParserResult) {
@@ -72,6 +134,45 @@ fn print_full(_str: &str, result: &ParserResult) {
     println!("{:#?}", result)
 }
 
+fn parse(
+    source: &[u8],
+    filename: &str,
+    args: &Args,
+    interner: &RefCell<Option<Interner>>,
+) -> ParserResult {
+    let options = ParserOptions {
+        buffer_name: filename.to_owned(),
+        debug: args.debug,
+        record_tokens: !args.no_tokens && !args.source_tokens,
+        record_source_tokens: args.source_tokens,
+        record_comments: !args.no_comments,
+        interner: interner.borrow_mut().take(),
+        ..Default::default()
+    };
+    let mut result = Parser::new(source, options).do_parse();
+    if args.shared_interner {
+        *interner.borrow_mut() = Some(std::mem::take(&mut result.interner));
+    }
+    result
+}
+
+fn retain(result: &mut ParserResult, args: &Args, retained: &RefCell<Retained>) {
+    if !args.memory {
+        return;
+    }
+    let mut retained = retained.borrow_mut();
+    retained.tokens.append(&mut result.tokens);
+    retained.source_tokens.append(&mut result.source_tokens);
+    if let Some(ast) = result.ast.take() {
+        if args.arena {
+            let root = retained.arena.alloc(&ast);
+            retained.roots.push(root);
+        } else {
+            retained.nodes.push(ast);
+        }
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Args = Args::parse();
 
@@ -88,16 +189,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     let profile = start_profiling(args.profile);
-    let debug = args.debug;
-
-    if let Some(code) = args.code {
-        let result = parse(code.as_bytes(), "(eval)", debug);
-        callback(&code, &result);
-    } else if let Some(path) = args.path {
-        each_ruby_file(&path, &|entry| {
+    let started_at = Instant::now();
+    let interner = RefCell::new(None);
+    let retained = RefCell::new(Retained::default());
+    let allocated_before = ALLOCATED.load(Ordering::Relaxed);
+
+    if let Some(code) = &args.code {
+        let mut result = parse(code.as_bytes(), "(eval)", &args, &interner);
+        callback(code, &result);
+        retain(&mut result, &args, &retained);
+    } else if let Some(path) = &args.path {
+        each_ruby_file(path, &|entry| {
             let code = fs::read(Path::new(entry))?;
-            let result = parse(&code, entry, debug);
+            let mut result = parse(&code, entry, &args, &interner);
             callback(&String::from_utf8_lossy(&code), &result);
+            retain(&mut result, &args, &retained);
             Ok(())
         })?;
     } else {
@@ -106,6 +212,42 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     stop_profiling(args.profile, profile)?;
 
+    if args.profile {
+        println!(
+            "Time taken: {:?} (tokens: {}, source tokens: {}, comments: {})",
+            started_at.elapsed(),
+            !args.no_tokens && !args.source_tokens,
+            args.source_tokens,
+            !args.no_comments
+        );
+        if let Some(interner) = &*interner.borrow() {
+            println!("Interned identifiers: {}", interner.len());
+        }
+    }
+
+    if args.memory {
+        let retained = retained.borrow();
+        let allocated = ALLOCATED
+            .load(Ordering::Relaxed)
+            .saturating_sub(allocated_before);
+        if args.arena {
+            println!(
+                "Retained {} ASTs in an arena ({} nodes, {} identifiers)",
+                retained.roots.len(),
+                retained.arena.len(),
+                retained.arena.interner().len()
+            );
+        } else {
+            println!("Retained {} owned ASTs", retained.nodes.len());
+        }
+        println!(
+            "Retained {} tokens and {} source tokens",
+            retained.tokens.len(),
+            retained.source_tokens.len()
+        );
+        println!("Allocated: {} bytes", allocated);
+    }
+
     println!("Done");
 
     Ok(())
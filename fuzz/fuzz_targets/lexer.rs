@@ -0,0 +1,9 @@
+#![no_main]
+use lib_ruby_parser::source::CustomDecoder;
+use lib_ruby_parser::Lexer;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let mut lexer = Lexer::new(data, "(fuzz)", CustomDecoder::default());
+    lexer.tokenize_until_eof();
+});
@@ -0,0 +1,7 @@
+#![no_main]
+use lib_ruby_parser_fuzz::parse;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    parse(data);
+});
@@ -0,0 +1,7 @@
+#![no_main]
+use lib_ruby_parser_fuzz::{parse, Program};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|program: Program| {
+    parse(&program.to_source());
+});
@@ -0,0 +1,55 @@
+# This script seeds corpora of "lexer" and "parser" fuzz targets with:
+#   - inputs of test fixtures (tests/fixtures/{lexer,parser}/**)
+#   - Ruby files of whitequark/parser (vendor/parser, lib and test dirs)
+#
+# Each input is saved as fuzz/corpus/<TARGET>/<SHA1 OF INPUT>,
+# so running it multiple times doesn't create duplicates.
+#
+# Usage:
+#   $ ruby fuzz/seed_corpus.rb
+#   $ cargo fuzz run parser
+
+require 'fileutils'
+require 'digest'
+
+ROOT = File.expand_path('..', __dir__)
+FIXTURES_DIR = File.join(ROOT, 'tests', 'fixtures')
+PARSER_DIR = File.join(ROOT, 'vendor', 'parser')
+CORPUS_DIR = File.join(__dir__, 'corpus')
+TARGETS = %w[lexer parser]
+
+# Returns contents of "--INPUT" section of a given fixture
+def fixture_input(path)
+  lines = File.binread(path).lines
+  input = lines.drop_while { |line| line.chomp != '--INPUT' }.drop(1)
+  input = input.take_while { |line| !line.match?(/\A--[A-Z]+\n?\z/n) }
+  input.join.chomp
+end
+
+inputs = []
+
+Dir[File.join(FIXTURES_DIR, '{lexer,parser}', '**', '*')].sort.each do |path|
+  next unless File.file?(path)
+
+  inputs << fixture_input(path)
+end
+puts "Found #{inputs.length} fixtures in #{FIXTURES_DIR}"
+
+ruby_files = Dir[File.join(PARSER_DIR, '{lib,test}', '**', '*.rb')].sort
+if ruby_files.empty?
+  puts "No Ruby files in #{PARSER_DIR} (run `git submodule update --init` to fetch them)"
+else
+  puts "Found #{ruby_files.length} Ruby files in #{PARSER_DIR}"
+  ruby_files.each { |path| inputs << File.binread(path) }
+end
+
+TARGETS.each do |target|
+  dir = File.join(CORPUS_DIR, target)
+  FileUtils.mkdir_p(dir)
+
+  inputs.uniq.each do |input|
+    File.binwrite(File.join(dir, Digest::SHA1.hexdigest(input)), input)
+  end
+end
+
+puts "Saved #{inputs.uniq.length} inputs to #{CORPUS_DIR}/{#{TARGETS.join(',')}}"
@@ -0,0 +1,4 @@
+foo.bar(1, *a, **b, &c)
+foo&.()
+foo&.bar ||= 1
+a, (b, *c), d = 1, 2
@@ -0,0 +1,10 @@
+class Foo < Bar
+  module Baz; end
+  class << self
+    alias $a $1
+    alias foo bar
+    undef baz, :qux
+  end
+end
+BEGIN { 1 }
+END { 2 }
@@ -0,0 +1,6 @@
+a = 1
+=begin
+comment
+=end
+__END__
+data
@@ -0,0 +1,13 @@
+begin
+  a
+rescue Foo, Bar => e
+  retry
+else
+  b
+ensure
+  c
+end
+while a do break end until b
+for x, y in z do next x end
+return foo 1 do end
+x = y rescue nil
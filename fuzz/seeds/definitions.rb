@@ -0,0 +1,5 @@
+def foo(a, b = 1, *c, d:, e: 2, **f, &g) = a
+def self.bar(...) = baz(...)
+[1].each { _1 + _2 }
+-> (x; y) { x }
+proc { |a, (b, c), *| }
@@ -0,0 +1,12 @@
+x = <<~HEREDOC
+  a #{b} #@c #$d
+    e
+HEREDOC
+y = <<-`CMD`.strip
+  ls
+  CMD
+foo(<<A, <<~B)
+a
+A
+  b
+B
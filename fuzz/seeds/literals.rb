@@ -0,0 +1 @@
+0x1F 0b10 0o7 1_000 1.5e-3 2r 3.5ri 1i __LINE__ __FILE__ __ENCODING__ $~ $1 @@cv
@@ -0,0 +1,8 @@
+case x
+in [1, *rest] if rest.empty? then 1
+in {a:, "b": c, **nil} then 2
+in Foo(bar: ^baz) | nil => y then 3
+in ^(1 + 2) then 4
+end
+x => {a: [*, 1, *]}
+x in Integer
@@ -0,0 +1,3 @@
+"a#{b}c" :"d#{e}" %w[a b] %I[c#{d} e] /f#{g}/mix `h #{i}`
+"\u{1F600 41}\xFF\M-\C-a"
+?a ?\n
@@ -0,0 +1,4 @@
+def foo(a
+  "b#{
+  <<~C
+  [1, {a: 
@@ -0,0 +1,1110 @@
+//! Structure-aware generator of Ruby-like source code.
+//!
+//! Random bytes mostly produce inputs that are rejected by the lexer
+//! on the first few characters, so `Program` is built from arbitrary data instead
+//! and printed as Ruby code that is (mostly) syntactically valid.
+//! Every piece can still contain raw bytes and stray tokens to exercise error recovery.
+
+use arbitrary::Arbitrary;
+
+const IDENTS: &[&str] = &[
+    "a", "b", "foo", "bar_baz", "_", "_x", "_1", "it", "x1", "é", "本", "nil?", "end", "self",
+];
+const CONSTS: &[&str] = &["A", "Foo", "BarBaz", "Object", "Ä", "B1", "C"];
+const TEXTS: &[&str] = &[
+    "", "abc", " ", "é", "😀", "#", "{", "}", "'", "\"", "\n", "\t", "EOS", "\\",
+];
+const ESCAPES: &[&str] = &[
+    "\\n",
+    "\\t",
+    "\\e",
+    "\\s",
+    "\\0",
+    "\\\\",
+    "\\x41",
+    "\\xFF",
+    "\\777",
+    "\\u00e9",
+    "\\u{1F600}",
+    "\\u{61 62}",
+    "\\uD800",
+    "\\u{110000}",
+    "\\M-a",
+    "\\C-a",
+    "\\c?",
+    "\\M-\\C-x",
+    "\\",
+];
+const TOKENS: &[&str] = &[
+    "(",
+    ")",
+    "[",
+    "]",
+    "{",
+    "}",
+    "|",
+    "\\",
+    ",",
+    ";",
+    ".",
+    "..",
+    "...",
+    "&.",
+    "::",
+    "->",
+    "=>",
+    "=",
+    "*",
+    "**",
+    "&",
+    "?",
+    ":",
+    "\n",
+    "#",
+    "=begin\n",
+    "=end\n",
+    "__END__\n",
+    "<<~",
+    "%",
+    "%w[",
+    "`",
+    "$",
+    "@",
+    "@@",
+    "def",
+    "class",
+    "module",
+    "end",
+    "if",
+    "unless",
+    "do",
+    "then",
+    "else",
+    "rescue",
+    "ensure",
+    "case",
+    "when",
+    "in",
+    "yield",
+    "super",
+    "BEGIN",
+    "__ENCODING__",
+];
+
+fn pick<'a>(list: &[&'a str], index: u8) -> &'a str {
+    list[index as usize % list.len()]
+}
+
+/// Writes Ruby code of `self` to `out`
+trait ToSource {
+    fn write(&self, out: &mut Vec<u8>);
+}
+
+impl<T: ToSource> ToSource for Box<T> {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.as_ref().write(out)
+    }
+}
+
+fn write_list<T: ToSource>(out: &mut Vec<u8>, items: &[T], sep: &str) {
+    for (idx, item) in items.iter().enumerate() {
+        if idx > 0 {
+            out.extend_from_slice(sep.as_bytes());
+        }
+        item.write(out);
+    }
+}
+
+fn write_body(out: &mut Vec<u8>, stmts: &[Stmt]) {
+    out.push(b'\n');
+    write_list(out, stmts, "\n");
+    out.push(b'\n');
+}
+
+/// Top-level list of statements
+#[derive(Debug, Arbitrary)]
+pub struct Program {
+    magic_comment: Option<MagicComment>,
+    stmts: Vec<Stmt>,
+}
+
+impl Program {
+    /// Returns source code of the program
+    pub fn to_source(&self) -> Vec<u8> {
+        let mut out = vec![];
+        if let Some(magic_comment) = &self.magic_comment {
+            magic_comment.write(&mut out);
+        }
+        write_list(&mut out, &self.stmts, "\n");
+        out
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum MagicComment {
+    Encoding(u8),
+    FrozenStringLiteral(bool),
+    Raw(Vec<u8>),
+}
+
+impl ToSource for MagicComment {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Encoding(idx) => {
+                let encoding = pick(&["utf-8", "binary", "us-ascii", "koi8-r", "unknown"], *idx);
+                out.extend_from_slice(format!("# encoding: {}\n", encoding).as_bytes())
+            }
+            Self::FrozenStringLiteral(value) => {
+                out.extend_from_slice(format!("# frozen_string_literal: {}\n", value).as_bytes())
+            }
+            Self::Raw(bytes) => {
+                out.extend_from_slice(b"# -*- ");
+                out.extend_from_slice(bytes);
+                out.extend_from_slice(b" -*-\n");
+            }
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+struct Ident(u8);
+
+impl ToSource for Ident {
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(pick(IDENTS, self.0).as_bytes())
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+struct Const(u8);
+
+impl ToSource for Const {
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(pick(CONSTS, self.0).as_bytes())
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum Stmt {
+    Expr(Expr),
+    Assign(Lhs, Option<BinOp>, Expr),
+    MultiAssign(Vec<Lhs>, Option<Lhs>, Vec<Expr>),
+    Def {
+        singleton: bool,
+        name: Ident,
+        args: Vec<Arg>,
+        body: Vec<Stmt>,
+    },
+    EndlessDef(Ident, Vec<Arg>, Expr),
+    Class(Const, Option<Expr>, Vec<Stmt>),
+    SingletonClass(Expr, Vec<Stmt>),
+    Module(Const, Vec<Stmt>),
+    If(Expr, Vec<Stmt>, Option<Vec<Stmt>>),
+    Unless(Expr, Vec<Stmt>),
+    While(bool, Expr, Vec<Stmt>),
+    For(Lhs, Expr, Vec<Stmt>),
+    Case(Option<Expr>, Vec<(Vec<Expr>, Vec<Stmt>)>, Option<Vec<Stmt>>),
+    CaseIn(Expr, Vec<(Pattern, Option<Expr>, Vec<Stmt>)>),
+    Begin(Vec<Stmt>, Vec<Rescue>, Option<Vec<Stmt>>, Option<Vec<Stmt>>),
+    Modifier(Box<Stmt>, Modifier, Expr),
+    Return(Option<Expr>),
+    Break(Option<Expr>),
+    Next,
+    Redo,
+    Retry,
+    Alias(Ident, Ident),
+    Undef(Vec<Ident>),
+    PreExe(Vec<Stmt>),
+    Heredoc(Heredoc),
+    /// a token that is likely to be misplaced
+    Token(u8),
+    Raw(Vec<u8>),
+}
+
+impl ToSource for Stmt {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Expr(expr) => expr.write(out),
+            Self::Assign(lhs, op, value) => {
+                lhs.write(out);
+                out.push(b' ');
+                if let Some(op) = op {
+                    op.write(out);
+                }
+                out.extend_from_slice(b"= ");
+                value.write(out);
+            }
+            Self::MultiAssign(lhs, splat, values) => {
+                write_list(out, lhs, ", ");
+                if let Some(splat) = splat {
+                    out.extend_from_slice(b", *");
+                    splat.write(out);
+                }
+                out.extend_from_slice(b" = ");
+                write_list(out, values, ", ");
+            }
+            Self::Def {
+                singleton,
+                name,
+                args,
+                body,
+            } => {
+                out.extend_from_slice(if *singleton { b"def self." } else { b"def " });
+                name.write(out);
+                out.push(b'(');
+                write_list(out, args, ", ");
+                out.push(b')');
+                write_body(out, body);
+                out.extend_from_slice(b"end");
+            }
+            Self::EndlessDef(name, args, body) => {
+                out.extend_from_slice(b"def ");
+                name.write(out);
+                out.push(b'(');
+                write_list(out, args, ", ");
+                out.extend_from_slice(b") = ");
+                body.write(out);
+            }
+            Self::Class(name, superclass, body) => {
+                out.extend_from_slice(b"class ");
+                name.write(out);
+                if let Some(superclass) = superclass {
+                    out.extend_from_slice(b" < ");
+                    superclass.write(out);
+                }
+                write_body(out, body);
+                out.extend_from_slice(b"end");
+            }
+            Self::SingletonClass(of, body) => {
+                out.extend_from_slice(b"class << ");
+                of.write(out);
+                write_body(out, body);
+                out.extend_from_slice(b"end");
+            }
+            Self::Module(name, body) => {
+                out.extend_from_slice(b"module ");
+                name.write(out);
+                write_body(out, body);
+                out.extend_from_slice(b"end");
+            }
+            Self::If(cond, if_true, if_false) => {
+                out.extend_from_slice(b"if ");
+                cond.write(out);
+                write_body(out, if_true);
+                if let Some(if_false) = if_false {
+                    out.extend_from_slice(b"else");
+                    write_body(out, if_false);
+                }
+                out.extend_from_slice(b"end");
+            }
+            Self::Unless(cond, body) => {
+                out.extend_from_slice(b"unless ");
+                cond.write(out);
+                write_body(out, body);
+                out.extend_from_slice(b"end");
+            }
+            Self::While(until, cond, body) => {
+                out.extend_from_slice(if *until { b"until " } else { b"while " });
+                cond.write(out);
+                out.extend_from_slice(b" do");
+                write_body(out, body);
+                out.extend_from_slice(b"end");
+            }
+            Self::For(var, iter, body) => {
+                out.extend_from_slice(b"for ");
+                var.write(out);
+                out.extend_from_slice(b" in ");
+                iter.write(out);
+                write_body(out, body);
+                out.extend_from_slice(b"end");
+            }
+            Self::Case(subject, whens, else_body) => {
+                out.extend_from_slice(b"case ");
+                if let Some(subject) = subject {
+                    subject.write(out);
+                }
+                out.push(b'\n');
+                for (values, body) in whens {
+                    out.extend_from_slice(b"when ");
+                    write_list(out, values, ", ");
+                    out.extend_from_slice(b" then");
+                    write_body(out, body);
+                }
+                if let Some(else_body) = else_body {
+                    out.extend_from_slice(b"else");
+                    write_body(out, else_body);
+                }
+                out.extend_from_slice(b"end");
+            }
+            Self::CaseIn(subject, branches) => {
+                out.extend_from_slice(b"case ");
+                subject.write(out);
+                out.push(b'\n');
+                for (pattern, guard, body) in branches {
+                    out.extend_from_slice(b"in ");
+                    pattern.write(out);
+                    if let Some(guard) = guard {
+                        out.extend_from_slice(b" if ");
+                        guard.write(out);
+                    }
+                    out.extend_from_slice(b" then");
+                    write_body(out, body);
+                }
+                out.extend_from_slice(b"end");
+            }
+            Self::Begin(body, rescues, else_body, ensure_body) => {
+                out.extend_from_slice(b"begin");
+                write_body(out, body);
+                write_list(out, rescues, "");
+                if let Some(else_body) = else_body {
+                    out.extend_from_slice(b"else");
+                    write_body(out, else_body);
+                }
+                if let Some(ensure_body) = ensure_body {
+                    out.extend_from_slice(b"ensure");
+                    write_body(out, ensure_body);
+                }
+                out.extend_from_slice(b"end");
+            }
+            Self::Modifier(stmt, modifier, cond) => {
+                stmt.write(out);
+                out.push(b' ');
+                modifier.write(out);
+                out.push(b' ');
+                cond.write(out);
+            }
+            Self::Return(value) => {
+                out.extend_from_slice(b"return");
+                if let Some(value) = value {
+                    out.push(b' ');
+                    value.write(out);
+                }
+            }
+            Self::Break(value) => {
+                out.extend_from_slice(b"break");
+                if let Some(value) = value {
+                    out.push(b' ');
+                    value.write(out);
+                }
+            }
+            Self::Next => out.extend_from_slice(b"next"),
+            Self::Redo => out.extend_from_slice(b"redo"),
+            Self::Retry => out.extend_from_slice(b"retry"),
+            Self::Alias(to, from) => {
+                out.extend_from_slice(b"alias ");
+                to.write(out);
+                out.push(b' ');
+                from.write(out);
+            }
+            Self::Undef(names) => {
+                out.extend_from_slice(b"undef ");
+                write_list(out, names, ", ");
+            }
+            Self::PreExe(body) => {
+                out.extend_from_slice(b"BEGIN {");
+                write_body(out, body);
+                out.push(b'}');
+            }
+            Self::Heredoc(heredoc) => heredoc.write(out),
+            Self::Token(idx) => out.extend_from_slice(pick(TOKENS, *idx).as_bytes()),
+            Self::Raw(bytes) => out.extend_from_slice(bytes),
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+struct Rescue {
+    classes: Vec<Const>,
+    var: Option<Lhs>,
+    body: Vec<Stmt>,
+}
+
+impl ToSource for Rescue {
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(b"rescue ");
+        write_list(out, &self.classes, ", ");
+        if let Some(var) = &self.var {
+            out.extend_from_slice(b" => ");
+            var.write(out);
+        }
+        write_body(out, &self.body);
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum Modifier {
+    If,
+    Unless,
+    While,
+    Until,
+    Rescue,
+}
+
+impl ToSource for Modifier {
+    fn write(&self, out: &mut Vec<u8>) {
+        let modifier: &[u8] = match self {
+            Self::If => b"if",
+            Self::Unless => b"unless",
+            Self::While => b"while",
+            Self::Until => b"until",
+            Self::Rescue => b"rescue",
+        };
+        out.extend_from_slice(modifier)
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum Lhs {
+    Lvar(Ident),
+    Ivar(Ident),
+    Cvar(Ident),
+    Gvar(Ident),
+    Const(Option<Box<Expr>>, Const),
+    Index(Box<Expr>, Vec<Expr>),
+    Attr(Box<Expr>, bool, Ident),
+    Nested(Vec<Lhs>),
+}
+
+impl ToSource for Lhs {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Lvar(name) => name.write(out),
+            Self::Ivar(name) => {
+                out.push(b'@');
+                name.write(out);
+            }
+            Self::Cvar(name) => {
+                out.extend_from_slice(b"@@");
+                name.write(out);
+            }
+            Self::Gvar(name) => {
+                out.push(b'$');
+                name.write(out);
+            }
+            Self::Const(scope, name) => {
+                if let Some(scope) = scope {
+                    scope.write(out);
+                }
+                out.extend_from_slice(b"::");
+                name.write(out);
+            }
+            Self::Index(recv, args) => {
+                recv.write(out);
+                out.push(b'[');
+                write_list(out, args, ", ");
+                out.push(b']');
+            }
+            Self::Attr(recv, safe_navigation, name) => {
+                recv.write(out);
+                out.extend_from_slice(if *safe_navigation { b"&." } else { b"." });
+                name.write(out);
+            }
+            Self::Nested(items) => {
+                out.push(b'(');
+                write_list(out, items, ", ");
+                out.push(b')');
+            }
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum Expr {
+    Nil,
+    True,
+    False,
+    SelfRef,
+    Int(u64),
+    Float(u32, u16),
+    Numeric(u8),
+    Str(StrKind, Vec<StrPart>),
+    Sym(Ident),
+    Char(u8),
+    Words(bool, Vec<Ident>),
+    Lvar(Ident),
+    Var(Lhs),
+    BackRef(u8),
+    Array(Vec<Expr>),
+    Hash(Vec<(Expr, Expr)>),
+    KwHash(Vec<(Ident, Option<Expr>)>),
+    Range(Option<Box<Expr>>, bool, Option<Box<Expr>>),
+    Binary(Box<Expr>, BinOp, Box<Expr>),
+    Unary(UnOp, Box<Expr>),
+    Ternary(Box<Expr>, Box<Expr>, Box<Expr>),
+    Call(
+        Option<Box<Expr>>,
+        bool,
+        Ident,
+        Option<Vec<CallArg>>,
+        Option<Block>,
+    ),
+    Super(Option<Vec<CallArg>>),
+    Yield(Vec<CallArg>),
+    Lambda(Vec<Arg>, Vec<Stmt>),
+    Defined(Box<Expr>),
+    Paren(Vec<Stmt>),
+    Match(Box<Expr>, bool, Box<Pattern>),
+    Magic(u8),
+}
+
+impl ToSource for Expr {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Nil => out.extend_from_slice(b"nil"),
+            Self::True => out.extend_from_slice(b"true"),
+            Self::False => out.extend_from_slice(b"false"),
+            Self::SelfRef => out.extend_from_slice(b"self"),
+            Self::Int(n) => out.extend_from_slice(n.to_string().as_bytes()),
+            Self::Float(int, frac) => {
+                out.extend_from_slice(format!("{}.{}e-{}", int, frac, frac % 400).as_bytes())
+            }
+            Self::Numeric(idx) => out.extend_from_slice(
+                pick(
+                    &[
+                        "0x1F", "0b101", "0o17", "017", "1_000", "1r", "2i", "1.5ri", "0d9", "1e",
+                        "0x", "1__2", "09", "1.e1",
+                    ],
+                    *idx,
+                )
+                .as_bytes(),
+            ),
+            Self::Str(kind, parts) => kind.write(out, parts),
+            Self::Sym(name) => {
+                out.push(b':');
+                name.write(out);
+            }
+            Self::Char(idx) => {
+                out.push(b'?');
+                out.extend_from_slice(
+                    pick(&["a", "é", "\\n", "\\u{1F600}", "\\C-a", " ", ""], *idx).as_bytes(),
+                );
+            }
+            Self::Words(symbols, words) => {
+                out.extend_from_slice(if *symbols { b"%i[" } else { b"%w[" });
+                write_list(out, words, " ");
+                out.push(b']');
+            }
+            Self::Lvar(name) => name.write(out),
+            Self::Var(lhs) => lhs.write(out),
+            Self::BackRef(n) => out.extend_from_slice(format!("${}", n).as_bytes()),
+            Self::Array(items) => {
+                out.push(b'[');
+                write_list(out, items, ", ");
+                out.push(b']');
+            }
+            Self::Hash(pairs) => {
+                out.push(b'{');
+                for (idx, (key, value)) in pairs.iter().enumerate() {
+                    if idx > 0 {
+                        out.extend_from_slice(b", ");
+                    }
+                    key.write(out);
+                    out.extend_from_slice(b" => ");
+                    value.write(out);
+                }
+                out.push(b'}');
+            }
+            Self::KwHash(pairs) => {
+                out.push(b'{');
+                for (idx, (key, value)) in pairs.iter().enumerate() {
+                    if idx > 0 {
+                        out.extend_from_slice(b", ");
+                    }
+                    key.write(out);
+                    out.push(b':');
+                    if let Some(value) = value {
+                        out.push(b' ');
+                        value.write(out);
+                    }
+                }
+                out.push(b'}');
+            }
+            Self::Range(left, exclusive, right) => {
+                out.push(b'(');
+                if let Some(left) = left {
+                    left.write(out);
+                }
+                out.extend_from_slice(if *exclusive { b"..." } else { b".." });
+                if let Some(right) = right {
+                    right.write(out);
+                }
+                out.push(b')');
+            }
+            Self::Binary(left, op, right) => {
+                left.write(out);
+                out.push(b' ');
+                op.write(out);
+                out.push(b' ');
+                right.write(out);
+            }
+            Self::Unary(op, value) => {
+                op.write(out);
+                value.write(out);
+            }
+            Self::Ternary(cond, if_true, if_false) => {
+                cond.write(out);
+                out.extend_from_slice(b" ? ");
+                if_true.write(out);
+                out.extend_from_slice(b" : ");
+                if_false.write(out);
+            }
+            Self::Call(recv, safe_navigation, name, args, block) => {
+                if let Some(recv) = recv {
+                    recv.write(out);
+                    out.extend_from_slice(if *safe_navigation { b"&." } else { b"." });
+                }
+                name.write(out);
+                if let Some(args) = args {
+                    out.push(b'(');
+                    write_list(out, args, ", ");
+                    out.push(b')');
+                }
+                if let Some(block) = block {
+                    out.push(b' ');
+                    block.write(out);
+                }
+            }
+            Self::Super(args) => {
+                out.extend_from_slice(b"super");
+                if let Some(args) = args {
+                    out.push(b'(');
+                    write_list(out, args, ", ");
+                    out.push(b')');
+                }
+            }
+            Self::Yield(args) => {
+                out.extend_from_slice(b"yield(");
+                write_list(out, args, ", ");
+                out.push(b')');
+            }
+            Self::Lambda(args, body) => {
+                out.extend_from_slice(b"->(");
+                write_list(out, args, ", ");
+                out.extend_from_slice(b") {");
+                write_body(out, body);
+                out.push(b'}');
+            }
+            Self::Defined(value) => {
+                out.extend_from_slice(b"defined?(");
+                value.write(out);
+                out.push(b')');
+            }
+            Self::Paren(stmts) => {
+                out.push(b'(');
+                write_list(out, stmts, "; ");
+                out.push(b')');
+            }
+            Self::Match(value, one_line_in, pattern) => {
+                value.write(out);
+                out.extend_from_slice(if *one_line_in { b" in " } else { b" => " });
+                pattern.write(out);
+            }
+            Self::Magic(idx) => out.extend_from_slice(
+                pick(
+                    &[
+                        "__FILE__",
+                        "__LINE__",
+                        "__ENCODING__",
+                        "__method__",
+                        "_1",
+                        "it",
+                        "...",
+                    ],
+                    *idx,
+                )
+                .as_bytes(),
+            ),
+        }
+    }
+}
+
+/// Call argument
+#[derive(Debug, Arbitrary)]
+enum CallArg {
+    Value(Expr),
+    Splat(Option<Expr>),
+    DoubleSplat(Option<Expr>),
+    BlockPass(Option<Expr>),
+    Label(Ident, Option<Expr>),
+    Forward,
+}
+
+impl ToSource for CallArg {
+    fn write(&self, out: &mut Vec<u8>) {
+        let (prefix, value): (&[u8], _) = match self {
+            Self::Value(value) => (b"", Some(value)),
+            Self::Splat(value) => (b"*", value.as_ref()),
+            Self::DoubleSplat(value) => (b"**", value.as_ref()),
+            Self::BlockPass(value) => (b"&", value.as_ref()),
+            Self::Label(name, value) => {
+                name.write(out);
+                (b": ", value.as_ref())
+            }
+            Self::Forward => (b"...", None),
+        };
+        out.extend_from_slice(prefix);
+        if let Some(value) = value {
+            value.write(out);
+        }
+    }
+}
+
+/// Formal argument
+#[derive(Debug, Arbitrary)]
+enum Arg {
+    Req(Ident),
+    Opt(Ident, Expr),
+    Rest(Option<Ident>),
+    Kw(Ident, Option<Expr>),
+    Kwrest(Option<Ident>),
+    NoKwargs,
+    Block(Option<Ident>),
+    Forward,
+    Mlhs(Vec<Arg>),
+}
+
+impl ToSource for Arg {
+    fn write(&self, out: &mut Vec<u8>) {
+        let write_name = |out: &mut Vec<u8>, prefix: &[u8], name: &Option<Ident>| {
+            out.extend_from_slice(prefix);
+            if let Some(name) = name {
+                name.write(out);
+            }
+        };
+
+        match self {
+            Self::Req(name) => name.write(out),
+            Self::Opt(name, default) => {
+                name.write(out);
+                out.extend_from_slice(b" = ");
+                default.write(out);
+            }
+            Self::Rest(name) => write_name(out, b"*", name),
+            Self::Kw(name, default) => {
+                name.write(out);
+                out.push(b':');
+                if let Some(default) = default {
+                    out.push(b' ');
+                    default.write(out);
+                }
+            }
+            Self::Kwrest(name) => write_name(out, b"**", name),
+            Self::NoKwargs => out.extend_from_slice(b"**nil"),
+            Self::Block(name) => write_name(out, b"&", name),
+            Self::Forward => out.extend_from_slice(b"..."),
+            Self::Mlhs(args) => {
+                out.push(b'(');
+                write_list(out, args, ", ");
+                out.push(b')');
+            }
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+struct Block {
+    braces: bool,
+    args: Option<Vec<Arg>>,
+    body: Vec<Stmt>,
+}
+
+impl ToSource for Block {
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(if self.braces { b"{" } else { b"do" });
+        if let Some(args) = &self.args {
+            out.extend_from_slice(b" |");
+            write_list(out, args, ", ");
+            out.push(b'|');
+        }
+        write_body(out, &self.body);
+        out.extend_from_slice(if self.braces { b"}" } else { b"end" });
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum Pattern {
+    Value(Expr),
+    Var(Ident),
+    Pin(Ident),
+    PinExpr(Expr),
+    Const(Const, Option<Vec<Pattern>>),
+    Array(Vec<Pattern>, Option<Option<Ident>>),
+    Find(Option<Ident>, Vec<Pattern>, Option<Ident>),
+    Hash(Vec<(Ident, Option<Pattern>)>, Option<Option<Ident>>),
+    StrKey(Vec<StrPart>, Box<Pattern>),
+    Alt(Box<Pattern>, Box<Pattern>),
+    Capture(Box<Pattern>, Ident),
+}
+
+impl ToSource for Pattern {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Value(value) => value.write(out),
+            Self::Var(name) => name.write(out),
+            Self::Pin(name) => {
+                out.push(b'^');
+                name.write(out);
+            }
+            Self::PinExpr(value) => {
+                out.extend_from_slice(b"^(");
+                value.write(out);
+                out.push(b')');
+            }
+            Self::Const(name, args) => {
+                name.write(out);
+                if let Some(args) = args {
+                    out.push(b'(');
+                    write_list(out, args, ", ");
+                    out.push(b')');
+                }
+            }
+            Self::Array(items, rest) => {
+                out.push(b'[');
+                write_list(out, items, ", ");
+                if let Some(rest) = rest {
+                    out.extend_from_slice(b", *");
+                    if let Some(rest) = rest {
+                        rest.write(out);
+                    }
+                }
+                out.push(b']');
+            }
+            Self::Find(pre, items, post) => {
+                out.extend_from_slice(b"[*");
+                if let Some(pre) = pre {
+                    pre.write(out);
+                }
+                out.extend_from_slice(b", ");
+                write_list(out, items, ", ");
+                out.extend_from_slice(b", *");
+                if let Some(post) = post {
+                    post.write(out);
+                }
+                out.push(b']');
+            }
+            Self::Hash(pairs, rest) => {
+                out.push(b'{');
+                for (idx, (key, value)) in pairs.iter().enumerate() {
+                    if idx > 0 {
+                        out.extend_from_slice(b", ");
+                    }
+                    key.write(out);
+                    out.push(b':');
+                    if let Some(value) = value {
+                        out.push(b' ');
+                        value.write(out);
+                    }
+                }
+                if let Some(rest) = rest {
+                    out.extend_from_slice(b", **");
+                    match rest {
+                        Some(rest) => rest.write(out),
+                        None => out.extend_from_slice(b"nil"),
+                    }
+                }
+                out.push(b'}');
+            }
+            Self::StrKey(key, value) => {
+                out.extend_from_slice(b"{\"");
+                write_list(out, key, "");
+                out.extend_from_slice(b"\": ");
+                value.write(out);
+                out.push(b'}');
+            }
+            Self::Alt(left, right) => {
+                left.write(out);
+                out.extend_from_slice(b" | ");
+                right.write(out);
+            }
+            Self::Capture(pattern, name) => {
+                pattern.write(out);
+                out.extend_from_slice(b" => ");
+                name.write(out);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum StrKind {
+    Double,
+    Single,
+    Percent(u8, u8),
+    Symbol,
+    XString,
+    Regexp(u8),
+}
+
+impl StrKind {
+    fn write(&self, out: &mut Vec<u8>, parts: &[StrPart]) {
+        let (begin, end) = match self {
+            Self::Double => ("\"".to_owned(), "\"".to_owned()),
+            Self::Single => ("'".to_owned(), "'".to_owned()),
+            Self::Percent(kind, delimiter) => {
+                let kind = pick(&["", "q", "Q", "w", "W", "i", "I", "s", "r", "x"], *kind);
+                let (begin, end) = [("(", ")"), ("[", "]"), ("{", "}"), ("<", ">"), ("|", "|")]
+                    [*delimiter as usize % 5];
+                (format!("%{}{}", kind, begin), end.to_owned())
+            }
+            Self::Symbol => (":\"".to_owned(), "\"".to_owned()),
+            Self::XString => ("`".to_owned(), "`".to_owned()),
+            Self::Regexp(options) => (
+                "/".to_owned(),
+                format!("/{}", pick(&["", "i", "mix", "o", "n", "u", "q"], *options)),
+            ),
+        };
+        out.extend_from_slice(begin.as_bytes());
+        write_list(out, parts, "");
+        out.extend_from_slice(end.as_bytes());
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum StrPart {
+    Text(u8),
+    Escape(u8),
+    Interpolation(Vec<Stmt>),
+    Ivar(Ident),
+    Raw(Vec<u8>),
+}
+
+impl ToSource for StrPart {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Text(idx) => out.extend_from_slice(pick(TEXTS, *idx).as_bytes()),
+            Self::Escape(idx) => out.extend_from_slice(pick(ESCAPES, *idx).as_bytes()),
+            Self::Interpolation(stmts) => {
+                out.extend_from_slice(b"#{");
+                write_list(out, stmts, "; ");
+                out.push(b'}');
+            }
+            Self::Ivar(name) => {
+                out.extend_from_slice(b"#@");
+                name.write(out);
+            }
+            Self::Raw(bytes) => out.extend_from_slice(bytes),
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+struct Heredoc {
+    kind: u8,
+    lines: Vec<(u8, Vec<StrPart>)>,
+    terminated: bool,
+}
+
+impl ToSource for Heredoc {
+    fn write(&self, out: &mut Vec<u8>) {
+        let (begin, raw) = [
+            ("<<EOS", false),
+            ("<<-EOS", false),
+            ("<<~EOS", false),
+            ("<<~'EOS'", true),
+            ("<<~`EOS`", false),
+            ("<<-\"EOS\"", false),
+        ][self.kind as usize % 6];
+        out.extend_from_slice(begin.as_bytes());
+        out.push(b'\n');
+        for (indent, parts) in &self.lines {
+            let indent_char = if indent % 2 == 0 { b' ' } else { b'\t' };
+            for _ in 0..(indent % 5) {
+                out.push(indent_char);
+            }
+            if raw {
+                out.push(b'#');
+            }
+            write_list(out, parts, "");
+            out.push(b'\n');
+        }
+        if self.terminated {
+            out.extend_from_slice(b"  EOS\n");
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Pow,
+    Eq,
+    NotEq,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    Cmp,
+    And,
+    Or,
+    KwAnd,
+    KwOr,
+    BitAnd,
+    BitOr,
+    Xor,
+    Shl,
+    Shr,
+    Match,
+    NotMatch,
+    CaseEq,
+}
+
+impl ToSource for BinOp {
+    fn write(&self, out: &mut Vec<u8>) {
+        let op: &[u8] = match self {
+            Self::Add => b"+",
+            Self::Sub => b"-",
+            Self::Mul => b"*",
+            Self::Div => b"/",
+            Self::Mod => b"%",
+            Self::Pow => b"**",
+            Self::Eq => b"==",
+            Self::NotEq => b"!=",
+            Self::Lt => b"<",
+            Self::Gt => b">",
+            Self::Le => b"<=",
+            Self::Ge => b">=",
+            Self::Cmp => b"<=>",
+            Self::And => b"&&",
+            Self::Or => b"||",
+            Self::KwAnd => b"and",
+            Self::KwOr => b"or",
+            Self::BitAnd => b"&",
+            Self::BitOr => b"|",
+            Self::Xor => b"^",
+            Self::Shl => b"<<",
+            Self::Shr => b">>",
+            Self::Match => b"=~",
+            Self::NotMatch => b"!~",
+            Self::CaseEq => b"===",
+        };
+        out.extend_from_slice(op)
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum UnOp {
+    Not,
+    KwNot,
+    Minus,
+    Plus,
+    Tilde,
+    Splat,
+}
+
+impl ToSource for UnOp {
+    fn write(&self, out: &mut Vec<u8>) {
+        let op: &[u8] = match self {
+            Self::Not => b"!",
+            Self::KwNot => b"not ",
+            Self::Minus => b"-",
+            Self::Plus => b"+",
+            Self::Tilde => b"~",
+            Self::Splat => b"*",
+        };
+        out.extend_from_slice(op)
+    }
+}
@@ -0,0 +1,33 @@
+mod generator;
+pub use generator::Program;
+
+use lib_ruby_parser::{Parser, ParserOptions};
+
+/// Parses given source and renders everything the parser has returned,
+/// panics if any of the returned locations is outside of the input
+pub fn parse(source: &[u8]) {
+    let options = ParserOptions {
+        buffer_name: "(fuzz)".to_owned(),
+        ..Default::default()
+    };
+    let result = Parser::new(source, options).do_parse();
+    let len = result.input.len();
+
+    for token in result.tokens.iter() {
+        assert!(
+            token.loc.begin <= token.loc.end && token.loc.end <= len,
+            "token {:?} is outside of the input ({} bytes)",
+            token,
+            len
+        );
+    }
+    for diagnostic in result.diagnostics.iter() {
+        diagnostic.render(&result.input);
+    }
+    for comment in result.comments.iter() {
+        comment.location.source(&result.input);
+    }
+    if let Some(ast) = result.ast {
+        ast.expression().source(&result.input);
+    }
+}
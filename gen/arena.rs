@@ -0,0 +1,154 @@
+extern crate lib_ruby_parser_nodes;
+
+use lib_ruby_parser_nodes::{Field, FieldType, Node};
+
+fn map_field(field_type: &FieldType) -> String {
+    match field_type {
+        FieldType::Node => "NodeId",
+        FieldType::Nodes => "Vec<NodeId>",
+        FieldType::MaybeNode => "Option<NodeId>",
+        FieldType::Range => "Range",
+        FieldType::MaybeRange => "Option<Range>",
+        FieldType::Str => "Symbol",
+        FieldType::MaybeStr => "Option<Symbol>",
+        FieldType::Chars => "Vec<char>",
+        FieldType::StringValue => "StringValue",
+        FieldType::U8 => "u8",
+        FieldType::Usize => "usize",
+        FieldType::RawString => "String",
+        FieldType::RegexOptions => "Option<NodeId>",
+    }
+    .to_owned()
+}
+
+const FIELD_PREFIX: &str = "            ";
+
+fn from_node_field_code(field: &Field) -> String {
+    let value = match &field.field_type {
+        FieldType::Node => format!("arena.alloc(&node.{})", field.field_name),
+        FieldType::Nodes => format!("arena.alloc_all(&node.{})", field.field_name),
+        FieldType::MaybeNode | FieldType::RegexOptions => {
+            format!("arena.maybe_alloc(&node.{})", field.field_name)
+        }
+        FieldType::Str => format!("arena.intern(&node.{})", field.field_name),
+        FieldType::MaybeStr => format!("arena.maybe_intern(&node.{})", field.field_name),
+        _ => format!("node.{}.clone()", field.field_name),
+    };
+    format!("{}{}: {},", FIELD_PREFIX, field.field_name, value)
+}
+
+fn to_node_field_code(field: &Field) -> String {
+    let value = match &field.field_type {
+        FieldType::Node => format!("arena.to_node(self.{})?", field.field_name),
+        FieldType::Nodes => format!("arena.to_nodes(&self.{})?", field.field_name),
+        FieldType::MaybeNode | FieldType::RegexOptions => {
+            format!("arena.maybe_to_node(&self.{})?", field.field_name)
+        }
+        FieldType::Str => format!("arena.resolve(self.{})?.to_owned()", field.field_name),
+        FieldType::MaybeStr => format!("arena.maybe_resolve(&self.{})?", field.field_name),
+        _ => format!("self.{}.clone()", field.field_name),
+    };
+    format!("{}{}: {},", FIELD_PREFIX, field.field_name, value)
+}
+
+fn has_reference_to_node(node: &Node) -> bool {
+    node.fields
+        .iter()
+        .any(|f| f.field_type.has_reference_to_node())
+}
+
+fn has_str(node: &Node) -> bool {
+    node.fields
+        .iter()
+        .any(|f| f.field_type == FieldType::Str || f.field_type == FieldType::MaybeStr)
+}
+
+fn uses(node: &Node) -> Vec<String> {
+    let mut uses = vec![];
+    if has_reference_to_node(node) {
+        uses.push("use crate::arena::{Arena, NodeId};".to_owned());
+    } else {
+        uses.push("use crate::arena::Arena;".to_owned());
+    }
+    uses.push("use crate::nodes;".to_owned());
+    uses.push("use crate::source::Range;".to_owned());
+    if node
+        .fields
+        .iter()
+        .any(|f| f.field_type == FieldType::StringValue)
+    {
+        uses.push("use crate::StringValue;".to_owned());
+    }
+    if has_str(node) {
+        uses.push("use crate::Symbol;".to_owned());
+    }
+    uses
+}
+
+fn prologue(_: &Node) -> String {
+    "".to_owned()
+}
+
+fn epilogue(node: &Node) -> String {
+    let arena_arg = if has_reference_to_node(node) || has_str(node) {
+        "arena"
+    } else {
+        "_arena"
+    };
+
+    format!(
+        "
+impl {struct_name} {{
+    /// Returns location of the full node expression
+    pub fn expression(&self) -> &Range {{
+        &self.expression_l
+    }}
+
+    /// Returns a whitequark/parser -like node name.
+    pub fn str_type(&self) -> &'static str {{
+        \"{str_type}\"
+    }}
+
+    pub(crate) fn from_node(node: &nodes::{struct_name}, {arena_arg}: &mut Arena) -> Self {{
+        Self {{
+{from_node_fields}
+        }}
+    }}
+
+    pub(crate) fn to_node(&self, {arena_arg}: &Arena) -> Option<nodes::{struct_name}> {{
+        Some(nodes::{struct_name} {{
+{to_node_fields}
+        }})
+    }}
+}}
+",
+        struct_name = node.struct_name,
+        str_type = node.str_type,
+        arena_arg = arena_arg,
+        from_node_fields = node
+            .fields
+            .iter()
+            .map(from_node_field_code)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        to_node_fields = node
+            .fields
+            .iter()
+            .map(to_node_field_code)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+pub fn generate_arena_nodes() {
+    let options = lib_ruby_parser_nodes::Options {
+        target_dir: "src/arena/types".to_owned(),
+        map_field: Box::new(map_field),
+        uses: Box::new(uses),
+        prologue: Box::new(prologue),
+        epilogue: Box::new(epilogue),
+    };
+
+    lib_ruby_parser_nodes::generate_nodes(&options).unwrap();
+    lib_ruby_parser_nodes::generate_mod(&options).unwrap();
+}
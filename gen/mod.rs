@@ -3,6 +3,11 @@ mod nodes;
 #[cfg(feature = "lib-ruby-parser-nodes")]
 pub use nodes::generate_nodes;
 
+#[cfg(feature = "lib-ruby-parser-nodes")]
+mod arena;
+#[cfg(feature = "lib-ruby-parser-nodes")]
+pub use arena::generate_arena_nodes;
+
 #[cfg(feature = "rust-bison-skeleton")]
 mod parser_y;
 #[cfg(feature = "rust-bison-skeleton")]
@@ -11,5 +16,8 @@ pub use parser_y::generate_parser_y;
 #[cfg(not(feature = "lib-ruby-parser-nodes"))]
 pub fn generate_nodes() {}
 
+#[cfg(not(feature = "lib-ruby-parser-nodes"))]
+pub fn generate_arena_nodes() {}
+
 #[cfg(not(feature = "rust-bison-skeleton"))]
 pub fn generate_parser_y() {}
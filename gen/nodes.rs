@@ -145,28 +145,42 @@ fn print_field_with_locs(field: &Field) -> Option<String> {
 
 fn uses(node: &Node) -> Vec<String> {
     let mut uses = vec![];
+    uses.push("use crate::nodes::FieldValue;".to_owned());
     uses.push("use crate::nodes::InnerNode;".to_owned());
+    uses.push("use crate::nodes::{StructuralHasher, StructureOptions};".to_owned());
+    if node.fields.iter().any(|f| f.field_type == FieldType::Nodes) {
+        uses.push("use crate::nodes::structurally_eq_nodes;".to_owned());
+    }
+    if node
+        .fields
+        .iter()
+        .any(|f| matches!(f.field_type, FieldType::MaybeNode | FieldType::RegexOptions))
+    {
+        uses.push("use crate::nodes::structurally_eq_maybe_node;".to_owned());
+    }
     if node
         .fields
         .iter()
         .any(|f| !f.field_type.has_reference_to_range())
     {
         uses.push("use crate::nodes::InspectVec;".to_owned());
+        uses.push("#[cfg(feature = \"json\")]\nuse crate::nodes::JsonVec;".to_owned());
     }
     uses.push("use crate::source::Range;".to_owned());
+    uses.push("use crate::Node;".to_owned());
     if node
         .fields
         .iter()
-        .any(|f| f.field_type.has_reference_to_node())
+        .any(|f| f.field_type == FieldType::StringValue)
     {
-        uses.push("use crate::Node;".to_owned());
+        uses.push("use crate::StringValue;".to_owned());
     }
     if node
         .fields
         .iter()
-        .any(|f| f.field_type == FieldType::StringValue)
+        .any(|f| matches!(f.field_type, FieldType::U8 | FieldType::Usize))
     {
-        uses.push("use crate::StringValue;".to_owned());
+        uses.push("use std::hash::Hasher;".to_owned());
     }
     uses
 }
@@ -213,6 +227,288 @@ fn print_with_locs_fn_declaration(node: &Node) -> String {
     )
 }
 
+fn children_fn_declaration(node: &Node) -> String {
+    let mut stmts = vec![];
+    for field in node.fields.iter() {
+        let stmt = match &field.field_type {
+            FieldType::Node => format!("{}result.push(&self.{});", FIELD_PREFIX, field.field_name),
+            FieldType::Nodes | FieldType::MaybeNode | FieldType::RegexOptions => format!(
+                "{}result.extend(self.{}.iter());",
+                FIELD_PREFIX, field.field_name
+            ),
+            _ => continue,
+        };
+        stmts.push(stmt);
+    }
+    if stmts.is_empty() {
+        "fn children(&self) -> Vec<&Node> {
+        vec![]
+    }"
+        .to_owned()
+    } else {
+        format!(
+            "fn children(&self) -> Vec<&Node> {{
+        let mut result = vec![];
+{}
+        result
+    }}",
+            stmts.join("\n")
+        )
+    }
+}
+
+fn field_value_code(field: &Field) -> String {
+    let value = match &field.field_type {
+        FieldType::Node => format!("Node(&self.{})", field.field_name),
+        FieldType::Nodes => format!("Nodes(&self.{})", field.field_name),
+        FieldType::MaybeNode | FieldType::RegexOptions => {
+            format!("MaybeNode(self.{}.as_ref())", field.field_name)
+        }
+        FieldType::Range => format!("Range(&self.{})", field.field_name),
+        FieldType::MaybeRange => format!("MaybeRange(self.{}.as_ref())", field.field_name),
+        FieldType::Str | FieldType::RawString => format!("Str(&self.{})", field.field_name),
+        FieldType::MaybeStr => format!("MaybeStr(self.{}.as_deref())", field.field_name),
+        FieldType::Chars => format!("Chars(&self.{})", field.field_name),
+        FieldType::StringValue => format!("StringValue(&self.{})", field.field_name),
+        FieldType::U8 => format!("U8(self.{})", field.field_name),
+        FieldType::Usize => format!("Usize(self.{})", field.field_name),
+    };
+    format!(
+        "{offset}(\"{name}\", FieldValue::{value}),",
+        offset = "            ",
+        name = field.field_name,
+        value = value
+    )
+}
+
+fn fields_fn_declaration(node: &Node) -> String {
+    let fields = node.fields.iter().map(field_value_code).collect::<Vec<_>>();
+
+    format!(
+        "fn fields(&self) -> Vec<(&'static str, FieldValue<'_>)> {{
+        vec![
+{}
+        ]
+    }}",
+        fields.join("\n")
+    )
+}
+
+fn map_ranges_field_code(field: &Field) -> Option<String> {
+    let code = match &field.field_type {
+        FieldType::Node => format!(
+            "{offset}self.{field_name}.inner_mut().map_ranges(f);",
+            offset = FIELD_PREFIX,
+            field_name = field.field_name
+        ),
+        FieldType::Nodes => format!(
+            "{offset}for node in self.{field_name}.iter_mut() {{
+{offset}    node.inner_mut().map_ranges(f);
+{offset}}}",
+            offset = FIELD_PREFIX,
+            field_name = field.field_name
+        ),
+        FieldType::MaybeNode | FieldType::RegexOptions => format!(
+            "{offset}if let Some(node) = &mut self.{field_name} {{
+{offset}    node.inner_mut().map_ranges(f);
+{offset}}}",
+            offset = FIELD_PREFIX,
+            field_name = field.field_name
+        ),
+        FieldType::Range => format!(
+            "{offset}f(&mut self.{field_name});",
+            offset = FIELD_PREFIX,
+            field_name = field.field_name
+        ),
+        FieldType::MaybeRange => format!(
+            "{offset}if let Some(range) = &mut self.{field_name} {{
+{offset}    f(range);
+{offset}}}",
+            offset = FIELD_PREFIX,
+            field_name = field.field_name
+        ),
+        _ => return None,
+    };
+    Some(code)
+}
+
+fn map_ranges_fn_declaration(node: &Node) -> String {
+    let stmts = node
+        .fields
+        .iter()
+        .filter_map(map_ranges_field_code)
+        .collect::<Vec<_>>();
+
+    format!(
+        "fn map_ranges(&mut self, f: &mut dyn FnMut(&mut Range)) {{
+{}
+    }}",
+        stmts.join("\n")
+    )
+}
+
+/// Values of literals (`int`, `str`, `sym`, ...) that can be ignored in structural comparison
+fn is_literal_value(field: &Field) -> bool {
+    match &field.field_type {
+        FieldType::StringValue => true,
+        FieldType::Str | FieldType::RawString => field.field_name == "value",
+        _ => false,
+    }
+}
+
+/// Returns true if structural comparison of the node depends on `StructureOptions`
+fn uses_structure_options(node: &Node) -> bool {
+    node.fields.iter().any(|field| {
+        is_literal_value(field)
+            || matches!(
+                field.field_type,
+                FieldType::Node | FieldType::Nodes | FieldType::MaybeNode | FieldType::RegexOptions
+            )
+    })
+}
+
+fn structurally_eq_field_code(field: &Field) -> Option<String> {
+    let code = match &field.field_type {
+        FieldType::Node => format!(
+            "self.{name}.structurally_eq_with(&other.{name}, options)",
+            name = field.field_name
+        ),
+        FieldType::Nodes => format!(
+            "structurally_eq_nodes(&self.{name}, &other.{name}, options)",
+            name = field.field_name
+        ),
+        FieldType::MaybeNode | FieldType::RegexOptions => format!(
+            "structurally_eq_maybe_node(&self.{name}, &other.{name}, options)",
+            name = field.field_name
+        ),
+        FieldType::Range | FieldType::MaybeRange => return None,
+        _ if is_literal_value(field) => format!(
+            "(options.ignore_literal_values || self.{name} == other.{name})",
+            name = field.field_name
+        ),
+        _ => format!("self.{name} == other.{name}", name = field.field_name),
+    };
+    Some(code)
+}
+
+fn structurally_eq_fn_declaration(node: &Node) -> String {
+    let mut conditions = node
+        .fields
+        .iter()
+        .filter_map(structurally_eq_field_code)
+        .collect::<Vec<_>>();
+    if conditions.len() == 1 {
+        // a single literal condition doesn't need parens
+        let condition = &conditions[0];
+        if let Some(unwrapped) = condition
+            .strip_prefix('(')
+            .and_then(|c| c.strip_suffix(')'))
+        {
+            conditions[0] = unwrapped.to_owned();
+        }
+    }
+    let uses_options = uses_structure_options(node);
+
+    if conditions.is_empty() {
+        format!(
+            "fn structurally_eq(&self, other: &Node, _options: &StructureOptions) -> bool {{
+        matches!(other, Node::{struct_name}(_))
+    }}",
+            struct_name = node.struct_name
+        )
+    } else {
+        format!(
+            "fn structurally_eq(&self, other: &Node, {options}: &StructureOptions) -> bool {{
+        if let Node::{struct_name}(other) = other {{
+            {conditions}
+        }} else {{
+            false
+        }}
+    }}",
+            options = if uses_options { "options" } else { "_options" },
+            struct_name = node.struct_name,
+            conditions = conditions.join("\n                && ")
+        )
+    }
+}
+
+fn hash_structure_field_code(field: &Field) -> Option<String> {
+    let code = match &field.field_type {
+        FieldType::Node => format!("state.write_node(&self.{}, options);", field.field_name),
+        FieldType::Nodes => format!("state.write_nodes(&self.{}, options);", field.field_name),
+        FieldType::MaybeNode | FieldType::RegexOptions => format!(
+            "state.write_maybe_node(&self.{}, options);",
+            field.field_name
+        ),
+        FieldType::Range | FieldType::MaybeRange => return None,
+        FieldType::Str | FieldType::RawString => {
+            format!("state.write_string(&self.{});", field.field_name)
+        }
+        FieldType::MaybeStr => format!("state.write_maybe_string(&self.{});", field.field_name),
+        FieldType::Chars => format!("state.write_chars(&self.{});", field.field_name),
+        FieldType::StringValue => {
+            format!("state.write_bytes(self.{}.as_bytes());", field.field_name)
+        }
+        FieldType::U8 => format!("state.write_u8(self.{});", field.field_name),
+        FieldType::Usize => format!("state.write_usize(self.{});", field.field_name),
+    };
+    let code = if is_literal_value(field) {
+        format!(
+            "{offset}if !options.ignore_literal_values {{
+{offset}    {code}
+{offset}}}",
+            offset = FIELD_PREFIX,
+            code = code
+        )
+    } else {
+        format!("{}{}", FIELD_PREFIX, code)
+    };
+    Some(code)
+}
+
+fn hash_structure_fn_declaration(node: &Node) -> String {
+    let mut stmts = vec![format!(
+        "{}state.write_string(\"{}\");",
+        FIELD_PREFIX, node.str_type
+    )];
+    stmts.extend(node.fields.iter().filter_map(hash_structure_field_code));
+    let uses_options = uses_structure_options(node);
+
+    format!(
+        "fn hash_structure(&self, state: &mut StructuralHasher, {options}: &StructureOptions) {{
+{stmts}
+    }}",
+        options = if uses_options { "options" } else { "_options" },
+        stmts = stmts.join("\n")
+    )
+}
+
+fn json_children_fn_declaration(node: &Node) -> String {
+    let mut result = vec![];
+    for field in node.fields.iter() {
+        if let Some(code) = print_field_code(field) {
+            result.push(code)
+        }
+    }
+    if result.is_empty() {
+        "#[cfg(feature = \"json\")]
+    fn json_children(&self) -> Vec<serde_json::Value> {
+        vec![]
+    }"
+        .to_owned()
+    } else {
+        format!(
+            "#[cfg(feature = \"json\")]
+    fn json_children(&self) -> Vec<serde_json::Value> {{
+        let mut result = JsonVec::new();
+{}
+        result.values()
+    }}",
+            result.join("\n")
+        )
+    }
+}
+
 fn prologue(_: &Node) -> String {
     "".to_owned()
 }
@@ -232,12 +528,30 @@ impl InnerNode for {struct_name} {{
     }}
 
     {print_with_locs}
+
+    {children}
+
+    {fields}
+
+    {structurally_eq}
+
+    {hash_structure}
+
+    {json_children}
+
+    {map_ranges}
 }}
 ",
         struct_name = node.struct_name,
         inspected_children = inspected_children_fn_declaration(node),
         str_type = node.str_type,
-        print_with_locs = print_with_locs_fn_declaration(node)
+        print_with_locs = print_with_locs_fn_declaration(node),
+        children = children_fn_declaration(node),
+        fields = fields_fn_declaration(node),
+        structurally_eq = structurally_eq_fn_declaration(node),
+        hash_structure = hash_structure_fn_declaration(node),
+        json_children = json_children_fn_declaration(node),
+        map_ranges = map_ranges_fn_declaration(node)
     )
 }
 
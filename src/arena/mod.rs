@@ -0,0 +1,19 @@
+//! Arena-backed representation of the AST.
+//!
+//! Use `Parser::do_parse_into_arena` or convert an existing `ParserResult`
+//! using `From`/`Into` to get it.
+//! The parser itself always builds an owned tree of `Node`s,
+//! the arena is a copy of it where nodes can be referenced by index.
+//! It's a representation for consumers of the AST: building it costs
+//! more than the owned tree, and `Builder` doesn't allocate into it.
+
+mod storage;
+pub use storage::{Arena, NodeId};
+
+mod node;
+pub use node::ArenaNode;
+
+mod result;
+pub use result::ArenaParserResult;
+
+pub mod types;
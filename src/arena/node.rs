@@ -0,0 +1,674 @@
+use crate::arena::types::*;
+use crate::arena::Arena;
+use crate::source::Range;
+use crate::Node;
+
+/// Arena-backed counterpart of `Node`.
+///
+/// Child nodes are stored as `NodeId`s pointing into the same `Arena`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArenaNode {
+    Alias(Alias),
+    And(And),
+    AndAsgn(AndAsgn),
+    Arg(Arg),
+    Args(Args),
+    Array(Array),
+    ArrayPattern(ArrayPattern),
+    ArrayPatternWithTail(ArrayPatternWithTail),
+    BackRef(BackRef),
+    Begin(Begin),
+    Block(Block),
+    Blockarg(Blockarg),
+    BlockPass(BlockPass),
+    Break(Break),
+    Case(Case),
+    CaseMatch(CaseMatch),
+    Casgn(Casgn),
+    Cbase(Cbase),
+    Class(Class),
+    Complex(Complex),
+    Const(Const),
+    ConstPattern(ConstPattern),
+    CSend(CSend),
+    Cvar(Cvar),
+    Cvasgn(Cvasgn),
+    Def(Def),
+    Defined(Defined),
+    Defs(Defs),
+    Dstr(Dstr),
+    Dsym(Dsym),
+    EFlipFlop(EFlipFlop),
+    EmptyElse(EmptyElse),
+    Encoding(Encoding),
+    Ensure(Ensure),
+    Erange(Erange),
+    False(False),
+    File(File),
+    FindPattern(FindPattern),
+    Float(Float),
+    For(For),
+    ForwardArg(ForwardArg),
+    ForwardedArgs(ForwardedArgs),
+    Gvar(Gvar),
+    Gvasgn(Gvasgn),
+    Hash(Hash),
+    HashPattern(HashPattern),
+    Heredoc(Heredoc),
+    If(If),
+    IfGuard(IfGuard),
+    IFlipFlop(IFlipFlop),
+    IfMod(IfMod),
+    IfTernary(IfTernary),
+    Index(Index),
+    IndexAsgn(IndexAsgn),
+    InMatch(InMatch),
+    InPattern(InPattern),
+    Int(Int),
+    Irange(Irange),
+    Ivar(Ivar),
+    Ivasgn(Ivasgn),
+    Kwarg(Kwarg),
+    KwBegin(KwBegin),
+    Kwnilarg(Kwnilarg),
+    Kwoptarg(Kwoptarg),
+    Kwrestarg(Kwrestarg),
+    Kwsplat(Kwsplat),
+    Lambda(Lambda),
+    Line(Line),
+    Lvar(Lvar),
+    Lvasgn(Lvasgn),
+    Masgn(Masgn),
+    MatchAlt(MatchAlt),
+    MatchAs(MatchAs),
+    MatchCurrentLine(MatchCurrentLine),
+    MatchNilPattern(MatchNilPattern),
+    MatchRest(MatchRest),
+    MatchVar(MatchVar),
+    MatchWithLvasgn(MatchWithLvasgn),
+    Mlhs(Mlhs),
+    Module(Module),
+    Next(Next),
+    Nil(Nil),
+    NthRef(NthRef),
+    Numblock(Numblock),
+    OpAsgn(OpAsgn),
+    Optarg(Optarg),
+    Or(Or),
+    OrAsgn(OrAsgn),
+    Pair(Pair),
+    Pin(Pin),
+    Postexe(Postexe),
+    Preexe(Preexe),
+    Procarg0(Procarg0),
+    Rational(Rational),
+    Redo(Redo),
+    Regexp(Regexp),
+    RegOpt(RegOpt),
+    Rescue(Rescue),
+    RescueBody(RescueBody),
+    Restarg(Restarg),
+    Retry(Retry),
+    Return(Return),
+    SClass(SClass),
+    Self_(Self_),
+    Send(Send),
+    Shadowarg(Shadowarg),
+    Splat(Splat),
+    Str(Str),
+    Super(Super),
+    Sym(Sym),
+    True(True),
+    Undef(Undef),
+    UnlessGuard(UnlessGuard),
+    Until(Until),
+    UntilPost(UntilPost),
+    When(When),
+    While(While),
+    WhilePost(WhilePost),
+    XHeredoc(XHeredoc),
+    Xstr(Xstr),
+    Yield(Yield),
+    ZSuper(ZSuper),
+}
+
+impl ArenaNode {
+    /// Returns location of the full node expression
+    pub fn expression(&self) -> &Range {
+        match self {
+            ArenaNode::Alias(inner) => inner.expression(),
+            ArenaNode::And(inner) => inner.expression(),
+            ArenaNode::AndAsgn(inner) => inner.expression(),
+            ArenaNode::Arg(inner) => inner.expression(),
+            ArenaNode::Args(inner) => inner.expression(),
+            ArenaNode::Array(inner) => inner.expression(),
+            ArenaNode::ArrayPattern(inner) => inner.expression(),
+            ArenaNode::ArrayPatternWithTail(inner) => inner.expression(),
+            ArenaNode::BackRef(inner) => inner.expression(),
+            ArenaNode::Begin(inner) => inner.expression(),
+            ArenaNode::Block(inner) => inner.expression(),
+            ArenaNode::Blockarg(inner) => inner.expression(),
+            ArenaNode::BlockPass(inner) => inner.expression(),
+            ArenaNode::Break(inner) => inner.expression(),
+            ArenaNode::Case(inner) => inner.expression(),
+            ArenaNode::CaseMatch(inner) => inner.expression(),
+            ArenaNode::Casgn(inner) => inner.expression(),
+            ArenaNode::Cbase(inner) => inner.expression(),
+            ArenaNode::Class(inner) => inner.expression(),
+            ArenaNode::Complex(inner) => inner.expression(),
+            ArenaNode::Const(inner) => inner.expression(),
+            ArenaNode::ConstPattern(inner) => inner.expression(),
+            ArenaNode::CSend(inner) => inner.expression(),
+            ArenaNode::Cvar(inner) => inner.expression(),
+            ArenaNode::Cvasgn(inner) => inner.expression(),
+            ArenaNode::Def(inner) => inner.expression(),
+            ArenaNode::Defined(inner) => inner.expression(),
+            ArenaNode::Defs(inner) => inner.expression(),
+            ArenaNode::Dstr(inner) => inner.expression(),
+            ArenaNode::Dsym(inner) => inner.expression(),
+            ArenaNode::EFlipFlop(inner) => inner.expression(),
+            ArenaNode::EmptyElse(inner) => inner.expression(),
+            ArenaNode::Encoding(inner) => inner.expression(),
+            ArenaNode::Ensure(inner) => inner.expression(),
+            ArenaNode::Erange(inner) => inner.expression(),
+            ArenaNode::False(inner) => inner.expression(),
+            ArenaNode::File(inner) => inner.expression(),
+            ArenaNode::FindPattern(inner) => inner.expression(),
+            ArenaNode::Float(inner) => inner.expression(),
+            ArenaNode::For(inner) => inner.expression(),
+            ArenaNode::ForwardArg(inner) => inner.expression(),
+            ArenaNode::ForwardedArgs(inner) => inner.expression(),
+            ArenaNode::Gvar(inner) => inner.expression(),
+            ArenaNode::Gvasgn(inner) => inner.expression(),
+            ArenaNode::Hash(inner) => inner.expression(),
+            ArenaNode::HashPattern(inner) => inner.expression(),
+            ArenaNode::Heredoc(inner) => inner.expression(),
+            ArenaNode::If(inner) => inner.expression(),
+            ArenaNode::IfGuard(inner) => inner.expression(),
+            ArenaNode::IFlipFlop(inner) => inner.expression(),
+            ArenaNode::IfMod(inner) => inner.expression(),
+            ArenaNode::IfTernary(inner) => inner.expression(),
+            ArenaNode::Index(inner) => inner.expression(),
+            ArenaNode::IndexAsgn(inner) => inner.expression(),
+            ArenaNode::InMatch(inner) => inner.expression(),
+            ArenaNode::InPattern(inner) => inner.expression(),
+            ArenaNode::Int(inner) => inner.expression(),
+            ArenaNode::Irange(inner) => inner.expression(),
+            ArenaNode::Ivar(inner) => inner.expression(),
+            ArenaNode::Ivasgn(inner) => inner.expression(),
+            ArenaNode::Kwarg(inner) => inner.expression(),
+            ArenaNode::KwBegin(inner) => inner.expression(),
+            ArenaNode::Kwnilarg(inner) => inner.expression(),
+            ArenaNode::Kwoptarg(inner) => inner.expression(),
+            ArenaNode::Kwrestarg(inner) => inner.expression(),
+            ArenaNode::Kwsplat(inner) => inner.expression(),
+            ArenaNode::Lambda(inner) => inner.expression(),
+            ArenaNode::Line(inner) => inner.expression(),
+            ArenaNode::Lvar(inner) => inner.expression(),
+            ArenaNode::Lvasgn(inner) => inner.expression(),
+            ArenaNode::Masgn(inner) => inner.expression(),
+            ArenaNode::MatchAlt(inner) => inner.expression(),
+            ArenaNode::MatchAs(inner) => inner.expression(),
+            ArenaNode::MatchCurrentLine(inner) => inner.expression(),
+            ArenaNode::MatchNilPattern(inner) => inner.expression(),
+            ArenaNode::MatchRest(inner) => inner.expression(),
+            ArenaNode::MatchVar(inner) => inner.expression(),
+            ArenaNode::MatchWithLvasgn(inner) => inner.expression(),
+            ArenaNode::Mlhs(inner) => inner.expression(),
+            ArenaNode::Module(inner) => inner.expression(),
+            ArenaNode::Next(inner) => inner.expression(),
+            ArenaNode::Nil(inner) => inner.expression(),
+            ArenaNode::NthRef(inner) => inner.expression(),
+            ArenaNode::Numblock(inner) => inner.expression(),
+            ArenaNode::OpAsgn(inner) => inner.expression(),
+            ArenaNode::Optarg(inner) => inner.expression(),
+            ArenaNode::Or(inner) => inner.expression(),
+            ArenaNode::OrAsgn(inner) => inner.expression(),
+            ArenaNode::Pair(inner) => inner.expression(),
+            ArenaNode::Pin(inner) => inner.expression(),
+            ArenaNode::Postexe(inner) => inner.expression(),
+            ArenaNode::Preexe(inner) => inner.expression(),
+            ArenaNode::Procarg0(inner) => inner.expression(),
+            ArenaNode::Rational(inner) => inner.expression(),
+            ArenaNode::Redo(inner) => inner.expression(),
+            ArenaNode::Regexp(inner) => inner.expression(),
+            ArenaNode::RegOpt(inner) => inner.expression(),
+            ArenaNode::Rescue(inner) => inner.expression(),
+            ArenaNode::RescueBody(inner) => inner.expression(),
+            ArenaNode::Restarg(inner) => inner.expression(),
+            ArenaNode::Retry(inner) => inner.expression(),
+            ArenaNode::Return(inner) => inner.expression(),
+            ArenaNode::SClass(inner) => inner.expression(),
+            ArenaNode::Self_(inner) => inner.expression(),
+            ArenaNode::Send(inner) => inner.expression(),
+            ArenaNode::Shadowarg(inner) => inner.expression(),
+            ArenaNode::Splat(inner) => inner.expression(),
+            ArenaNode::Str(inner) => inner.expression(),
+            ArenaNode::Super(inner) => inner.expression(),
+            ArenaNode::Sym(inner) => inner.expression(),
+            ArenaNode::True(inner) => inner.expression(),
+            ArenaNode::Undef(inner) => inner.expression(),
+            ArenaNode::UnlessGuard(inner) => inner.expression(),
+            ArenaNode::Until(inner) => inner.expression(),
+            ArenaNode::UntilPost(inner) => inner.expression(),
+            ArenaNode::When(inner) => inner.expression(),
+            ArenaNode::While(inner) => inner.expression(),
+            ArenaNode::WhilePost(inner) => inner.expression(),
+            ArenaNode::XHeredoc(inner) => inner.expression(),
+            ArenaNode::Xstr(inner) => inner.expression(),
+            ArenaNode::Yield(inner) => inner.expression(),
+            ArenaNode::ZSuper(inner) => inner.expression(),
+        }
+    }
+
+    /// Returns a whitequark/parser -like node name.
+    pub fn str_type(&self) -> &'static str {
+        match self {
+            ArenaNode::Alias(inner) => inner.str_type(),
+            ArenaNode::And(inner) => inner.str_type(),
+            ArenaNode::AndAsgn(inner) => inner.str_type(),
+            ArenaNode::Arg(inner) => inner.str_type(),
+            ArenaNode::Args(inner) => inner.str_type(),
+            ArenaNode::Array(inner) => inner.str_type(),
+            ArenaNode::ArrayPattern(inner) => inner.str_type(),
+            ArenaNode::ArrayPatternWithTail(inner) => inner.str_type(),
+            ArenaNode::BackRef(inner) => inner.str_type(),
+            ArenaNode::Begin(inner) => inner.str_type(),
+            ArenaNode::Block(inner) => inner.str_type(),
+            ArenaNode::Blockarg(inner) => inner.str_type(),
+            ArenaNode::BlockPass(inner) => inner.str_type(),
+            ArenaNode::Break(inner) => inner.str_type(),
+            ArenaNode::Case(inner) => inner.str_type(),
+            ArenaNode::CaseMatch(inner) => inner.str_type(),
+            ArenaNode::Casgn(inner) => inner.str_type(),
+            ArenaNode::Cbase(inner) => inner.str_type(),
+            ArenaNode::Class(inner) => inner.str_type(),
+            ArenaNode::Complex(inner) => inner.str_type(),
+            ArenaNode::Const(inner) => inner.str_type(),
+            ArenaNode::ConstPattern(inner) => inner.str_type(),
+            ArenaNode::CSend(inner) => inner.str_type(),
+            ArenaNode::Cvar(inner) => inner.str_type(),
+            ArenaNode::Cvasgn(inner) => inner.str_type(),
+            ArenaNode::Def(inner) => inner.str_type(),
+            ArenaNode::Defined(inner) => inner.str_type(),
+            ArenaNode::Defs(inner) => inner.str_type(),
+            ArenaNode::Dstr(inner) => inner.str_type(),
+            ArenaNode::Dsym(inner) => inner.str_type(),
+            ArenaNode::EFlipFlop(inner) => inner.str_type(),
+            ArenaNode::EmptyElse(inner) => inner.str_type(),
+            ArenaNode::Encoding(inner) => inner.str_type(),
+            ArenaNode::Ensure(inner) => inner.str_type(),
+            ArenaNode::Erange(inner) => inner.str_type(),
+            ArenaNode::False(inner) => inner.str_type(),
+            ArenaNode::File(inner) => inner.str_type(),
+            ArenaNode::FindPattern(inner) => inner.str_type(),
+            ArenaNode::Float(inner) => inner.str_type(),
+            ArenaNode::For(inner) => inner.str_type(),
+            ArenaNode::ForwardArg(inner) => inner.str_type(),
+            ArenaNode::ForwardedArgs(inner) => inner.str_type(),
+            ArenaNode::Gvar(inner) => inner.str_type(),
+            ArenaNode::Gvasgn(inner) => inner.str_type(),
+            ArenaNode::Hash(inner) => inner.str_type(),
+            ArenaNode::HashPattern(inner) => inner.str_type(),
+            ArenaNode::Heredoc(inner) => inner.str_type(),
+            ArenaNode::If(inner) => inner.str_type(),
+            ArenaNode::IfGuard(inner) => inner.str_type(),
+            ArenaNode::IFlipFlop(inner) => inner.str_type(),
+            ArenaNode::IfMod(inner) => inner.str_type(),
+            ArenaNode::IfTernary(inner) => inner.str_type(),
+            ArenaNode::Index(inner) => inner.str_type(),
+            ArenaNode::IndexAsgn(inner) => inner.str_type(),
+            ArenaNode::InMatch(inner) => inner.str_type(),
+            ArenaNode::InPattern(inner) => inner.str_type(),
+            ArenaNode::Int(inner) => inner.str_type(),
+            ArenaNode::Irange(inner) => inner.str_type(),
+            ArenaNode::Ivar(inner) => inner.str_type(),
+            ArenaNode::Ivasgn(inner) => inner.str_type(),
+            ArenaNode::Kwarg(inner) => inner.str_type(),
+            ArenaNode::KwBegin(inner) => inner.str_type(),
+            ArenaNode::Kwnilarg(inner) => inner.str_type(),
+            ArenaNode::Kwoptarg(inner) => inner.str_type(),
+            ArenaNode::Kwrestarg(inner) => inner.str_type(),
+            ArenaNode::Kwsplat(inner) => inner.str_type(),
+            ArenaNode::Lambda(inner) => inner.str_type(),
+            ArenaNode::Line(inner) => inner.str_type(),
+            ArenaNode::Lvar(inner) => inner.str_type(),
+            ArenaNode::Lvasgn(inner) => inner.str_type(),
+            ArenaNode::Masgn(inner) => inner.str_type(),
+            ArenaNode::MatchAlt(inner) => inner.str_type(),
+            ArenaNode::MatchAs(inner) => inner.str_type(),
+            ArenaNode::MatchCurrentLine(inner) => inner.str_type(),
+            ArenaNode::MatchNilPattern(inner) => inner.str_type(),
+            ArenaNode::MatchRest(inner) => inner.str_type(),
+            ArenaNode::MatchVar(inner) => inner.str_type(),
+            ArenaNode::MatchWithLvasgn(inner) => inner.str_type(),
+            ArenaNode::Mlhs(inner) => inner.str_type(),
+            ArenaNode::Module(inner) => inner.str_type(),
+            ArenaNode::Next(inner) => inner.str_type(),
+            ArenaNode::Nil(inner) => inner.str_type(),
+            ArenaNode::NthRef(inner) => inner.str_type(),
+            ArenaNode::Numblock(inner) => inner.str_type(),
+            ArenaNode::OpAsgn(inner) => inner.str_type(),
+            ArenaNode::Optarg(inner) => inner.str_type(),
+            ArenaNode::Or(inner) => inner.str_type(),
+            ArenaNode::OrAsgn(inner) => inner.str_type(),
+            ArenaNode::Pair(inner) => inner.str_type(),
+            ArenaNode::Pin(inner) => inner.str_type(),
+            ArenaNode::Postexe(inner) => inner.str_type(),
+            ArenaNode::Preexe(inner) => inner.str_type(),
+            ArenaNode::Procarg0(inner) => inner.str_type(),
+            ArenaNode::Rational(inner) => inner.str_type(),
+            ArenaNode::Redo(inner) => inner.str_type(),
+            ArenaNode::Regexp(inner) => inner.str_type(),
+            ArenaNode::RegOpt(inner) => inner.str_type(),
+            ArenaNode::Rescue(inner) => inner.str_type(),
+            ArenaNode::RescueBody(inner) => inner.str_type(),
+            ArenaNode::Restarg(inner) => inner.str_type(),
+            ArenaNode::Retry(inner) => inner.str_type(),
+            ArenaNode::Return(inner) => inner.str_type(),
+            ArenaNode::SClass(inner) => inner.str_type(),
+            ArenaNode::Self_(inner) => inner.str_type(),
+            ArenaNode::Send(inner) => inner.str_type(),
+            ArenaNode::Shadowarg(inner) => inner.str_type(),
+            ArenaNode::Splat(inner) => inner.str_type(),
+            ArenaNode::Str(inner) => inner.str_type(),
+            ArenaNode::Super(inner) => inner.str_type(),
+            ArenaNode::Sym(inner) => inner.str_type(),
+            ArenaNode::True(inner) => inner.str_type(),
+            ArenaNode::Undef(inner) => inner.str_type(),
+            ArenaNode::UnlessGuard(inner) => inner.str_type(),
+            ArenaNode::Until(inner) => inner.str_type(),
+            ArenaNode::UntilPost(inner) => inner.str_type(),
+            ArenaNode::When(inner) => inner.str_type(),
+            ArenaNode::While(inner) => inner.str_type(),
+            ArenaNode::WhilePost(inner) => inner.str_type(),
+            ArenaNode::XHeredoc(inner) => inner.str_type(),
+            ArenaNode::Xstr(inner) => inner.str_type(),
+            ArenaNode::Yield(inner) => inner.str_type(),
+            ArenaNode::ZSuper(inner) => inner.str_type(),
+        }
+    }
+
+    pub(crate) fn from_node(node: &Node, arena: &mut Arena) -> Self {
+        match node {
+            Node::Alias(inner) => ArenaNode::Alias(Alias::from_node(inner, arena)),
+            Node::And(inner) => ArenaNode::And(And::from_node(inner, arena)),
+            Node::AndAsgn(inner) => ArenaNode::AndAsgn(AndAsgn::from_node(inner, arena)),
+            Node::Arg(inner) => ArenaNode::Arg(Arg::from_node(inner, arena)),
+            Node::Args(inner) => ArenaNode::Args(Args::from_node(inner, arena)),
+            Node::Array(inner) => ArenaNode::Array(Array::from_node(inner, arena)),
+            Node::ArrayPattern(inner) => {
+                ArenaNode::ArrayPattern(ArrayPattern::from_node(inner, arena))
+            }
+            Node::ArrayPatternWithTail(inner) => {
+                ArenaNode::ArrayPatternWithTail(ArrayPatternWithTail::from_node(inner, arena))
+            }
+            Node::BackRef(inner) => ArenaNode::BackRef(BackRef::from_node(inner, arena)),
+            Node::Begin(inner) => ArenaNode::Begin(Begin::from_node(inner, arena)),
+            Node::Block(inner) => ArenaNode::Block(Block::from_node(inner, arena)),
+            Node::Blockarg(inner) => ArenaNode::Blockarg(Blockarg::from_node(inner, arena)),
+            Node::BlockPass(inner) => ArenaNode::BlockPass(BlockPass::from_node(inner, arena)),
+            Node::Break(inner) => ArenaNode::Break(Break::from_node(inner, arena)),
+            Node::Case(inner) => ArenaNode::Case(Case::from_node(inner, arena)),
+            Node::CaseMatch(inner) => ArenaNode::CaseMatch(CaseMatch::from_node(inner, arena)),
+            Node::Casgn(inner) => ArenaNode::Casgn(Casgn::from_node(inner, arena)),
+            Node::Cbase(inner) => ArenaNode::Cbase(Cbase::from_node(inner, arena)),
+            Node::Class(inner) => ArenaNode::Class(Class::from_node(inner, arena)),
+            Node::Complex(inner) => ArenaNode::Complex(Complex::from_node(inner, arena)),
+            Node::Const(inner) => ArenaNode::Const(Const::from_node(inner, arena)),
+            Node::ConstPattern(inner) => {
+                ArenaNode::ConstPattern(ConstPattern::from_node(inner, arena))
+            }
+            Node::CSend(inner) => ArenaNode::CSend(CSend::from_node(inner, arena)),
+            Node::Cvar(inner) => ArenaNode::Cvar(Cvar::from_node(inner, arena)),
+            Node::Cvasgn(inner) => ArenaNode::Cvasgn(Cvasgn::from_node(inner, arena)),
+            Node::Def(inner) => ArenaNode::Def(Def::from_node(inner, arena)),
+            Node::Defined(inner) => ArenaNode::Defined(Defined::from_node(inner, arena)),
+            Node::Defs(inner) => ArenaNode::Defs(Defs::from_node(inner, arena)),
+            Node::Dstr(inner) => ArenaNode::Dstr(Dstr::from_node(inner, arena)),
+            Node::Dsym(inner) => ArenaNode::Dsym(Dsym::from_node(inner, arena)),
+            Node::EFlipFlop(inner) => ArenaNode::EFlipFlop(EFlipFlop::from_node(inner, arena)),
+            Node::EmptyElse(inner) => ArenaNode::EmptyElse(EmptyElse::from_node(inner, arena)),
+            Node::Encoding(inner) => ArenaNode::Encoding(Encoding::from_node(inner, arena)),
+            Node::Ensure(inner) => ArenaNode::Ensure(Ensure::from_node(inner, arena)),
+            Node::Erange(inner) => ArenaNode::Erange(Erange::from_node(inner, arena)),
+            Node::False(inner) => ArenaNode::False(False::from_node(inner, arena)),
+            Node::File(inner) => ArenaNode::File(File::from_node(inner, arena)),
+            Node::FindPattern(inner) => {
+                ArenaNode::FindPattern(FindPattern::from_node(inner, arena))
+            }
+            Node::Float(inner) => ArenaNode::Float(Float::from_node(inner, arena)),
+            Node::For(inner) => ArenaNode::For(For::from_node(inner, arena)),
+            Node::ForwardArg(inner) => ArenaNode::ForwardArg(ForwardArg::from_node(inner, arena)),
+            Node::ForwardedArgs(inner) => {
+                ArenaNode::ForwardedArgs(ForwardedArgs::from_node(inner, arena))
+            }
+            Node::Gvar(inner) => ArenaNode::Gvar(Gvar::from_node(inner, arena)),
+            Node::Gvasgn(inner) => ArenaNode::Gvasgn(Gvasgn::from_node(inner, arena)),
+            Node::Hash(inner) => ArenaNode::Hash(Hash::from_node(inner, arena)),
+            Node::HashPattern(inner) => {
+                ArenaNode::HashPattern(HashPattern::from_node(inner, arena))
+            }
+            Node::Heredoc(inner) => ArenaNode::Heredoc(Heredoc::from_node(inner, arena)),
+            Node::If(inner) => ArenaNode::If(If::from_node(inner, arena)),
+            Node::IfGuard(inner) => ArenaNode::IfGuard(IfGuard::from_node(inner, arena)),
+            Node::IFlipFlop(inner) => ArenaNode::IFlipFlop(IFlipFlop::from_node(inner, arena)),
+            Node::IfMod(inner) => ArenaNode::IfMod(IfMod::from_node(inner, arena)),
+            Node::IfTernary(inner) => ArenaNode::IfTernary(IfTernary::from_node(inner, arena)),
+            Node::Index(inner) => ArenaNode::Index(Index::from_node(inner, arena)),
+            Node::IndexAsgn(inner) => ArenaNode::IndexAsgn(IndexAsgn::from_node(inner, arena)),
+            Node::InMatch(inner) => ArenaNode::InMatch(InMatch::from_node(inner, arena)),
+            Node::InPattern(inner) => ArenaNode::InPattern(InPattern::from_node(inner, arena)),
+            Node::Int(inner) => ArenaNode::Int(Int::from_node(inner, arena)),
+            Node::Irange(inner) => ArenaNode::Irange(Irange::from_node(inner, arena)),
+            Node::Ivar(inner) => ArenaNode::Ivar(Ivar::from_node(inner, arena)),
+            Node::Ivasgn(inner) => ArenaNode::Ivasgn(Ivasgn::from_node(inner, arena)),
+            Node::Kwarg(inner) => ArenaNode::Kwarg(Kwarg::from_node(inner, arena)),
+            Node::KwBegin(inner) => ArenaNode::KwBegin(KwBegin::from_node(inner, arena)),
+            Node::Kwnilarg(inner) => ArenaNode::Kwnilarg(Kwnilarg::from_node(inner, arena)),
+            Node::Kwoptarg(inner) => ArenaNode::Kwoptarg(Kwoptarg::from_node(inner, arena)),
+            Node::Kwrestarg(inner) => ArenaNode::Kwrestarg(Kwrestarg::from_node(inner, arena)),
+            Node::Kwsplat(inner) => ArenaNode::Kwsplat(Kwsplat::from_node(inner, arena)),
+            Node::Lambda(inner) => ArenaNode::Lambda(Lambda::from_node(inner, arena)),
+            Node::Line(inner) => ArenaNode::Line(Line::from_node(inner, arena)),
+            Node::Lvar(inner) => ArenaNode::Lvar(Lvar::from_node(inner, arena)),
+            Node::Lvasgn(inner) => ArenaNode::Lvasgn(Lvasgn::from_node(inner, arena)),
+            Node::Masgn(inner) => ArenaNode::Masgn(Masgn::from_node(inner, arena)),
+            Node::MatchAlt(inner) => ArenaNode::MatchAlt(MatchAlt::from_node(inner, arena)),
+            Node::MatchAs(inner) => ArenaNode::MatchAs(MatchAs::from_node(inner, arena)),
+            Node::MatchCurrentLine(inner) => {
+                ArenaNode::MatchCurrentLine(MatchCurrentLine::from_node(inner, arena))
+            }
+            Node::MatchNilPattern(inner) => {
+                ArenaNode::MatchNilPattern(MatchNilPattern::from_node(inner, arena))
+            }
+            Node::MatchRest(inner) => ArenaNode::MatchRest(MatchRest::from_node(inner, arena)),
+            Node::MatchVar(inner) => ArenaNode::MatchVar(MatchVar::from_node(inner, arena)),
+            Node::MatchWithLvasgn(inner) => {
+                ArenaNode::MatchWithLvasgn(MatchWithLvasgn::from_node(inner, arena))
+            }
+            Node::Mlhs(inner) => ArenaNode::Mlhs(Mlhs::from_node(inner, arena)),
+            Node::Module(inner) => ArenaNode::Module(Module::from_node(inner, arena)),
+            Node::Next(inner) => ArenaNode::Next(Next::from_node(inner, arena)),
+            Node::Nil(inner) => ArenaNode::Nil(Nil::from_node(inner, arena)),
+            Node::NthRef(inner) => ArenaNode::NthRef(NthRef::from_node(inner, arena)),
+            Node::Numblock(inner) => ArenaNode::Numblock(Numblock::from_node(inner, arena)),
+            Node::OpAsgn(inner) => ArenaNode::OpAsgn(OpAsgn::from_node(inner, arena)),
+            Node::Optarg(inner) => ArenaNode::Optarg(Optarg::from_node(inner, arena)),
+            Node::Or(inner) => ArenaNode::Or(Or::from_node(inner, arena)),
+            Node::OrAsgn(inner) => ArenaNode::OrAsgn(OrAsgn::from_node(inner, arena)),
+            Node::Pair(inner) => ArenaNode::Pair(Pair::from_node(inner, arena)),
+            Node::Pin(inner) => ArenaNode::Pin(Pin::from_node(inner, arena)),
+            Node::Postexe(inner) => ArenaNode::Postexe(Postexe::from_node(inner, arena)),
+            Node::Preexe(inner) => ArenaNode::Preexe(Preexe::from_node(inner, arena)),
+            Node::Procarg0(inner) => ArenaNode::Procarg0(Procarg0::from_node(inner, arena)),
+            Node::Rational(inner) => ArenaNode::Rational(Rational::from_node(inner, arena)),
+            Node::Redo(inner) => ArenaNode::Redo(Redo::from_node(inner, arena)),
+            Node::Regexp(inner) => ArenaNode::Regexp(Regexp::from_node(inner, arena)),
+            Node::RegOpt(inner) => ArenaNode::RegOpt(RegOpt::from_node(inner, arena)),
+            Node::Rescue(inner) => ArenaNode::Rescue(Rescue::from_node(inner, arena)),
+            Node::RescueBody(inner) => ArenaNode::RescueBody(RescueBody::from_node(inner, arena)),
+            Node::Restarg(inner) => ArenaNode::Restarg(Restarg::from_node(inner, arena)),
+            Node::Retry(inner) => ArenaNode::Retry(Retry::from_node(inner, arena)),
+            Node::Return(inner) => ArenaNode::Return(Return::from_node(inner, arena)),
+            Node::SClass(inner) => ArenaNode::SClass(SClass::from_node(inner, arena)),
+            Node::Self_(inner) => ArenaNode::Self_(Self_::from_node(inner, arena)),
+            Node::Send(inner) => ArenaNode::Send(Send::from_node(inner, arena)),
+            Node::Shadowarg(inner) => ArenaNode::Shadowarg(Shadowarg::from_node(inner, arena)),
+            Node::Splat(inner) => ArenaNode::Splat(Splat::from_node(inner, arena)),
+            Node::Str(inner) => ArenaNode::Str(Str::from_node(inner, arena)),
+            Node::Super(inner) => ArenaNode::Super(Super::from_node(inner, arena)),
+            Node::Sym(inner) => ArenaNode::Sym(Sym::from_node(inner, arena)),
+            Node::True(inner) => ArenaNode::True(True::from_node(inner, arena)),
+            Node::Undef(inner) => ArenaNode::Undef(Undef::from_node(inner, arena)),
+            Node::UnlessGuard(inner) => {
+                ArenaNode::UnlessGuard(UnlessGuard::from_node(inner, arena))
+            }
+            Node::Until(inner) => ArenaNode::Until(Until::from_node(inner, arena)),
+            Node::UntilPost(inner) => ArenaNode::UntilPost(UntilPost::from_node(inner, arena)),
+            Node::When(inner) => ArenaNode::When(When::from_node(inner, arena)),
+            Node::While(inner) => ArenaNode::While(While::from_node(inner, arena)),
+            Node::WhilePost(inner) => ArenaNode::WhilePost(WhilePost::from_node(inner, arena)),
+            Node::XHeredoc(inner) => ArenaNode::XHeredoc(XHeredoc::from_node(inner, arena)),
+            Node::Xstr(inner) => ArenaNode::Xstr(Xstr::from_node(inner, arena)),
+            Node::Yield(inner) => ArenaNode::Yield(Yield::from_node(inner, arena)),
+            Node::ZSuper(inner) => ArenaNode::ZSuper(ZSuper::from_node(inner, arena)),
+        }
+    }
+
+    pub(crate) fn to_node(&self, arena: &Arena) -> Option<Node> {
+        let node = match self {
+            ArenaNode::Alias(inner) => Node::Alias(Box::new(inner.to_node(arena)?)),
+            ArenaNode::And(inner) => Node::And(Box::new(inner.to_node(arena)?)),
+            ArenaNode::AndAsgn(inner) => Node::AndAsgn(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Arg(inner) => Node::Arg(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Args(inner) => Node::Args(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Array(inner) => Node::Array(Box::new(inner.to_node(arena)?)),
+            ArenaNode::ArrayPattern(inner) => Node::ArrayPattern(Box::new(inner.to_node(arena)?)),
+            ArenaNode::ArrayPatternWithTail(inner) => {
+                Node::ArrayPatternWithTail(Box::new(inner.to_node(arena)?))
+            }
+            ArenaNode::BackRef(inner) => Node::BackRef(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Begin(inner) => Node::Begin(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Block(inner) => Node::Block(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Blockarg(inner) => Node::Blockarg(Box::new(inner.to_node(arena)?)),
+            ArenaNode::BlockPass(inner) => Node::BlockPass(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Break(inner) => Node::Break(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Case(inner) => Node::Case(Box::new(inner.to_node(arena)?)),
+            ArenaNode::CaseMatch(inner) => Node::CaseMatch(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Casgn(inner) => Node::Casgn(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Cbase(inner) => Node::Cbase(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Class(inner) => Node::Class(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Complex(inner) => Node::Complex(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Const(inner) => Node::Const(Box::new(inner.to_node(arena)?)),
+            ArenaNode::ConstPattern(inner) => Node::ConstPattern(Box::new(inner.to_node(arena)?)),
+            ArenaNode::CSend(inner) => Node::CSend(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Cvar(inner) => Node::Cvar(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Cvasgn(inner) => Node::Cvasgn(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Def(inner) => Node::Def(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Defined(inner) => Node::Defined(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Defs(inner) => Node::Defs(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Dstr(inner) => Node::Dstr(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Dsym(inner) => Node::Dsym(Box::new(inner.to_node(arena)?)),
+            ArenaNode::EFlipFlop(inner) => Node::EFlipFlop(Box::new(inner.to_node(arena)?)),
+            ArenaNode::EmptyElse(inner) => Node::EmptyElse(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Encoding(inner) => Node::Encoding(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Ensure(inner) => Node::Ensure(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Erange(inner) => Node::Erange(Box::new(inner.to_node(arena)?)),
+            ArenaNode::False(inner) => Node::False(Box::new(inner.to_node(arena)?)),
+            ArenaNode::File(inner) => Node::File(Box::new(inner.to_node(arena)?)),
+            ArenaNode::FindPattern(inner) => Node::FindPattern(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Float(inner) => Node::Float(Box::new(inner.to_node(arena)?)),
+            ArenaNode::For(inner) => Node::For(Box::new(inner.to_node(arena)?)),
+            ArenaNode::ForwardArg(inner) => Node::ForwardArg(Box::new(inner.to_node(arena)?)),
+            ArenaNode::ForwardedArgs(inner) => Node::ForwardedArgs(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Gvar(inner) => Node::Gvar(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Gvasgn(inner) => Node::Gvasgn(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Hash(inner) => Node::Hash(Box::new(inner.to_node(arena)?)),
+            ArenaNode::HashPattern(inner) => Node::HashPattern(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Heredoc(inner) => Node::Heredoc(Box::new(inner.to_node(arena)?)),
+            ArenaNode::If(inner) => Node::If(Box::new(inner.to_node(arena)?)),
+            ArenaNode::IfGuard(inner) => Node::IfGuard(Box::new(inner.to_node(arena)?)),
+            ArenaNode::IFlipFlop(inner) => Node::IFlipFlop(Box::new(inner.to_node(arena)?)),
+            ArenaNode::IfMod(inner) => Node::IfMod(Box::new(inner.to_node(arena)?)),
+            ArenaNode::IfTernary(inner) => Node::IfTernary(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Index(inner) => Node::Index(Box::new(inner.to_node(arena)?)),
+            ArenaNode::IndexAsgn(inner) => Node::IndexAsgn(Box::new(inner.to_node(arena)?)),
+            ArenaNode::InMatch(inner) => Node::InMatch(Box::new(inner.to_node(arena)?)),
+            ArenaNode::InPattern(inner) => Node::InPattern(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Int(inner) => Node::Int(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Irange(inner) => Node::Irange(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Ivar(inner) => Node::Ivar(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Ivasgn(inner) => Node::Ivasgn(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Kwarg(inner) => Node::Kwarg(Box::new(inner.to_node(arena)?)),
+            ArenaNode::KwBegin(inner) => Node::KwBegin(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Kwnilarg(inner) => Node::Kwnilarg(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Kwoptarg(inner) => Node::Kwoptarg(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Kwrestarg(inner) => Node::Kwrestarg(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Kwsplat(inner) => Node::Kwsplat(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Lambda(inner) => Node::Lambda(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Line(inner) => Node::Line(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Lvar(inner) => Node::Lvar(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Lvasgn(inner) => Node::Lvasgn(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Masgn(inner) => Node::Masgn(Box::new(inner.to_node(arena)?)),
+            ArenaNode::MatchAlt(inner) => Node::MatchAlt(Box::new(inner.to_node(arena)?)),
+            ArenaNode::MatchAs(inner) => Node::MatchAs(Box::new(inner.to_node(arena)?)),
+            ArenaNode::MatchCurrentLine(inner) => {
+                Node::MatchCurrentLine(Box::new(inner.to_node(arena)?))
+            }
+            ArenaNode::MatchNilPattern(inner) => {
+                Node::MatchNilPattern(Box::new(inner.to_node(arena)?))
+            }
+            ArenaNode::MatchRest(inner) => Node::MatchRest(Box::new(inner.to_node(arena)?)),
+            ArenaNode::MatchVar(inner) => Node::MatchVar(Box::new(inner.to_node(arena)?)),
+            ArenaNode::MatchWithLvasgn(inner) => {
+                Node::MatchWithLvasgn(Box::new(inner.to_node(arena)?))
+            }
+            ArenaNode::Mlhs(inner) => Node::Mlhs(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Module(inner) => Node::Module(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Next(inner) => Node::Next(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Nil(inner) => Node::Nil(Box::new(inner.to_node(arena)?)),
+            ArenaNode::NthRef(inner) => Node::NthRef(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Numblock(inner) => Node::Numblock(Box::new(inner.to_node(arena)?)),
+            ArenaNode::OpAsgn(inner) => Node::OpAsgn(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Optarg(inner) => Node::Optarg(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Or(inner) => Node::Or(Box::new(inner.to_node(arena)?)),
+            ArenaNode::OrAsgn(inner) => Node::OrAsgn(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Pair(inner) => Node::Pair(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Pin(inner) => Node::Pin(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Postexe(inner) => Node::Postexe(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Preexe(inner) => Node::Preexe(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Procarg0(inner) => Node::Procarg0(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Rational(inner) => Node::Rational(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Redo(inner) => Node::Redo(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Regexp(inner) => Node::Regexp(Box::new(inner.to_node(arena)?)),
+            ArenaNode::RegOpt(inner) => Node::RegOpt(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Rescue(inner) => Node::Rescue(Box::new(inner.to_node(arena)?)),
+            ArenaNode::RescueBody(inner) => Node::RescueBody(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Restarg(inner) => Node::Restarg(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Retry(inner) => Node::Retry(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Return(inner) => Node::Return(Box::new(inner.to_node(arena)?)),
+            ArenaNode::SClass(inner) => Node::SClass(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Self_(inner) => Node::Self_(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Send(inner) => Node::Send(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Shadowarg(inner) => Node::Shadowarg(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Splat(inner) => Node::Splat(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Str(inner) => Node::Str(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Super(inner) => Node::Super(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Sym(inner) => Node::Sym(Box::new(inner.to_node(arena)?)),
+            ArenaNode::True(inner) => Node::True(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Undef(inner) => Node::Undef(Box::new(inner.to_node(arena)?)),
+            ArenaNode::UnlessGuard(inner) => Node::UnlessGuard(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Until(inner) => Node::Until(Box::new(inner.to_node(arena)?)),
+            ArenaNode::UntilPost(inner) => Node::UntilPost(Box::new(inner.to_node(arena)?)),
+            ArenaNode::When(inner) => Node::When(Box::new(inner.to_node(arena)?)),
+            ArenaNode::While(inner) => Node::While(Box::new(inner.to_node(arena)?)),
+            ArenaNode::WhilePost(inner) => Node::WhilePost(Box::new(inner.to_node(arena)?)),
+            ArenaNode::XHeredoc(inner) => Node::XHeredoc(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Xstr(inner) => Node::Xstr(Box::new(inner.to_node(arena)?)),
+            ArenaNode::Yield(inner) => Node::Yield(Box::new(inner.to_node(arena)?)),
+            ArenaNode::ZSuper(inner) => Node::ZSuper(Box::new(inner.to_node(arena)?)),
+        };
+        Some(node)
+    }
+}
@@ -0,0 +1,96 @@
+use crate::arena::{Arena, NodeId};
+use crate::source::buffer::Input;
+use crate::source::Comment;
+use crate::source::MagicComment;
+use crate::Diagnostic;
+use crate::ParserResult;
+use crate::SourceToken;
+use crate::Token;
+
+/// Same as `ParserResult`, but the AST is stored in the `Arena`
+#[derive(Debug)]
+pub struct ArenaParserResult {
+    /// Id of the root node in the `arena`.
+    /// Contains `None` if the code gives no AST nodes (e.g. empty file)
+    pub ast: Option<NodeId>,
+
+    /// Storage of all AST nodes.
+    /// Owns `ParserResult.interner`, identifiers of nodes are added to it
+    pub arena: Arena,
+
+    /// List of tokens, see `ParserResult.tokens`
+    pub tokens: Vec<Token>,
+
+    /// List of range-backed tokens, see `ParserResult.source_tokens`
+    pub source_tokens: Vec<SourceToken>,
+
+    /// List of diagnostics, see `ParserResult.diagnostics`
+    pub diagnostics: Vec<Diagnostic>,
+
+    /// List of comments, see `ParserResult.comments`
+    pub comments: Vec<Comment>,
+
+    /// List of magic comments, see `ParserResult.magic_comments`
+    pub magic_comments: Vec<MagicComment>,
+
+    /// Input that was used for parsing, see `ParserResult.input`
+    pub input: Input,
+}
+
+impl From<ParserResult> for ArenaParserResult {
+    fn from(result: ParserResult) -> Self {
+        let ParserResult {
+            ast,
+            tokens,
+            source_tokens,
+            diagnostics,
+            comments,
+            magic_comments,
+            input,
+            interner,
+        } = result;
+
+        let mut arena = Arena::with_interner(interner);
+        let ast = arena.maybe_alloc(&ast);
+
+        Self {
+            ast,
+            arena,
+            tokens,
+            source_tokens,
+            diagnostics,
+            comments,
+            magic_comments,
+            input,
+        }
+    }
+}
+
+/// `ast` of the returned result is `None`
+/// if the root `NodeId` (or any symbol) doesn't belong to the `arena`.
+/// `interner` of the returned result is the arena's interner
+impl From<ArenaParserResult> for ParserResult {
+    fn from(result: ArenaParserResult) -> Self {
+        let ArenaParserResult {
+            ast,
+            arena,
+            tokens,
+            source_tokens,
+            diagnostics,
+            comments,
+            magic_comments,
+            input,
+        } = result;
+
+        Self {
+            ast: ast.and_then(|root| arena.to_node(root)),
+            tokens,
+            source_tokens,
+            diagnostics,
+            comments,
+            magic_comments,
+            input,
+            interner: arena.into_interner(),
+        }
+    }
+}
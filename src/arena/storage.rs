@@ -0,0 +1,160 @@
+use crate::arena::ArenaNode;
+use crate::{Interner, Node, Symbol};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static NEXT_ARENA_ID: AtomicU32 = AtomicU32::new(0);
+
+/// Index of the node in the `Arena`.
+///
+/// Ids are bound to the arena that has created them,
+/// the arena never resolves an id of another arena.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId {
+    arena_id: u32,
+    index: usize,
+}
+
+impl NodeId {
+    /// Returns a numeric index of the node in the `Arena`
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// Flat storage of AST nodes.
+///
+/// All nodes live in a single `Vec`, children are referenced by `NodeId`.
+/// Identifiers stored in nodes (like names of variables and methods)
+/// are interned, nodes keep `Symbol`s of the arena's `Interner`.
+/// The arena is filled by copying an owned `Node` (see `alloc`),
+/// so building it costs more than the owned tree itself.
+#[derive(Debug)]
+pub struct Arena {
+    id: u32,
+    nodes: Vec<ArenaNode>,
+    interner: Interner,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Arena {
+    pub fn new() -> Self {
+        Self::with_interner(Interner::new())
+    }
+
+    /// Constructs an arena that stores identifiers in a given `interner`
+    /// (e.g. in `ParserResult.interner` that already has names of local variables)
+    pub fn with_interner(interner: Interner) -> Self {
+        Self {
+            id: NEXT_ARENA_ID.fetch_add(1, Ordering::Relaxed),
+            nodes: vec![],
+            interner,
+        }
+    }
+
+    /// Converts a given `ast` into an arena-backed tree,
+    /// returns a new arena and an id of the root node
+    pub fn from_ast(ast: &Option<Node>) -> (Self, Option<NodeId>) {
+        let mut arena = Self::new();
+        let root = arena.maybe_alloc(ast);
+        (arena, root)
+    }
+
+    /// Copies a given `node` (and all its children) into `self`,
+    /// returns an id of the copied `node`
+    pub fn alloc(&mut self, node: &Node) -> NodeId {
+        let node = ArenaNode::from_node(node, self);
+        self.nodes.push(node);
+        self.node_id(self.nodes.len() - 1)
+    }
+
+    pub(crate) fn alloc_all(&mut self, nodes: &[Node]) -> Vec<NodeId> {
+        nodes.iter().map(|node| self.alloc(node)).collect()
+    }
+
+    pub(crate) fn maybe_alloc(&mut self, node: &Option<Node>) -> Option<NodeId> {
+        node.as_ref().map(|node| self.alloc(node))
+    }
+
+    /// Returns a node with a given `id`,
+    /// or `None` if `id` doesn't belong to this arena
+    pub fn get(&self, id: NodeId) -> Option<&ArenaNode> {
+        if id.arena_id != self.id {
+            return None;
+        }
+        self.nodes.get(id.index)
+    }
+
+    /// Constructs an owned `Node` from the node with a given `id`,
+    /// returns `None` if `id` (or an id of any child node) doesn't belong to this arena
+    pub fn to_node(&self, id: NodeId) -> Option<Node> {
+        self.get(id)?.to_node(self)
+    }
+
+    pub(crate) fn to_nodes(&self, ids: &[NodeId]) -> Option<Vec<Node>> {
+        ids.iter().map(|id| self.to_node(*id)).collect()
+    }
+
+    pub(crate) fn maybe_to_node(&self, id: &Option<NodeId>) -> Option<Option<Node>> {
+        id.map(|id| self.to_node(id)).transpose()
+    }
+
+    pub(crate) fn intern(&mut self, s: &str) -> Symbol {
+        self.interner.intern(s)
+    }
+
+    pub(crate) fn maybe_intern(&mut self, s: &Option<String>) -> Option<Symbol> {
+        s.as_ref().map(|s| self.interner.intern(s))
+    }
+
+    /// Returns a string for a given symbol stored in a node,
+    /// or `None` if the symbol doesn't belong to this arena
+    pub fn resolve(&self, symbol: Symbol) -> Option<&str> {
+        self.interner.resolve(symbol)
+    }
+
+    pub(crate) fn maybe_resolve(&self, symbol: &Option<Symbol>) -> Option<Option<String>> {
+        symbol
+            .map(|symbol| self.resolve(symbol).map(str::to_owned))
+            .transpose()
+    }
+
+    /// Returns an interner with all identifiers stored in nodes
+    pub fn interner(&self) -> &Interner {
+        &self.interner
+    }
+
+    /// Drops all nodes and returns the interner,
+    /// so it can be passed to the next parser via `ParserOptions.interner`
+    pub fn into_interner(self) -> Interner {
+        self.interner
+    }
+
+    /// Returns number of nodes in the arena
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns an iterator over all nodes in the arena
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &ArenaNode)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(move |(idx, node)| (self.node_id(idx), node))
+    }
+
+    fn node_id(&self, index: usize) -> NodeId {
+        NodeId {
+            arena_id: self.id,
+            index,
+        }
+    }
+}
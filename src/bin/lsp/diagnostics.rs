@@ -0,0 +1,32 @@
+use crate::document::Document;
+use serde_json::{json, Value};
+
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+
+/// Returns `textDocument/publishDiagnostics` params
+pub(crate) fn publish_diagnostics(uri: &str, document: Option<&Document>) -> Value {
+    let diagnostics = match document {
+        Some(document) => document
+            .result
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let severity = if diagnostic.is_error() {
+                    SEVERITY_ERROR
+                } else {
+                    SEVERITY_WARNING
+                };
+                json!({
+                    "range": document.range_to_json(&diagnostic.range),
+                    "severity": severity,
+                    "source": "lib-ruby-parser",
+                    "message": diagnostic.render_message(),
+                })
+            })
+            .collect(),
+        None => vec![],
+    };
+
+    json!({ "uri": uri, "diagnostics": diagnostics })
+}
@@ -0,0 +1,75 @@
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::{Parser, ParserOptions, ParserResult};
+use serde_json::{json, Value};
+
+/// Parsed text document
+pub(crate) struct Document {
+    pub(crate) result: ParserResult,
+}
+
+impl Document {
+    pub(crate) fn new(uri: &str, text: &str) -> Self {
+        let options = ParserOptions {
+            buffer_name: uri.to_owned(),
+            ..Default::default()
+        };
+        let result = Parser::new(text.as_bytes(), options).do_parse();
+        Self { result }
+    }
+
+    fn bytes(&self) -> &[u8] {
+        &self.result.input.bytes
+    }
+
+    /// Returns 0-based line of a given byte offset
+    pub(crate) fn line(&self, offset: usize) -> usize {
+        let offset = std::cmp::min(offset, self.bytes().len());
+        self.result.input.line_for_pos(offset).unwrap_or(0)
+    }
+
+    /// Returns LSP position (0-based line and UTF-16 column) of a given byte offset
+    pub(crate) fn position(&self, offset: usize) -> (usize, usize) {
+        let offset = std::cmp::min(offset, self.bytes().len());
+        self.result
+            .input
+            .line_utf16_col_for_pos(offset)
+            .unwrap_or((0, 0))
+    }
+
+    /// Returns byte offset of a given LSP position (0-based line and UTF-16 column)
+    pub(crate) fn offset(&self, line: usize, character: usize) -> usize {
+        self.result
+            .input
+            .pos_for_line_utf16_col(line, character)
+            .unwrap_or_else(|| self.bytes().len())
+    }
+
+    /// Returns byte offset of a given LSP `Position` object
+    pub(crate) fn offset_of(&self, position: &Value) -> usize {
+        let line = position["line"].as_u64().unwrap_or(0) as usize;
+        let character = position["character"].as_u64().unwrap_or(0) as usize;
+        self.offset(line, character)
+    }
+
+    pub(crate) fn position_to_json(&self, offset: usize) -> Value {
+        let (line, character) = self.position(offset);
+        json!({ "line": line, "character": character })
+    }
+
+    pub(crate) fn range_to_json(&self, range: &Range) -> Value {
+        json!({
+            "start": self.position_to_json(range.begin_pos),
+            "end": self.position_to_json(range.end_pos),
+        })
+    }
+
+    /// Returns source code of a given range
+    pub(crate) fn source(&self, range: &Range) -> String {
+        range.source(&self.result.input).unwrap_or_default()
+    }
+
+    /// Returns UTF-16 length of a given range
+    pub(crate) fn utf16_len(&self, begin: usize, end: usize) -> usize {
+        self.result.input.utf16_count(begin, end)
+    }
+}
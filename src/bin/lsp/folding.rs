@@ -0,0 +1,84 @@
+use crate::document::Document;
+use lib_ruby_parser::source::{CommentType, Range};
+use lib_ruby_parser::Node;
+use serde_json::{json, Value};
+
+/// Returns a range of lines that can be folded for a given node.
+///
+/// For `end`-terminated constructions the line with `end` is kept visible,
+/// for heredocs the whole body (including terminator) is folded.
+fn folding_lines(node: &Node, document: &Document) -> Option<(usize, usize)> {
+    let (begin, end) = match node {
+        Node::Def(def) => (&def.expression_l, def.end_l.as_ref()?),
+        Node::Defs(defs) => (&defs.expression_l, defs.end_l.as_ref()?),
+        Node::Class(class) => (&class.expression_l, &class.end_l),
+        Node::Module(module) => (&module.expression_l, &module.end_l),
+        Node::SClass(sclass) => (&sclass.expression_l, &sclass.end_l),
+        Node::KwBegin(begin) => (&begin.expression_l, begin.end_l.as_ref()?),
+        Node::Heredoc(heredoc) => {
+            return heredoc_lines(&heredoc.expression_l, &heredoc.heredoc_end_l, document)
+        }
+        Node::XHeredoc(heredoc) => {
+            return heredoc_lines(&heredoc.expression_l, &heredoc.heredoc_end_l, document)
+        }
+        _ => return None,
+    };
+
+    let start_line = document.line(begin.begin_pos);
+    let end_line = document.line(end.begin_pos);
+    if end_line > start_line + 1 {
+        Some((start_line, end_line - 1))
+    } else {
+        None
+    }
+}
+
+fn heredoc_lines(
+    expression_l: &Range,
+    heredoc_end_l: &Range,
+    document: &Document,
+) -> Option<(usize, usize)> {
+    let start_line = document.line(expression_l.begin_pos);
+    let end_line = document.line(heredoc_end_l.begin_pos);
+    if end_line > start_line {
+        Some((start_line, end_line))
+    } else {
+        None
+    }
+}
+
+fn collect(node: &Node, document: &Document, ranges: &mut Vec<Value>) {
+    if let Some((start_line, end_line)) = folding_lines(node, document) {
+        ranges.push(json!({ "startLine": start_line, "endLine": end_line }));
+    }
+    for child in node.children() {
+        collect(child, document, ranges);
+    }
+}
+
+/// Returns a response to `textDocument/foldingRange`
+pub(crate) fn folding_ranges(document: &Document) -> Value {
+    let mut ranges = vec![];
+
+    if let Some(ast) = &document.result.ast {
+        collect(ast, document, &mut ranges);
+    }
+
+    for comment in &document.result.comments {
+        if comment.kind != CommentType::Document {
+            continue;
+        }
+        // `=end` line is a part of the comment location
+        let start_line = document.line(comment.location.begin_pos);
+        let end_line = document.line(comment.location.end_pos.saturating_sub(1));
+        if end_line > start_line {
+            ranges.push(json!({
+                "startLine": start_line,
+                "endLine": end_line,
+                "kind": "comment",
+            }));
+        }
+    }
+
+    Value::Array(ranges)
+}
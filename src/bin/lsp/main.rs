@@ -0,0 +1,45 @@
+//! Language server built on top of the parser.
+//!
+//! Speaks LSP over stdio and supports:
+//! + `textDocument/publishDiagnostics`
+//! + `textDocument/documentSymbol`
+//! + `textDocument/foldingRange`
+//! + `textDocument/selectionRange`
+//! + `textDocument/semanticTokens/full`
+
+mod diagnostics;
+mod document;
+mod folding;
+mod selection;
+mod semantic_tokens;
+mod server;
+mod symbols;
+mod transport;
+
+use server::{parse_error, Server};
+use std::io;
+use transport::{read_message, write_message};
+
+fn main() -> io::Result<()> {
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+
+    let mut server = Server::new();
+
+    while let Some(message) = read_message(&mut reader)? {
+        let outgoing = match message {
+            Ok(message) => server.handle(&message),
+            Err(err) => vec![parse_error(&err.to_string())],
+        };
+        for outgoing in outgoing {
+            write_message(&mut writer, &outgoing)?;
+        }
+        if let Some(exit_code) = server.exit_code() {
+            std::process::exit(exit_code);
+        }
+    }
+
+    Ok(())
+}
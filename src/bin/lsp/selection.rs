@@ -0,0 +1,65 @@
+use crate::document::Document;
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::Node;
+use serde_json::{json, Value};
+
+/// Returns expressions of all nodes containing `offset`, from the root to the innermost node
+fn enclosing_ranges(ast: &Node, offset: usize) -> Vec<Range> {
+    let mut ranges: Vec<Range> = vec![];
+    let mut node = ast;
+
+    loop {
+        let range = node.expression();
+        if ranges.last() != Some(range) {
+            ranges.push(range.clone());
+        }
+
+        let child = node.children().into_iter().find(|child| {
+            let range = child.expression();
+            range.begin_pos <= offset && offset <= range.end_pos
+        });
+        match child {
+            Some(child) => node = child,
+            None => break,
+        }
+    }
+
+    ranges
+}
+
+fn selection_range(document: &Document, position: &Value) -> Value {
+    let offset = document.offset_of(position);
+
+    let ranges = match &document.result.ast {
+        Some(ast) if ast.expression().begin_pos <= offset && offset <= ast.expression().end_pos => {
+            enclosing_ranges(ast, offset)
+        }
+        _ => vec![],
+    };
+
+    let mut result: Option<Value> = None;
+    for range in ranges {
+        let mut selection_range = json!({ "range": document.range_to_json(&range) });
+        if let Some(parent) = result {
+            selection_range["parent"] = parent;
+        }
+        result = Some(selection_range);
+    }
+
+    result.unwrap_or_else(|| {
+        // LSP requires a selection range for every position
+        let empty = Range::new(offset, offset);
+        json!({ "range": document.range_to_json(&empty) })
+    })
+}
+
+/// Returns a response to `textDocument/selectionRange`
+pub(crate) fn selection_ranges(document: &Document, params: &Value) -> Value {
+    let positions = params["positions"].as_array().cloned().unwrap_or_default();
+    Value::Array(
+        positions
+            .iter()
+            .map(|position| selection_range(document, position))
+            .collect(),
+    )
+}
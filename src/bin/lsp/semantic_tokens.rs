@@ -0,0 +1,160 @@
+use crate::document::Document;
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::{token_name, Node};
+use serde_json::{json, Value};
+
+// Indexes in the legend below
+const KEYWORD: u32 = 0;
+const NUMBER: u32 = 1;
+const STRING: u32 = 2;
+const REGEXP: u32 = 3;
+const COMMENT: u32 = 4;
+const VARIABLE: u32 = 5;
+const PARAMETER: u32 = 6;
+const METHOD: u32 = 7;
+const CLASS: u32 = 8;
+const PROPERTY: u32 = 9;
+
+const DECLARATION: u32 = 1 << 0;
+
+/// Legend that is sent to the client in `initialize` response
+pub(crate) fn legend() -> Value {
+    json!({
+        "tokenTypes": [
+            "keyword", "number", "string", "regexp", "comment",
+            "variable", "parameter", "method", "class", "property"
+        ],
+        "tokenModifiers": ["declaration"],
+    })
+}
+
+struct SemanticToken {
+    begin: usize,
+    end: usize,
+    token_type: u32,
+    modifiers: u32,
+}
+
+fn token_type_for(name: &str) -> Option<u32> {
+    let token_type = match name {
+        "tINTEGER" | "tFLOAT" | "tRATIONAL" | "tIMAGINARY" => NUMBER,
+        "tSTRING_BEG" | "tSTRING_END" | "tSTRING_CONTENT" | "tXSTRING_BEG" | "tCHAR"
+        | "tWORDS_BEG" | "tQWORDS_BEG" | "tSYMBOLS_BEG" | "tQSYMBOLS_BEG" => STRING,
+        "tREGEXP_BEG" | "tREGEXP_END" => REGEXP,
+        "tIVAR" | "tCVAR" => PROPERTY,
+        "tGVAR" | "tNTH_REF" | "tBACK_REF" => VARIABLE,
+        "tCONSTANT" => CLASS,
+        _ if name.starts_with('k') => KEYWORD,
+        _ => return None,
+    };
+    Some(token_type)
+}
+
+fn push(tokens: &mut Vec<SemanticToken>, range: &Range, token_type: u32, modifiers: u32) {
+    tokens.push(SemanticToken {
+        begin: range.begin_pos,
+        end: range.end_pos,
+        token_type,
+        modifiers,
+    })
+}
+
+fn collect_from_ast(node: &Node, tokens: &mut Vec<SemanticToken>) {
+    match node {
+        Node::Def(def) => push(tokens, &def.name_l, METHOD, DECLARATION),
+        Node::Defs(defs) => push(tokens, &defs.name_l, METHOD, DECLARATION),
+        Node::Send(send) => {
+            if let Some(selector_l) = &send.selector_l {
+                push(tokens, selector_l, METHOD, 0)
+            }
+        }
+        Node::CSend(csend) => push(tokens, &csend.selector_l, METHOD, 0),
+        Node::Arg(arg) => push(tokens, &arg.expression_l, PARAMETER, DECLARATION),
+        Node::Optarg(arg) => push(tokens, &arg.name_l, PARAMETER, DECLARATION),
+        Node::Kwarg(arg) => push(tokens, &arg.name_l, PARAMETER, DECLARATION),
+        Node::Kwoptarg(arg) => push(tokens, &arg.name_l, PARAMETER, DECLARATION),
+        Node::Lvar(lvar) => push(tokens, &lvar.expression_l, VARIABLE, 0),
+        Node::Lvasgn(lvasgn) => push(tokens, &lvasgn.name_l, VARIABLE, DECLARATION),
+        _ => {}
+    }
+
+    for child in node.children() {
+        collect_from_ast(child, tokens);
+    }
+}
+
+/// Splits multiline tokens, LSP doesn't allow them by default
+fn split_by_lines(token: SemanticToken, document: &Document, result: &mut Vec<SemanticToken>) {
+    let lines = &document.result.input.lines;
+    let mut begin = token.begin;
+
+    while begin < token.end {
+        let line = &lines[document.line(begin)];
+        let end = std::cmp::min(token.end, line.line_end());
+        if end > begin {
+            result.push(SemanticToken {
+                begin,
+                end,
+                token_type: token.token_type,
+                modifiers: token.modifiers,
+            });
+        }
+        if line.end <= begin {
+            break;
+        }
+        begin = line.end;
+    }
+}
+
+/// Returns a response to `textDocument/semanticTokens/full`
+pub(crate) fn semantic_tokens(document: &Document) -> Value {
+    let mut tokens = vec![];
+
+    for token in &document.result.tokens {
+        if let Some(token_type) = token_type_for(&token_name(token.token_type)) {
+            tokens.push(SemanticToken {
+                begin: token.loc.begin,
+                end: token.loc.end,
+                token_type,
+                modifiers: 0,
+            });
+        }
+    }
+    for comment in &document.result.comments {
+        push(&mut tokens, &comment.location, COMMENT, 0);
+    }
+    if let Some(ast) = &document.result.ast {
+        collect_from_ast(ast, &mut tokens);
+    }
+
+    tokens.sort_by_key(|token| token.begin);
+    let mut lines_tokens = vec![];
+    let mut last_end = 0;
+    for token in tokens {
+        // tokens from the AST can overlap with tokens from the lexer
+        if token.begin < last_end || token.begin >= token.end {
+            continue;
+        }
+        last_end = token.end;
+        split_by_lines(token, document, &mut lines_tokens);
+    }
+
+    let mut data = vec![];
+    let (mut prev_line, mut prev_col) = (0, 0);
+    for token in lines_tokens {
+        let (line, col) = document.position(token.begin);
+        let delta_line = line - prev_line;
+        let delta_col = if delta_line == 0 { col - prev_col } else { col };
+        data.extend_from_slice(&[
+            delta_line as u32,
+            delta_col as u32,
+            document.utf16_len(token.begin, token.end) as u32,
+            token.token_type,
+            token.modifiers,
+        ]);
+        prev_line = line;
+        prev_col = col;
+    }
+
+    json!({ "data": data })
+}
@@ -0,0 +1,186 @@
+use crate::diagnostics::publish_diagnostics;
+use crate::document::Document;
+use crate::folding::folding_ranges;
+use crate::selection::selection_ranges;
+use crate::semantic_tokens::{legend, semantic_tokens};
+use crate::symbols::document_symbols;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+// https://microsoft.github.io/language-server-protocol/specifications/specification-current/#errorCodes
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+
+const TEXT_DOCUMENT_SYNC_FULL: u8 = 1;
+
+// https://microsoft.github.io/language-server-protocol/specifications/specification-current/#messageType
+const MESSAGE_TYPE_ERROR: u8 = 1;
+
+/// State of the language server
+#[derive(Default)]
+pub(crate) struct Server {
+    documents: HashMap<String, Document>,
+    shutdown_requested: bool,
+    exit_requested: bool,
+}
+
+fn response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error_response(id: Value, code: i32, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// Returns a response to a message that isn't valid JSON,
+/// its id is unknown, so it's `null`
+pub(crate) fn parse_error(message: &str) -> Value {
+    error_response(Value::Null, PARSE_ERROR, message)
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+// Notifications can't be answered with an error,
+// so invalid ones are reported to the client log
+fn log_error(message: &str) -> Vec<Value> {
+    vec![notification(
+        "window/logMessage",
+        json!({ "type": MESSAGE_TYPE_ERROR, "message": message }),
+    )]
+}
+
+fn document_uri(params: &Value) -> Option<&str> {
+    params["textDocument"]["uri"].as_str()
+}
+
+impl Server {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns exit code of the process once `exit` notification is received
+    pub(crate) fn exit_code(&self) -> Option<i32> {
+        if self.exit_requested {
+            Some(if self.shutdown_requested { 0 } else { 1 })
+        } else {
+            None
+        }
+    }
+
+    /// Handles an incoming message, returns a list of outgoing messages
+    pub(crate) fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = match message["method"].as_str() {
+            Some(method) => method,
+            // responses from the client are ignored
+            None => return vec![],
+        };
+        let params = &message["params"];
+
+        match message.get("id") {
+            Some(id) => vec![self.handle_request(id.clone(), method, params)],
+            None => self.handle_notification(method, params),
+        }
+    }
+
+    fn handle_request(&mut self, id: Value, method: &str, params: &Value) -> Value {
+        if self.shutdown_requested {
+            return error_response(id, INVALID_REQUEST, "server is shutting down");
+        }
+
+        match method {
+            "initialize" => return response(id, Self::capabilities()),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                return response(id, Value::Null);
+            }
+            _ => {}
+        }
+
+        let handler: fn(&Document, &Value) -> Value = match method {
+            "textDocument/documentSymbol" => |document, _| document_symbols(document),
+            "textDocument/foldingRange" => |document, _| folding_ranges(document),
+            "textDocument/selectionRange" => selection_ranges,
+            "textDocument/semanticTokens/full" => |document, _| semantic_tokens(document),
+            _ => return error_response(id, METHOD_NOT_FOUND, "method not found"),
+        };
+
+        match document_uri(params).and_then(|uri| self.documents.get(uri)) {
+            Some(document) => response(id, handler(document, params)),
+            None => error_response(id, INVALID_PARAMS, "unknown document"),
+        }
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        match method {
+            "exit" => {
+                self.exit_requested = true;
+                vec![]
+            }
+            "textDocument/didOpen" => match params["textDocument"]["text"].as_str() {
+                Some(text) => self.update(document_uri(params), text),
+                None => log_error("textDocument/didOpen: missing text"),
+            },
+            "textDocument/didChange" => {
+                // full sync, the last change contains the whole text
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                match text {
+                    Some(text) => self.update(document_uri(params), text),
+                    // the document is kept as is
+                    None => log_error("textDocument/didChange: missing text of the full document"),
+                }
+            }
+            "textDocument/didClose" => match document_uri(params) {
+                Some(uri) => {
+                    self.documents.remove(uri);
+                    vec![notification(
+                        "textDocument/publishDiagnostics",
+                        publish_diagnostics(uri, None),
+                    )]
+                }
+                None => vec![],
+            },
+            _ => vec![],
+        }
+    }
+
+    fn update(&mut self, uri: Option<&str>, text: &str) -> Vec<Value> {
+        let uri = match uri {
+            Some(uri) => uri,
+            None => return vec![],
+        };
+        let document = Document::new(uri, text);
+        let diagnostics = publish_diagnostics(uri, Some(&document));
+        self.documents.insert(uri.to_owned(), document);
+        vec![notification("textDocument/publishDiagnostics", diagnostics)]
+    }
+
+    fn capabilities() -> Value {
+        json!({
+            "capabilities": {
+                "textDocumentSync": TEXT_DOCUMENT_SYNC_FULL,
+                "documentSymbolProvider": true,
+                "foldingRangeProvider": true,
+                "selectionRangeProvider": true,
+                "semanticTokensProvider": {
+                    "legend": legend(),
+                    "full": true,
+                },
+            },
+            "serverInfo": {
+                "name": "lib-ruby-parser",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })
+    }
+}
@@ -0,0 +1,97 @@
+use crate::document::Document;
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::Node;
+use serde_json::{json, Value};
+
+// https://microsoft.github.io/language-server-protocol/specifications/specification-current/#symbolKind
+const KIND_MODULE: u8 = 2;
+const KIND_CLASS: u8 = 5;
+const KIND_METHOD: u8 = 6;
+const KIND_CONSTANT: u8 = 14;
+
+struct Symbol<'a> {
+    name: String,
+    kind: u8,
+    range: &'a Range,
+    selection_range: &'a Range,
+}
+
+fn symbol_for<'a>(node: &'a Node, document: &Document) -> Option<Symbol<'a>> {
+    let symbol = match node {
+        Node::Class(class) => Symbol {
+            name: document.source(class.name.expression()),
+            kind: KIND_CLASS,
+            range: &class.expression_l,
+            selection_range: class.name.expression(),
+        },
+        Node::Module(module) => Symbol {
+            name: document.source(module.name.expression()),
+            kind: KIND_MODULE,
+            range: &module.expression_l,
+            selection_range: module.name.expression(),
+        },
+        Node::SClass(sclass) => Symbol {
+            name: format!("class << {}", document.source(sclass.expr.expression())),
+            kind: KIND_CLASS,
+            range: &sclass.expression_l,
+            selection_range: &sclass.keyword_l,
+        },
+        Node::Def(def) => Symbol {
+            name: def.name.clone(),
+            kind: KIND_METHOD,
+            range: &def.expression_l,
+            selection_range: &def.name_l,
+        },
+        Node::Defs(defs) => Symbol {
+            name: format!(
+                "{}.{}",
+                document.source(defs.definee.expression()),
+                defs.name
+            ),
+            kind: KIND_METHOD,
+            range: &defs.expression_l,
+            selection_range: &defs.name_l,
+        },
+        Node::Casgn(casgn) => Symbol {
+            name: casgn.name.clone(),
+            kind: KIND_CONSTANT,
+            range: &casgn.expression_l,
+            selection_range: &casgn.name_l,
+        },
+        _ => return None,
+    };
+    Some(symbol)
+}
+
+fn collect(node: &Node, document: &Document, symbols: &mut Vec<Value>) {
+    match symbol_for(node, document) {
+        Some(symbol) => {
+            let mut children = vec![];
+            for child in node.children() {
+                collect(child, document, &mut children);
+            }
+            symbols.push(json!({
+                "name": symbol.name,
+                "kind": symbol.kind,
+                "range": document.range_to_json(symbol.range),
+                "selectionRange": document.range_to_json(symbol.selection_range),
+                "children": children,
+            }));
+        }
+        None => {
+            for child in node.children() {
+                collect(child, document, symbols);
+            }
+        }
+    }
+}
+
+/// Returns a response to `textDocument/documentSymbol`
+/// (a tree of `DocumentSymbol`)
+pub(crate) fn document_symbols(document: &Document) -> Value {
+    let mut symbols = vec![];
+    if let Some(ast) = &document.result.ast {
+        collect(ast, document, &mut symbols);
+    }
+    Value::Array(symbols)
+}
@@ -0,0 +1,56 @@
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Reads a single `Content-Length`-framed JSON-RPC message.
+///
+/// Returns `Ok(None)` if the stream is closed.
+/// A message with invalid JSON is returned as `Ok(Some(Err(..)))`,
+/// the stream stays in sync and the next message can be read.
+pub(crate) fn read_message(
+    reader: &mut impl BufRead,
+) -> io::Result<Option<serde_json::Result<Value>>> {
+    let mut content_length: Option<usize> = None;
+
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let mut parts = header.splitn(2, ':');
+        let name = parts.next().unwrap_or_default().trim();
+        let value = parts.next().unwrap_or_default().trim();
+        if name.eq_ignore_ascii_case("Content-Length") {
+            let length = value
+                .parse()
+                .map_err(|_| invalid_data(format!("invalid Content-Length: {}", value)))?;
+            content_length = Some(length);
+        }
+    }
+
+    let content_length =
+        content_length.ok_or_else(|| invalid_data("missing Content-Length".to_owned()))?;
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+
+    Ok(Some(serde_json::from_slice(&content)))
+}
+
+/// Writes a single `Content-Length`-framed JSON-RPC message
+pub(crate) fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    writer.flush()
+}
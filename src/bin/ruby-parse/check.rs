@@ -0,0 +1,56 @@
+use crate::input::InputArgs;
+use crate::json::diagnostic_to_json;
+use crate::{parse_source, print_line, CommandResult};
+use clap::Clap;
+use serde_json::json;
+
+#[derive(Debug, Clap)]
+pub(crate) struct CheckArgs {
+    #[clap(
+        long,
+        default_value = "text",
+        possible_values = &["text", "json"],
+        about = "output format"
+    )]
+    format: String,
+
+    #[clap(long, about = "don't print warnings")]
+    no_warnings: bool,
+
+    #[clap(flatten)]
+    input: InputArgs,
+}
+
+pub(crate) fn run(args: &CheckArgs) -> CommandResult {
+    let mut valid = true;
+
+    args.input.each_source(&mut |name, code| {
+        let result = parse_source(name, code, false);
+        let diagnostics = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.is_error() || !args.no_warnings)
+            .collect::<Vec<_>>();
+
+        if diagnostics.iter().any(|d| d.is_error()) {
+            valid = false;
+        }
+
+        if args.format == "json" {
+            let diagnostics = diagnostics
+                .iter()
+                .map(|d| diagnostic_to_json(d, &result.input))
+                .collect::<Vec<_>>();
+            print_line(json!({ "file": name, "diagnostics": diagnostics }))?;
+        } else {
+            for diagnostic in diagnostics {
+                if let Some(rendered) = diagnostic.render(&result.input) {
+                    print_line(rendered)?;
+                }
+            }
+        }
+        Ok(())
+    })?;
+
+    Ok(valid)
+}
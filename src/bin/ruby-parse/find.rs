@@ -0,0 +1,67 @@
+use crate::input::InputArgs;
+use crate::{parse_source, print_line, CommandResult};
+use clap::Clap;
+use lib_ruby_parser::traverse::Find;
+use serde_json::{json, Value};
+
+#[derive(Debug, Clap)]
+pub(crate) struct FindArgs {
+    #[clap(about = "whitespace-separated pattern (like \"body stmt[0] arg[0]\")")]
+    pattern: String,
+
+    #[clap(
+        long,
+        default_value = "text",
+        possible_values = &["text", "json"],
+        about = "output format"
+    )]
+    format: String,
+
+    #[clap(flatten)]
+    input: InputArgs,
+}
+
+pub(crate) fn run(args: &FindArgs) -> CommandResult {
+    let pattern = args
+        .pattern
+        .split_whitespace()
+        .map(|item| item.to_owned())
+        .collect::<Vec<_>>();
+    let mut found_any = false;
+
+    args.input.each_source(&mut |name, code| {
+        let result = parse_source(name, code, false);
+        let found = match &result.ast {
+            Some(ast) => Find::run(&pattern, ast)?,
+            None => None,
+        };
+
+        if args.format == "json" {
+            let node = match &found {
+                Some(node) => node.to_json_value(),
+                None => Value::Null,
+            };
+            let source = found
+                .as_ref()
+                .and_then(|node| node.expression().source(&result.input));
+            print_line(json!({ "file": name, "node": node, "source": source }))?;
+        } else if let Some(node) = &found {
+            let (line, column) = node
+                .expression()
+                .begin_line_col(&result.input)
+                .unwrap_or((0, 0));
+            print_line(format!(
+                "{}:{}:{}: {}",
+                name,
+                line + 1,
+                column,
+                node.expression().source(&result.input).unwrap_or_default()
+            ))?;
+        }
+
+        found_any |= found.is_some();
+        Ok(())
+    })?;
+
+    Ok(found_any)
+}
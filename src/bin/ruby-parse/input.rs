@@ -0,0 +1,73 @@
+use clap::Clap;
+use glob::glob;
+use std::error::Error;
+use std::io::Read;
+use std::path::Path;
+
+#[derive(Debug, Clap)]
+pub(crate) struct InputArgs {
+    #[clap(about = "files, directories or glob patterns to read, stdin if omitted or '-'")]
+    paths: Vec<String>,
+
+    #[clap(short = 'e', conflicts_with = "paths", about = "code to evaluate")]
+    code: Option<String>,
+}
+
+pub(crate) type Callback<'a> = dyn FnMut(&str, &[u8]) -> Result<(), Box<dyn Error>> + 'a;
+
+fn read_stdin(cb: &mut Callback) -> Result<(), Box<dyn Error>> {
+    let mut code = vec![];
+    std::io::stdin().read_to_end(&mut code)?;
+    cb("(stdin)", &code)
+}
+
+// A pattern that matches nothing is most likely a typo,
+// so it's an error instead of an empty input
+fn read_glob(pattern: &str, cb: &mut Callback) -> Result<(), Box<dyn Error>> {
+    let mut matched = false;
+    for entry in glob(pattern)? {
+        let entry = entry?;
+        if entry.is_file() {
+            matched = true;
+            let code = std::fs::read(&entry)?;
+            cb(&entry.to_string_lossy(), &code)?;
+        }
+    }
+    if matched {
+        Ok(())
+    } else {
+        Err(format!("{}: no files match", pattern).into())
+    }
+}
+
+fn is_glob(path: &str) -> bool {
+    path.contains(|c| matches!(c, '*' | '?' | '['))
+}
+
+impl InputArgs {
+    /// Calls `cb` with a name and content of every given source
+    pub(crate) fn each_source(&self, cb: &mut Callback) -> Result<(), Box<dyn Error>> {
+        if let Some(code) = &self.code {
+            return cb("(eval)", code.as_bytes());
+        }
+        if self.paths.is_empty() {
+            return read_stdin(cb);
+        }
+
+        for path in &self.paths {
+            if path == "-" {
+                read_stdin(cb)?;
+            } else if Path::new(path).is_dir() {
+                read_glob(&format!("{}/**/*.rb", path.trim_end_matches('/')), cb)?;
+            } else if Path::new(path).is_file() {
+                let code = std::fs::read(path)?;
+                cb(path, &code)?;
+            } else if is_glob(path) {
+                read_glob(path, cb)?;
+            } else {
+                return Err(format!("{}: no such file or directory", path).into());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use lib_ruby_parser::source::buffer::Input;
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::{token_name, Diagnostic, Token};
+use serde_json::{json, Value};
+
+/// Same as locations of `Node::to_json`, but with a line and a column
+pub(crate) fn range_to_json(range: &Range, input: &Input) -> Value {
+    let (line, column) = range.begin_line_col(input).unwrap_or((0, 0));
+    json!({
+        "begin_pos": range.begin_pos,
+        "end_pos": range.end_pos,
+        "line": line + 1,
+        "column": column,
+    })
+}
+
+pub(crate) fn token_to_json(token: &Token, input: &Input) -> Value {
+    json!({
+        "name": token_name(token.token_type),
+        "value": token.to_string_lossy(),
+        "location": range_to_json(&Range::new(token.loc.begin, token.loc.end), input),
+    })
+}
+
+pub(crate) fn diagnostic_to_json(diagnostic: &Diagnostic, input: &Input) -> Value {
+    json!({
+        "level": if diagnostic.is_error() { "error" } else { "warning" },
+        "message": diagnostic.render_message(),
+        "location": range_to_json(&diagnostic.range, input),
+    })
+}
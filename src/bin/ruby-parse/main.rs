@@ -0,0 +1,101 @@
+//! `ruby-parse` command-line tool.
+//!
+//! ```text
+//! ruby-parse parse [--format sexp|json|locations] [PATHS... | -e CODE]
+//! ruby-parse tokens [--format text|json] [PATHS... | -e CODE]
+//! ruby-parse check [--format text|json] [PATHS... | -e CODE]
+//! ruby-parse find PATTERN [--format text|json] [PATHS... | -e CODE]
+//! ```
+//!
+//! `PATHS` can be files, directories (all `.rb` files are read recursively)
+//! or glob patterns, stdin is read if no paths are given.
+//! A path that doesn't exist or a pattern that matches no files
+//! is an error (exit code 2).
+//! JSON output is printed as JSON Lines, one object per source,
+//! nodes are printed using `Node::to_json_value`.
+
+use clap::Clap;
+use lib_ruby_parser::{Parser, ParserOptions, ParserResult};
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+mod check;
+mod find;
+mod input;
+mod json;
+mod parse;
+mod tokens;
+
+#[derive(Debug, Clap)]
+#[clap(name = "ruby-parse", version = env!("CARGO_PKG_VERSION"))]
+struct Args {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Clap)]
+enum Command {
+    #[clap(about = "print AST")]
+    Parse(parse::ParseArgs),
+
+    #[clap(about = "print tokens")]
+    Tokens(tokens::TokensArgs),
+
+    #[clap(about = "print diagnostics, exit with non-zero code if there are errors")]
+    Check(check::CheckArgs),
+
+    #[clap(about = "find nodes using traverse::Find pattern (like \"body stmt[0] arg[0]\")")]
+    Find(find::FindArgs),
+}
+
+pub(crate) fn parse_source(name: &str, code: &[u8], record_tokens: bool) -> ParserResult {
+    let options = ParserOptions {
+        buffer_name: name.to_owned(),
+        record_tokens,
+        ..Default::default()
+    };
+    Parser::new(code, options).do_parse()
+}
+
+fn main() {
+    let args: Args = Args::parse();
+
+    let result = match args.command {
+        Command::Parse(args) => parse::run(&args),
+        Command::Tokens(args) => tokens::run(&args),
+        Command::Check(args) => check::run(&args),
+        Command::Find(args) => find::run(&args),
+    };
+
+    match result {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        // output is piped to a command that has exited (like `head`)
+        Err(err) if is_broken_pipe(err.as_ref()) => {}
+        Err(err) => {
+            eprintln!("ruby-parse: {}", err);
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Prints a line to stdout.
+///
+/// Unlike `println!` it returns an error instead of panicking if stdout is closed
+pub(crate) fn print_line(line: impl Display) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    writeln!(stdout, "{}", line)
+}
+
+fn is_broken_pipe(err: &(dyn Error + 'static)) -> bool {
+    match err.downcast_ref::<io::Error>() {
+        Some(err) => err.kind() == io::ErrorKind::BrokenPipe,
+        None => false,
+    }
+}
+
+/// Result of the command, `Ok(false)` means that the command has failed
+/// (e.g. `check` has found errors) and the process exits with code 1
+pub(crate) type CommandResult = Result<bool, Box<dyn Error>>;
@@ -0,0 +1,83 @@
+use crate::input::InputArgs;
+use crate::json::diagnostic_to_json;
+use crate::{parse_source, print_line, CommandResult};
+use clap::Clap;
+use lib_ruby_parser::nodes::FieldValue;
+use lib_ruby_parser::Node;
+use serde_json::{json, Value};
+use std::io;
+
+#[derive(Debug, Clap)]
+pub(crate) struct ParseArgs {
+    #[clap(
+        long,
+        default_value = "sexp",
+        possible_values = &["sexp", "json", "locations"],
+        about = "output format"
+    )]
+    format: String,
+
+    #[clap(flatten)]
+    input: InputArgs,
+}
+
+pub(crate) fn run(args: &ParseArgs) -> CommandResult {
+    args.input.each_source(&mut |name, code| {
+        let result = parse_source(name, code, false);
+
+        match &args.format[..] {
+            "json" => {
+                let ast = match &result.ast {
+                    Some(ast) => ast.to_json_value(),
+                    None => Value::Null,
+                };
+                let diagnostics = result
+                    .diagnostics
+                    .iter()
+                    .map(|d| diagnostic_to_json(d, &result.input))
+                    .collect::<Vec<_>>();
+                print_line(json!({ "file": name, "ast": ast, "diagnostics": diagnostics }))?;
+            }
+            format => {
+                for diagnostic in &result.diagnostics {
+                    if let Some(rendered) = diagnostic.render(&result.input) {
+                        eprintln!("{}", rendered);
+                    }
+                }
+                if let Some(ast) = &result.ast {
+                    if format == "locations" {
+                        print_line(String::from_utf8_lossy(code))?;
+                        print_with_locs(ast)?;
+                    } else {
+                        print_line(ast.inspect(0))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    })?;
+    Ok(true)
+}
+
+// Same output as `Node::print_with_locs`, but printed with `print_line`
+fn print_with_locs(node: &Node) -> io::Result<()> {
+    print_line(node.inspect(0))?;
+    for (name, value) in node.fields().into_iter().rev() {
+        match value {
+            FieldValue::Node(child) | FieldValue::MaybeNode(Some(child)) => print_with_locs(child)?,
+            FieldValue::Nodes(children) => {
+                for child in children {
+                    print_with_locs(child)?
+                }
+            }
+            FieldValue::Range(range) | FieldValue::MaybeRange(Some(range)) => print_line(format!(
+                "{}{} {}",
+                " ".repeat(range.begin_pos),
+                "~".repeat(range.size()),
+                name.strip_suffix("_l").unwrap_or(name)
+            ))?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use crate::input::InputArgs;
+use crate::json::token_to_json;
+use crate::{parse_source, print_line, CommandResult};
+use clap::Clap;
+use lib_ruby_parser::token_name;
+use serde_json::json;
+
+#[derive(Debug, Clap)]
+pub(crate) struct TokensArgs {
+    #[clap(
+        long,
+        default_value = "text",
+        possible_values = &["text", "json"],
+        about = "output format"
+    )]
+    format: String,
+
+    #[clap(flatten)]
+    input: InputArgs,
+}
+
+pub(crate) fn run(args: &TokensArgs) -> CommandResult {
+    args.input.each_source(&mut |name, code| {
+        let result = parse_source(name, code, true);
+
+        if args.format == "json" {
+            let tokens = result
+                .tokens
+                .iter()
+                .map(|token| token_to_json(token, &result.input))
+                .collect::<Vec<_>>();
+            print_line(json!({ "file": name, "tokens": tokens }))?;
+        } else {
+            for token in &result.tokens {
+                print_line(format!(
+                    "{} {:?} [{}, {}]",
+                    token_name(token.token_type),
+                    token.to_string_lossy(),
+                    token.loc.begin,
+                    token.loc.end
+                ))?;
+            }
+        }
+        Ok(())
+    })?;
+    Ok(true)
+}
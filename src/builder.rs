@@ -8,8 +8,8 @@ use crate::nodes::*;
 use crate::source::Range;
 use crate::StringValue;
 use crate::{
-    Context, CurrentArgStack, Lexer, Loc, MaxNumparamStack, Node, StaticEnvironment, Token,
-    VariablesStack,
+    Context, CurrentArgStack, Lexer, Loc, MaxNumparamStack, Node, RubyVersion, StaticEnvironment,
+    Token, VariablesStack,
 };
 use crate::{Diagnostic, DiagnosticMessage, ErrorLevel};
 
@@ -55,6 +55,17 @@ pub(crate) enum ArgsType {
     Numargs(u8),
 }
 
+impl ArgsType {
+    // Numbered parameters are used in the body, so a numblock without body
+    // can't be built and becomes a block without arguments
+    fn into_args(self) -> Option<Node> {
+        match self {
+            ArgsType::Args(args) => args,
+            ArgsType::Numargs(_) => None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct Builder {
     static_env: StaticEnvironment,
@@ -64,6 +75,7 @@ pub(crate) struct Builder {
     pattern_variables: VariablesStack,
     pattern_hash_keys: VariablesStack,
     diagnostics: Diagnostics,
+    ruby_version: RubyVersion,
 }
 
 impl Builder {
@@ -75,6 +87,7 @@ impl Builder {
         pattern_variables: VariablesStack,
         pattern_hash_keys: VariablesStack,
         diagnostics: Diagnostics,
+        ruby_version: RubyVersion,
     ) -> Self {
         Self {
             static_env,
@@ -84,6 +97,7 @@ impl Builder {
             pattern_variables,
             pattern_hash_keys,
             diagnostics,
+            ruby_version,
         }
     }
 
@@ -174,7 +188,7 @@ impl Builder {
                 inner.expression_l = new_operator_l.join(&inner.expression_l);
                 inner.operator_l = Some(new_operator_l);
             }
-            _ => unreachable!(),
+            _ => {}
         }
 
         numeric
@@ -241,7 +255,6 @@ impl Builder {
                 let value = inner.value.clone();
                 return self.str_node(begin_t, value, parts, end_t);
             }
-            [Node::Dstr(_)] | [Node::Heredoc(_)] => unreachable!(),
             _ => {}
         };
 
@@ -380,13 +393,10 @@ impl Builder {
     // Indented (interpolated, noninterpolated, executable) strings
 
     pub(crate) fn heredoc_dedent(&self, node: &mut Node, dedent_level: i32) {
-        if dedent_level == 0 {
-            return;
-        }
-
-        let dedent_level: usize = dedent_level
-            .try_into()
-            .expect("dedent_level must be positive");
+        let dedent_level: usize = match dedent_level.try_into() {
+            Ok(0) | Err(_) => return,
+            Ok(dedent_level) => dedent_level,
+        };
 
         let dedent_heredoc_parts = |parts: &mut Vec<Node>| {
             for part in parts.iter_mut() {
@@ -394,8 +404,8 @@ impl Builder {
                     Node::Str(inner) => {
                         Self::dedent_string(&mut inner.as_mut().value, dedent_level)
                     }
-                    Node::Begin(_) => {}
-                    _ => unreachable!("unsupported heredoc child {}", part.str_type()),
+                    // interpolated code and variables (like `#@ivar`) are kept as is
+                    _ => {}
                 }
             }
         };
@@ -407,7 +417,7 @@ impl Builder {
             Node::XHeredoc(heredoc) => {
                 dedent_heredoc_parts(&mut heredoc.parts);
             }
-            other => unreachable!("unsupported heredoc_dedent argument {}", other.str_type()),
+            _ => {}
         }
     }
 
@@ -443,12 +453,13 @@ impl Builder {
     // Regular expressions
 
     pub(crate) fn regexp_options(&self, regexp_end_t: Token) -> Option<Node> {
-        if regexp_end_t.loc.end - regexp_end_t.loc.begin == 1 {
+        let regexp_end_l = self.loc(&regexp_end_t);
+        let mut options = match value(regexp_end_t).get(1..) {
             // no regexp options, only trailing "/"
-            return None;
-        }
-        let expression_l = self.loc(&regexp_end_t).adjust_begin(1);
-        let mut options = value(regexp_end_t)[1..].chars().collect::<Vec<_>>();
+            None | Some("") => return None,
+            Some(options) => options.chars().collect::<Vec<_>>(),
+        };
+        let expression_l = regexp_end_l.adjust_begin(1);
         options.sort_unstable();
         options.dedup();
 
@@ -473,8 +484,7 @@ impl Builder {
             Some(Node::RegOpt(inner)) => {
                 self.validate_static_regexp(&parts, &inner.options, &expression_l)
             }
-            None => self.validate_static_regexp(&parts, &[], &expression_l),
-            _ => unreachable!("must be Option<RegOpt>"),
+            _ => self.validate_static_regexp(&parts, &[], &expression_l),
         };
         Node::Regexp(Box::new(Regexp {
             parts,
@@ -516,15 +526,9 @@ impl Builder {
     }
 
     pub(crate) fn word(&self, parts: Vec<Node>) -> Node {
-        match &parts[..] {
-            [Node::Str(_)] | [Node::Dstr(_)] => {
-                // collapse_string_parts? == true
-                return parts
-                    .into_iter()
-                    .next()
-                    .expect("parts is supposed to have exactly 1 element");
-            }
-            _ => {}
+        if let [Node::Str(_)] | [Node::Dstr(_)] = &parts[..] {
+            // collapse_string_parts? == true
+            return first(parts);
         }
 
         let (begin_l, end_l, expression_l) = self.collection_map(&None, &parts, &None);
@@ -1006,7 +1010,8 @@ impl Builder {
                 );
                 return Err(());
             }
-            _ => unreachable!("{:?} can't be used in assignment", node),
+            // not produced by the grammar, kept as is
+            other => other,
         };
 
         Ok(node)
@@ -1032,7 +1037,7 @@ impl Builder {
                     operator_l: None,
                 }))
             }
-            _ => unreachable!("unsupported const_op_assignable arument: {:?}", node),
+            other => other,
         }
     }
 
@@ -1074,22 +1079,14 @@ impl Builder {
             Node::Send(inner) => {
                 inner.expression_l = expr_l;
                 inner.operator_l = op_l;
-                if inner.args.is_empty() {
-                    inner.args = vec![new_rhs];
-                } else {
-                    unreachable!("can't assign to method call with args")
-                }
+                inner.args.push(new_rhs);
             }
             Node::CSend(inner) => {
                 inner.expression_l = expr_l;
                 inner.operator_l = op_l;
-                if inner.args.is_empty() {
-                    inner.args = vec![new_rhs];
-                } else {
-                    unreachable!("can't assign to method call with args")
-                }
+                inner.args.push(new_rhs);
             }
-            _ => unreachable!("{:?} can't be used in assignment", lhs),
+            _ => {}
         }
 
         lhs
@@ -1140,7 +1137,7 @@ impl Builder {
                 );
                 return Err(());
             }
-            _ => unreachable!("unsupported op_assign lhs {:?}", lhs),
+            _ => {}
         };
 
         let recv = lhs;
@@ -1199,10 +1196,20 @@ impl Builder {
     }
 
     pub(crate) fn rassign(&self, lhs: Node, eql_t: Token, rhs: Node) -> Node {
+        self.check_ruby_version(
+            "rightward assignment",
+            RubyVersion::Ruby30,
+            &self.loc(&eql_t),
+        );
         self.assign(rhs, eql_t, lhs)
     }
 
     pub(crate) fn multi_rassign(&self, lhs: Node, eql_t: Token, rhs: Node) -> Node {
+        self.check_ruby_version(
+            "rightward assignment",
+            RubyVersion::Ruby30,
+            &self.loc(&eql_t),
+        );
         self.multi_assign(rhs, eql_t, lhs)
     }
 
@@ -1318,16 +1325,19 @@ impl Builder {
         assignment_t: Token,
         body: Option<Node>,
     ) -> Result<Node, ()> {
-        let body_l = maybe_node_expr(&body.as_ref())
-            .unwrap_or_else(|| unreachable!("endless method always has a body"));
-
         let keyword_l = self.loc(&def_t);
-        let expression_l = keyword_l.join(&body_l);
         let name_l = self.loc(&name_t);
         let assignment_l = self.loc(&assignment_t);
+        let body_l = maybe_node_expr(&body.as_ref()).unwrap_or_else(|| assignment_l.clone());
+        let expression_l = keyword_l.join(&body_l);
 
         let name = value(name_t);
         self.check_reserved_for_numparam(&name, &name_l)?;
+        self.check_ruby_version(
+            "endless method definition",
+            RubyVersion::Ruby30,
+            &assignment_l,
+        );
 
         Ok(Node::Def(Box::new(Def {
             name,
@@ -1384,17 +1394,20 @@ impl Builder {
         assignment_t: Token,
         body: Option<Node>,
     ) -> Result<Node, ()> {
-        let body_l = maybe_node_expr(&body.as_ref())
-            .unwrap_or_else(|| unreachable!("endless method always has body"));
-
         let keyword_l = self.loc(&def_t);
         let operator_l = self.loc(&dot_t);
         let name_l = self.loc(&name_t);
         let assignment_l = self.loc(&assignment_t);
+        let body_l = maybe_node_expr(&body.as_ref()).unwrap_or_else(|| assignment_l.clone());
         let expression_l = keyword_l.join(&body_l);
 
         let name = value(name_t);
         self.check_reserved_for_numparam(&name, &name_l)?;
+        self.check_ruby_version(
+            "endless method definition",
+            RubyVersion::Ruby30,
+            &assignment_l,
+        );
 
         Ok(Node::Defs(Box::new(Defs {
             definee,
@@ -1639,7 +1652,12 @@ impl Builder {
                 begin_l: None,
                 end_l: None,
             })),
-            other => unreachable!("unsupported procarg0 child {:?}", other),
+            other => Node::Procarg0(Box::new(Procarg0 {
+                expression_l: other.expression().clone(),
+                args: vec![other],
+                begin_l: None,
+                end_l: None,
+            })),
         }
     }
 
@@ -1672,16 +1690,16 @@ impl Builder {
         args: Vec<Node>,
         rparen_t: Option<Token>,
     ) -> Node {
-        let begin_l = maybe_node_expr(&receiver.as_ref())
-            .or_else(|| self.maybe_loc(&selector_t))
-            .unwrap_or_else(|| unreachable!("can't compute begin_l"));
-        let end_l = self
-            .maybe_loc(&rparen_t)
-            .or_else(|| maybe_node_expr(&args.last()))
-            .or_else(|| self.maybe_loc(&selector_t))
-            .unwrap_or_else(|| unreachable!("can't compute end_l"));
-
-        let expression_l = begin_l.join(&end_l);
+        let expression_l = merge_maybe_locs(vec![
+            maybe_node_expr(&receiver.as_ref()),
+            self.maybe_loc(&dot_t),
+            self.maybe_loc(&selector_t),
+            self.maybe_loc(&lparen_t),
+            collection_expr(&args),
+            self.maybe_loc(&rparen_t),
+        ])
+        // the grammar always gives a receiver or a method name
+        .unwrap_or_else(|| Range::new(0, 0));
 
         let dot_l = self.maybe_loc(&dot_t);
         let selector_l = self.maybe_loc(&selector_t);
@@ -1690,25 +1708,26 @@ impl Builder {
 
         let method_name = maybe_value(selector_t).unwrap_or_else(|| "call".to_owned());
 
-        match self.call_type_for_dot(&dot_t) {
-            MethodCallType::Send => Node::Send(Box::new(Send {
+        match (self.call_type_for_dot(&dot_t), receiver, dot_l) {
+            (MethodCallType::CSend, Some(recv), Some(dot_l)) => Node::CSend(Box::new(CSend {
                 method_name,
-                recv: receiver,
+                recv,
                 args,
+                // `foo&.()` has no method name, it's an empty range after `&.`
+                selector_l: selector_l.unwrap_or_else(|| dot_l.end()),
                 dot_l,
-                selector_l,
                 begin_l,
                 end_l,
                 operator_l: None,
                 expression_l,
             })),
 
-            MethodCallType::CSend => Node::CSend(Box::new(CSend {
+            (_, recv, dot_l) => Node::Send(Box::new(Send {
                 method_name,
-                recv: receiver.expect("csend node must have a receiver"),
+                recv,
                 args,
-                dot_l: dot_l.expect("csend node must have &."),
-                selector_l: selector_l.expect("csend node must have a method name"),
+                dot_l,
+                selector_l,
                 begin_l,
                 end_l,
                 operator_l: None,
@@ -1779,19 +1798,19 @@ impl Builder {
             let end_l = self.loc(&end_t);
             let expression_l = actual_send.expression().join(&end_l);
 
-            let block = match block_args {
-                ArgsType::Args(args) => Node::Block(Box::new(Block {
+            let block = match (block_args, block_body) {
+                (ArgsType::Numargs(numargs), Some(body)) => Node::Numblock(Box::new(Numblock {
                     call: actual_send,
-                    args,
-                    body: block_body,
+                    numargs,
+                    body,
                     begin_l,
                     end_l,
                     expression_l,
                 })),
-                ArgsType::Numargs(numargs) => Node::Numblock(Box::new(Numblock {
+                (block_args, body) => Node::Block(Box::new(Block {
                     call: actual_send,
-                    numargs,
-                    body: block_body.expect("numblock always has body"),
+                    args: block_args.into_args(),
+                    body,
                     begin_l,
                     end_l,
                     expression_l,
@@ -1804,42 +1823,8 @@ impl Builder {
             (args, expr_l)
         };
 
-        match &method_call {
-            Node::Send(_)
-            | Node::CSend(_)
-            | Node::Index(_)
-            | Node::Super(_)
-            | Node::ZSuper(_)
-            | Node::Lambda(_) => {
-                let begin_l = self.loc(&begin_t);
-                let end_l = self.loc(&end_t);
-                let expression_l = method_call.expression().join(&end_l);
-
-                let result = match block_args {
-                    ArgsType::Args(args) => Node::Block(Box::new(Block {
-                        call: method_call,
-                        args,
-                        body: block_body,
-                        begin_l,
-                        end_l,
-                        expression_l,
-                    })),
-                    ArgsType::Numargs(numargs) => Node::Numblock(Box::new(Numblock {
-                        numargs,
-                        call: method_call,
-                        body: block_body.expect("numblock always has body"),
-                        begin_l,
-                        end_l,
-                        expression_l,
-                    })),
-                };
-                return Ok(result);
-            }
-            _ => {}
-        };
-
         let result = match method_call {
-            Node::Return(inner) => {
+            Node::Return(inner) if !inner.args.is_empty() => {
                 let Return {
                     args,
                     keyword_l,
@@ -1853,7 +1838,7 @@ impl Builder {
                     expression_l,
                 }))
             }
-            Node::Next(inner) => {
+            Node::Next(inner) if !inner.args.is_empty() => {
                 let Next {
                     args,
                     keyword_l,
@@ -1867,7 +1852,7 @@ impl Builder {
                     expression_l,
                 }))
             }
-            Node::Break(inner) => {
+            Node::Break(inner) if !inner.args.is_empty() => {
                 let Break {
                     args,
                     keyword_l,
@@ -1881,7 +1866,32 @@ impl Builder {
                     expression_l,
                 }))
             }
-            _ => unreachable!("unsupported method call {:?}", method_call),
+            method_call => {
+                let begin_l = self.loc(&begin_t);
+                let end_l = self.loc(&end_t);
+                let expression_l = method_call.expression().join(&end_l);
+
+                match (block_args, block_body) {
+                    (ArgsType::Numargs(numargs), Some(body)) => {
+                        Node::Numblock(Box::new(Numblock {
+                            numargs,
+                            call: method_call,
+                            body,
+                            begin_l,
+                            end_l,
+                            expression_l,
+                        }))
+                    }
+                    (block_args, body) => Node::Block(Box::new(Block {
+                        call: method_call,
+                        args: block_args.into_args(),
+                        body,
+                        begin_l,
+                        end_l,
+                        expression_l,
+                    })),
+                }
+            }
         };
 
         Ok(result)
@@ -2191,15 +2201,11 @@ impl Builder {
         cond_t: Token,
         cond: Node,
     ) -> Node {
-        let pre = match (&if_true, &if_false) {
-            (None, None) => unreachable!("at least one of if_true/if_false is required"),
-            (None, Some(if_false)) => if_false,
-            (Some(if_true), None) => if_true,
-            (Some(_), Some(_)) => unreachable!("only one of if_true/if_false is required"),
-        };
-
-        let expression_l = pre.expression().join(&cond.expression());
         let keyword_l = self.loc(&cond_t);
+        let pre_l = maybe_node_expr(&if_true.as_ref())
+            .or_else(|| maybe_node_expr(&if_false.as_ref()))
+            .unwrap_or_else(|| keyword_l.clone());
+        let expression_l = pre_l.join(&cond.expression());
 
         Node::IfMod(Box::new(IfMod {
             cond: self.check_condition(cond),
@@ -2428,13 +2434,27 @@ impl Builder {
                 keyword_l,
                 expression_l,
             })),
-            KeywordCmd::Defined => Node::Defined(Box::new(Defined {
-                value: args.pop().expect("defined? always has an argument"),
-                keyword_l,
-                begin_l,
-                end_l,
-                expression_l,
-            })),
+            KeywordCmd::Defined => match args.pop() {
+                Some(value) => Node::Defined(Box::new(Defined {
+                    value,
+                    keyword_l,
+                    begin_l,
+                    end_l,
+                    expression_l,
+                })),
+                // the grammar always gives an argument, otherwise it's a plain method call
+                None => Node::Send(Box::new(Send {
+                    method_name: "defined?".to_owned(),
+                    recv: None,
+                    args,
+                    dot_l: None,
+                    selector_l: Some(keyword_l),
+                    begin_l,
+                    end_l,
+                    operator_l: None,
+                    expression_l,
+                })),
+            },
             KeywordCmd::Next => Node::Next(Box::new(Next {
                 args,
                 keyword_l,
@@ -2552,16 +2572,16 @@ impl Builder {
     ) -> Option<Node> {
         let mut result: Option<Node>;
 
-        if !rescue_bodies.is_empty() {
+        if let Some(rescue_bodies_l) = collection_expr(&rescue_bodies) {
+            let begin_l =
+                maybe_node_expr(&compound_stmt.as_ref()).unwrap_or_else(|| rescue_bodies_l.clone());
+
             if let Some((else_t, else_)) = else_ {
-                let begin_l = maybe_node_expr(&compound_stmt.as_ref())
-                    .or_else(|| maybe_node_expr(&rescue_bodies.first()))
-                    .unwrap_or_else(|| unreachable!("can't compute begin_l"));
+                let else_l = self.loc(&else_t);
 
-                let end_l = maybe_node_expr(&else_.as_ref()).unwrap_or_else(|| self.loc(&else_t));
+                let end_l = maybe_node_expr(&else_.as_ref()).unwrap_or_else(|| else_l.clone());
 
                 let expression_l = begin_l.join(&end_l);
-                let else_l = self.loc(&else_t);
 
                 result = Some(Node::Rescue(Box::new(Rescue {
                     body: compound_stmt,
@@ -2571,14 +2591,7 @@ impl Builder {
                     expression_l,
                 })))
             } else {
-                let begin_l = maybe_node_expr(&compound_stmt.as_ref())
-                    .or_else(|| maybe_node_expr(&rescue_bodies.first()))
-                    .unwrap_or_else(|| unreachable!("can't compute begin_l"));
-
-                let end_l = maybe_node_expr(&rescue_bodies.last())
-                    .unwrap_or_else(|| unreachable!("can't compute end_l"));
-
-                let expression_l = begin_l.join(&end_l);
+                let expression_l = begin_l.join(&rescue_bodies_l);
                 let else_l = self.maybe_loc(&None);
 
                 result = Some(Node::Rescue(Box::new(Rescue {
@@ -2904,19 +2917,11 @@ impl Builder {
                 self.static_env.declare(&name);
 
                 if let Some(begin_l) = &begin_l {
-                    let begin_pos_d: i32 = begin_l
-                        .size()
-                        .try_into()
-                        .expect("failed to convert usize loc into i32, is it too big?");
-                    name_l = name_l.adjust_begin(begin_pos_d)
+                    name_l = name_l.with_begin(begin_l.end_pos)
                 }
 
                 if let Some(end_l) = &end_l {
-                    let end_pos_d: i32 = end_l
-                        .size()
-                        .try_into()
-                        .expect("failed to convert usize loc into i32, is it too big?");
-                    name_l = name_l.adjust_end(-end_pos_d)
+                    name_l = name_l.with_end(end_l.begin_pos)
                 }
 
                 let expression_l = self
@@ -3018,6 +3023,7 @@ impl Builder {
         rbrack_t: Option<Token>,
     ) -> Node {
         let (begin_l, end_l, expression_l) = self.collection_map(&lbrack_t, &elements, &rbrack_t);
+        self.check_ruby_version("find pattern", RubyVersion::Ruby30, &expression_l);
         Node::FindPattern(Box::new(FindPattern {
             elements,
             begin_l,
@@ -3221,8 +3227,7 @@ impl Builder {
                 Node::Procarg0(inner) => {
                     self.check_duplicate_args(&inner.args, map);
                 }
-                Node::ForwardArg(_) | Node::Kwnilarg(_) => {}
-                _ => unreachable!("unsupported arg type {:?}", arg),
+                _ => {}
             }
         }
     }
@@ -3237,7 +3242,7 @@ impl Builder {
             Node::Blockarg(inner) => Some(&inner.name),
             Node::Restarg(inner) => inner.name.as_ref(),
             Node::Kwrestarg(inner) => inner.name.as_ref(),
-            _ => unreachable!("unsupported arg {:?}", node),
+            _ => None,
         }
     }
 
@@ -3251,8 +3256,7 @@ impl Builder {
             Node::Blockarg(inner) => &inner.name_l,
             Node::Restarg(inner) => inner.name_l.as_ref().unwrap_or(&inner.expression_l),
             Node::Kwrestarg(inner) => inner.name_l.as_ref().unwrap_or(&inner.expression_l),
-
-            _ => unreachable!("unsupported arg {:?}", node),
+            _ => node.expression(),
         }
     }
 
@@ -3319,18 +3323,15 @@ impl Builder {
     }
 
     pub(crate) fn arg_name_collides(&self, this_name: &str, that_name: &str) -> bool {
-        &this_name[0..1] != "_" && this_name == that_name
+        !this_name.starts_with('_') && this_name == that_name
     }
 
     pub(crate) fn check_lvar_name(&self, name: &str, loc: &Range) -> Result<(), ()> {
-        let first = name
-            .chars()
-            .next()
-            .expect("local variable name can't be empty");
-        let rest = &name[1..];
-
-        if (first.is_lowercase() || first == '_')
-            && rest.chars().all(|c| c.is_alphanumeric() || c == '_')
+        let mut chars = name.chars();
+        let first = chars.next();
+
+        if first.map_or(false, |c| c.is_lowercase() || c == '_')
+            && chars.all(|c| c.is_alphanumeric() || c == '_')
         {
             Ok(())
         } else {
@@ -3495,9 +3496,8 @@ impl Builder {
             collection_expr(&parts),
             end_l.clone(),
         ])
-        .unwrap_or_else(|| {
-            unreachable!("empty collection without begin_t/end_t, can't build source map")
-        });
+        // the grammar never builds empty collections without begin_t/end_t
+        .unwrap_or_else(|| Range::new(0, 0));
 
         (begin_l, end_l, expr_l)
     }
@@ -3508,11 +3508,8 @@ impl Builder {
         parts: &[Node],
         end_t: &Option<Token>,
     ) -> StringMap {
-        if let Some(begin_t) = begin_t {
+        if let (Some(begin_t), Some(end_t)) = (begin_t, end_t) {
             if clone_value(&begin_t).starts_with("<<") {
-                let end_t = end_t
-                    .as_ref()
-                    .unwrap_or_else(|| unreachable!("heredoc must have end_t"));
                 let heredoc_body_l = collection_expr(&parts).unwrap_or_else(|| self.loc(end_t));
                 let expression_l = self.loc(begin_t);
                 let heredoc_end_l = self.loc(end_t);
@@ -3534,6 +3531,18 @@ impl Builder {
             .emit(Diagnostic::new(ErrorLevel::Warning, message, range))
     }
 
+    pub(crate) fn check_ruby_version(&self, feature: &str, since: RubyVersion, range: &Range) {
+        if self.ruby_version < since {
+            self.error(
+                DiagnosticMessage::UnsupportedInRubyVersion {
+                    feature: feature.to_owned(),
+                    ruby_version: self.ruby_version,
+                },
+                range.clone(),
+            );
+        }
+    }
+
     pub(crate) fn value_expr(&self, node: &Node) -> Result<(), ()> {
         if let Some(void_node) = self.void_value(node) {
             self.error(
@@ -0,0 +1,298 @@
+use super::tree::{isomorphic, Tree};
+
+/// Subtrees lower than this are matched only by the bottom-up phase
+const MIN_HEIGHT: usize = 1;
+
+/// Minimal ratio of common descendants for two containers to be matched
+const MIN_DICE: f64 = 0.5;
+
+/// Mapping between nodes of two trees, built with the GumTree algorithm:
+/// a greedy top-down phase matches the largest isomorphic subtrees,
+/// a bottom-up phase matches containers that share many matched descendants.
+pub(crate) struct Mapping {
+    old_to_new: Vec<Option<usize>>,
+    new_to_old: Vec<Option<usize>>,
+}
+
+impl Mapping {
+    pub(crate) fn new(old: &Tree, new: &Tree) -> Self {
+        let mut mapping = Self {
+            old_to_new: vec![None; old.len()],
+            new_to_old: vec![None; new.len()],
+        };
+        mapping.top_down(old, new);
+        mapping.bottom_up(old, new);
+        mapping
+    }
+
+    pub(crate) fn old_to_new(&self, old_id: usize) -> Option<usize> {
+        self.old_to_new[old_id]
+    }
+
+    pub(crate) fn new_to_old(&self, new_id: usize) -> Option<usize> {
+        self.new_to_old[new_id]
+    }
+
+    fn add(&mut self, old_id: usize, new_id: usize) {
+        self.old_to_new[old_id] = Some(new_id);
+        self.new_to_old[new_id] = Some(old_id);
+    }
+
+    fn add_isomorphic(&mut self, old: &Tree, old_id: usize, new: &Tree, new_id: usize) {
+        for (o, n) in
+            (old_id..old_id + old.nodes[old_id].size).zip(new_id..new_id + new.nodes[new_id].size)
+        {
+            self.add(o, n);
+        }
+    }
+
+    fn top_down(&mut self, old: &Tree, new: &Tree) {
+        let mut old_queue = vec![0];
+        let mut new_queue = vec![0];
+        let mut ambiguous = vec![];
+
+        loop {
+            let old_height = max_height(old, &old_queue);
+            let new_height = max_height(new, &new_queue);
+            let height = old_height.min(new_height);
+            if height < MIN_HEIGHT {
+                break;
+            }
+
+            if old_height != new_height {
+                if old_height > new_height {
+                    open(old, &mut old_queue, old_height);
+                } else {
+                    open(new, &mut new_queue, new_height);
+                }
+                continue;
+            }
+
+            let old_ids = take_with_height(old, &mut old_queue, height);
+            let new_ids = take_with_height(new, &mut new_queue, height);
+
+            let mut matched_old = vec![false; old_ids.len()];
+            let mut matched_new = vec![false; new_ids.len()];
+            for (i, old_id) in old_ids.iter().enumerate() {
+                let candidates = new_ids
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, new_id)| isomorphic(old, *old_id, new, **new_id))
+                    .collect::<Vec<_>>();
+                if candidates.is_empty() {
+                    continue;
+                }
+                matched_old[i] = true;
+                for (j, _) in candidates.iter() {
+                    matched_new[*j] = true;
+                }
+
+                let (_, new_id) = candidates[0];
+                let unique_in_old = old_ids
+                    .iter()
+                    .filter(|other| isomorphic(old, **other, new, *new_id))
+                    .count()
+                    == 1;
+                if candidates.len() == 1 && unique_in_old {
+                    self.add_isomorphic(old, *old_id, new, *new_id);
+                } else {
+                    for (_, new_id) in candidates {
+                        ambiguous.push((*old_id, *new_id));
+                    }
+                }
+            }
+
+            for (i, old_id) in old_ids.iter().enumerate() {
+                if !matched_old[i] {
+                    old_queue.extend(old.nodes[*old_id].children.iter());
+                }
+            }
+            for (j, new_id) in new_ids.iter().enumerate() {
+                if !matched_new[j] {
+                    new_queue.extend(new.nodes[*new_id].children.iter());
+                }
+            }
+        }
+
+        // Among several equal candidates prefer the ones with similar parents,
+        // then keep their relative order
+        let mut ambiguous = ambiguous
+            .into_iter()
+            .map(|(old_id, new_id)| {
+                let parents_dice = match (old.nodes[old_id].parent, new.nodes[new_id].parent) {
+                    (Some(old_parent), Some(new_parent)) => {
+                        self.dice(old, old_parent, new, new_parent)
+                    }
+                    _ => 0.0,
+                };
+                (parents_dice, old_id, new_id)
+            })
+            .collect::<Vec<_>>();
+        ambiguous.sort_by(|a, b| {
+            b.0.partial_cmp(&a.0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then((a.1, a.2).cmp(&(b.1, b.2)))
+        });
+        for (_, old_id, new_id) in ambiguous {
+            if self.is_unmatched_subtree(old, old_id, new, new_id) {
+                self.add_isomorphic(old, old_id, new, new_id);
+            }
+        }
+    }
+
+    fn bottom_up(&mut self, old: &Tree, new: &Tree) {
+        for old_id in old.post_order() {
+            if self.old_to_new[old_id].is_some() {
+                continue;
+            }
+
+            let new_id = if old_id == 0 {
+                Some(0).filter(|new_id| {
+                    self.new_to_old[*new_id].is_none()
+                        && old.node_type(old_id) == new.node_type(*new_id)
+                })
+            } else {
+                self.best_container(old, old_id, new)
+            };
+
+            if let Some(new_id) = new_id {
+                self.add(old_id, new_id);
+                self.recover(old, old_id, new, new_id);
+            }
+        }
+    }
+
+    /// Finds an unmatched node of the same type that has the most matched descendants in common
+    fn best_container(&self, old: &Tree, old_id: usize, new: &Tree) -> Option<usize> {
+        let mut candidates = old
+            .descendants(old_id)
+            .filter_map(|id| self.old_to_new[id])
+            .flat_map(|new_id| Ancestors::new(new, new_id))
+            .filter(|new_id| {
+                self.new_to_old[*new_id].is_none()
+                    && old.node_type(old_id) == new.node_type(*new_id)
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        candidates
+            .into_iter()
+            .map(|new_id| (self.dice(old, old_id, new, new_id), new_id))
+            .filter(|(dice, _)| *dice >= MIN_DICE)
+            .fold(None, |best: Option<(f64, usize)>, candidate| match best {
+                Some(best) if best.0 >= candidate.0 => Some(best),
+                _ => Some(candidate),
+            })
+            .map(|(_, new_id)| new_id)
+    }
+
+    /// Matches remaining children of two matched containers:
+    /// first equal subtrees, then nodes of the same type
+    fn recover(&mut self, old: &Tree, old_id: usize, new: &Tree, new_id: usize) {
+        for old_child in old.nodes[old_id].children.iter().copied() {
+            if self.old_to_new[old_child].is_some() {
+                continue;
+            }
+            let found = new.nodes[new_id]
+                .children
+                .iter()
+                .copied()
+                .find(|new_child| {
+                    self.new_to_old[*new_child].is_none()
+                        && isomorphic(old, old_child, new, *new_child)
+                        && self.is_unmatched_subtree(old, old_child, new, *new_child)
+                });
+            if let Some(new_child) = found {
+                self.add_isomorphic(old, old_child, new, new_child);
+            }
+        }
+
+        for old_child in old.nodes[old_id].children.iter().copied() {
+            if self.old_to_new[old_child].is_some() {
+                continue;
+            }
+            let found = new.nodes[new_id]
+                .children
+                .iter()
+                .copied()
+                .find(|new_child| {
+                    self.new_to_old[*new_child].is_none()
+                        && old.node_type(old_child) == new.node_type(*new_child)
+                });
+            if let Some(new_child) = found {
+                self.add(old_child, new_child);
+                self.recover(old, old_child, new, new_child);
+            }
+        }
+    }
+
+    fn is_unmatched_subtree(&self, old: &Tree, old_id: usize, new: &Tree, new_id: usize) -> bool {
+        (old_id..old_id + old.nodes[old_id].size).all(|id| self.old_to_new[id].is_none())
+            && (new_id..new_id + new.nodes[new_id].size).all(|id| self.new_to_old[id].is_none())
+    }
+
+    /// Ratio of matched descendants shared by two subtrees
+    fn dice(&self, old: &Tree, old_id: usize, new: &Tree, new_id: usize) -> f64 {
+        let new_descendants = new.descendants(new_id);
+        let common = old
+            .descendants(old_id)
+            .filter_map(|id| self.old_to_new[id])
+            .filter(|id| new_descendants.contains(id))
+            .count();
+        let total = old.nodes[old_id].size + new.nodes[new_id].size - 2;
+        if total == 0 {
+            0.0
+        } else {
+            2.0 * common as f64 / total as f64
+        }
+    }
+}
+
+fn max_height(tree: &Tree, queue: &[usize]) -> usize {
+    queue
+        .iter()
+        .map(|id| tree.nodes[*id].height)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Replaces nodes of the given height with their children
+fn open(tree: &Tree, queue: &mut Vec<usize>, height: usize) {
+    for id in take_with_height(tree, queue, height) {
+        queue.extend(tree.nodes[id].children.iter());
+    }
+}
+
+fn take_with_height(tree: &Tree, queue: &mut Vec<usize>, height: usize) -> Vec<usize> {
+    let (taken, rest) = queue
+        .iter()
+        .partition(|id| tree.nodes[**id].height == height);
+    *queue = rest;
+    taken
+}
+
+/// Iterator over strict ancestors of a node
+struct Ancestors<'t, 'a> {
+    tree: &'t Tree<'a>,
+    current: Option<usize>,
+}
+
+impl<'t, 'a> Ancestors<'t, 'a> {
+    fn new(tree: &'t Tree<'a>, id: usize) -> Self {
+        Self {
+            tree,
+            current: tree.nodes[id].parent,
+        }
+    }
+}
+
+impl Iterator for Ancestors<'_, '_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let id = self.current?;
+        self.current = self.tree.nodes[id].parent;
+        Some(id)
+    }
+}
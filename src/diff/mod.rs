@@ -0,0 +1,233 @@
+//! Structural diff of two ASTs.
+//!
+//! Nodes of the old and the new tree are matched with a GumTree-style algorithm
+//! (equal subtrees first, then containers that share most of their content),
+//! and the mapping is turned into a list of `Change`s. Locations are not compared,
+//! so re-formatting or shifting code around produces no changes as long as
+//! the structure stays the same.
+//!
+//! ```
+//! use lib_ruby_parser::{diff, Parser, ParserOptions};
+//!
+//! let old = Parser::new(b"foo(1)", ParserOptions::default()).do_parse();
+//! let new = Parser::new(b"foo(2, 3)", ParserOptions::default()).do_parse();
+//!
+//! let changes = diff::diff(old.ast.as_ref(), new.ast.as_ref());
+//! let changes = changes.iter().map(|c| c.inspect()).collect::<Vec<_>>();
+//! assert_eq!(changes, vec!["update int 4...5 -> 4...5", "insert int 7...8"]);
+//! ```
+
+mod matcher;
+use matcher::Mapping;
+
+mod tree;
+use tree::Tree;
+
+use crate::source::Range;
+use crate::Node;
+
+/// Kind of a single change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// A subtree exists only in the new tree
+    Insert,
+    /// A subtree exists only in the old tree
+    Remove,
+    /// A subtree was moved to another parent or re-ordered within its parent
+    Move,
+    /// Names or values of a node have changed, or one of its optional children
+    /// has been set or unset (the children themselves are reported separately)
+    Update,
+}
+
+/// A change between two trees
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub kind: ChangeKind,
+
+    /// Type of the changed node, like `"send"` or `"int"`
+    pub node_type: &'static str,
+
+    /// Expression location of the node in the old source, `None` for `Insert`
+    pub old_range: Option<Range>,
+
+    /// Expression location of the node in the new source, `None` for `Remove`
+    pub new_range: Option<Range>,
+}
+
+impl Change {
+    /// Returns a one-line representation like `update int 4...5 -> 4...5`
+    pub fn inspect(&self) -> String {
+        let kind = match self.kind {
+            ChangeKind::Insert => "insert",
+            ChangeKind::Remove => "remove",
+            ChangeKind::Move => "move",
+            ChangeKind::Update => "update",
+        };
+        match (&self.old_range, &self.new_range) {
+            (Some(old), Some(new)) => format!("{} {} {:?} -> {:?}", kind, self.node_type, old, new),
+            (Some(range), None) | (None, Some(range)) => {
+                format!("{} {} {:?}", kind, self.node_type, range)
+            }
+            (None, None) => format!("{} {}", kind, self.node_type),
+        }
+    }
+}
+
+/// Computes changes between two ASTs.
+///
+/// Removals are returned first (in the order of the old source),
+/// followed by insertions, moves and updates (in the order of the new source).
+/// Only the topmost node of an inserted or removed subtree is reported.
+pub fn diff(old: Option<&Node>, new: Option<&Node>) -> Vec<Change> {
+    match (old, new) {
+        (None, None) => vec![],
+        (Some(old), None) => vec![change(ChangeKind::Remove, old, None)],
+        (None, Some(new)) => vec![change(ChangeKind::Insert, new, None)],
+        (Some(old), Some(new)) => {
+            let old = Tree::new(old);
+            let new = Tree::new(new);
+            let mapping = Mapping::new(&old, &new);
+            edit_script(&old, &new, &mapping)
+        }
+    }
+}
+
+fn change(kind: ChangeKind, node: &Node, other: Option<&Node>) -> Change {
+    let range = node.expression().clone();
+    let (old_range, new_range) = match (kind, other) {
+        (ChangeKind::Remove, _) => (Some(range), None),
+        (ChangeKind::Insert, _) => (None, Some(range)),
+        (_, Some(old)) => (Some(old.expression().clone()), Some(range)),
+        (_, None) => (None, Some(range)),
+    };
+    Change {
+        kind,
+        node_type: node.str_type(),
+        old_range,
+        new_range,
+    }
+}
+
+fn edit_script(old: &Tree, new: &Tree, mapping: &Mapping) -> Vec<Change> {
+    let mut changes = vec![];
+
+    for (old_id, old_node) in old.nodes.iter().enumerate() {
+        let parent_is_mapped = old_node
+            .parent
+            .map(|parent| mapping.old_to_new(parent).is_some())
+            .unwrap_or(true);
+        if mapping.old_to_new(old_id).is_none() && parent_is_mapped {
+            changes.push(change(ChangeKind::Remove, old_node.node, None));
+        }
+    }
+
+    let reordered = reordered_nodes(old, new, mapping);
+
+    for (new_id, new_node) in new.nodes.iter().enumerate() {
+        let old_id = match mapping.new_to_old(new_id) {
+            Some(old_id) => old_id,
+            None => {
+                let parent_is_mapped = new_node
+                    .parent
+                    .map(|parent| mapping.new_to_old(parent).is_some())
+                    .unwrap_or(true);
+                if parent_is_mapped {
+                    changes.push(change(ChangeKind::Insert, new_node.node, None));
+                }
+                continue;
+            }
+        };
+        let old_node = &old.nodes[old_id];
+
+        let moved_to_another_parent = match (old_node.parent, new_node.parent) {
+            (Some(old_parent), Some(new_parent)) => {
+                mapping.old_to_new(old_parent) != Some(new_parent)
+            }
+            (None, None) => false,
+            _ => true,
+        };
+        if moved_to_another_parent || reordered[new_id] {
+            changes.push(change(ChangeKind::Move, new_node.node, Some(old_node.node)));
+        }
+
+        if old_node.label != new_node.label {
+            changes.push(change(
+                ChangeKind::Update,
+                new_node.node,
+                Some(old_node.node),
+            ));
+        }
+    }
+
+    changes
+}
+
+/// Marks nodes of the new tree that stayed under the same parent but changed their order
+/// (i.e. are not a part of the longest common subsequence of children)
+fn reordered_nodes(old: &Tree, new: &Tree, mapping: &Mapping) -> Vec<bool> {
+    let mut reordered = vec![false; new.len()];
+
+    for (new_parent, new_node) in new.nodes.iter().enumerate() {
+        let old_parent = match mapping.new_to_old(new_parent) {
+            Some(old_parent) => old_parent,
+            None => continue,
+        };
+
+        let old_children = old.nodes[old_parent]
+            .children
+            .iter()
+            .filter_map(|old_child| mapping.old_to_new(*old_child))
+            .filter(|new_child| new.nodes[*new_child].parent == Some(new_parent))
+            .collect::<Vec<_>>();
+        let new_children = new_node
+            .children
+            .iter()
+            .copied()
+            .filter(|new_child| {
+                mapping
+                    .new_to_old(*new_child)
+                    .map(|old_child| old.nodes[old_child].parent == Some(old_parent))
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+
+        let in_lcs = lcs(&old_children, &new_children);
+        for (new_child, in_lcs) in new_children.iter().zip(in_lcs) {
+            if !in_lcs {
+                reordered[*new_child] = true;
+            }
+        }
+    }
+
+    reordered
+}
+
+/// Returns a flag for every item of `b` showing whether it's a part of the LCS of `a` and `b`
+fn lcs(a: &[usize], b: &[usize]) -> Vec<bool> {
+    let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            table[i][j] = if a[i] == b[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = vec![false; b.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            result[j] = true;
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    result
+}
@@ -0,0 +1,140 @@
+use crate::nodes::FieldValue;
+use crate::Node;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Node of a flattened tree, all references are indexes in `Tree::nodes`
+pub(crate) struct TreeNode<'a> {
+    pub(crate) node: &'a Node,
+    pub(crate) parent: Option<usize>,
+    pub(crate) children: Vec<usize>,
+    /// Non-location fields of the node: names and values,
+    /// and which node fields are set (like `recv=nil;` or `recv=node;`)
+    pub(crate) label: String,
+    /// Hash of the type, label, sizes of node lists and children hashes, locations are ignored
+    pub(crate) hash: u64,
+    /// 1 for leaves
+    pub(crate) height: usize,
+    /// Number of nodes in the subtree (including the node itself)
+    pub(crate) size: usize,
+}
+
+/// A tree with nodes stored in pre-order
+pub(crate) struct Tree<'a> {
+    pub(crate) nodes: Vec<TreeNode<'a>>,
+}
+
+impl<'a> Tree<'a> {
+    pub(crate) fn new(root: &'a Node) -> Self {
+        let mut tree = Self { nodes: vec![] };
+        tree.push(root, None);
+        tree
+    }
+
+    fn push(&mut self, node: &'a Node, parent: Option<usize>) -> usize {
+        let id = self.nodes.len();
+        let mut label = String::new();
+        // Sizes of node lists are not a part of the label (inserting an argument
+        // is not an update of the call), but they tell which field a child belongs to
+        let mut list_sizes = vec![];
+        let mut children = vec![];
+        for (name, value) in node.fields() {
+            match value {
+                FieldValue::Node(node) | FieldValue::MaybeNode(Some(node)) => {
+                    label.push_str(&format!("{}=node;", name));
+                    children.push(node)
+                }
+                FieldValue::MaybeNode(None) => label.push_str(&format!("{}=nil;", name)),
+                FieldValue::Nodes(nodes) => {
+                    label.push_str(&format!("{}=[..];", name));
+                    list_sizes.push(nodes.len());
+                    children.extend(nodes.iter())
+                }
+                FieldValue::Range(_) | FieldValue::MaybeRange(_) => continue,
+                FieldValue::Str(value) => label.push_str(&format!("{}={:?};", name, value)),
+                FieldValue::MaybeStr(value) => label.push_str(&format!("{}={:?};", name, value)),
+                FieldValue::Chars(value) => label.push_str(&format!("{}={:?};", name, value)),
+                FieldValue::StringValue(value) => {
+                    label.push_str(&format!("{}={:?};", name, value.as_bytes()))
+                }
+                FieldValue::U8(value) => label.push_str(&format!("{}={};", name, value)),
+                FieldValue::Usize(value) => label.push_str(&format!("{}={};", name, value)),
+            }
+        }
+
+        self.nodes.push(TreeNode {
+            node,
+            parent,
+            children: vec![],
+            label,
+            hash: 0,
+            height: 1,
+            size: 1,
+        });
+
+        let children = children
+            .into_iter()
+            .map(|child| self.push(child, Some(id)))
+            .collect::<Vec<_>>();
+
+        let mut hasher = DefaultHasher::new();
+        node.str_type().hash(&mut hasher);
+        self.nodes[id].label.hash(&mut hasher);
+        list_sizes.hash(&mut hasher);
+        for child in children.iter() {
+            self.nodes[*child].hash.hash(&mut hasher);
+        }
+
+        let height = 1 + children
+            .iter()
+            .map(|child| self.nodes[*child].height)
+            .max()
+            .unwrap_or(0);
+        let size = 1 + children
+            .iter()
+            .map(|child| self.nodes[*child].size)
+            .sum::<usize>();
+
+        let tree_node = &mut self.nodes[id];
+        tree_node.children = children;
+        tree_node.hash = hasher.finish();
+        tree_node.height = height;
+        tree_node.size = size;
+        id
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub(crate) fn node_type(&self, id: usize) -> &'static str {
+        self.nodes[id].node.str_type()
+    }
+
+    /// Ids of all nodes in the subtree (excluding `id` itself), pre-order makes them contiguous
+    pub(crate) fn descendants(&self, id: usize) -> std::ops::Range<usize> {
+        id + 1..id + self.nodes[id].size
+    }
+
+    pub(crate) fn post_order(&self) -> Vec<usize> {
+        let mut ids = (0..self.len()).collect::<Vec<_>>();
+        // a node goes after all of its descendants
+        ids.sort_by_key(|id| (id + self.nodes[*id].size, std::cmp::Reverse(*id)));
+        ids
+    }
+}
+
+/// Returns true if two subtrees are equal ignoring locations
+pub(crate) fn isomorphic(t1: &Tree, id1: usize, t2: &Tree, id2: usize) -> bool {
+    let (n1, n2) = (&t1.nodes[id1], &t2.nodes[id2]);
+    n1.hash == n2.hash
+        && n1.size == n2.size
+        && n1.node.str_type() == n2.node.str_type()
+        && n1.label == n2.label
+        && n1.children.len() == n2.children.len()
+        && n1
+            .children
+            .iter()
+            .zip(n2.children.iter())
+            .all(|(c1, c2)| isomorphic(t1, *c1, t2, *c2))
+}
@@ -0,0 +1,128 @@
+//! Parsing of Ruby code that has a prefix on every line.
+//!
+//! Code embedded into other documents is often indented or commented out:
+//! YARD `@example` blocks are inside of `#   ` comments, `<<~` heredocs
+//! and code blocks of Markdown lists are indented. The prefix is removed
+//! from every line before parsing, and all locations of the result are
+//! mapped back to the code with prefixes, so columns refer to lines of the document.
+//!
+//! ```
+//! use lib_ruby_parser::{embedded, ParserOptions};
+//!
+//! let ruby = "class User\n  # @example\n  #   user.rename(a, a)\n  def rename; end\nend\n";
+//! let begin = ruby.find("  #   user").unwrap();
+//! let end = ruby.find("  def").unwrap();
+//!
+//! let options = ParserOptions { first_line: 3, byte_offset: begin, ..Default::default() };
+//! let result = embedded::parse(ruby[begin..end].as_bytes(), b"  #   ", options);
+//!
+//! let range = result.ast.unwrap().expression().clone();
+//! assert_eq!(&ruby[range.to_range()], "user.rename(a, a)");
+//! assert_eq!(range.begin_line_col(&result.input), Some((2, 6)));
+//! ```
+
+use crate::erb::SourceMap;
+use crate::source::buffer::Input;
+use crate::{Parser, ParserOptions, ParserResult};
+
+/// Ruby code with line prefixes removed
+#[derive(Debug, Clone)]
+pub struct Stripped {
+    /// Code without prefixes
+    pub code: Vec<u8>,
+
+    /// Mapping of offsets in `code` to offsets in the code with prefixes
+    pub source_map: SourceMap,
+}
+
+/// Removes `prefix` from the beginning of every line of `code`.
+///
+/// Lines that start with a part of the prefix only (like an empty line
+/// in an indented block or a line with a single `#` in a YARD example)
+/// lose this part.
+pub fn strip_line_prefix(code: &[u8], prefix: &[u8]) -> Stripped {
+    let mut stripped = vec![];
+    let mut source_map = SourceMap::new(code.len());
+
+    let mut line_start = 0;
+    while line_start < code.len() {
+        let line_end = code[line_start..]
+            .iter()
+            .position(|byte| *byte == b'\n')
+            .map_or(code.len(), |idx| line_start + idx + 1);
+        let line = &code[line_start..line_end];
+
+        let prefix_len = line
+            .iter()
+            .zip(prefix.iter())
+            .take_while(|(byte, prefix_byte)| byte == prefix_byte && **byte != b'\n')
+            .count();
+        let content_start = line_start + prefix_len;
+
+        stripped.extend_from_slice(&code[content_start..line_end]);
+        source_map.push(line_end - content_start, content_start, line_end, true);
+        line_start = line_end;
+    }
+
+    Stripped {
+        code: stripped,
+        source_map,
+    }
+}
+
+/// Returns the longest whitespace prefix of all non-blank lines,
+/// i.e. the indentation that `<<~` heredocs remove
+pub fn common_indent(code: &[u8]) -> &[u8] {
+    let mut indent: Option<&[u8]> = None;
+    for line in code.split(|byte| *byte == b'\n') {
+        let line_indent_len = line
+            .iter()
+            .take_while(|byte| **byte == b' ' || **byte == b'\t')
+            .count();
+        if line[line_indent_len..].iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        let line_indent = &line[..line_indent_len];
+        indent = Some(match indent {
+            None => line_indent,
+            Some(indent) => {
+                let len = indent
+                    .iter()
+                    .zip(line_indent.iter())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                &indent[..len]
+            }
+        });
+    }
+    indent.unwrap_or(b"")
+}
+
+/// Parses `code` that has `prefix` on every line.
+///
+/// All locations in the result (of nodes, tokens, comments and diagnostics)
+/// refer to `code` with prefixes, and `result.input` is `code` itself,
+/// so lines and columns are counted the same way as in the enclosing document.
+///
+/// `options.first_line` and `options.byte_offset` are applied
+/// to `code` (i.e. they describe its position in the enclosing document),
+/// `code` should start at the beginning of a line.
+pub fn parse(code: &[u8], prefix: &[u8], mut options: ParserOptions) -> ParserResult {
+    let Stripped {
+        code: stripped,
+        source_map,
+    } = strip_line_prefix(code, prefix);
+
+    let first_line = std::mem::replace(&mut options.first_line, 1);
+    let byte_offset = std::mem::replace(&mut options.byte_offset, 0);
+    let buffer_name = options.buffer_name.clone();
+    let mut result = Parser::new(&stripped, options).do_parse();
+
+    result.map_ranges(|range| *range = source_map.template_range(range));
+
+    result.input = Input::default();
+    result.input.name = buffer_name;
+    result.input.set_bytes(code.to_vec());
+    result.move_to(first_line, byte_offset);
+    result
+}
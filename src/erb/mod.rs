@@ -0,0 +1,203 @@
+//! Parsing of ERB templates.
+//!
+//! A template is compiled to Ruby code that appends text and values
+//! of `<%= %>` tags to a buffer (much like ERB and Erubi do), the code
+//! is parsed by `Parser` and all locations are mapped back to the template:
+//!
+//! + `<% code %>` is a Ruby statement
+//! + `<%= expr %>` (and `<%== expr %>`) is an expression that is appended to the buffer,
+//!   it can also open a block (`<%= form_for(user) do |f| %>`) like in Rails
+//! + `<%# comment %>` is ignored
+//! + `<%%` is a literal `<%`
+//! + `<%-` removes indentation of the tag, `-%>` removes a line break that follows it
+//!
+//! ```
+//! use lib_ruby_parser::{erb, ParserOptions};
+//!
+//! let template = b"<ul>\n<% users.each do |user| -%>\n  <li><%= user.name %></li>\n<% end -%>\n</ul>\n";
+//! let result = erb::parse(template, ParserOptions::default());
+//! assert!(result.diagnostics.is_empty());
+//!
+//! let tokens = result
+//!     .tokens
+//!     .iter()
+//!     .map(|token| std::str::from_utf8(&template[token.loc.begin..token.loc.end]).unwrap())
+//!     .collect::<Vec<_>>();
+//! assert_eq!(
+//!     tokens,
+//!     vec!["users", ".", "each", "do", "|", "user", "|", "user", ".", "name", "end"]
+//! );
+//! ```
+
+mod scanner;
+use scanner::{scan, Part};
+
+mod source_map;
+pub use source_map::SourceMap;
+
+use crate::source::buffer::Input;
+use crate::source::Range;
+use crate::{Diagnostic, Parser, ParserOptions, ParserResult};
+
+/// Name of the local variable that collects the output
+const BUFFER_VARIABLE: &str = "_erbout";
+
+/// Ruby code compiled from an ERB template
+#[derive(Debug, Clone)]
+pub struct Compiled {
+    /// Ruby code of the template
+    pub ruby: Vec<u8>,
+
+    /// Mapping of offsets in `ruby` to offsets in the template
+    pub source_map: SourceMap,
+
+    /// Errors of the template itself (like unterminated tags)
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Compiles given ERB template to Ruby code.
+///
+/// The code expects an `_erbout` local variable to be defined.
+pub fn compile(template: &[u8]) -> Compiled {
+    let mut diagnostics = vec![];
+    let parts = scan(template, &mut diagnostics);
+
+    let mut output = Output::new(template.len());
+    for part in parts {
+        match part {
+            Part::Text { value, range } => {
+                let mut code = format!("{} << '", BUFFER_VARIABLE).into_bytes();
+                for byte in value {
+                    if byte == b'\\' || byte == b'\'' {
+                        code.push(b'\\');
+                    }
+                    code.push(byte);
+                }
+                code.push(b'\'');
+                output.synthesized(&code, range.begin_pos, range.end_pos);
+                output.synthesized(b"\n", range.end_pos, range.end_pos);
+            }
+            Part::Code { code_l, tag_l } => {
+                output.copied(template, &code_l);
+                output.synthesized(b"\n", tag_l.end_pos, tag_l.end_pos);
+            }
+            Part::Output { code_l, tag_l } => {
+                if is_block_expression(&template[code_l.to_range()]) {
+                    // the block is closed by one of the next tags,
+                    // so the call can't be wrapped into parens
+                    let prefix = format!("{}.append= ", BUFFER_VARIABLE);
+                    output.synthesized(prefix.as_bytes(), tag_l.begin_pos, code_l.begin_pos);
+                    output.copied(template, &code_l);
+                } else {
+                    let prefix = format!("{} << ((", BUFFER_VARIABLE);
+                    output.synthesized(prefix.as_bytes(), tag_l.begin_pos, code_l.begin_pos);
+                    output.copied(template, &code_l);
+                    // the code may end with a comment
+                    output.synthesized(b"\n)).to_s", code_l.end_pos, tag_l.end_pos);
+                }
+                output.synthesized(b"\n", tag_l.end_pos, tag_l.end_pos);
+            }
+        }
+    }
+
+    Compiled {
+        ruby: output.ruby,
+        source_map: output.source_map,
+        diagnostics,
+    }
+}
+
+/// Parses given ERB template.
+///
+/// All locations in the result (of nodes, tokens, comments and diagnostics)
+/// refer to the template, and `result.input` is the template itself.
+/// Only tokens of the Ruby code written in the template are recorded,
+/// code that is synthesized around it has no tokens.
+///
+/// `options.first_line` and `options.byte_offset` are applied
+/// to the template (i.e. they describe its position in an enclosing document).
+pub fn parse(template: &[u8], mut options: ParserOptions) -> ParserResult {
+    let Compiled {
+        ruby,
+        source_map,
+        mut diagnostics,
+    } = compile(template);
+
+    let first_line = std::mem::replace(&mut options.first_line, 1);
+    let byte_offset = std::mem::replace(&mut options.byte_offset, 0);
+    let buffer_name = options.buffer_name.clone();
+    options.local_variables.push(BUFFER_VARIABLE.to_owned());
+    let mut result = Parser::new(&ruby, options).do_parse();
+
+    result
+        .tokens
+        .retain(|token| source_map.is_template_code(token.loc.begin));
+    result
+        .source_tokens
+        .retain(|token| source_map.is_template_code(token.loc.begin_pos));
+    result.map_ranges(|range| *range = source_map.template_range(range));
+    diagnostics.append(&mut result.diagnostics);
+    result.diagnostics = diagnostics;
+
+    result.input = Input::default();
+    result.input.name = buffer_name;
+    result.input.set_bytes(template.to_vec());
+    result.move_to(first_line, byte_offset);
+    result
+}
+
+struct Output {
+    ruby: Vec<u8>,
+    source_map: SourceMap,
+}
+
+impl Output {
+    fn new(template_len: usize) -> Self {
+        Self {
+            ruby: vec![],
+            source_map: SourceMap::new(template_len),
+        }
+    }
+
+    fn copied(&mut self, template: &[u8], range: &Range) {
+        self.ruby.extend_from_slice(&template[range.to_range()]);
+        self.source_map
+            .push(range.size(), range.begin_pos, range.end_pos, true);
+    }
+
+    fn synthesized(&mut self, code: &[u8], template_begin: usize, template_end: usize) {
+        self.ruby.extend_from_slice(code);
+        self.source_map
+            .push(code.len(), template_begin, template_end, false);
+    }
+}
+
+// Same as `BLOCK_EXPR` of Rails: the code ends with `do`, `{`,
+// optionally followed by block arguments (`do |a, b|`)
+fn is_block_expression(code: &[u8]) -> bool {
+    let mut code = trim_end(code);
+    if code.ends_with(b"|") {
+        let args = &code[..code.len() - 1];
+        match args.iter().rposition(|byte| *byte == b'|') {
+            Some(idx) => code = trim_end(&args[..idx]),
+            None => return false,
+        }
+    }
+
+    if code.ends_with(b"{") {
+        return true;
+    }
+    code.ends_with(b"do")
+        && matches!(
+            code[..code.len() - 2].last(),
+            Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') | Some(b')')
+        )
+}
+
+fn trim_end(bytes: &[u8]) -> &[u8] {
+    let len = bytes
+        .iter()
+        .rposition(|byte| !byte.is_ascii_whitespace())
+        .map_or(0, |idx| idx + 1);
+    &bytes[..len]
+}
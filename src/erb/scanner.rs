@@ -0,0 +1,155 @@
+use crate::source::Range;
+use crate::{Diagnostic, DiagnosticMessage, ErrorLevel};
+
+/// A part of an ERB template that produces Ruby code
+#[derive(Debug)]
+pub(crate) enum Part {
+    /// Plain text, `value` has `<%%` unescaped and trimmed whitespace removed
+    Text { value: Vec<u8>, range: Range },
+
+    /// `<% code %>`
+    Code { code_l: Range, tag_l: Range },
+
+    /// `<%= code %>` (or `<%== code %>`)
+    Output { code_l: Range, tag_l: Range },
+}
+
+enum TagKind {
+    Code,
+    Output,
+    Comment,
+}
+
+/// Text between two tags
+struct Text {
+    value: Vec<u8>,
+    begin: usize,
+    end: usize,
+}
+
+impl Text {
+    fn new(begin: usize) -> Self {
+        Self {
+            value: vec![],
+            begin,
+            end: begin,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8], end: usize) {
+        self.value.extend_from_slice(bytes);
+        self.end = end;
+    }
+
+    // `<%-` removes indentation of the tag, i.e. spaces and tabs
+    // between the beginning of the line and the tag
+    fn trim_indentation(&mut self, template: &[u8]) {
+        let spaces = self
+            .value
+            .iter()
+            .rev()
+            .take_while(|byte| **byte == b' ' || **byte == b'\t')
+            .count();
+        let line_start = if spaces == self.value.len() {
+            self.begin == 0 || template[self.begin - 1] == b'\n'
+        } else {
+            self.value[self.value.len() - spaces - 1] == b'\n'
+        };
+
+        if spaces > 0 && line_start {
+            self.value.truncate(self.value.len() - spaces);
+            self.end -= spaces;
+        }
+    }
+
+    fn into_part(self) -> Option<Part> {
+        if self.value.is_empty() {
+            None
+        } else {
+            Some(Part::Text {
+                value: self.value,
+                range: Range::new(self.begin, self.end),
+            })
+        }
+    }
+}
+
+/// Splits given template into text, code and output parts.
+///
+/// Comments (`<%# %>`) are skipped, unterminated tags
+/// are reported as `DiagnosticMessage::UnterminatedErbTag`.
+pub(crate) fn scan(template: &[u8], diagnostics: &mut Vec<Diagnostic>) -> Vec<Part> {
+    let mut parts = vec![];
+    let mut text = Text::new(0);
+    let mut pos = 0;
+
+    while let Some(tag_begin) = find(template, b"<%", pos) {
+        if template.get(tag_begin + 2) == Some(&b'%') {
+            // `<%%` is a literal `<%`
+            text.push(&template[pos..tag_begin + 2], tag_begin + 3);
+            pos = tag_begin + 3;
+            continue;
+        }
+        text.push(&template[pos..tag_begin], tag_begin);
+
+        let (kind, code_begin) = match template.get(tag_begin + 2) {
+            Some(b'=') if template.get(tag_begin + 3) == Some(&b'=') => {
+                (TagKind::Output, tag_begin + 4)
+            }
+            Some(b'=') => (TagKind::Output, tag_begin + 3),
+            Some(b'#') => (TagKind::Comment, tag_begin + 3),
+            Some(b'-') => {
+                text.trim_indentation(template);
+                (TagKind::Code, tag_begin + 3)
+            }
+            _ => (TagKind::Code, tag_begin + 2),
+        };
+
+        let (code_end, tag_end, trim_newline) = match find(template, b"%>", code_begin) {
+            Some(close) if close > code_begin && template[close - 1] == b'-' => {
+                (close - 1, close + 2, true)
+            }
+            Some(close) => (close, close + 2, false),
+            None => {
+                diagnostics.push(Diagnostic::new(
+                    ErrorLevel::Error,
+                    DiagnosticMessage::UnterminatedErbTag,
+                    Range::new(tag_begin, code_begin),
+                ));
+                (template.len(), template.len(), false)
+            }
+        };
+
+        parts.extend(std::mem::replace(&mut text, Text::new(tag_end)).into_part());
+        let code_l = Range::new(code_begin, code_end);
+        let tag_l = Range::new(tag_begin, tag_end);
+        match kind {
+            TagKind::Code => parts.push(Part::Code { code_l, tag_l }),
+            TagKind::Output => parts.push(Part::Output { code_l, tag_l }),
+            TagKind::Comment => {}
+        }
+
+        pos = tag_end;
+        if trim_newline {
+            // `-%>` removes a line break that follows the tag
+            if template[pos..].starts_with(b"\n") {
+                pos += 1;
+            } else if template[pos..].starts_with(b"\r\n") {
+                pos += 2;
+            }
+            text = Text::new(pos);
+        }
+    }
+
+    text.push(&template[pos..], template.len());
+    parts.extend(text.into_part());
+    parts
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|idx| from + idx)
+}
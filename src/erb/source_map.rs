@@ -0,0 +1,102 @@
+use crate::source::Range;
+
+/// Maps offsets in the Ruby code compiled from an ERB template
+/// to offsets in the template.
+///
+/// Ruby code of `<% %>` and `<%= %>` tags is copied as is and is mapped
+/// byte-to-byte. Code that is synthesized around it (appending text
+/// and values of `<%= %>` tags to the buffer) is mapped to the text
+/// or to the tag that it comes from: locations that start there
+/// are mapped to the beginning of the text/tag, locations that end there
+/// are mapped to its end.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    pieces: Vec<Piece>,
+    ruby_len: usize,
+    template_len: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Piece {
+    ruby_pos: usize,
+    template_begin: usize,
+    template_end: usize,
+    exact: bool,
+}
+
+impl SourceMap {
+    pub(crate) fn new(template_len: usize) -> Self {
+        Self {
+            pieces: vec![],
+            ruby_len: 0,
+            template_len,
+        }
+    }
+
+    /// Records next `len` bytes of Ruby code that come from `template_begin..template_end`
+    pub(crate) fn push(
+        &mut self,
+        len: usize,
+        template_begin: usize,
+        template_end: usize,
+        exact: bool,
+    ) {
+        if len == 0 {
+            return;
+        }
+        self.pieces.push(Piece {
+            ruby_pos: self.ruby_len,
+            template_begin,
+            template_end,
+            exact,
+        });
+        self.ruby_len += len;
+    }
+
+    /// Converts an offset in Ruby code to an offset in the template
+    pub fn template_pos(&self, pos: usize) -> usize {
+        match self.piece_at(pos) {
+            Some(piece) if piece.exact => piece.template_begin + (pos - piece.ruby_pos),
+            Some(piece) => piece.template_begin,
+            None => self.template_len,
+        }
+    }
+
+    /// Converts a range of Ruby code to a range of the template
+    pub fn template_range(&self, range: &Range) -> Range {
+        let begin_pos = self.template_pos(range.begin_pos);
+        if range.end_pos <= range.begin_pos {
+            return Range::new(begin_pos, begin_pos);
+        }
+
+        // the end is mapped using the last byte of the range,
+        // so it doesn't leak into the text or tag that comes next
+        let last = range.end_pos - 1;
+        let end_pos = match self.piece_at(last) {
+            Some(piece) if piece.exact => piece.template_begin + (range.end_pos - piece.ruby_pos),
+            Some(piece) => piece.template_end,
+            None => self.template_len,
+        };
+        Range::new(begin_pos, std::cmp::max(begin_pos, end_pos))
+    }
+
+    /// Returns `true` if a byte at `pos` of Ruby code
+    /// has been copied from the template
+    pub fn is_template_code(&self, pos: usize) -> bool {
+        matches!(self.piece_at(pos), Some(piece) if piece.exact)
+    }
+
+    fn piece_at(&self, pos: usize) -> Option<&Piece> {
+        if pos >= self.ruby_len {
+            return None;
+        }
+        let idx = match self
+            .pieces
+            .binary_search_by(|piece| piece.ruby_pos.cmp(&pos))
+        {
+            Ok(idx) => idx,
+            Err(idx) => idx - 1,
+        };
+        self.pieces.get(idx)
+    }
+}
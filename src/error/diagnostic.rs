@@ -27,14 +27,15 @@ impl Diagnostic {
         let line = line_loc.source(input)?;
 
         let filename = &input.name;
-        let (_, start_col) = self.range.begin_line_col(input)?;
+        let (_, start_col) = self.range.begin_line_char_col(input)?;
+        let size = input.char_count(self.range.begin_pos, self.range.end_pos);
 
         let prefix = format!("{}:{}", filename, line_no + 1);
         let highlight = format!(
             "{indent}^{tildes}",
             indent = " ".repeat(start_col),
-            tildes = if self.range.size() > 0 {
-                "~".repeat(self.range.size() - 1)
+            tildes = if size > 0 {
+                "~".repeat(size - 1)
             } else {
                 "".to_owned()
             }
@@ -1,3 +1,5 @@
+use crate::RubyVersion;
+
 #[derive(Debug, Clone)]
 pub enum DiagnosticMessage {
     // Lexer errors
@@ -49,6 +51,7 @@ pub enum DiagnosticMessage {
     UnknownRegexOptions(String),
     UnterminatedUnicodeEscape,
     EncodingError(String),
+    InvalidMultibyteChar,
 
     // Lexer warnings
     AmbiguousTernaryOperator(String),
@@ -102,6 +105,28 @@ pub enum DiagnosticMessage {
     RegexError(String),
     InvalidSymbol(String),
     VoidValueExpression,
+
+    // Limit errors
+    InputTooLarge {
+        max_size: usize,
+    },
+    TooManyTokens {
+        max_tokens: usize,
+    },
+    NestingTooDeep {
+        max_depth: usize,
+    },
+    ParsingCancelled,
+    ParsingTimeout,
+
+    // Ruby version errors
+    UnsupportedInRubyVersion {
+        feature: String,
+        ruby_version: RubyVersion,
+    },
+
+    // ERB errors
+    UnterminatedErbTag,
 }
 
 impl DiagnosticMessage {
@@ -159,6 +184,7 @@ impl DiagnosticMessage {
             Self::AmbiguousTernaryOperator(pre) => format!("`?' just followed by `{}' is interpreted as a conditional operator, put a space after `?'", pre),
             Self::UnterminatedUnicodeEscape => "unterminated Unicode escape".to_owned(),
             Self::EncodingError(err) => format!("encoding error: {}", err),
+            Self::InvalidMultibyteChar => "invalid multibyte char (UTF-8)".to_owned(),
 
             // Parser errors
             Self::ElseWithoutRescue => "else without rescue is useless".to_owned(),
@@ -209,6 +235,19 @@ impl DiagnosticMessage {
             Self::RegexError(message) => message.to_owned(),
             Self::InvalidSymbol(encoding) => format!("invalid symbol in encoding {}", encoding),
             Self::VoidValueExpression => "void value expression".to_owned(),
+
+            // Limit errors
+            Self::InputTooLarge { max_size } => format!("input is too large (more than {} bytes)", max_size),
+            Self::TooManyTokens { max_tokens } => format!("too many tokens (more than {})", max_tokens),
+            Self::NestingTooDeep { max_depth } => format!("nesting is too deep (more than {} levels)", max_depth),
+            Self::ParsingCancelled => "parsing has been cancelled".to_owned(),
+            Self::ParsingTimeout => "parsing has exceeded its deadline".to_owned(),
+
+            // Ruby version errors
+            Self::UnsupportedInRubyVersion { feature, ruby_version } => format!("{} is not supported in Ruby {}", feature, ruby_version),
+
+            // ERB errors
+            Self::UnterminatedErbTag => "unterminated ERB tag meets end of file".to_owned(),
         }
     }
 }
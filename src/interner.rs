@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+static NEXT_INTERNER_ID: AtomicU32 = AtomicU32::new(0);
+
+/// Handle of the interned string.
+///
+/// Can be resolved back to a string using `Interner::resolve`
+/// of the interner that has created it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol {
+    interner_id: u32,
+    index: u32,
+}
+
+/// String interner that stores every unique string once.
+///
+/// It's a plain table owned by a single parser (no locks and no shared state).
+/// `StaticEnvironment` uses it to store names of local variables,
+/// and after parsing it's returned in `ParserResult.interner`.
+/// To share identifiers between parsers pass it to the next
+/// parser via `ParserOptions.interner`; dropping it frees all strings.
+///
+/// Symbols are bound to the interner that has created them:
+/// `resolve` and `lookup` of another interner never confuse them.
+#[derive(Debug)]
+pub struct Interner {
+    id: u32,
+    symbols: HashMap<Arc<str>, u32>,
+    strings: Vec<Arc<str>>,
+}
+
+impl Default for Interner {
+    fn default() -> Self {
+        Self {
+            id: NEXT_INTERNER_ID.fetch_add(1, Ordering::Relaxed),
+            symbols: HashMap::new(),
+            strings: vec![],
+        }
+    }
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a symbol for a given string, stores the string if it's new
+    pub fn intern(&mut self, s: &str) -> Symbol {
+        if let Some(symbol) = self.lookup(s) {
+            return symbol;
+        }
+
+        let index = self.strings.len() as u32;
+        let s: Arc<str> = Arc::from(s);
+        self.strings.push(Arc::clone(&s));
+        self.symbols.insert(s, index);
+        self.symbol(index)
+    }
+
+    /// Returns a symbol for a given string if it has been interned before
+    pub fn lookup(&self, s: &str) -> Option<Symbol> {
+        self.symbols.get(s).map(|index| self.symbol(*index))
+    }
+
+    /// Returns a string for a given symbol,
+    /// or `None` if the symbol has been created by a different interner
+    pub fn resolve(&self, symbol: Symbol) -> Option<&str> {
+        if symbol.interner_id != self.id {
+            return None;
+        }
+        self.strings.get(symbol.index as usize).map(|s| &**s)
+    }
+
+    /// Returns number of unique strings in the interner
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    fn symbol(&self, index: u32) -> Symbol {
+        Symbol {
+            interner_id: self.id,
+            index,
+        }
+    }
+}
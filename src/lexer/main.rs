@@ -50,6 +50,7 @@ pub struct Lexer {
     pub(crate) diagnostics: Diagnostics,
     pub(crate) comments: Vec<Comment>,
     pub(crate) magic_comments: Vec<MagicComment>,
+    pub(crate) record_comments: bool,
 }
 
 impl Lexer {
@@ -66,6 +67,7 @@ impl Lexer {
             lpar_beg: -1, /* make lambda_beginning_p() == FALSE at first */
             buffer: Buffer::new(name, bytes.to_owned(), decoder),
             context: Context::new(),
+            record_comments: true,
             ..Self::default()
         }
     }
@@ -104,18 +106,23 @@ impl Lexer {
         let begin = std::mem::take(&mut self.lval_start).unwrap_or(self.buffer.ptok);
         let mut end = std::mem::take(&mut self.lval_end).unwrap_or(self.buffer.pcur);
 
-        let mut token_value = self
-            .lval
-            .take()
-            .or_else(|| {
-                // take raw value if nothing was manually captured
-                self.buffer.substr_at(begin, end).map(|s| {
-                    TokenValue::String(
-                        String::from_utf8(s.to_vec()).expect("source must be encoded in utf-8"),
-                    )
-                })
-            })
-            .unwrap_or_else(|| TokenValue::String("".to_owned()));
+        let mut token_value = match self.lval.take() {
+            Some(token_value) => token_value,
+            // take raw value if nothing was manually captured
+            None => match self.buffer.substr_at(begin, end) {
+                Some(bytes) => match String::from_utf8(bytes.to_vec()) {
+                    Ok(s) => TokenValue::String(s),
+                    Err(err) => {
+                        self.compile_error(
+                            DiagnosticMessage::InvalidMultibyteChar,
+                            Range::new(begin, end),
+                        );
+                        TokenValue::InvalidString(err.into_bytes())
+                    }
+                },
+                None => TokenValue::String("".to_owned()),
+            },
+        };
 
         if token_type == Self::tNL {
             token_value = TokenValue::String("\n".to_owned());
@@ -222,8 +229,7 @@ impl Lexer {
                             Err(_) => return Self::END_OF_INPUT,
                         }
                         self.buffer.goto_eol();
-                        self.comments
-                            .push(Comment::new(self.current_range(), &self.buffer.input))
+                        self.add_comment(self.current_range())
                     }
                     self.token_seen = token_seen;
                     let cc = self
@@ -390,10 +396,7 @@ impl Lexer {
                                 self.buffer.pushback(&c);
                             }
                             self.buffer.goto_eol();
-                            self.comments.push(Comment::new(
-                                begin_range.with_end(self.buffer.pcur),
-                                &self.buffer.input,
-                            ));
+                            self.add_comment(begin_range.with_end(self.buffer.pcur));
                             continue 'retrying;
                         }
                     }
@@ -1092,6 +1095,12 @@ impl Lexer {
         self.diagnostics.emit(diagnostic);
     }
 
+    pub(crate) fn add_comment(&mut self, range: Range) {
+        if self.record_comments {
+            self.comments.push(Comment::new(range, &self.buffer.input))
+        }
+    }
+
     pub(crate) fn new_strterm(
         &self,
         func: usize,
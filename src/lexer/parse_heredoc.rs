@@ -322,10 +322,8 @@ impl ParseHeredoc for Lexer {
         let value = self
             .buffer
             .substr_at(end_starts_at, end)
-            .expect("failed to get heredoc end");
-
-        let value =
-            String::from_utf8(value.to_vec()).expect("expected heredoc id to be valid in utf-8");
+            .expect("failed to get heredoc end")
+            .to_vec();
 
         HeredocEnd { start, end, value }
     }
@@ -353,7 +351,7 @@ impl ParseHeredoc for Lexer {
         let heredoc_end = self.compute_heredoc_end();
         self.lval_start = Some(heredoc_end.start);
         self.lval_end = Some(heredoc_end.end);
-        self.set_yylval_str(&TokenBuf::new(heredoc_end.value.as_bytes()));
+        self.set_yylval_str(&TokenBuf::new(&heredoc_end.value));
 
         self.heredoc_restore(&here);
         self.token_flush();
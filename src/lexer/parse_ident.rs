@@ -29,7 +29,8 @@ impl ParseIdent for Lexer {
         self.set_yylval_name();
         match self.tokenbuf.borrow_string() {
             Ok(s) => s.to_owned(),
-            Err(bytes) => unreachable!("locals can't have non-utf chars: {:?}", bytes),
+            // invalid bytes are reported by set_yylval_name
+            Err(bytes) => String::from_utf8_lossy(bytes).into_owned(),
         }
     }
 
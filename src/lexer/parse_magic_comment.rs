@@ -305,24 +305,22 @@ impl ParseMagicComment for Lexer {
             }
 
             n = end - beg;
-            name = String::from_utf8(
+            name = String::from_utf8_lossy(
                 self.buffer
                     .substr_at(beg, beg + n)
-                    .expect("failed to get magic comment name")
-                    .to_vec(),
+                    .expect("failed to get magic comment name"),
             )
-            .expect("expected source to be encoded in utf-8");
+            .into_owned();
             let name_to_compare = name.replace("-", "_");
             for (name, kind) in MAGIC_COMMENTS.iter() {
                 if &name_to_compare == name {
                     if kind == &MagicCommentKind::Encoding {
-                        let encoding = String::from_utf8(
+                        let encoding = String::from_utf8_lossy(
                             self.buffer
                                 .substr_at(vbeg, vend)
-                                .expect("bug: Can't be None")
-                                .to_vec(),
+                                .expect("bug: Can't be None"),
                         )
-                        .unwrap();
+                        .into_owned();
                         match self.buffer.set_encoding(&encoding) {
                             Ok(_) => {}
                             Err(err) => {
@@ -335,11 +333,13 @@ impl ParseMagicComment for Lexer {
                         }
                     }
 
-                    let key_l = self.range(beg, beg + n);
-                    let value_l = self.range(vbeg, vend);
+                    if self.record_comments {
+                        let key_l = self.range(beg, beg + n);
+                        let value_l = self.range(vbeg, vend);
 
-                    let magic_comment = MagicComment::new(kind.clone(), key_l, value_l);
-                    self.magic_comments.push(magic_comment);
+                        let magic_comment = MagicComment::new(kind.clone(), key_l, value_l);
+                        self.magic_comments.push(magic_comment);
+                    }
                 }
             }
         }
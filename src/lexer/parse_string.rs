@@ -76,7 +76,7 @@ impl ParseString for Lexer {
                 if let Some(heredoc_end) = quote.heredoc_end() {
                     self.lval_start = Some(heredoc_end.start);
                     self.lval_end = Some(heredoc_end.end);
-                    self.set_yylval_str(&TokenBuf::new(heredoc_end.value.as_bytes()));
+                    self.set_yylval_str(&TokenBuf::new(&heredoc_end.value));
                 }
                 return Self::tSTRING_END;
             }
@@ -430,19 +430,21 @@ impl ParseString for Lexer {
     }
 
     fn tokcopy(&mut self, n: usize) {
-        let substr = self
-            .buffer
-            .substr_at(self.buffer.pcur - n, self.buffer.pcur)
-            .unwrap_or_else(|| panic!("no substr {}..{}", self.buffer.pcur - n, self.buffer.pcur));
-        self.tokenbuf.append(&substr);
+        let start = self.buffer.pcur.saturating_sub(n);
+        if let Some(substr) = self.buffer.substr_at(start, self.buffer.pcur) {
+            self.tokenbuf.append(substr);
+        }
     }
 
     fn tokaddmbc(&mut self, codepoint: usize) {
-        let utf8_char =
-            std::char::from_u32(codepoint.try_into().expect("expected codepoint to be u32"))
-                .expect("expected codepoint to have digits");
-        let utf8_bytes = utf8_char.to_string().into_bytes();
-        for byte in utf8_bytes {
+        // surrogates and too large codepoints are reported by tokadd_codepoint
+        let utf8_char = codepoint
+            .try_into()
+            .ok()
+            .and_then(std::char::from_u32)
+            .unwrap_or(std::char::REPLACEMENT_CHARACTER);
+        let mut utf8_bytes = [0; 4];
+        for byte in utf8_char.encode_utf8(&mut utf8_bytes).bytes() {
             self.tokadd(byte)
         }
     }
@@ -1,5 +1,6 @@
 use crate::lexer::*;
 use crate::parser::TokenValue;
+use crate::DiagnosticMessage;
 use crate::TokenBuf;
 
 pub(crate) trait Yylval {
@@ -46,6 +47,13 @@ impl Yylval for Lexer {
         if self.debug {
             println!("set_yyval_name({:#?})", self.tokenbuf);
         }
-        self.lval = Some(self.tokenbuf.to_token_value());
+        let value = self.tokenbuf.to_token_value();
+        if let TokenValue::InvalidString(_) = value {
+            self.compile_error(
+                DiagnosticMessage::InvalidMultibyteChar,
+                self.current_range(),
+            );
+        }
+        self.lval = Some(value);
     }
 }
@@ -1,4 +1,5 @@
-#![forbid(unsafe_code)]
+#![cfg_attr(not(any(feature = "wasm", feature = "python")), forbid(unsafe_code))]
+#![cfg_attr(any(feature = "wasm", feature = "python"), deny(unsafe_code))]
 
 pub mod source;
 
@@ -8,17 +9,29 @@ pub use lexer::Lexer;
 mod static_environment;
 pub use static_environment::StaticEnvironment;
 
+mod interner;
+pub use interner::{Interner, Symbol};
+
 pub(crate) mod parse_value;
 
 mod parser_options;
 pub use parser_options::ParserOptions;
 
+mod ruby_version;
+pub use ruby_version::RubyVersion;
+
 mod parser_result;
 pub use parser_result::ParserResult;
 
+mod parse_limits;
+pub(crate) use parse_limits::ParseLimits;
+
 mod parser;
 pub(crate) use parser::Loc;
-pub use parser::{token_name, Parser, Token};
+pub use parser::{token_name, Parser, Token, TokenValue};
+
+mod source_token;
+pub use source_token::{SourceToken, SourceTokenValue};
 
 mod builder;
 pub(crate) use builder::Builder;
@@ -58,7 +71,24 @@ pub(crate) use context::{Context, ContextItem};
 pub mod nodes;
 pub use nodes::Node;
 
+pub mod arena;
+
 pub mod traverse;
 
+#[cfg(feature = "mri-ast")]
+pub mod mri_ast;
+
+pub mod diff;
+
+pub mod erb;
+
+pub mod embedded;
+
 mod string_value;
 pub use string_value::StringValue;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "python")]
+pub mod python;
@@ -0,0 +1,1655 @@
+use super::node::inspect_symbol;
+use super::{MriNode, MriValue};
+use crate::nodes::*;
+use crate::source::{Input, Range};
+use crate::Node;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScopeKind {
+    Method,
+    Block,
+}
+
+struct Scope {
+    kind: ScopeKind,
+    locals: Vec<Option<String>>,
+}
+
+enum LocalKind {
+    Method,
+    CurrentBlock,
+    OuterBlock,
+}
+
+enum StrPiece {
+    Static(String, Range),
+    Dynamic(MriNode),
+}
+
+pub(crate) struct Converter<'a> {
+    input: &'a Input,
+    scopes: Vec<Scope>,
+}
+
+fn wrap(node: MriNode) -> MriValue {
+    MriValue::Node(Box::new(node))
+}
+
+fn sym(name: &str) -> MriValue {
+    MriValue::Symbol(name.to_owned())
+}
+
+fn spanning(node_type: &str, first: &MriNode, last: &MriNode, children: Vec<MriValue>) -> MriNode {
+    MriNode {
+        node_type: node_type.to_owned(),
+        children,
+        first_lineno: first.first_lineno,
+        first_column: first.first_column,
+        last_lineno: last.last_lineno,
+        last_column: last.last_column,
+    }
+}
+
+fn join_nodes(nodes: &[&Node]) -> Option<Range> {
+    let first = nodes.first()?;
+    let last = nodes.last()?;
+    Some(first.expression().join(last.expression()))
+}
+
+const OPERATOR_METHODS: &[&str] = &[
+    "+", "-", "*", "/", "%", "**", "==", "===", "!=", "=~", "!~", "<=>", "<", "<=", ">", ">=",
+    "<<", ">>", "&", "|", "^", "~", "!", "+@", "-@",
+];
+
+impl<'a> Converter<'a> {
+    pub(crate) fn new(input: &'a Input) -> Self {
+        Self {
+            input,
+            scopes: vec![],
+        }
+    }
+
+    pub(crate) fn program(&mut self, ast: Option<&Node>) -> MriNode {
+        self.push_scope(ScopeKind::Method);
+        let body = match ast {
+            Some(node) => wrap(self.convert(node)),
+            None => MriValue::Nil,
+        };
+        let tbl = self.pop_scope();
+        let range = match ast {
+            Some(node) => node.expression().clone(),
+            None => Range::new(self.input.byte_offset(), self.input.byte_offset()),
+        };
+        self.scope_node(&range, tbl, MriValue::Nil, body)
+    }
+
+    // Scopes and local variables
+
+    fn push_scope(&mut self, kind: ScopeKind) {
+        self.scopes.push(Scope {
+            kind,
+            locals: vec![],
+        })
+    }
+
+    fn pop_scope(&mut self) -> MriValue {
+        let locals = self
+            .scopes
+            .pop()
+            .map(|scope| scope.locals)
+            .unwrap_or_default();
+        MriValue::Array(
+            locals
+                .into_iter()
+                .map(|local| match local {
+                    Some(name) => MriValue::Symbol(name),
+                    None => MriValue::Nil,
+                })
+                .collect(),
+        )
+    }
+
+    fn lookup(&self, name: &str) -> Option<LocalKind> {
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            if scope
+                .locals
+                .iter()
+                .any(|local| local.as_deref() == Some(name))
+            {
+                return Some(match (scope.kind, depth) {
+                    (ScopeKind::Method, _) => LocalKind::Method,
+                    (ScopeKind::Block, 0) => LocalKind::CurrentBlock,
+                    (ScopeKind::Block, _) => LocalKind::OuterBlock,
+                });
+            }
+            if scope.kind == ScopeKind::Method {
+                break;
+            }
+        }
+        None
+    }
+
+    fn declare(&mut self, name: &str) {
+        if self.lookup(name).is_none() {
+            self.declare_internal(Some(name.to_owned()))
+        }
+    }
+
+    fn declare_internal(&mut self, name: Option<String>) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.locals.push(name)
+        }
+    }
+
+    fn lvar_type(&self, name: &str) -> &'static str {
+        match self.lookup(name) {
+            Some(LocalKind::CurrentBlock) | Some(LocalKind::OuterBlock) => "DVAR",
+            _ => "LVAR",
+        }
+    }
+
+    fn lasgn_type(&mut self, name: &str) -> &'static str {
+        self.declare(name);
+        match self.lookup(name) {
+            Some(LocalKind::CurrentBlock) => "DASGN_CURR",
+            Some(LocalKind::OuterBlock) => "DASGN",
+            _ => "LASGN",
+        }
+    }
+
+    // Node construction
+
+    fn node(&self, node_type: &str, range: &Range, children: Vec<MriValue>) -> MriNode {
+        let (first_line, first_column) = self
+            .input
+            .line_col_for_pos(range.begin_pos)
+            .unwrap_or((0, 0));
+        let (last_line, last_column) = self
+            .input
+            .line_col_for_pos(range.end_pos)
+            .unwrap_or((first_line, first_column));
+        MriNode {
+            node_type: node_type.to_owned(),
+            children,
+            first_lineno: first_line + 1,
+            first_column,
+            last_lineno: last_line + 1,
+            last_column,
+        }
+    }
+
+    fn value(&self, node_type: &str, range: &Range, children: Vec<MriValue>) -> MriValue {
+        wrap(self.node(node_type, range, children))
+    }
+
+    fn scope_node(&self, range: &Range, tbl: MriValue, args: MriValue, body: MriValue) -> MriNode {
+        self.node("SCOPE", range, vec![tbl, args, body])
+    }
+
+    fn maybe(&mut self, node: &Option<Node>) -> MriValue {
+        match node {
+            Some(node) => wrap(self.convert(node)),
+            None => MriValue::Nil,
+        }
+    }
+
+    fn source(&self, range: &Range) -> String {
+        range.source(self.input).unwrap_or_default()
+    }
+
+    fn statements(&mut self, range: &Range, statements: &[Node]) -> MriNode {
+        match statements {
+            [] => self.node("BEGIN", range, vec![MriValue::Nil]),
+            [statement] => self.convert(statement),
+            _ => {
+                let children = statements
+                    .iter()
+                    .map(|statement| wrap(self.convert(statement)))
+                    .collect::<Vec<_>>();
+                let range = join_nodes(&statements.iter().collect::<Vec<_>>())
+                    .unwrap_or_else(|| range.clone());
+                self.node("BLOCK", &range, children)
+            }
+        }
+    }
+
+    pub(crate) fn convert(&mut self, node: &Node) -> MriNode {
+        let range = node.expression();
+        match node {
+            Node::Begin(inner) => self.statements(range, &inner.statements),
+            Node::KwBegin(inner) => self.statements(range, &inner.statements),
+
+            Node::Nil(_) => self.node("NIL", range, vec![]),
+            Node::True(_) => self.node("TRUE", range, vec![]),
+            Node::False(_) => self.node("FALSE", range, vec![]),
+            Node::Self_(_) => self.node("SELF", range, vec![]),
+            Node::Redo(_) => self.node("REDO", range, vec![]),
+            Node::Retry(_) => self.node("RETRY", range, vec![]),
+            Node::ZSuper(_) => self.node("ZSUPER", range, vec![]),
+
+            Node::Int(inner) => self.literal(range, int_literal(&inner.value)),
+            Node::Float(inner) => self.literal(range, float_literal(&inner.value)),
+            Node::Rational(inner) => self.literal(range, rational_literal(&inner.value)),
+            Node::Complex(inner) => self.literal(range, complex_literal(&inner.value)),
+            Node::Sym(inner) => self.literal(range, inspect_symbol(&inner.name.to_string_lossy())),
+            Node::Line(_) => {
+                let line = self.node("LIT", range, vec![]).first_lineno;
+                self.literal(range, line.to_string())
+            }
+            Node::File(_) => self.node("STR", range, vec![MriValue::Str(self.input.name.clone())]),
+            Node::Encoding(_) => self.literal(range, "#<Encoding:UTF-8>".to_owned()),
+
+            Node::Str(inner) => self.node(
+                "STR",
+                range,
+                vec![MriValue::Str(inner.value.to_string_lossy())],
+            ),
+            Node::Dstr(inner) => self.interpolated(range, &inner.parts, "STR", "DSTR"),
+            Node::Heredoc(inner) => self.interpolated(range, &inner.parts, "STR", "DSTR"),
+            Node::Xstr(inner) => self.interpolated(range, &inner.parts, "XSTR", "DXSTR"),
+            Node::XHeredoc(inner) => self.interpolated(range, &inner.parts, "XSTR", "DXSTR"),
+            Node::Dsym(inner) => {
+                let node = self.interpolated(range, &inner.parts, "STR", "DSYM");
+                match (node.node_type.as_str(), node.children.first()) {
+                    ("STR", Some(MriValue::Str(name))) => self.literal(range, inspect_symbol(name)),
+                    _ => node,
+                }
+            }
+            Node::Regexp(inner) => self.regexp(range, inner),
+
+            Node::Array(inner) => {
+                let elements = inner.elements.iter().collect::<Vec<_>>();
+                let mut list = match self.args_list(&elements) {
+                    Some(list) => list,
+                    None => return self.node("ZLIST", range, vec![]),
+                };
+                if list.node_type == "LIST" && inner.begin_l.is_some() {
+                    let with_brackets = self.node("LIST", range, vec![]);
+                    list.first_lineno = with_brackets.first_lineno;
+                    list.first_column = with_brackets.first_column;
+                    list.last_lineno = with_brackets.last_lineno;
+                    list.last_column = with_brackets.last_column;
+                }
+                list
+            }
+            Node::Hash(inner) => self.hash(range, &inner.pairs),
+
+            Node::Lvar(inner) => {
+                let node_type = self.lvar_type(&inner.name);
+                self.node(node_type, range, vec![sym(&inner.name)])
+            }
+            Node::Ivar(inner) => self.node("IVAR", range, vec![sym(&inner.name)]),
+            Node::Gvar(inner) => self.node("GVAR", range, vec![sym(&inner.name)]),
+            Node::Cvar(inner) => self.node("CVAR", range, vec![sym(&inner.name)]),
+            Node::BackRef(inner) => self.node("BACK_REF", range, vec![sym(&inner.name)]),
+            Node::NthRef(inner) => {
+                self.node("NTH_REF", range, vec![sym(&format!("${}", inner.name))])
+            }
+            Node::Const(inner) => match &inner.scope {
+                None => self.node("CONST", range, vec![sym(&inner.name)]),
+                Some(Node::Cbase(_)) => self.node("COLON3", range, vec![sym(&inner.name)]),
+                Some(scope) => {
+                    let scope = wrap(self.convert(scope));
+                    self.node("COLON2", range, vec![scope, sym(&inner.name)])
+                }
+            },
+
+            Node::Lvasgn(inner) => {
+                let node_type = self.lasgn_type(&inner.name);
+                let value = self.maybe(&inner.value);
+                self.node(node_type, range, vec![sym(&inner.name), value])
+            }
+            Node::Ivasgn(inner) => {
+                let value = self.maybe(&inner.value);
+                self.node("IASGN", range, vec![sym(&inner.name), value])
+            }
+            Node::Gvasgn(inner) => {
+                let value = self.maybe(&inner.value);
+                self.node("GASGN", range, vec![sym(&inner.name), value])
+            }
+            Node::Cvasgn(inner) => {
+                let value = self.maybe(&inner.value);
+                self.node("CVASGN", range, vec![sym(&inner.name), value])
+            }
+            Node::Casgn(inner) => {
+                let target = match &inner.scope {
+                    None => sym(&inner.name),
+                    Some(scope) => wrap(self.colon(scope, &inner.name, &inner.name_l)),
+                };
+                let value = self.maybe(&inner.value);
+                self.node("CDECL", range, vec![target, value])
+            }
+            Node::Masgn(inner) => {
+                let (head, rest) = self.mlhs(&inner.lhs);
+                let value = wrap(self.convert(&inner.rhs));
+                self.node("MASGN", range, vec![value, head, rest])
+            }
+            Node::OpAsgn(inner) => self.op_asgn(range, &inner.recv, &inner.operator, &inner.value),
+            Node::OrAsgn(inner) => self.op_asgn(range, &inner.recv, "||", &inner.value),
+            Node::AndAsgn(inner) => self.op_asgn(range, &inner.recv, "&&", &inner.value),
+
+            Node::Send(inner) => self.send(
+                range,
+                inner.recv.as_ref(),
+                &inner.method_name,
+                &inner.args,
+                inner.dot_l.is_some(),
+                inner.begin_l.is_some(),
+                false,
+            ),
+            Node::CSend(inner) => self.send(
+                range,
+                Some(&inner.recv),
+                &inner.method_name,
+                &inner.args,
+                true,
+                inner.begin_l.is_some(),
+                true,
+            ),
+            Node::Index(inner) => {
+                let recv = wrap(self.convert(&inner.recv));
+                let indexes = inner.indexes.iter().collect::<Vec<_>>();
+                let args = self.maybe_args_list(&indexes);
+                self.node("CALL", range, vec![recv, sym("[]"), args])
+            }
+            Node::IndexAsgn(inner) => {
+                let recv = wrap(self.convert(&inner.recv));
+                let mut args = inner.indexes.iter().collect::<Vec<_>>();
+                if let Some(value) = &inner.value {
+                    args.push(value);
+                }
+                let args = self.maybe_args_list(&args);
+                self.node("ATTRASGN", range, vec![recv, sym("[]="), args])
+            }
+            Node::Super(inner) => {
+                let args = self.call_args(&inner.args);
+                self.node("SUPER", range, vec![args])
+            }
+            Node::Yield(inner) => {
+                let args = self.call_args(&inner.args);
+                self.node("YIELD", range, vec![args])
+            }
+            Node::Block(inner) => self.block(range, inner),
+            Node::Numblock(inner) => self.numblock(range, inner),
+
+            Node::And(_) => self.logop(node, "AND"),
+            Node::Or(_) => self.logop(node, "OR"),
+            Node::If(inner) => {
+                let unless = self.source(&inner.keyword_l) == "unless";
+                self.if_(range, &inner.cond, &inner.if_true, &inner.if_false, unless)
+            }
+            Node::IfMod(inner) => {
+                let unless = self.source(&inner.keyword_l) == "unless";
+                self.if_(range, &inner.cond, &inner.if_true, &inner.if_false, unless)
+            }
+            Node::IfTernary(inner) => {
+                let cond = wrap(self.convert(&inner.cond));
+                let if_true = wrap(self.convert(&inner.if_true));
+                let if_false = wrap(self.convert(&inner.if_false));
+                self.node("IF", range, vec![cond, if_true, if_false])
+            }
+            Node::While(inner) => {
+                self.loop_("WHILE", range, &inner.cond, inner.body.as_ref(), true)
+            }
+            Node::Until(inner) => {
+                self.loop_("UNTIL", range, &inner.cond, inner.body.as_ref(), true)
+            }
+            Node::WhilePost(inner) => {
+                self.loop_("WHILE", range, &inner.cond, Some(&inner.body), false)
+            }
+            Node::UntilPost(inner) => {
+                self.loop_("UNTIL", range, &inner.cond, Some(&inner.body), false)
+            }
+            Node::For(inner) => {
+                let iteratee = wrap(self.convert(&inner.iteratee));
+                let iterator = wrap(self.target(&inner.iterator));
+                let body = self.maybe(&inner.body);
+                self.node("FOR", range, vec![iteratee, iterator, body])
+            }
+            Node::Case(inner) => {
+                let expr = self.maybe(&inner.expr);
+                let whens = inner
+                    .when_bodies
+                    .iter()
+                    .map(|when| match when {
+                        Node::When(when) => {
+                            let patterns = when.patterns.iter().collect::<Vec<_>>();
+                            let patterns = self.maybe_args_list(&patterns);
+                            let body = self.maybe(&when.body);
+                            (when.expression_l.clone(), vec![patterns, body])
+                        }
+                        other => (other.expression().clone(), vec![wrap(self.convert(other))]),
+                    })
+                    .collect::<Vec<_>>();
+                let else_body = self.maybe(&inner.else_body);
+                let chain = self.chain("WHEN", whens, else_body);
+                let node_type = if inner.expr.is_some() {
+                    "CASE"
+                } else {
+                    "CASE2"
+                };
+                self.node(node_type, range, vec![expr, chain])
+            }
+            Node::CaseMatch(inner) => {
+                let expr = wrap(self.convert(&inner.expr));
+                let ins = inner
+                    .in_bodies
+                    .iter()
+                    .map(|in_body| match in_body {
+                        Node::InPattern(in_pattern) => {
+                            let pattern = wrap(self.convert(&in_pattern.pattern));
+                            let body = self.maybe(&in_pattern.body);
+                            (in_pattern.expression_l.clone(), vec![pattern, body])
+                        }
+                        other => (other.expression().clone(), vec![wrap(self.convert(other))]),
+                    })
+                    .collect::<Vec<_>>();
+                let else_body = self.maybe(&inner.else_body);
+                let chain = self.chain("IN", ins, else_body);
+                self.node("CASE3", range, vec![expr, chain])
+            }
+
+            Node::Return(inner) => self.jump("RETURN", range, &inner.args),
+            Node::Break(inner) => self.jump("BREAK", range, &inner.args),
+            Node::Next(inner) => self.jump("NEXT", range, &inner.args),
+
+            Node::Rescue(inner) => {
+                let body = self.maybe(&inner.body);
+                let resbodies = inner
+                    .rescue_bodies
+                    .iter()
+                    .map(|rescue_body| match rescue_body {
+                        Node::RescueBody(rescue_body) => {
+                            let children = self.rescue_body(rescue_body);
+                            (rescue_body.expression_l.clone(), children)
+                        }
+                        other => (other.expression().clone(), vec![wrap(self.convert(other))]),
+                    })
+                    .collect::<Vec<_>>();
+                let resbody = self.chain("RESBODY", resbodies, MriValue::Nil);
+                let else_ = self.maybe(&inner.else_);
+                self.node("RESCUE", range, vec![body, resbody, else_])
+            }
+            Node::Ensure(inner) => {
+                let body = self.maybe(&inner.body);
+                let ensure = self.maybe(&inner.ensure);
+                self.node("ENSURE", range, vec![body, ensure])
+            }
+
+            Node::Def(inner) => {
+                self.push_scope(ScopeKind::Method);
+                let args = self.def_args(&inner.args, &inner.name_l);
+                let body = self.maybe(&inner.body);
+                let tbl = self.pop_scope();
+                let scope = wrap(self.scope_node(range, tbl, args, body));
+                self.node("DEFN", range, vec![sym(&inner.name), scope])
+            }
+            Node::Defs(inner) => {
+                let definee = wrap(self.convert(&inner.definee));
+                self.push_scope(ScopeKind::Method);
+                let args = self.def_args(&inner.args, &inner.name_l);
+                let body = self.maybe(&inner.body);
+                let tbl = self.pop_scope();
+                let scope = wrap(self.scope_node(range, tbl, args, body));
+                self.node("DEFS", range, vec![definee, sym(&inner.name), scope])
+            }
+            Node::Class(inner) => {
+                let cpath = wrap(self.cpath(&inner.name));
+                let superclass = self.maybe(&inner.superclass);
+                let header = inner.superclass.as_ref().unwrap_or(&inner.name);
+                let scope = wrap(self.body_scope(range, header, &inner.body));
+                self.node("CLASS", range, vec![cpath, superclass, scope])
+            }
+            Node::Module(inner) => {
+                let cpath = wrap(self.cpath(&inner.name));
+                let scope = wrap(self.body_scope(range, &inner.name, &inner.body));
+                self.node("MODULE", range, vec![cpath, scope])
+            }
+            Node::SClass(inner) => {
+                let expr = wrap(self.convert(&inner.expr));
+                let scope = wrap(self.body_scope(range, &inner.expr, &inner.body));
+                self.node("SCLASS", range, vec![expr, scope])
+            }
+
+            Node::Alias(inner) => match (&inner.to, &inner.from) {
+                (Node::Gvar(_), _) | (Node::BackRef(_), _) | (Node::NthRef(_), _) => {
+                    let to = self.source(inner.to.expression());
+                    let from = self.source(inner.from.expression());
+                    self.node("VALIAS", range, vec![sym(&to), sym(&from)])
+                }
+                (to, from) => {
+                    let to = wrap(self.convert(to));
+                    let from = wrap(self.convert(from));
+                    self.node("ALIAS", range, vec![to, from])
+                }
+            },
+            Node::Undef(inner) => {
+                let mut undefs = inner
+                    .names
+                    .iter()
+                    .map(|name| {
+                        let lit = wrap(self.convert(name));
+                        self.node("UNDEF", name.expression(), vec![lit])
+                    })
+                    .collect::<Vec<_>>();
+                let last = undefs.pop();
+                match last {
+                    Some(undef) if undefs.is_empty() => undef,
+                    last => {
+                        undefs.extend(last);
+                        self.node("BLOCK", range, undefs.into_iter().map(wrap).collect())
+                    }
+                }
+            }
+            Node::Defined(inner) => {
+                let value = wrap(self.convert(&inner.value));
+                self.node("DEFINED", range, vec![value])
+            }
+            Node::Splat(inner) => {
+                let value = self.maybe(&inner.value);
+                self.node("SPLAT", range, vec![value])
+            }
+            Node::Irange(inner) => {
+                let left = self.maybe(&inner.left);
+                let right = self.maybe(&inner.right);
+                self.node("DOT2", range, vec![left, right])
+            }
+            Node::Erange(inner) => {
+                let left = self.maybe(&inner.left);
+                let right = self.maybe(&inner.right);
+                self.node("DOT3", range, vec![left, right])
+            }
+            Node::IFlipFlop(inner) => {
+                let left = self.maybe(&inner.left);
+                let right = self.maybe(&inner.right);
+                self.node("FLIP2", range, vec![left, right])
+            }
+            Node::EFlipFlop(inner) => {
+                let left = self.maybe(&inner.left);
+                let right = self.maybe(&inner.right);
+                self.node("FLIP3", range, vec![left, right])
+            }
+            Node::MatchCurrentLine(inner) => {
+                let re = wrap(self.convert(&inner.re));
+                self.node("MATCH", range, vec![re])
+            }
+            Node::MatchWithLvasgn(inner) => {
+                for name in named_captures(&self.source(inner.re.expression())) {
+                    self.declare(&name);
+                }
+                let re = wrap(self.convert(&inner.re));
+                let value = wrap(self.convert(&inner.value));
+                self.node("MATCH2", range, vec![re, value])
+            }
+            Node::Postexe(inner) => {
+                let body = self.maybe(&inner.body);
+                self.node("POSTEXE", range, vec![body])
+            }
+
+            _ => self.generic(node),
+        }
+    }
+
+    fn generic(&mut self, node: &Node) -> MriNode {
+        let children = node
+            .children()
+            .into_iter()
+            .map(|child| wrap(self.convert(child)))
+            .collect();
+        self.node(&node.str_type().to_uppercase(), node.expression(), children)
+    }
+
+    fn literal(&self, range: &Range, inspected: String) -> MriNode {
+        self.node("LIT", range, vec![MriValue::Literal(inspected)])
+    }
+
+    // Strings, symbols and regexps
+
+    fn string_pieces(&mut self, parts: &[Node], out: &mut Vec<StrPiece>) {
+        for part in parts {
+            match part {
+                Node::Str(inner) => {
+                    let value = inner.value.to_string_lossy();
+                    match out.last_mut() {
+                        Some(StrPiece::Static(prev, prev_range)) => {
+                            prev.push_str(&value);
+                            *prev_range = prev_range.join(&inner.expression_l);
+                        }
+                        _ => out.push(StrPiece::Static(value, inner.expression_l.clone())),
+                    }
+                }
+                Node::Dstr(inner) => self.string_pieces(&inner.parts, out),
+                Node::Begin(inner) => {
+                    let body = match inner.statements.as_slice() {
+                        [] => MriValue::Nil,
+                        statements => wrap(self.statements(&inner.expression_l, statements)),
+                    };
+                    let evstr = self.node("EVSTR", &inner.expression_l, vec![body]);
+                    out.push(StrPiece::Dynamic(evstr))
+                }
+                other => {
+                    let value = wrap(self.convert(other));
+                    let evstr = self.node("EVSTR", other.expression(), vec![value]);
+                    out.push(StrPiece::Dynamic(evstr))
+                }
+            }
+        }
+    }
+
+    fn interpolated(
+        &mut self,
+        range: &Range,
+        parts: &[Node],
+        static_type: &str,
+        dynamic_type: &str,
+    ) -> MriNode {
+        let mut pieces = vec![];
+        self.string_pieces(parts, &mut pieces);
+        self.compose(range, pieces, dynamic_type, |converter, value| {
+            converter.node(static_type, range, vec![MriValue::Str(value)])
+        })
+    }
+
+    fn compose<F>(
+        &mut self,
+        range: &Range,
+        pieces: Vec<StrPiece>,
+        dynamic_type: &str,
+        make_static: F,
+    ) -> MriNode
+    where
+        F: FnOnce(&mut Self, String) -> MriNode,
+    {
+        let static_value = pieces
+            .iter()
+            .map(|piece| match piece {
+                StrPiece::Static(value, _) => Some(value.as_str()),
+                StrPiece::Dynamic(_) => None,
+            })
+            .collect::<Option<String>>();
+        if let Some(value) = static_value {
+            return make_static(self, value);
+        }
+
+        let mut pieces = pieces.into_iter().peekable();
+        let prefix = match pieces.next_if(|piece| matches!(piece, StrPiece::Static(_, _))) {
+            Some(StrPiece::Static(value, _)) => MriValue::Str(value),
+            _ => MriValue::Nil,
+        };
+        let mut rest = pieces
+            .map(|piece| match piece {
+                StrPiece::Static(value, range) => {
+                    self.node("STR", &range, vec![MriValue::Str(value)])
+                }
+                StrPiece::Dynamic(node) => node,
+            })
+            .collect::<Vec<_>>()
+            .into_iter();
+        let head = rest.next().map(wrap).unwrap_or(MriValue::Nil);
+        let tail = rest.collect::<Vec<_>>();
+        let tail = match (tail.first(), tail.last()) {
+            (Some(first), Some(last)) => {
+                let mut children = tail.iter().cloned().map(wrap).collect::<Vec<_>>();
+                children.push(MriValue::Nil);
+                wrap(spanning("LIST", first, last, children))
+            }
+            _ => MriValue::Nil,
+        };
+        self.node(dynamic_type, range, vec![prefix, head, tail])
+    }
+
+    fn regexp(&mut self, range: &Range, regexp: &Regexp) -> MriNode {
+        let options = match &regexp.options {
+            Some(Node::RegOpt(regopt)) => "mixn"
+                .chars()
+                .filter(|c| regopt.options.contains(c))
+                .collect::<String>(),
+            _ => String::new(),
+        };
+        let mut pieces = vec![];
+        self.string_pieces(&regexp.parts, &mut pieces);
+        self.compose(range, pieces, "DREGX", |converter, source| {
+            converter.literal(range, format!("/{}/{}", source, options))
+        })
+    }
+
+    // Collections and arguments
+
+    fn list_append(list: &mut MriNode, item: MriNode) {
+        list.last_lineno = item.last_lineno;
+        list.last_column = item.last_column;
+        let nil_idx = list.children.len().saturating_sub(1);
+        list.children.insert(nil_idx, wrap(item));
+    }
+
+    fn args_list(&mut self, args: &[&Node]) -> Option<MriNode> {
+        let mut acc: Option<MriNode> = None;
+
+        for arg in args {
+            match arg {
+                Node::Splat(splat) => {
+                    let value = self.maybe(&splat.value);
+                    acc = Some(match acc {
+                        None => self.node("SPLAT", arg.expression(), vec![value]),
+                        Some(prev) => {
+                            let end = self.node("SPLAT", arg.expression(), vec![]);
+                            spanning("ARGSCAT", &prev, &end, vec![wrap(prev.clone()), value])
+                        }
+                    });
+                }
+                _ => {
+                    let value = self.convert(arg);
+                    acc = Some(match acc {
+                        None => spanning(
+                            "LIST",
+                            &value,
+                            &value,
+                            vec![wrap(value.clone()), MriValue::Nil],
+                        ),
+                        Some(mut prev) if prev.node_type == "LIST" => {
+                            Self::list_append(&mut prev, value);
+                            prev
+                        }
+                        Some(mut prev) if prev.node_type == "ARGSCAT" && ends_with_list(&prev) => {
+                            if let Some(MriValue::Node(body)) = prev.children.get_mut(1) {
+                                Self::list_append(body, value.clone());
+                            }
+                            prev.last_lineno = value.last_lineno;
+                            prev.last_column = value.last_column;
+                            prev
+                        }
+                        Some(prev) if prev.node_type == "ARGSPUSH" => {
+                            let mut children = prev.children.clone();
+                            match children.pop() {
+                                Some(MriValue::Node(pushed)) => {
+                                    let head = children.pop().unwrap_or(MriValue::Nil);
+                                    let body = spanning(
+                                        "LIST",
+                                        &pushed,
+                                        &value,
+                                        vec![
+                                            MriValue::Node(pushed.clone()),
+                                            wrap(value.clone()),
+                                            MriValue::Nil,
+                                        ],
+                                    );
+                                    spanning("ARGSCAT", &prev, &value, vec![head, wrap(body)])
+                                }
+                                _ => spanning(
+                                    "ARGSPUSH",
+                                    &prev,
+                                    &value,
+                                    vec![wrap(prev.clone()), wrap(value.clone())],
+                                ),
+                            }
+                        }
+                        Some(prev) => spanning(
+                            "ARGSPUSH",
+                            &prev,
+                            &value,
+                            vec![wrap(prev.clone()), wrap(value.clone())],
+                        ),
+                    });
+                }
+            }
+        }
+
+        acc
+    }
+
+    fn maybe_args_list(&mut self, args: &[&Node]) -> MriValue {
+        match self.args_list(args) {
+            Some(list) => wrap(list),
+            None => MriValue::Nil,
+        }
+    }
+
+    fn call_args(&mut self, args: &[Node]) -> MriValue {
+        let (args, block_pass) = match args.split_last() {
+            Some((Node::BlockPass(block_pass), rest)) => (rest, Some(block_pass)),
+            _ => (args, None),
+        };
+        let args = args.iter().collect::<Vec<_>>();
+        let list = self.maybe_args_list(&args);
+        match block_pass {
+            None => list,
+            Some(block_pass) => {
+                let value = wrap(self.convert(&block_pass.value));
+                self.value("BLOCK_PASS", &block_pass.expression_l, vec![list, value])
+            }
+        }
+    }
+
+    fn hash(&mut self, range: &Range, pairs: &[Node]) -> MriNode {
+        let mut items = vec![];
+        for pair in pairs {
+            match pair {
+                Node::Pair(pair) => {
+                    items.push(wrap(self.convert(&pair.key)));
+                    items.push(wrap(self.convert(&pair.value)));
+                }
+                Node::Kwsplat(kwsplat) => {
+                    items.push(MriValue::Nil);
+                    items.push(wrap(self.convert(&kwsplat.value)));
+                }
+                other => {
+                    items.push(MriValue::Nil);
+                    items.push(wrap(self.convert(other)));
+                }
+            }
+        }
+        let list = match join_nodes(&pairs.iter().collect::<Vec<_>>()) {
+            Some(list_range) => {
+                items.push(MriValue::Nil);
+                self.value("LIST", &list_range, items)
+            }
+            None => MriValue::Nil,
+        };
+        self.node("HASH", range, vec![list])
+    }
+
+    fn jump(&mut self, node_type: &str, range: &Range, args: &[Node]) -> MriNode {
+        let value = match args {
+            [] => MriValue::Nil,
+            [arg] => wrap(self.convert(arg)),
+            args => self.maybe_args_list(&args.iter().collect::<Vec<_>>()),
+        };
+        self.node(node_type, range, vec![value])
+    }
+
+    // Calls
+
+    #[allow(clippy::too_many_arguments)]
+    fn send(
+        &mut self,
+        range: &Range,
+        recv: Option<&Node>,
+        method_name: &str,
+        args: &[Node],
+        has_dot: bool,
+        has_parens: bool,
+        safe: bool,
+    ) -> MriNode {
+        let recv = match recv {
+            None => {
+                if args.is_empty() && !has_parens {
+                    return self.node("VCALL", range, vec![sym(method_name)]);
+                }
+                let args = self.call_args(args);
+                return self.node("FCALL", range, vec![sym(method_name), args]);
+            }
+            Some(recv) => recv,
+        };
+
+        if method_name == "=~" && !has_dot && args.len() == 1 {
+            if let Node::Regexp(_) = recv {
+                let recv = wrap(self.convert(recv));
+                let value = wrap(self.convert(&args[0]));
+                return self.node("MATCH2", range, vec![recv, value]);
+            }
+            if let Node::Regexp(_) = &args[0] {
+                let value = wrap(self.convert(recv));
+                let re = wrap(self.convert(&args[0]));
+                return self.node("MATCH3", range, vec![re, value]);
+            }
+        }
+
+        let recv = wrap(self.convert(recv));
+        let args = self.call_args(args);
+        let node_type = if safe {
+            "QCALL"
+        } else if !has_dot && OPERATOR_METHODS.contains(&method_name) {
+            "OPCALL"
+        } else if has_dot && is_attr_assign(method_name) {
+            "ATTRASGN"
+        } else {
+            "CALL"
+        };
+        self.node(node_type, range, vec![recv, sym(method_name), args])
+    }
+
+    fn block(&mut self, range: &Range, block: &Block) -> MriNode {
+        let call = match &block.call {
+            Node::Lambda(_) => None,
+            call => Some(wrap(self.convert(call))),
+        };
+        let scope_range = block.begin_l.join(&block.end_l);
+
+        self.push_scope(ScopeKind::Block);
+        let args = match &block.args {
+            Some(Node::Args(args)) => self.args(args),
+            Some(other) => wrap(self.convert(other)),
+            None => MriValue::Nil,
+        };
+        let body = self.maybe(&block.body);
+        let tbl = self.pop_scope();
+        let scope = match &call {
+            None => wrap(self.scope_node(range, tbl, args, body)),
+            Some(_) => wrap(self.scope_node(&scope_range, tbl, args, body)),
+        };
+
+        match call {
+            None => self.node("LAMBDA", range, vec![scope]),
+            Some(call) => self.node("ITER", range, vec![call, scope]),
+        }
+    }
+
+    fn numblock(&mut self, range: &Range, numblock: &Numblock) -> MriNode {
+        let call = match &numblock.call {
+            Node::Lambda(_) => None,
+            call => Some(wrap(self.convert(call))),
+        };
+        let scope_range = numblock.begin_l.join(&numblock.end_l);
+
+        self.push_scope(ScopeKind::Block);
+        for n in 1..=numblock.numargs {
+            self.declare(&format!("_{}", n));
+        }
+        let args = self.value(
+            "ARGS",
+            &scope_range,
+            vec![
+                MriValue::Integer(numblock.numargs as usize),
+                MriValue::Nil,
+                MriValue::Nil,
+                MriValue::Nil,
+                MriValue::Integer(0),
+                MriValue::Nil,
+                MriValue::Nil,
+                MriValue::Nil,
+                MriValue::Nil,
+                MriValue::Nil,
+            ],
+        );
+        let body = wrap(self.convert(&numblock.body));
+        let tbl = self.pop_scope();
+
+        match call {
+            None => {
+                let scope = wrap(self.scope_node(range, tbl, args, body));
+                self.node("LAMBDA", range, vec![scope])
+            }
+            Some(call) => {
+                let scope = wrap(self.scope_node(&scope_range, tbl, args, body));
+                self.node("ITER", range, vec![call, scope])
+            }
+        }
+    }
+
+    fn logop(&mut self, node: &Node, node_type: &str) -> MriNode {
+        let mut operands = vec![];
+        collect_logop_operands(node, node_type == "AND", &mut operands);
+        let operands = operands
+            .into_iter()
+            .map(|operand| self.convert(operand))
+            .collect::<Vec<_>>();
+
+        // MRI appends each new operand to the innermost node of the chain,
+        // so every nested node ends where its right operand was added
+        let whole = self.node(node_type, node.expression(), vec![]);
+        let first = &operands[0];
+        let mut acc = operands[operands.len() - 1].clone();
+        for idx in (0..operands.len() - 1).rev() {
+            let last = if idx == 0 { &whole } else { &operands[idx + 1] };
+            acc = spanning(
+                node_type,
+                first,
+                last,
+                vec![wrap(operands[idx].clone()), wrap(acc)],
+            );
+        }
+        acc
+    }
+
+    fn if_(
+        &mut self,
+        range: &Range,
+        cond: &Node,
+        if_true: &Option<Node>,
+        if_false: &Option<Node>,
+        unless: bool,
+    ) -> MriNode {
+        let cond = wrap(self.convert(cond));
+        let if_true = self.maybe(if_true);
+        let if_false = self.maybe(if_false);
+        if unless {
+            self.node("UNLESS", range, vec![cond, if_false, if_true])
+        } else {
+            self.node("IF", range, vec![cond, if_true, if_false])
+        }
+    }
+
+    fn loop_(
+        &mut self,
+        node_type: &str,
+        range: &Range,
+        cond: &Node,
+        body: Option<&Node>,
+        begin_end_while: bool,
+    ) -> MriNode {
+        let cond = wrap(self.convert(cond));
+        let body = match body {
+            Some(body) => wrap(self.convert(body)),
+            None => MriValue::Nil,
+        };
+        self.node(
+            node_type,
+            range,
+            vec![cond, body, MriValue::Bool(begin_end_while)],
+        )
+    }
+
+    fn chain(
+        &mut self,
+        node_type: &str,
+        links: Vec<(Range, Vec<MriValue>)>,
+        last: MriValue,
+    ) -> MriValue {
+        links
+            .into_iter()
+            .rev()
+            .fold(last, |next, (range, mut children)| {
+                children.push(next);
+                self.value(node_type, &range, children)
+            })
+    }
+
+    fn rescue_body(&mut self, rescue_body: &RescueBody) -> Vec<MriValue> {
+        let exc_list = match &rescue_body.exc_list {
+            Some(Node::Array(array)) => {
+                self.maybe_args_list(&array.elements.iter().collect::<Vec<_>>())
+            }
+            Some(other) => self.maybe_args_list(&[other]),
+            None => MriValue::Nil,
+        };
+        let body = match &rescue_body.exc_var {
+            None => self.maybe(&rescue_body.body),
+            Some(exc_var) => {
+                let errinfo = self.node("ERRINFO", exc_var.expression(), vec![]);
+                let mut assign = self.target(exc_var);
+                assign.children.pop();
+                assign.children.push(wrap(errinfo));
+                let body = rescue_body.body.as_ref().map(|body| self.convert(body));
+                match body {
+                    None => wrap(assign),
+                    Some(body) => {
+                        let mut children = vec![wrap(assign.clone())];
+                        if body.node_type == "BLOCK" {
+                            children.extend(body.children.iter().cloned());
+                        } else {
+                            children.push(wrap(body.clone()));
+                        }
+                        wrap(spanning("BLOCK", &assign, &body, children))
+                    }
+                }
+            }
+        };
+        vec![exc_list, body]
+    }
+
+    // Assignments
+
+    fn target(&mut self, node: &Node) -> MriNode {
+        let range = node.expression();
+        match node {
+            Node::Lvasgn(inner) => {
+                let node_type = self.lasgn_type(&inner.name);
+                self.node(node_type, range, vec![sym(&inner.name), MriValue::Nil])
+            }
+            Node::Send(inner) if inner.recv.is_some() => {
+                let recv = self.maybe(&inner.recv);
+                self.node(
+                    "ATTRASGN",
+                    range,
+                    vec![recv, sym(&inner.method_name), MriValue::Nil],
+                )
+            }
+            Node::CSend(inner) => {
+                let recv = wrap(self.convert(&inner.recv));
+                self.node(
+                    "ATTRASGN",
+                    range,
+                    vec![recv, sym(&inner.method_name), MriValue::Nil],
+                )
+            }
+            Node::Mlhs(_) => {
+                let (head, rest) = self.mlhs(node);
+                self.node("MASGN", range, vec![MriValue::Nil, head, rest])
+            }
+            Node::Splat(inner) => match &inner.value {
+                Some(value) => self.target(value),
+                None => self.node("SPLAT", range, vec![MriValue::Nil]),
+            },
+            other => self.convert(other),
+        }
+    }
+
+    fn mlhs(&mut self, node: &Node) -> (MriValue, MriValue) {
+        let items = match node {
+            Node::Mlhs(mlhs) => mlhs.items.iter().collect::<Vec<_>>(),
+            other => vec![other],
+        };
+        let splat_idx = items.iter().position(|item| matches!(item, Node::Splat(_)));
+        let (pre, splat, post) = match splat_idx {
+            Some(idx) => (&items[..idx], Some(items[idx]), &items[idx + 1..]),
+            None => (&items[..], None, &items[..0]),
+        };
+
+        let pre_targets = pre
+            .iter()
+            .map(|item| wrap(self.target(item)))
+            .collect::<Vec<_>>();
+        let head = match join_nodes(pre) {
+            Some(range) => {
+                let mut children = pre_targets;
+                children.push(MriValue::Nil);
+                self.value("LIST", &range, children)
+            }
+            None => MriValue::Nil,
+        };
+
+        let rest = match splat {
+            None => MriValue::Nil,
+            Some(splat) => {
+                let rest = match splat {
+                    Node::Splat(inner) => match &inner.value {
+                        Some(value) => wrap(self.target(value)),
+                        None => sym("NODE_SPECIAL_NO_NAME_REST"),
+                    },
+                    _ => sym("NODE_SPECIAL_NO_NAME_REST"),
+                };
+                match join_nodes(post) {
+                    None => rest,
+                    Some(post_range) => {
+                        let mut children = post
+                            .iter()
+                            .map(|item| wrap(self.target(item)))
+                            .collect::<Vec<_>>();
+                        children.push(MriValue::Nil);
+                        let post_list = self.value("LIST", &post_range, children);
+                        let range = splat.expression().join(&post_range);
+                        self.value("POSTARG", &range, vec![rest, post_list])
+                    }
+                }
+            }
+        };
+
+        (head, rest)
+    }
+
+    fn op_asgn(&mut self, range: &Range, recv: &Node, operator: &str, value: &Node) -> MriNode {
+        let var = match recv {
+            Node::Lvasgn(inner) => {
+                let asgn_type = self.lasgn_type(&inner.name);
+                Some((self.lvar_type(&inner.name), asgn_type, &inner.name))
+            }
+            Node::Ivasgn(inner) => Some(("IVAR", "IASGN", &inner.name)),
+            Node::Gvasgn(inner) => Some(("GVAR", "GASGN", &inner.name)),
+            Node::Cvasgn(inner) => Some(("CVAR", "CVASGN", &inner.name)),
+            _ => None,
+        };
+
+        if let Some((read_type, asgn_type, name)) = var {
+            let read = self.node(read_type, recv.expression(), vec![sym(name)]);
+            let value = self.convert(value);
+            return match operator {
+                "||" | "&&" => {
+                    let node_type = if operator == "||" {
+                        "OP_ASGN_OR"
+                    } else {
+                        "OP_ASGN_AND"
+                    };
+                    let asgn = self.node(asgn_type, range, vec![sym(name), wrap(value)]);
+                    self.node(node_type, range, vec![wrap(read), wrap(asgn)])
+                }
+                _ => {
+                    let args = spanning(
+                        "LIST",
+                        &value,
+                        &value,
+                        vec![wrap(value.clone()), MriValue::Nil],
+                    );
+                    let call =
+                        self.node("CALL", range, vec![wrap(read), sym(operator), wrap(args)]);
+                    self.node(asgn_type, range, vec![sym(name), wrap(call)])
+                }
+            };
+        }
+
+        match recv {
+            Node::Casgn(inner) => {
+                let path = match &inner.scope {
+                    None => self.node("CONST", &inner.name_l, vec![sym(&inner.name)]),
+                    Some(scope) => self.colon(scope, &inner.name, &inner.name_l),
+                };
+                let value = wrap(self.convert(value));
+                self.node("OP_CDECL", range, vec![wrap(path), sym(operator), value])
+            }
+            Node::Send(inner) if inner.recv.is_some() => {
+                let send_recv = self.maybe(&inner.recv);
+                let value = wrap(self.convert(value));
+                self.node(
+                    "OP_ASGN2",
+                    range,
+                    vec![
+                        send_recv,
+                        MriValue::Bool(false),
+                        sym(&inner.method_name),
+                        sym(operator),
+                        value,
+                    ],
+                )
+            }
+            Node::CSend(inner) => {
+                let send_recv = wrap(self.convert(&inner.recv));
+                let value = wrap(self.convert(value));
+                self.node(
+                    "OP_ASGN2",
+                    range,
+                    vec![
+                        send_recv,
+                        MriValue::Bool(true),
+                        sym(&inner.method_name),
+                        sym(operator),
+                        value,
+                    ],
+                )
+            }
+            Node::IndexAsgn(inner) => {
+                let index_recv = wrap(self.convert(&inner.recv));
+                let indexes = inner.indexes.iter().collect::<Vec<_>>();
+                let indexes = self.maybe_args_list(&indexes);
+                let value = wrap(self.convert(value));
+                self.node(
+                    "OP_ASGN1",
+                    range,
+                    vec![index_recv, sym(operator), indexes, value],
+                )
+            }
+            other => {
+                let target = wrap(self.convert(other));
+                let value = wrap(self.convert(value));
+                self.node("OP_ASGN", range, vec![target, sym(operator), value])
+            }
+        }
+    }
+
+    // Definitions
+
+    fn colon(&mut self, scope: &Node, name: &str, name_l: &Range) -> MriNode {
+        let range = scope.expression().join(name_l);
+        match scope {
+            Node::Cbase(_) => self.node("COLON3", &range, vec![sym(name)]),
+            scope => {
+                let scope = wrap(self.convert(scope));
+                self.node("COLON2", &range, vec![scope, sym(name)])
+            }
+        }
+    }
+
+    fn cpath(&mut self, name: &Node) -> MriNode {
+        match name {
+            Node::Const(inner) if inner.scope.is_none() => self.node(
+                "COLON2",
+                &inner.expression_l,
+                vec![MriValue::Nil, sym(&inner.name)],
+            ),
+            other => self.convert(other),
+        }
+    }
+
+    fn body_scope(&mut self, range: &Range, header: &Node, body: &Option<Node>) -> MriNode {
+        self.push_scope(ScopeKind::Method);
+        // MRI keeps an empty `BEGIN` right after the header for empty bodies
+        let body = match body {
+            Some(body) => wrap(self.convert(body)),
+            None => self.value("BEGIN", &header.expression().end(), vec![MriValue::Nil]),
+        };
+        let tbl = self.pop_scope();
+        self.scope_node(range, tbl, MriValue::Nil, body)
+    }
+
+    fn def_args(&mut self, args: &Option<Node>, name_l: &Range) -> MriValue {
+        match args {
+            Some(Node::Args(args)) => self.args(args),
+            Some(other) => wrap(self.convert(other)),
+            None => {
+                let empty = Args {
+                    args: vec![],
+                    begin_l: None,
+                    end_l: None,
+                    expression_l: name_l.end(),
+                };
+                self.args(&empty)
+            }
+        }
+    }
+
+    fn args(&mut self, args: &Args) -> MriValue {
+        let flat = args
+            .args
+            .iter()
+            .flat_map(|arg| match arg {
+                Node::Procarg0(procarg0) => procarg0.args.iter().collect::<Vec<_>>(),
+                arg => vec![arg],
+            })
+            .collect::<Vec<_>>();
+
+        // MRI declares required keywords before optional ones and reserves
+        // an unnamed slot for the keyword bitmap right after them
+        let mut kw_declared = false;
+        let declare_kwargs = |converter: &mut Self| {
+            for arg in flat.iter() {
+                if let Node::Kwarg(kwarg) = arg {
+                    converter.declare(&kwarg.name);
+                }
+            }
+            for arg in flat.iter() {
+                if let Node::Kwoptarg(kwoptarg) = arg {
+                    converter.declare(&kwoptarg.name);
+                }
+            }
+            converter.declare_internal(None);
+        };
+        for arg in flat.iter() {
+            match arg {
+                Node::Kwarg(_) | Node::Kwoptarg(_) if !kw_declared => {
+                    declare_kwargs(self);
+                    kw_declared = true;
+                }
+                Node::Arg(arg) => self.declare(&arg.name),
+                Node::Optarg(arg) => self.declare(&arg.name),
+                Node::Restarg(restarg) => match &restarg.name {
+                    Some(name) => self.declare(name),
+                    None => self.declare_internal(None),
+                },
+                Node::Kwrestarg(kwrestarg) => match &kwrestarg.name {
+                    Some(name) => self.declare(name),
+                    None => self.declare_internal(None),
+                },
+                Node::Blockarg(arg) => self.declare(&arg.name),
+                Node::Shadowarg(arg) => self.declare(&arg.name),
+                Node::Mlhs(_) => {
+                    self.declare_internal(None);
+                    self.declare_targets(arg);
+                }
+                _ => {}
+            }
+        }
+
+        let mut pre_num = 0;
+        let mut post_num = 0;
+        let mut first_post = MriValue::Nil;
+        let mut opts = vec![];
+        let mut rest = MriValue::Nil;
+        let mut kws = vec![];
+        let mut kwrest = MriValue::Nil;
+        let mut no_kwarg = false;
+        let mut block = MriValue::Nil;
+        let mut seen_rest_or_opt = false;
+
+        for arg in flat.iter() {
+            match arg {
+                Node::Arg(_) | Node::Mlhs(_) => {
+                    if seen_rest_or_opt {
+                        if post_num == 0 {
+                            if let Node::Arg(arg) = arg {
+                                first_post = sym(&arg.name);
+                            }
+                        }
+                        post_num += 1;
+                    } else {
+                        pre_num += 1;
+                    }
+                }
+                Node::Optarg(optarg) => {
+                    seen_rest_or_opt = true;
+                    let node_type = self.lasgn_type(&optarg.name);
+                    let default = wrap(self.convert(&optarg.default));
+                    let asgn = self.value(
+                        node_type,
+                        &optarg.expression_l,
+                        vec![sym(&optarg.name), default],
+                    );
+                    opts.push((optarg.expression_l.clone(), vec![asgn]));
+                }
+                Node::Restarg(restarg) => {
+                    seen_rest_or_opt = true;
+                    rest = match &restarg.name {
+                        Some(name) => sym(name),
+                        None => MriValue::Nil,
+                    };
+                }
+                Node::Kwarg(kwarg) => {
+                    let node_type = self.lasgn_type(&kwarg.name);
+                    let asgn = self.value(
+                        node_type,
+                        &kwarg.expression_l,
+                        vec![sym(&kwarg.name), sym("NODE_SPECIAL_REQUIRED_KEYWORD")],
+                    );
+                    kws.push((kwarg.expression_l.clone(), vec![asgn]));
+                }
+                Node::Kwoptarg(kwoptarg) => {
+                    let node_type = self.lasgn_type(&kwoptarg.name);
+                    let default = wrap(self.convert(&kwoptarg.default));
+                    let asgn = self.value(
+                        node_type,
+                        &kwoptarg.expression_l,
+                        vec![sym(&kwoptarg.name), default],
+                    );
+                    kws.push((kwoptarg.expression_l.clone(), vec![asgn]));
+                }
+                Node::Kwrestarg(kwrestarg) => {
+                    let name = match &kwrestarg.name {
+                        Some(name) => sym(name),
+                        None => MriValue::Nil,
+                    };
+                    kwrest = self.value("DVAR", &kwrestarg.expression_l, vec![name]);
+                }
+                Node::Kwnilarg(_) => no_kwarg = true,
+                Node::Blockarg(blockarg) => block = sym(&blockarg.name),
+                _ => {}
+            }
+        }
+
+        let opt = self.chain("OPT_ARG", opts, MriValue::Nil);
+        let kw = self.chain("KW_ARG", kws, MriValue::Nil);
+        let (kw, kwrest) = if no_kwarg {
+            (MriValue::Bool(false), MriValue::Bool(false))
+        } else {
+            (kw, kwrest)
+        };
+
+        let range = join_nodes(&args.args.iter().collect::<Vec<_>>())
+            .unwrap_or_else(|| args.expression_l.clone());
+        self.value(
+            "ARGS",
+            &range,
+            vec![
+                MriValue::Integer(pre_num),
+                MriValue::Nil,
+                opt,
+                first_post,
+                MriValue::Integer(post_num),
+                MriValue::Nil,
+                rest,
+                kw,
+                kwrest,
+                block,
+            ],
+        )
+    }
+
+    fn declare_targets(&mut self, node: &Node) {
+        match node {
+            Node::Arg(arg) => self.declare(&arg.name),
+            Node::Restarg(restarg) => {
+                if let Some(name) = &restarg.name {
+                    self.declare(name)
+                }
+            }
+            Node::Mlhs(mlhs) => {
+                for item in mlhs.items.iter() {
+                    self.declare_targets(item)
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_logop_operands<'n>(node: &'n Node, is_and: bool, out: &mut Vec<&'n Node>) {
+    match (node, is_and) {
+        (Node::And(inner), true) => {
+            collect_logop_operands(&inner.lhs, is_and, out);
+            out.push(&inner.rhs);
+        }
+        (Node::Or(inner), false) => {
+            collect_logop_operands(&inner.lhs, is_and, out);
+            out.push(&inner.rhs);
+        }
+        _ => out.push(node),
+    }
+}
+
+fn ends_with_list(node: &MriNode) -> bool {
+    match node.children.get(1) {
+        Some(MriValue::Node(body)) => body.node_type == "LIST",
+        _ => false,
+    }
+}
+
+fn is_attr_assign(method_name: &str) -> bool {
+    method_name.ends_with('=') && !matches!(method_name, "==" | "!=" | "===" | "<=" | ">=")
+}
+
+fn named_captures(source: &str) -> Vec<String> {
+    source
+        .split("(?<")
+        .skip(1)
+        .filter_map(|rest| {
+            let name = rest.split('>').next()?;
+            if !name.is_empty()
+                && !name.starts_with(['=', '!'])
+                && name.chars().all(|c| c == '_' || c.is_alphanumeric())
+            {
+                Some(name.to_owned())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+// Literals are rendered the way `#inspect` renders them in Ruby
+
+fn split_sign(value: &str) -> (&str, &str) {
+    match value.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", value.strip_prefix('+').unwrap_or(value)),
+    }
+}
+
+fn parse_int(value: &str) -> Option<i128> {
+    let clean = value.replace('_', "").to_ascii_lowercase();
+    let (sign, digits) = split_sign(&clean);
+    let (radix, digits) = if let Some(digits) = digits.strip_prefix("0x") {
+        (16, digits)
+    } else if let Some(digits) = digits.strip_prefix("0b") {
+        (2, digits)
+    } else if let Some(digits) = digits.strip_prefix("0o") {
+        (8, digits)
+    } else if let Some(digits) = digits.strip_prefix("0d") {
+        (10, digits)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        (8, &digits[1..])
+    } else {
+        (10, digits)
+    };
+    let value = i128::from_str_radix(digits, radix).ok()?;
+    Some(if sign == "-" { -value } else { value })
+}
+
+fn int_literal(value: &str) -> String {
+    match parse_int(value) {
+        Some(value) => value.to_string(),
+        None => value.to_owned(),
+    }
+}
+
+fn ruby_float(value: f64) -> String {
+    let abs = value.abs();
+    if abs == 0.0 || (1e-4..1e16).contains(&abs) {
+        return format!("{:?}", value);
+    }
+    if !value.is_finite() {
+        return format!("{:?}", value);
+    }
+    let formatted = format!("{:e}", value);
+    let (mantissa, exponent) = formatted.split_at(formatted.find('e').unwrap_or(formatted.len()));
+    let mantissa = if mantissa.contains('.') {
+        mantissa.to_owned()
+    } else {
+        format!("{}.0", mantissa)
+    };
+    let exponent = exponent.trim_start_matches('e');
+    let (sign, digits) = match exponent.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("+", exponent),
+    };
+    format!("{}e{}{:0>2}", mantissa, sign, digits)
+}
+
+fn float_literal(value: &str) -> String {
+    match value.replace('_', "").parse::<f64>() {
+        Ok(parsed) => ruby_float(parsed),
+        Err(_) => value.to_owned(),
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn rational_parts(value: &str) -> Option<(i128, i128)> {
+    let value = value.trim_end_matches('r').replace('_', "");
+    let (numerator, denominator) = match value.find('.') {
+        Some(dot) => {
+            let fraction = &value[dot + 1..];
+            let digits = format!("{}{}", &value[..dot], fraction);
+            (
+                digits.parse::<i128>().ok()?,
+                10i128.checked_pow(fraction.len() as u32)?,
+            )
+        }
+        None => (parse_int(&value)?, 1),
+    };
+    let divisor = gcd(numerator, denominator).max(1);
+    Some((numerator / divisor, denominator / divisor))
+}
+
+fn rational_literal(value: &str) -> String {
+    match rational_parts(value) {
+        Some((numerator, denominator)) => format!("({}/{})", numerator, denominator),
+        None => value.to_owned(),
+    }
+}
+
+fn complex_literal(value: &str) -> String {
+    let imaginary = value.trim_end_matches('i');
+    let (sign, imaginary) = split_sign(imaginary);
+    let sign = if sign == "-" { "-" } else { "+" };
+    if imaginary.ends_with('r') {
+        format!("(0{}{}*i)", sign, rational_literal(imaginary))
+    } else if imaginary.contains('.')
+        || (imaginary.contains(['e', 'E']) && !imaginary.starts_with("0x"))
+    {
+        format!("(0{}{}i)", sign, float_literal(imaginary))
+    } else {
+        format!("(0{}{}i)", sign, int_literal(imaginary))
+    }
+}
@@ -0,0 +1,39 @@
+//! Conversion of `Node` into the tree returned by MRI's `RubyVM::AbstractSyntaxTree.parse`.
+//!
+//! The result follows MRI's node types, child ordering and
+//! `first_lineno/first_column/last_lineno/last_column` locations
+//! (lines are 1-based, columns are 0-based byte offsets),
+//! so it can be compared with MRI output without running Ruby.
+//! `MriNode::inspect` prints the same text as this Ruby snippet:
+//!
+//! ```text
+//! def dump(node)
+//!   case node
+//!   when RubyVM::AbstractSyntaxTree::Node
+//!     loc = "#{node.first_lineno}:#{node.first_column}-#{node.last_lineno}:#{node.last_column}"
+//!     ["(#{node.type}@#{loc}", *node.children.map { |c| dump(c) }].join(' ') + ')'
+//!   when Array then "[#{node.map { |c| dump(c) }.join(', ')}]"
+//!   else node.inspect
+//!   end
+//! end
+//!
+//! puts dump(RubyVM::AbstractSyntaxTree.parse(code))
+//! ```
+//!
+//! Conversion follows Ruby 3.0. Pattern matching, `for` loops and `BEGIN` blocks
+//! are converted on a best-effort basis, and nodes that have no MRI counterpart here
+//! keep their own type name in upper case (e.g. `ARRAY_PATTERN`).
+
+mod converter;
+use converter::Converter;
+
+mod node;
+pub use node::{MriNode, MriValue};
+
+use crate::source::Input;
+use crate::Node;
+
+/// Converts a parsed AST into a `SCOPE` node, like `RubyVM::AbstractSyntaxTree.parse` does
+pub fn convert(ast: Option<&Node>, input: &Input) -> MriNode {
+    Converter::new(input).program(ast)
+}
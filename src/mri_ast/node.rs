@@ -0,0 +1,149 @@
+/// A node in the shape of `RubyVM::AbstractSyntaxTree::Node`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MriNode {
+    /// Node type without the `NODE_` prefix, like `"CALL"` or `"LASGN"`
+    pub node_type: String,
+
+    /// Children in the order returned by `RubyVM::AbstractSyntaxTree::Node#children`
+    pub children: Vec<MriValue>,
+
+    /// 1-based line of the first byte
+    pub first_lineno: usize,
+
+    /// 0-based byte column of the first byte
+    pub first_column: usize,
+
+    /// 1-based line of the last byte
+    pub last_lineno: usize,
+
+    /// 0-based byte column right after the last byte
+    pub last_column: usize,
+}
+
+/// A child of `MriNode`
+#[derive(Debug, Clone, PartialEq)]
+pub enum MriValue {
+    Nil,
+    Node(Box<MriNode>),
+    Symbol(String),
+    Str(String),
+    Integer(usize),
+    Bool(bool),
+    /// A literal object (`Integer`, `Float`, `Regexp`, ...) stored as its `#inspect`
+    Literal(String),
+    Array(Vec<MriValue>),
+}
+
+impl MriNode {
+    /// Returns `(TYPE@first_lineno:first_column-last_lineno:last_column child child ...)`
+    pub fn inspect(&self) -> String {
+        let mut out = format!(
+            "({}@{}:{}-{}:{}",
+            self.node_type,
+            self.first_lineno,
+            self.first_column,
+            self.last_lineno,
+            self.last_column
+        );
+        for child in self.children.iter() {
+            out.push(' ');
+            out.push_str(&child.inspect());
+        }
+        out.push(')');
+        out
+    }
+}
+
+impl MriValue {
+    pub fn inspect(&self) -> String {
+        match self {
+            MriValue::Nil => "nil".to_owned(),
+            MriValue::Node(node) => node.inspect(),
+            MriValue::Symbol(name) => inspect_symbol(name),
+            MriValue::Str(value) => inspect_string(value),
+            MriValue::Integer(value) => value.to_string(),
+            MriValue::Bool(value) => value.to_string(),
+            MriValue::Literal(value) => value.clone(),
+            MriValue::Array(items) => format!(
+                "[{}]",
+                items
+                    .iter()
+                    .map(|item| item.inspect())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    pub fn as_node(&self) -> Option<&MriNode> {
+        match self {
+            MriValue::Node(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn inspect_string(value: &str) -> String {
+    let mut out = String::from("\"");
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            '\x0c' => out.push_str("\\f"),
+            '\x0b' => out.push_str("\\v"),
+            '\x08' => out.push_str("\\b"),
+            '\x07' => out.push_str("\\a"),
+            '\x1b' => out.push_str("\\e"),
+            '#' if matches!(chars.peek(), Some('{') | Some('$') | Some('@')) => out.push_str("\\#"),
+            c if (c as u32) < 0x20 || c as u32 == 0x7f => {
+                out.push_str(&format!("\\x{:02X}", c as u32))
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+const OPERATOR_SYMBOLS: &[&str] = &[
+    "[]", "[]=", "+", "-", "*", "/", "%", "**", "==", "===", "!=", "=~", "!~", "<=>", "<", "<=",
+    ">", ">=", "<<", ">>", "&", "|", "^", "~", "!", "+@", "-@", "`",
+];
+
+pub(crate) fn inspect_symbol(name: &str) -> String {
+    if OPERATOR_SYMBOLS.contains(&name) || is_plain_symbol(name) {
+        format!(":{}", name)
+    } else {
+        format!(":{}", inspect_string(name))
+    }
+}
+
+fn is_plain_symbol(name: &str) -> bool {
+    let ident = name
+        .trim_start_matches("@@")
+        .trim_start_matches('@')
+        .trim_start_matches('$');
+    let sigils = name.len() - ident.len();
+    if sigils == 1 && name.starts_with('$') && ident.len() == 1 {
+        // special globals like $~ or $!
+        return true;
+    }
+    let ident = if sigils == 0 {
+        ident
+            .strip_suffix(|c| c == '?' || c == '!' || c == '=')
+            .unwrap_or(ident)
+    } else {
+        ident
+    };
+    let mut chars = ident.chars();
+    match chars.next() {
+        Some(c) if c == '_' || c.is_alphabetic() || !c.is_ascii() => {}
+        Some(c) if c.is_ascii_digit() && name.starts_with('$') => {}
+        _ => return false,
+    }
+    chars.all(|c| c == '_' || c.is_alphanumeric() || !c.is_ascii())
+}
@@ -0,0 +1,19 @@
+use crate::source::Range;
+use crate::Node;
+use crate::StringValue;
+
+/// Value of a single node field, returned by `Node::fields`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldValue<'a> {
+    Node(&'a Node),
+    Nodes(&'a [Node]),
+    MaybeNode(Option<&'a Node>),
+    Range(&'a Range),
+    MaybeRange(Option<&'a Range>),
+    Str(&'a str),
+    MaybeStr(Option<&'a str>),
+    Chars(&'a [char]),
+    StringValue(&'a StringValue),
+    U8(u8),
+    Usize(usize),
+}
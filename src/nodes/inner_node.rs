@@ -1,3 +1,5 @@
+use crate::nodes::FieldValue;
+use crate::nodes::{StructuralHasher, StructureOptions};
 use crate::source::Range;
 use crate::Node;
 use crate::StringValue;
@@ -21,6 +23,18 @@ pub trait InnerNode {
     }
 
     fn print_with_locs(&self);
+
+    fn children(&self) -> Vec<&Node>;
+
+    fn fields(&self) -> Vec<(&'static str, FieldValue<'_>)>;
+
+    fn structurally_eq(&self, other: &Node, options: &StructureOptions) -> bool;
+    fn hash_structure(&self, state: &mut StructuralHasher, options: &StructureOptions);
+
+    #[cfg(feature = "json")]
+    fn json_children(&self) -> Vec<serde_json::Value>;
+
+    fn map_ranges(&mut self, f: &mut dyn FnMut(&mut Range));
 }
 
 pub(crate) struct InspectVec {
@@ -0,0 +1,120 @@
+use crate::nodes::FieldValue;
+use crate::source::Range;
+use crate::Node;
+use crate::StringValue;
+use serde_json::{json, Map, Value};
+
+fn range_to_json(range: &Range) -> Value {
+    json!({ "begin_pos": range.begin_pos, "end_pos": range.end_pos })
+}
+
+/// Returns `{"type":..,"children":[..],"location":{..}}` representation of the node.
+///
+/// Location map is built from `*_l` fields of the node (without the `_l` suffix)
+pub(crate) fn node_to_json(node: &Node) -> Value {
+    let mut location = Map::new();
+    for (name, value) in node.fields() {
+        let range = match value {
+            FieldValue::Range(range) => Some(range),
+            FieldValue::MaybeRange(range) => range,
+            _ => continue,
+        };
+        let name = name.strip_suffix("_l").unwrap_or(name);
+        location.insert(name.to_owned(), range.map_or(Value::Null, range_to_json));
+    }
+
+    json!({
+        "type": node.str_type(),
+        "children": node.inner_ref().json_children(),
+        "location": location,
+    })
+}
+
+/// JSON counterpart of `InspectVec`, collects children
+/// in the same order and format as `Node::inspect`
+pub(crate) struct JsonVec {
+    values: Vec<Value>,
+}
+
+impl JsonVec {
+    pub(crate) fn new() -> Self {
+        Self { values: vec![] }
+    }
+
+    pub(crate) fn push_str(&mut self, string: &str) {
+        self.values.push(Value::String(string.to_owned()));
+    }
+
+    // Raw strings are printed by `inspect` without quotes (like `1` in `s(:nth_ref, 1)`),
+    // so they are numbers in JSON unless they don't fit into u64
+    pub(crate) fn push_raw_str(&mut self, string: &str) {
+        match string.parse::<u64>() {
+            Ok(n) => self.values.push(Value::from(n)),
+            Err(_) => self.push_str(string),
+        }
+    }
+
+    pub(crate) fn push_maybe_str(&mut self, string: &Option<String>) {
+        if let Some(string) = string {
+            self.push_str(string);
+        }
+    }
+
+    pub(crate) fn push_nil(&mut self) {
+        self.values.push(Value::Null);
+    }
+
+    pub(crate) fn push_u8(&mut self, n: &u8) {
+        self.values.push(Value::from(*n))
+    }
+
+    pub(crate) fn push_node(&mut self, node: &Node) {
+        self.values.push(node_to_json(node))
+    }
+
+    pub(crate) fn push_maybe_node(&mut self, node: &Option<Node>) {
+        if let Some(node) = node {
+            self.push_node(node)
+        }
+    }
+
+    pub(crate) fn push_regex_options(&mut self, node: &Option<Node>) {
+        if let Some(node) = node {
+            self.push_node(node)
+        } else {
+            self.values.push(json!({
+                "type": "regopt",
+                "children": [],
+                "location": { "expression": null },
+            }))
+        }
+    }
+
+    pub(crate) fn push_maybe_node_or_nil(&mut self, node: &Option<Node>) {
+        if let Some(node) = node {
+            self.push_node(node)
+        } else {
+            self.push_nil()
+        }
+    }
+
+    pub(crate) fn push_nodes(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.push_node(node)
+        }
+    }
+
+    pub(crate) fn push_chars(&mut self, chars: &[char]) {
+        for c in chars {
+            self.push_str(&c.to_string());
+        }
+    }
+
+    pub(crate) fn push_string_value(&mut self, s: &StringValue) {
+        self.push_str(&s.to_string_lossy())
+    }
+
+    pub(crate) fn values(self) -> Vec<Value> {
+        self.values
+    }
+}
@@ -2,6 +2,15 @@ mod node;
 pub use node::Node;
 mod inner_node;
 pub(crate) use inner_node::{InnerNode, InspectVec};
+mod field_value;
+pub use field_value::FieldValue;
+mod structure;
+pub(crate) use structure::{structurally_eq_maybe_node, structurally_eq_nodes, StructuralHasher};
+pub use structure::StructureOptions;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "json")]
+pub(crate) use json::{node_to_json, JsonVec};
 
 mod types;
 pub use types::*;
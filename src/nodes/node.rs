@@ -1,6 +1,8 @@
 use crate::nodes::InnerNode;
 use crate::nodes::*;
+use crate::nodes::{StructuralHasher, StructureOptions};
 use crate::source::Range;
+use std::hash::Hasher;
 
 /// Generic combination of all known nodes.
 #[derive(Debug, Clone, PartialEq)]
@@ -257,6 +259,133 @@ impl Node {
         }
     }
 
+    pub(crate) fn inner_mut(&mut self) -> &mut dyn InnerNode {
+        match self {
+            Node::Alias(inner) => inner.as_mut(),
+            Node::And(inner) => inner.as_mut(),
+            Node::AndAsgn(inner) => inner.as_mut(),
+            Node::Arg(inner) => inner.as_mut(),
+            Node::Args(inner) => inner.as_mut(),
+            Node::Array(inner) => inner.as_mut(),
+            Node::ArrayPattern(inner) => inner.as_mut(),
+            Node::ArrayPatternWithTail(inner) => inner.as_mut(),
+            Node::BackRef(inner) => inner.as_mut(),
+            Node::Begin(inner) => inner.as_mut(),
+            Node::Block(inner) => inner.as_mut(),
+            Node::Blockarg(inner) => inner.as_mut(),
+            Node::BlockPass(inner) => inner.as_mut(),
+            Node::Break(inner) => inner.as_mut(),
+            Node::Case(inner) => inner.as_mut(),
+            Node::CaseMatch(inner) => inner.as_mut(),
+            Node::Casgn(inner) => inner.as_mut(),
+            Node::Cbase(inner) => inner.as_mut(),
+            Node::Class(inner) => inner.as_mut(),
+            Node::Complex(inner) => inner.as_mut(),
+            Node::Const(inner) => inner.as_mut(),
+            Node::ConstPattern(inner) => inner.as_mut(),
+            Node::CSend(inner) => inner.as_mut(),
+            Node::Cvar(inner) => inner.as_mut(),
+            Node::Cvasgn(inner) => inner.as_mut(),
+            Node::Def(inner) => inner.as_mut(),
+            Node::Defined(inner) => inner.as_mut(),
+            Node::Defs(inner) => inner.as_mut(),
+            Node::Dstr(inner) => inner.as_mut(),
+            Node::Dsym(inner) => inner.as_mut(),
+            Node::EFlipFlop(inner) => inner.as_mut(),
+            Node::EmptyElse(inner) => inner.as_mut(),
+            Node::Encoding(inner) => inner.as_mut(),
+            Node::Ensure(inner) => inner.as_mut(),
+            Node::Erange(inner) => inner.as_mut(),
+            Node::False(inner) => inner.as_mut(),
+            Node::File(inner) => inner.as_mut(),
+            Node::FindPattern(inner) => inner.as_mut(),
+            Node::Float(inner) => inner.as_mut(),
+            Node::For(inner) => inner.as_mut(),
+            Node::ForwardArg(inner) => inner.as_mut(),
+            Node::ForwardedArgs(inner) => inner.as_mut(),
+            Node::Gvar(inner) => inner.as_mut(),
+            Node::Gvasgn(inner) => inner.as_mut(),
+            Node::Hash(inner) => inner.as_mut(),
+            Node::HashPattern(inner) => inner.as_mut(),
+            Node::Heredoc(inner) => inner.as_mut(),
+            Node::If(inner) => inner.as_mut(),
+            Node::IfGuard(inner) => inner.as_mut(),
+            Node::IFlipFlop(inner) => inner.as_mut(),
+            Node::IfMod(inner) => inner.as_mut(),
+            Node::IfTernary(inner) => inner.as_mut(),
+            Node::Index(inner) => inner.as_mut(),
+            Node::IndexAsgn(inner) => inner.as_mut(),
+            Node::InMatch(inner) => inner.as_mut(),
+            Node::InPattern(inner) => inner.as_mut(),
+            Node::Int(inner) => inner.as_mut(),
+            Node::Irange(inner) => inner.as_mut(),
+            Node::Ivar(inner) => inner.as_mut(),
+            Node::Ivasgn(inner) => inner.as_mut(),
+            Node::Kwarg(inner) => inner.as_mut(),
+            Node::KwBegin(inner) => inner.as_mut(),
+            Node::Kwnilarg(inner) => inner.as_mut(),
+            Node::Kwoptarg(inner) => inner.as_mut(),
+            Node::Kwrestarg(inner) => inner.as_mut(),
+            Node::Kwsplat(inner) => inner.as_mut(),
+            Node::Lambda(inner) => inner.as_mut(),
+            Node::Line(inner) => inner.as_mut(),
+            Node::Lvar(inner) => inner.as_mut(),
+            Node::Lvasgn(inner) => inner.as_mut(),
+            Node::Masgn(inner) => inner.as_mut(),
+            Node::MatchAlt(inner) => inner.as_mut(),
+            Node::MatchAs(inner) => inner.as_mut(),
+            Node::MatchCurrentLine(inner) => inner.as_mut(),
+            Node::MatchNilPattern(inner) => inner.as_mut(),
+            Node::MatchRest(inner) => inner.as_mut(),
+            Node::MatchVar(inner) => inner.as_mut(),
+            Node::MatchWithLvasgn(inner) => inner.as_mut(),
+            Node::Mlhs(inner) => inner.as_mut(),
+            Node::Module(inner) => inner.as_mut(),
+            Node::Next(inner) => inner.as_mut(),
+            Node::Nil(inner) => inner.as_mut(),
+            Node::NthRef(inner) => inner.as_mut(),
+            Node::Numblock(inner) => inner.as_mut(),
+            Node::OpAsgn(inner) => inner.as_mut(),
+            Node::Optarg(inner) => inner.as_mut(),
+            Node::Or(inner) => inner.as_mut(),
+            Node::OrAsgn(inner) => inner.as_mut(),
+            Node::Pair(inner) => inner.as_mut(),
+            Node::Pin(inner) => inner.as_mut(),
+            Node::Postexe(inner) => inner.as_mut(),
+            Node::Preexe(inner) => inner.as_mut(),
+            Node::Procarg0(inner) => inner.as_mut(),
+            Node::Rational(inner) => inner.as_mut(),
+            Node::Redo(inner) => inner.as_mut(),
+            Node::Regexp(inner) => inner.as_mut(),
+            Node::RegOpt(inner) => inner.as_mut(),
+            Node::Rescue(inner) => inner.as_mut(),
+            Node::RescueBody(inner) => inner.as_mut(),
+            Node::Restarg(inner) => inner.as_mut(),
+            Node::Retry(inner) => inner.as_mut(),
+            Node::Return(inner) => inner.as_mut(),
+            Node::SClass(inner) => inner.as_mut(),
+            Node::Self_(inner) => inner.as_mut(),
+            Node::Send(inner) => inner.as_mut(),
+            Node::Shadowarg(inner) => inner.as_mut(),
+            Node::Splat(inner) => inner.as_mut(),
+            Node::Str(inner) => inner.as_mut(),
+            Node::Super(inner) => inner.as_mut(),
+            Node::Sym(inner) => inner.as_mut(),
+            Node::True(inner) => inner.as_mut(),
+            Node::Undef(inner) => inner.as_mut(),
+            Node::UnlessGuard(inner) => inner.as_mut(),
+            Node::Until(inner) => inner.as_mut(),
+            Node::UntilPost(inner) => inner.as_mut(),
+            Node::When(inner) => inner.as_mut(),
+            Node::While(inner) => inner.as_mut(),
+            Node::WhilePost(inner) => inner.as_mut(),
+            Node::XHeredoc(inner) => inner.as_mut(),
+            Node::Xstr(inner) => inner.as_mut(),
+            Node::Yield(inner) => inner.as_mut(),
+            Node::ZSuper(inner) => inner.as_mut(),
+        }
+    }
+
     /// Returs a whitequark/parser -like representation of `self`.
     ///
     /// Used in tests and example scripts
@@ -280,4 +409,119 @@ impl Node {
     pub fn print_with_locs(&self) {
         self.inner_ref().print_with_locs()
     }
+
+    /// Returns direct children of the node in the order of fields
+    pub fn children(&self) -> Vec<&Node> {
+        self.inner_ref().children()
+    }
+
+    /// Returns all fields of the node (including locations)
+    /// as `(field_name, value)` pairs in the order of declaration
+    pub fn fields(&self) -> Vec<(&'static str, FieldValue<'_>)> {
+        self.inner_ref().fields()
+    }
+
+    /// Returns true if `self` and `other` have the same types, names and values
+    /// of all nested nodes. Unlike `==` locations are not compared,
+    /// so the same code written at two places is structurally equal.
+    pub fn structurally_eq(&self, other: &Node) -> bool {
+        self.structurally_eq_with(other, &StructureOptions::default())
+    }
+
+    /// Same as `structurally_eq`, but allows ignoring literal values
+    pub fn structurally_eq_with(&self, other: &Node, options: &StructureOptions) -> bool {
+        self.inner_ref().structurally_eq(other, options)
+    }
+
+    /// Returns a hash of the node that ignores locations.
+    ///
+    /// Structurally equal nodes have equal hashes. The hash doesn't depend
+    /// on the platform or the Rust version, so it can be stored as a fingerprint of the code.
+    pub fn structural_hash(&self) -> u64 {
+        self.structural_hash_with(&StructureOptions::default())
+    }
+
+    /// Same as `structural_hash`, but allows ignoring literal values
+    pub fn structural_hash_with(&self, options: &StructureOptions) -> u64 {
+        let mut state = StructuralHasher::new();
+        state.write_node(self, options);
+        state.finish()
+    }
+
+    /// Returns a whitequark/parser -like JSON representation of `self`:
+    ///
+    /// ```json
+    /// {"type":"send","children":[null,"foo"],"location":{"begin":null,...,"expression":{"begin_pos":0,"end_pos":3},"selector":{...}}}
+    /// ```
+    ///
+    /// Children are the same as in `inspect`, location map
+    /// contains all `*_l` fields of the node (`null` if absent).
+    #[cfg(feature = "json")]
+    pub fn to_json_value(&self) -> serde_json::Value {
+        crate::nodes::node_to_json(self)
+    }
+
+    /// Same as `to_json_value`, but serialized to a string
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        self.to_json_value().to_string()
+    }
+
+    /// Calls `f` with every location of the node and its nested nodes
+    /// (including optional locations that are present).
+    ///
+    /// Can be used to move the tree to another coordinate system,
+    /// e.g. when the code is a part of a bigger file:
+    ///
+    /// ```
+    /// use lib_ruby_parser::{Parser, ParserOptions};
+    ///
+    /// let mut ast = Parser::new(b"foo(1)", ParserOptions::default()).do_parse().ast.unwrap();
+    /// ast.map_ranges(|range| *range = range.adjust_begin(10).adjust_end(10));
+    ///
+    /// assert_eq!(format!("{:?}", ast.expression()), "10...16");
+    /// ```
+    pub fn map_ranges<F: FnMut(&mut Range)>(&mut self, mut f: F) {
+        self.inner_mut().map_ranges(&mut f)
+    }
+
+    /// Returns a literal value of the node:
+    /// + numeric value for numeric literals (e.g. "42" for `int`)
+    /// + string value for `str`/`sym`
+    /// + name for variables, constants, arguments, method calls and definitions
+    ///
+    /// Returns `None` for all other nodes
+    pub fn value(&self) -> Option<&[u8]> {
+        let value: &[u8] = match self {
+            Node::Int(inner) => inner.value.as_bytes(),
+            Node::Float(inner) => inner.value.as_bytes(),
+            Node::Rational(inner) => inner.value.as_bytes(),
+            Node::Complex(inner) => inner.value.as_bytes(),
+            Node::Str(inner) => inner.value.as_bytes(),
+            Node::Sym(inner) => inner.name.as_bytes(),
+            Node::Lvar(inner) => inner.name.as_bytes(),
+            Node::Ivar(inner) => inner.name.as_bytes(),
+            Node::Gvar(inner) => inner.name.as_bytes(),
+            Node::Cvar(inner) => inner.name.as_bytes(),
+            Node::BackRef(inner) => inner.name.as_bytes(),
+            Node::Const(inner) => inner.name.as_bytes(),
+            Node::Lvasgn(inner) => inner.name.as_bytes(),
+            Node::Ivasgn(inner) => inner.name.as_bytes(),
+            Node::Gvasgn(inner) => inner.name.as_bytes(),
+            Node::Cvasgn(inner) => inner.name.as_bytes(),
+            Node::Casgn(inner) => inner.name.as_bytes(),
+            Node::Arg(inner) => inner.name.as_bytes(),
+            Node::Optarg(inner) => inner.name.as_bytes(),
+            Node::Kwarg(inner) => inner.name.as_bytes(),
+            Node::Kwoptarg(inner) => inner.name.as_bytes(),
+            Node::Shadowarg(inner) => inner.name.as_bytes(),
+            Node::MatchVar(inner) => inner.name.as_bytes(),
+            Node::Def(inner) => inner.name.as_bytes(),
+            Node::Defs(inner) => inner.name.as_bytes(),
+            Node::Send(inner) => inner.method_name.as_bytes(),
+            Node::CSend(inner) => inner.method_name.as_bytes(),
+            _ => return None,
+        };
+        Some(value)
+    }
 }
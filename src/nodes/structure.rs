@@ -0,0 +1,125 @@
+use crate::Node;
+use std::hash::Hasher;
+
+/// Options of `Node::structurally_eq_with` and `Node::structural_hash_with`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StructureOptions {
+    /// Treat all literals of the same type as equal,
+    /// i.e. ignore values of numbers, strings and symbols
+    /// (`foo(1)` and `foo(2)` have the same structure)
+    pub ignore_literal_values: bool,
+}
+
+pub(crate) fn structurally_eq_nodes(lhs: &[Node], rhs: &[Node], options: &StructureOptions) -> bool {
+    lhs.len() == rhs.len()
+        && lhs
+            .iter()
+            .zip(rhs.iter())
+            .all(|(lhs, rhs)| lhs.structurally_eq_with(rhs, options))
+}
+
+pub(crate) fn structurally_eq_maybe_node(
+    lhs: &Option<Node>,
+    rhs: &Option<Node>,
+    options: &StructureOptions,
+) -> bool {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => lhs.structurally_eq_with(rhs, options),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// 64-bit FNV-1a hasher.
+///
+/// Unlike `DefaultHasher` its output doesn't depend on the Rust version
+/// or the platform, so structural hashes can be stored and compared between runs.
+pub struct StructuralHasher {
+    hash: u64,
+}
+
+impl StructuralHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    pub(crate) fn new() -> Self {
+        Self {
+            hash: Self::OFFSET_BASIS,
+        }
+    }
+
+    pub(crate) fn write_node(&mut self, node: &Node, options: &StructureOptions) {
+        node.inner_ref().hash_structure(self, options)
+    }
+
+    pub(crate) fn write_nodes(&mut self, nodes: &[Node], options: &StructureOptions) {
+        self.write_usize(nodes.len());
+        for node in nodes.iter() {
+            self.write_node(node, options);
+        }
+    }
+
+    pub(crate) fn write_maybe_node(&mut self, node: &Option<Node>, options: &StructureOptions) {
+        match node {
+            Some(node) => {
+                self.write_u8(1);
+                self.write_node(node, options);
+            }
+            None => self.write_u8(0),
+        }
+    }
+
+    pub(crate) fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_usize(bytes.len());
+        self.write(bytes);
+    }
+
+    pub(crate) fn write_string(&mut self, s: &str) {
+        self.write_bytes(s.as_bytes())
+    }
+
+    pub(crate) fn write_maybe_string(&mut self, s: &Option<String>) {
+        match s {
+            Some(s) => {
+                self.write_u8(1);
+                self.write_string(s);
+            }
+            None => self.write_u8(0),
+        }
+    }
+
+    pub(crate) fn write_chars(&mut self, chars: &[char]) {
+        self.write_usize(chars.len());
+        for c in chars.iter() {
+            self.write_u32(*c as u32);
+        }
+    }
+}
+
+impl Hasher for StructuralHasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes.iter() {
+            self.hash ^= *byte as u64;
+            self.hash = self.hash.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    // Integers are always written in little-endian and `usize` is always 64-bit
+    // to get the same hash on every platform.
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+}
@@ -0,0 +1,156 @@
+use crate::source::Range;
+use crate::{DiagnosticMessage, Node};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+// Reading the clock is much slower than lexing a token,
+// so the deadline is checked once per this number of tokens
+const DEADLINE_CHECK_INTERVAL: usize = 64;
+
+// Tracks resource usage while parsing.
+//
+// Limits on tokens are checked by the parser every time it takes
+// a token from the lexer, depth of the AST is checked every time
+// a grammar rule builds a node.
+#[derive(Debug, Default)]
+pub(crate) struct ParseLimits {
+    max_depth: Option<usize>,
+    max_tokens: Option<usize>,
+    max_input_size: Option<usize>,
+    cancelled: Option<Arc<AtomicBool>>,
+    deadline: Option<Instant>,
+
+    tokens_count: usize,
+
+    // Locations and depths of nodes that have been built by grammar rules
+    // but haven't become children of other checked nodes yet, in the order
+    // they were built. It grows and shrinks like the parser's value stack:
+    // a rule takes its children from the top, so children of a new node
+    // are the entries that start at or after the beginning of the node.
+    pending: Vec<(Range, usize)>,
+}
+
+impl ParseLimits {
+    pub(crate) fn new(
+        max_depth: Option<usize>,
+        max_tokens: Option<usize>,
+        max_input_size: Option<usize>,
+        cancelled: Option<Arc<AtomicBool>>,
+        deadline: Option<Instant>,
+    ) -> Self {
+        Self {
+            max_depth,
+            max_tokens,
+            max_input_size,
+            cancelled,
+            deadline,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn check_input_size(&self, input_size: usize) -> Result<(), DiagnosticMessage> {
+        match self.max_input_size {
+            Some(max_size) if input_size > max_size => {
+                Err(DiagnosticMessage::InputTooLarge { max_size })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_token(&mut self) -> Result<(), DiagnosticMessage> {
+        self.tokens_count += 1;
+        if let Some(max_tokens) = self.max_tokens {
+            if self.tokens_count > max_tokens {
+                return Err(DiagnosticMessage::TooManyTokens { max_tokens });
+            }
+        }
+
+        if let Some(cancelled) = &self.cancelled {
+            if cancelled.load(Ordering::Relaxed) {
+                return Err(DiagnosticMessage::ParsingCancelled);
+            }
+        }
+
+        if let Some(deadline) = self.deadline {
+            if (self.tokens_count - 1) % DEADLINE_CHECK_INTERVAL == 0 && Instant::now() >= deadline
+            {
+                return Err(DiagnosticMessage::ParsingTimeout);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Computes depth of the AST under a given `node`
+    // that has just been built by a grammar rule.
+    //
+    // Depths of checked nodes are remembered until they become children
+    // of another checked node, so only nodes that have been created
+    // by the builder in between (like a `begin` that wraps a list
+    // of statements) and leaves that come from the lexer are traversed.
+    pub(crate) fn check_node(&mut self, node: &Node) -> Result<(), DiagnosticMessage> {
+        let max_depth = match self.max_depth {
+            Some(max_depth) => max_depth,
+            None => return Ok(()),
+        };
+
+        let expression_l = node.expression();
+        let children_start = self
+            .pending
+            .iter()
+            .rposition(|(range, _)| range.begin_pos < expression_l.begin_pos)
+            .map_or(0, |idx| idx + 1);
+        let mut checked_children = self.pending.split_off(children_start);
+        checked_children.sort_unstable_by_key(|(range, _)| (range.begin_pos, range.end_pos));
+
+        let depth = match find_checked(&checked_children, expression_l) {
+            // the same node is passed from one rule to another
+            Some(depth)
+                if node
+                    .children()
+                    .iter()
+                    .all(|child| find_checked(&checked_children, child.expression()).is_none()) =>
+            {
+                depth
+            }
+            _ => compute_depth(node, &checked_children),
+        };
+        self.pending.push((expression_l.clone(), depth));
+
+        if depth > max_depth {
+            Err(DiagnosticMessage::NestingTooDeep { max_depth })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// Traverses `node` down to the nodes that have been checked before,
+// `checked` is sorted by location
+fn compute_depth(node: &Node, checked: &[(Range, usize)]) -> usize {
+    let mut max_depth = 0;
+    let mut stack = vec![(node, 1)];
+
+    while let Some((node, depth)) = stack.pop() {
+        max_depth = max_depth.max(depth);
+
+        for child in node.children() {
+            match find_checked(checked, child.expression()) {
+                Some(child_depth) => max_depth = max_depth.max(depth + child_depth),
+                None => stack.push((child, depth + 1)),
+            }
+        }
+    }
+
+    max_depth
+}
+
+fn find_checked(checked: &[(Range, usize)], range: &Range) -> Option<usize> {
+    checked
+        .binary_search_by_key(&(range.begin_pos, range.end_pos), |(range, _)| {
+            (range.begin_pos, range.end_pos)
+        })
+        .ok()
+        .map(|idx| checked[idx].1)
+}
@@ -13,17 +13,25 @@
     current_arg_stack: CurrentArgStack,
     pub static_env: StaticEnvironment,
     context: ParserContext,
-    last_token: Token,
+    last_token_type: i32,
     max_numparam_stack: MaxNumparamStack,
     pattern_variables: VariablesStack,
     pattern_hash_keys: VariablesStack,
     tokens: Vec<Token>,
+    record_tokens: bool,
+    source_tokens: Vec<SourceToken>,
+    record_source_tokens: bool,
+    limits: ParseLimits,
+    limit_reached: bool,
     diagnostics: Diagnostics,
+    first_line: usize,
+    byte_offset: usize,
 }
 
 %code use {
-    use crate::{ParserOptions, ParserResult};
+    use crate::{ParserOptions, ParserResult, SourceToken};
     use crate::{Lexer, Builder, CurrentArgStack, StaticEnvironment, MaxNumparamStack, VariablesStack};
+    use crate::ParseLimits;
     use crate::lex_states::*;
     use crate::{Context as ParserContext, ContextItem};
     use crate::builder::{LoopType, KeywordCmd, LogicalOp, PKwLabel, ArgsType};
@@ -354,9 +362,9 @@
                 | klBEGIN begin_block
                     {
                         let BeginBlock { begin_t, body, end_t } = $<BeginBlock>2;
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.preexe($<Token>1, begin_t, body, end_t)
-                        );
+                        )?);
                     }
                 ;
 
@@ -464,29 +472,29 @@
                     }
                   fitem
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.alias($<Token>1, $<Node>2, $<Node>4)
-                        );
+                        )?);
                     }
                 | kALIAS tGVAR tGVAR
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.alias(
                                 $<Token>1,
                                 self.builder.gvar($<Token>2),
                                 self.builder.gvar($<Token>3),
                             )
-                        )
+                        )?)
                     }
                 | kALIAS tGVAR tBACK_REF
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.alias(
                                 $<Token>1,
                                 self.builder.gvar($<Token>2),
                                 self.builder.back_ref($<Token>3),
                             )
-                        )
+                        )?)
                     }
                 | kALIAS tGVAR tNTH_REF
                     {
@@ -494,56 +502,56 @@
                     }
                 | kUNDEF undef_list
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.undef_method(
                                 $<Token>1,
                                 $<NodeList>2
                             )
-                        )
+                        )?)
                     }
                 | stmt kIF_MOD expr_value
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.condition_mod(
                                 Some($<Node>1),
                                 None,
                                 $<Token>2,
                                 $<Node>3,
                             )
-                        );
+                        )?);
                     }
                 | stmt kUNLESS_MOD expr_value
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.condition_mod(
                                 None,
                                 Some($<Node>1),
                                 $<Token>2,
                                 $<Node>3,
                             )
-                        );
+                        )?);
                     }
                 | stmt kWHILE_MOD expr_value
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.loop_mod(
                                 LoopType::While,
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3,
                             )
-                        );
+                        )?);
                     }
                 | stmt kUNTIL_MOD expr_value
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.loop_mod(
                                 LoopType::Until,
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3,
                             )
-                        );
+                        )?);
                     }
                 | stmt kRESCUE_MOD stmt
                     {
@@ -556,14 +564,14 @@
                             Some($<Node>3)
                         );
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.begin_body(
                                 Some($<Node>1),
                                 vec![rescue_body],
                                 None,
                                 None,
                             ).expect("expected begin_body to return Some (compound_stmt was given)")
-                        );
+                        )?);
                     }
                 | klEND tLCURLY compstmt tRCURLY
                     {
@@ -571,14 +579,14 @@
                             self.warn(&@1, DiagnosticMessage::EndInMethod);
                         }
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.postexe(
                                 $<Token>1,
                                 $<Token>2,
                                 $<MaybeNode>3,
                                 $<Token>4,
                             )
-                        );
+                        )?);
                     }
                 | command_asgn
                     {
@@ -589,13 +597,13 @@
                         let command_call = $<Node>3;
                         self.value_expr(&command_call)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.multi_assign(
                                 $<Node>1,
                                 $<Token>2,
                                 command_call
                             )
-                        );
+                        )?);
                     }
                 | lhs tEQL mrhs
                     {
@@ -606,13 +614,13 @@
                         );
                         self.value_expr(&mrhs)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assign(
                                 $<Node>1,
                                 $<Token>2,
                                 mrhs
                             )
-                        );
+                        )?);
                     }
                 | mlhs tEQL mrhs_arg kRESCUE_MOD stmt
                     {
@@ -635,23 +643,23 @@
                             None
                         ).expect("expected begin_body to return Some (compound_stmt was given)");
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.multi_assign(
                                 $<Node>1,
                                 $<Token>2,
                                 begin_body
                             )
-                        );
+                        )?);
                     }
                 | mlhs tEQL mrhs_arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.multi_assign(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )
-                        );
+                        )?);
                     }
                 | rassign
                     {
@@ -665,69 +673,69 @@
 
          rassign: arg_value tASSOC lhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.rassign(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )
-                        );
+                        )?);
                     }
                 | arg_value tASSOC mlhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.multi_rassign(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )
-                        );
+                        )?);
                     }
                 | rassign tASSOC lhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.rassign(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )
-                        );
+                        )?);
                     }
                 | rassign tASSOC mlhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.multi_rassign(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )
-                        );
+                        )?);
                     }
                 ;
 
     command_asgn: lhs tEQL command_rhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assign(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )
-                        );
+                        )?);
                     }
                 | var_lhs tOP_ASGN command_rhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )?
-                        );
+                        )?);
                     }
                 | primary_value tLBRACK2 opt_call_args rbracket tOP_ASGN command_rhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 self.builder.index(
                                     $<Node>1,
@@ -738,11 +746,11 @@
                                 $<Token>5,
                                 $<Node>6
                             )?
-                        );
+                        )?);
                     }
                 | primary_value call_op tIDENTIFIER tOP_ASGN command_rhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 self.builder.call_method(
                                     Some($<Node>1),
@@ -755,11 +763,11 @@
                                 $<Token>4,
                                 $<Node>5
                             )?
-                        );
+                        )?);
                     }
                 | primary_value call_op tCONSTANT tOP_ASGN command_rhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 self.builder.call_method(
                                     Some($<Node>1),
@@ -772,7 +780,7 @@
                                 $<Token>4,
                                 $<Node>5
                             )?
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 tCONSTANT tOP_ASGN command_rhs
                     {
@@ -783,17 +791,17 @@
                                 $<Token>3
                             )
                         );
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 const_,
                                 $<Token>4,
                                 $<Node>5
                             )?
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 tIDENTIFIER tOP_ASGN command_rhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 self.builder.call_method(
                                     Some($<Node>1),
@@ -806,17 +814,17 @@
                                 $<Token>4,
                                 $<Node>5
                             )?
-                        );
+                        )?);
                     }
                 | backref tOP_ASGN command_rhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )?
-                        );
+                        )?);
                     }
                 ;
 
@@ -824,7 +832,7 @@
                     {
                         let command_call = $<Node>1;
                         self.value_expr(&command_call)?;
-                        $$ = Value::Node(command_call);
+                        $$ = Value::Node(self.check_depth(command_call)?);
                     }
                 | command_call kRESCUE_MOD stmt
                     {
@@ -840,14 +848,14 @@
                             Some($<Node>3)
                         );
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.begin_body(
                                 Some(command_call),
                                 vec![ rescue_body ],
                                 None,
                                 None
                             ).expect("expected begin_body to return Some (compound_stmt was given)")
-                        );
+                        )?);
                     }
                 | command_asgn
                     {
@@ -861,47 +869,47 @@
                     }
                 | expr kAND expr
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.logical_op(
                                 LogicalOp::And,
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )?
-                        );
+                        )?);
                     }
                 | expr kOR expr
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.logical_op(
                                 LogicalOp::Or,
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )?
-                        );
+                        )?);
                     }
                 | kNOT opt_nl expr
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.not_op(
                                 $<Token>1,
                                 None,
                                 Some($<Node>3),
                                 None
                             )?
-                        );
+                        )?);
                     }
                 | tBANG command_call
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.not_op(
                                 $<Token>1,
                                 None,
                                 Some($<Node>2),
                                 None
                             )?
-                        );
+                        )?);
                     }
                 | arg kIN
                     {
@@ -923,13 +931,13 @@
                         self.pattern_variables.pop();
                         self.yylexer.in_kwarg = $<Bool>3;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.in_match(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>4
                             )
-                        );
+                        )?);
                     }
                 | arg %prec tLBRACE_ARG
                     {
@@ -986,7 +994,7 @@
                     {
                         let expr = $<Node>1;
                         self.value_expr(&expr)?;
-                        $$ = Value::Node(expr);
+                        $$ = Value::Node(self.check_depth(expr)?);
                     }
                 ;
 
@@ -1024,7 +1032,7 @@
                     }
                 | block_call call_op2 operation2 command_args
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.call_method(
                                 Some($<Node>1),
                                 Some($<Token>2),
@@ -1033,7 +1041,7 @@
                                 $<NodeList>4,
                                 None
                             )
-                        );
+                        )?);
                     }
                 ;
 
@@ -1065,7 +1073,7 @@
 
          command: fcall command_args       %prec tLOWEST
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.call_method(
                                 None,
                                 None,
@@ -1074,7 +1082,7 @@
                                 $<NodeList>2,
                                 None
                             )
-                        );
+                        )?);
                     }
                 | fcall command_args cmd_brace_block
                     {
@@ -1088,7 +1096,7 @@
                         );
                         let CmdBraceBlock { begin_t, args_type, body, end_t } = $<CmdBraceBlock>3;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.block(
                                 method_call,
                                 begin_t,
@@ -1096,11 +1104,11 @@
                                 body,
                                 end_t
                             )?
-                        );
+                        )?);
                     }
                 | primary_value call_op operation2 command_args %prec tLOWEST
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.call_method(
                                 Some($<Node>1),
                                 Some($<Token>2),
@@ -1109,7 +1117,7 @@
                                 $<NodeList>4,
                                 None
                             )
-                        );
+                        )?);
                     }
                 | primary_value call_op operation2 command_args cmd_brace_block
                     {
@@ -1123,7 +1131,7 @@
                         );
                         let CmdBraceBlock { begin_t, args_type, body, end_t } = $<CmdBraceBlock>5;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.block(
                                 method_call,
                                 begin_t,
@@ -1131,11 +1139,11 @@
                                 body,
                                 end_t
                             )?
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 operation2 command_args %prec tLOWEST
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.call_method(
                                 Some($<Node>1),
                                 Some($<Token>2),
@@ -1144,7 +1152,7 @@
                                 $<NodeList>4,
                                 None
                             )
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 operation2 command_args cmd_brace_block
                     {
@@ -1158,7 +1166,7 @@
                         );
                         let CmdBraceBlock { begin_t, args_type, body, end_t } = $<CmdBraceBlock>5;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.block(
                                 method_call,
                                 begin_t,
@@ -1166,11 +1174,11 @@
                                 body,
                                 end_t
                             )?
-                        );
+                        )?);
                     }
                 | kSUPER command_args
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Super,
                                 $<Token>1,
@@ -1178,11 +1186,11 @@
                                 $<NodeList>2,
                                 None
                             )?
-                        );
+                        )?);
                     }
                 | kYIELD command_args
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Yield,
                                 $<Token>1,
@@ -1190,11 +1198,11 @@
                                 $<NodeList>2,
                                 None
                             )?
-                        );
+                        )?);
                     }
                 | k_return call_args
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Return,
                                 $<Token>1,
@@ -1202,11 +1210,11 @@
                                 $<NodeList>2,
                                 None
                             )?
-                        );
+                        )?);
                     }
                 | kBREAK call_args
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Break,
                                 $<Token>1,
@@ -1214,11 +1222,11 @@
                                 $<NodeList>2,
                                 None
                             )?
-                        );
+                        )?);
                     }
                 | kNEXT call_args
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Next,
                                 $<Token>1,
@@ -1226,41 +1234,41 @@
                                 $<NodeList>2,
                                 None
                             )?
-                        );
+                        )?);
                     }
                 ;
 
             mlhs: mlhs_basic
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.multi_lhs(
                                 None,
                                 $<NodeList>1,
                                 None
                             )
-                        );
+                        )?);
                     }
                 | tLPAREN mlhs_inner rparen
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.begin(
                                 $<Token>1,
                                 Some($<Node>2),
                                 $<Token>3
                             )
-                        );
+                        )?);
                     }
                 ;
 
       mlhs_inner: mlhs_basic
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.multi_lhs(
                                 None,
                                 $<NodeList>1,
                                 None
                             )
-                        );
+                        )?);
                     }
                 | tLPAREN mlhs_inner rparen
                     {
@@ -1269,13 +1277,13 @@
                             other => unreachable!("unsupported mlhs item {:?}", other)
                         };
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.multi_lhs(
                                 Some($<Token>1),
                                 mlhs_items,
                                 Some($<Token>3)
                             )
-                        );
+                        )?);
                     }
                 ;
 
@@ -1359,13 +1367,13 @@
                     }
                 | tLPAREN mlhs_inner rparen
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.begin(
                                 $<Token>1,
                                 Some($<Node>2),
                                 $<Token>3
                             )
-                        );
+                        )?);
                     }
                 ;
 
@@ -1395,26 +1403,26 @@
 
        mlhs_node: user_variable
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assignable($<Node>1)?
-                        );
+                        )?);
                     }
                 | keyword_variable
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assignable($<Node>1)?
-                        );
+                        )?);
                     }
                 | primary_value tLBRACK2 opt_call_args rbracket
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.index_asgn(
                                 $<Node>1,
                                 $<Token>2,
                                 $<NodeList>3,
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 | primary_value call_op tIDENTIFIER
                     {
@@ -1423,23 +1431,23 @@
                             return self.yyerror(&@2, DiagnosticMessage::CsendInsideMasgn);
                         }
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.attr_asgn(
                                 $<Node>1,
                                 op_t,
                                 $<Token>3
                             )
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 tIDENTIFIER
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.attr_asgn(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Token>3
                             )
-                        );
+                        )?);
                     }
                 | primary_value call_op tCONSTANT
                     {
@@ -1448,17 +1456,17 @@
                             return self.yyerror(&@2, DiagnosticMessage::CsendInsideMasgn);
                         }
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.attr_asgn(
                                 $<Node>1,
                                 op_t,
                                 $<Token>3
                             )
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 tCONSTANT
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assignable(
                                 self.builder.const_fetch(
                                     $<Node>1,
@@ -1466,85 +1474,85 @@
                                     $<Token>3
                                 )
                             )?
-                        );
+                        )?);
                     }
                 | tCOLON3 tCONSTANT
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assignable(
                                 self.builder.const_global(
                                     $<Token>1,
                                     $<Token>2
                                 )
                             )?
-                        );
+                        )?);
                     }
                 | backref
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assignable(
                                 $<Node>1
                             )?
-                        );
+                        )?);
                     }
                 ;
 
              lhs: user_variable
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assignable($<Node>1)?
-                        );
+                        )?);
                     }
                 | keyword_variable
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assignable($<Node>1)?
-                        );
+                        )?);
                     }
                 | primary_value tLBRACK2 opt_call_args rbracket
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.index_asgn(
                                 $<Node>1,
                                 $<Token>2,
                                 $<NodeList>3,
                                 $<Token>4
                             )
-                        )
+                        )?)
                     }
                 | primary_value call_op tIDENTIFIER
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.attr_asgn(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Token>3
                             )
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 tIDENTIFIER
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.attr_asgn(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Token>3
                             )
-                        );
+                        )?);
                     }
                 | primary_value call_op tCONSTANT
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.attr_asgn(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Token>3
                             )
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 tCONSTANT
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assignable(
                                 self.builder.const_fetch(
                                     $<Node>1,
@@ -1552,26 +1560,26 @@
                                     $<Token>3,
                                 )
                             )?
-                        );
+                        )?);
                     }
                 | tCOLON3 tCONSTANT
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assignable(
                                 self.builder.const_global(
                                     $<Token>1,
                                     $<Token>2,
                                 )
                             )?
-                        );
+                        )?);
                     }
                 | backref
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assignable(
                                 $<Node>1
                             )?
-                        );
+                        )?);
                     }
                 ;
 
@@ -1587,25 +1595,25 @@
 
            cpath: tCOLON3 cname
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_global($<Token>1, $<Token>2)
-                        );
+                        )?);
                     }
                 | cname
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_($<Token>1)
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 cname
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_fetch(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Token>3,
                             )
-                        );
+                        )?);
                     }
                 ;
 
@@ -1634,9 +1642,9 @@
 
            fitem: fname
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.symbol_internal($<Token>1)
-                        );
+                        )?);
                     }
                 | symbol
                     {
@@ -1738,27 +1746,27 @@
 
              arg: lhs tEQL arg_rhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assign(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )
-                        );
+                        )?);
                     }
                 | var_lhs tOP_ASGN arg_rhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )?
-                        );
+                        )?);
                     }
                 | primary_value tLBRACK2 opt_call_args rbracket tOP_ASGN arg_rhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 self.builder.index(
                                     $<Node>1,
@@ -1769,11 +1777,11 @@
                                 $<Token>5,
                                 $<Node>6
                             )?
-                        );
+                        )?);
                     }
                 | primary_value call_op tIDENTIFIER tOP_ASGN arg_rhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 self.builder.call_method(
                                     Some($<Node>1),
@@ -1786,11 +1794,11 @@
                                 $<Token>4,
                                 $<Node>5
                             )?
-                        );
+                        )?);
                     }
                 | primary_value call_op tCONSTANT tOP_ASGN arg_rhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 self.builder.call_method(
                                     Some($<Node>1),
@@ -1803,11 +1811,11 @@
                                 $<Token>4,
                                 $<Node>5
                             )?
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 tIDENTIFIER tOP_ASGN arg_rhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 self.builder.call_method(
                                     Some($<Node>1),
@@ -1820,7 +1828,7 @@
                                 $<Token>4,
                                 $<Node>5
                             )?
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 tCONSTANT tOP_ASGN arg_rhs
                     {
@@ -1831,13 +1839,13 @@
                                 $<Token>3
                             )
                         );
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 const_,
                                 $<Token>4,
                                 $<Node>5
                             )?
-                        );
+                        )?);
                     }
                 | tCOLON3 tCONSTANT tOP_ASGN arg_rhs
                     {
@@ -1847,23 +1855,23 @@
                                 $<Token>2
                             )
                         );
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 const_,
                                 $<Token>3,
                                 $<Node>4
                             )?
-                        );
+                        )?);
                     }
                 | backref tOP_ASGN arg_rhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.op_assign(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )?
-                        );
+                        )?);
                     }
                 | arg tDOT2 arg
                     {
@@ -1873,13 +1881,13 @@
                         let right = $<Node>3;
                         self.value_expr(&right)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.range_inclusive(
                                 Some(left),
                                 $<Token>2,
                                 Some(right)
                             )
-                        );
+                        )?);
                     }
                 | arg tDOT3 arg
                     {
@@ -1889,105 +1897,105 @@
                         let right = $<Node>3;
                         self.value_expr(&right)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.range_exclusive(
                                 Some(left),
                                 $<Token>2,
                                 Some(right)
                             )
-                        );
+                        )?);
                     }
                 | arg tDOT2
                     {
                         let left = $<Node>1;
                         self.value_expr(&left)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.range_inclusive(
                                 Some(left),
                                 $<Token>2,
                                 None
                             )
-                        );
+                        )?);
                     }
                 | arg tDOT3
                     {
                         let left = $<Node>1;
                         self.value_expr(&left)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.range_exclusive(
                                 Some(left),
                                 $<Token>2,
                                 None
                             )
-                        );
+                        )?);
                     }
                 | tBDOT2 arg
                     {
                         let right = $<Node>2;
                         self.value_expr(&right)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.range_inclusive(
                                 None,
                                 $<Token>1,
                                 Some(right)
                             )
-                        );
+                        )?);
                     }
                 | tBDOT3 arg
                     {
                         let right = $<Node>2;
                         self.value_expr(&right)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.range_exclusive(
                                 None,
                                 $<Token>1,
                                 Some(right)
                             )
-                        );
+                        )?);
                     }
                 | arg tPLUS arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | arg tMINUS arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | arg tSTAR2 arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | arg tDIVIDE arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | arg tPERCENT arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | arg tPOW arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | tUMINUS_NUM simple_numeric tPOW arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.unary_op(
                                 $<Token>1,
                                 self.builder.binary_op(
@@ -1996,49 +2004,49 @@
                                     $<Node>4
                                 )?
                             )?
-                        );
+                        )?);
                     }
                 | tUPLUS arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.unary_op(
                                 $<Token>1,
                                 $<Node>2
                             )?
-                        );
+                        )?);
                     }
                 | tUMINUS arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.unary_op(
                                 $<Token>1,
                                 $<Node>2
                             )?
-                        );
+                        )?);
                     }
                 | arg tPIPE arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | arg tCARET arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | arg tAMPER2 arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | arg tCMP arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | rel_expr   %prec tCMP
                     {
@@ -2046,95 +2054,95 @@
                     }
                 | arg tEQ arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | arg tEQQ arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | arg tNEQ arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | arg tMATCH arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.match_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | arg tNMATCH arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )?
-                        );
+                        )?);
                     }
                 | tBANG arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.not_op(
                                 $<Token>1,
                                 None,
                                 Some($<Node>2),
                                 None
                             )?
-                        );
+                        )?);
                     }
                 | tTILDE arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.unary_op(
                                 $<Token>1,
                                 $<Node>2
                             )?
-                        );
+                        )?);
                     }
                 | arg tLSHFT arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | arg tRSHFT arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op($<Node>1, $<Token>2, $<Node>3)?
-                        );
+                        )?);
                     }
                 | arg tANDOP arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.logical_op(
                                 LogicalOp::And,
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )?
-                        );
+                        )?);
                     }
                 | arg tOROP arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.logical_op(
                                 LogicalOp::Or,
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )?
-                        );
+                        )?);
                     }
                 | kDEFINED opt_nl arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Defined,
                                 $<Token>1,
@@ -2142,14 +2150,14 @@
                                 vec![ $<Node>3 ],
                                 None
                             )?
-                        );
+                        )?);
                     }
                 | arg tEH arg opt_nl tCOLON arg
                     {
                         let expr = $<Node>1;
                         self.value_expr(&expr)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.ternary(
                                 expr,
                                 $<Token>2,
@@ -2157,7 +2165,7 @@
                                 $<Token>5,
                                 $<Node>6
                             )
-                        );
+                        )?);
                     }
                 | defn_head f_paren_args tEQL arg
                     {
@@ -2168,7 +2176,7 @@
                             return self.yyerror(&name_t.loc, DiagnosticMessage::EndlessSetterDefinition);
                         }
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.def_endless_method(
                                 def_t,
                                 name_t,
@@ -2176,7 +2184,7 @@
                                 $<Token>3,
                                 Some($<Node>4)
                             )?
-                        );
+                        )?);
 
                         self.yylexer.cmdarg.pop();
                         self.yylexer.cond.pop();
@@ -2204,7 +2212,7 @@
                             None
                         );
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.def_endless_method(
                                 def_t,
                                 name_t,
@@ -2212,7 +2220,7 @@
                                 $<Token>3,
                                 method_body
                             )?
-                        );
+                        )?);
 
                         self.yylexer.cmdarg.pop();
                         self.yylexer.cond.pop();
@@ -2224,7 +2232,7 @@
                     {
                         let DefsHead { def_t, definee, dot_t, name_t } = $<DefsHead>1;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.def_endless_singleton(
                                 def_t,
                                 definee,
@@ -2234,7 +2242,7 @@
                                 $<Token>3,
                                 Some($<Node>4)
                             )?
-                        );
+                        )?);
 
                         self.yylexer.cmdarg.pop();
                         self.yylexer.cond.pop();
@@ -2262,7 +2270,7 @@
                             None
                         );
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.def_endless_singleton(
                                 def_t,
                                 definee,
@@ -2272,7 +2280,7 @@
                                 $<Token>3,
                                 method_body
                             )?
-                        );
+                        )?);
 
                         self.yylexer.cmdarg.pop();
                         self.yylexer.cond.pop();
@@ -2306,25 +2314,25 @@
 
         rel_expr: arg relop arg   %prec tGT
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )?
-                        );
+                        )?);
                     }
                 | rel_expr relop arg   %prec tGT
                     {
                         let op_t = $<Token>2;
                         self.warn(&@2, DiagnosticMessage::ComparisonAfterComparison(clone_value(&op_t)));
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.binary_op(
                                 $<Node>1,
                                 op_t,
                                 $<Node>3
                             )?
-                        );
+                        )?);
                     }
                 ;
 
@@ -2332,7 +2340,7 @@
                     {
                         let arg = $<Node>1;
                         self.value_expr(&arg)?;
-                        $$ = Value::Node(arg);
+                        $$ = Value::Node(self.check_depth(arg)?);
                     }
                 ;
 
@@ -2366,7 +2374,7 @@
                     {
                         let arg = $<Node>1;
                         self.value_expr(&arg)?;
-                        $$ = Value::Node(arg);
+                        $$ = Value::Node(self.check_depth(arg)?);
                     }
                 | arg kRESCUE_MOD arg
                     {
@@ -2382,14 +2390,14 @@
                             Some($<Node>3)
                         );
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.begin_body(
                                 Some(arg),
                                 vec![ rescue_body ],
                                 None,
                                 None
                             ).expect("expected begin_body to return Some (compound_stmt was given)")
-                        );
+                        )?);
                     }
                 ;
 
@@ -2525,7 +2533,7 @@
     command_args:   {
                         let lookahead =
                             matches!(
-                                self.last_token.token_type,
+                                self.last_token_type,
                                 Lexer::tLPAREN2
                                     | Lexer::tLPAREN
                                     | Lexer:: tLPAREN_ARG
@@ -2540,7 +2548,7 @@
                     }
                   call_args
                     {
-                        let lookahead = matches!(self.last_token.token_type, Lexer::tLBRACE_ARG);
+                        let lookahead = matches!(self.last_token_type, Lexer::tLBRACE_ARG);
 
                         if lookahead { self.yylexer.cmdarg.pop() }
                         self.yylexer.cmdarg.pop();
@@ -2552,12 +2560,12 @@
 
        block_arg: tAMPER arg_value
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.block_pass(
                                 $<Token>1,
                                 $<Node>2
                             )
-                        );
+                        )?);
                     }
                 ;
 
@@ -2599,9 +2607,9 @@
 
         mrhs_arg: mrhs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.array(None, $<NodeList>1, None)
-                        );
+                        )?);
                     }
                 | arg_value
                     {
@@ -2675,7 +2683,7 @@
                     }
                 | tFID
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.call_method(
                                 None,
                                 None,
@@ -2684,7 +2692,7 @@
                                 vec![],
                                 None
                             )
-                        );
+                        )?);
                     }
                 | k_begin
                     {
@@ -2696,79 +2704,79 @@
                     {
                         self.yylexer.cmdarg.pop();
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.begin_keyword($<Token>1, $<MaybeNode>3, $<Token>4)
-                        );
+                        )?);
                     }
                 | tLPAREN_ARG { self.yylexer.lex_state.set(EXPR_ENDARG); $<None>$ = Value::None; } rparen
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.begin(
                                 $<Token>1,
                                 None,
                                 $<Token>3
                             )
-                        );
+                        )?);
                     }
                 | tLPAREN_ARG stmt { self.yylexer.lex_state.set(EXPR_ENDARG); $<None>$ = Value::None; } rparen
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.begin(
                                 $<Token>1,
                                 Some($<Node>2),
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 | tLPAREN compstmt tRPAREN
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.begin(
                                 $<Token>1,
                                 $<MaybeNode>2,
                                 $<Token>3
                             )
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 tCONSTANT
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_fetch(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Token>3,
                             )
-                        );
+                        )?);
                     }
                 | tCOLON3 tCONSTANT
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_global($<Token>1, $<Token>2)
-                        );
+                        )?);
                     }
                 | tLBRACK aref_args tRBRACK
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.array(
                                 Some($<Token>1),
                                 $<NodeList>2,
                                 Some($<Token>3)
                             )
-                        );
+                        )?);
                     }
                 | tLBRACE assoc_list tRCURLY
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.associate(
                                 Some($<Token>1),
                                 $<NodeList>2,
                                 Some($<Token>3)
                             )
-                        );
+                        )?);
                     }
                 | k_return
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Return,
                                 $<Token>1,
@@ -2776,11 +2784,11 @@
                                 vec![],
                                 None
                             )?
-                        );
+                        )?);
                     }
                 | kYIELD tLPAREN2 call_args rparen
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Yield,
                                 $<Token>1,
@@ -2788,11 +2796,11 @@
                                 $<NodeList>3,
                                 Some($<Token>4)
                             )?
-                        );
+                        )?);
                     }
                 | kYIELD tLPAREN2 rparen
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Yield,
                                 $<Token>1,
@@ -2800,11 +2808,11 @@
                                 vec![],
                                 Some($<Token>3)
                             )?
-                        );
+                        )?);
                     }
                 | kYIELD
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Yield,
                                 $<Token>1,
@@ -2812,11 +2820,11 @@
                                 vec![],
                                 None
                             )?
-                        );
+                        )?);
                     }
                 | kDEFINED opt_nl tLPAREN2 expr rparen
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Defined,
                                 $<Token>1,
@@ -2824,29 +2832,29 @@
                                 vec![ $<Node>4 ],
                                 Some($<Token>5)
                             )?
-                        );
+                        )?);
                     }
                 | kNOT tLPAREN2 expr rparen
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.not_op(
                                 $<Token>1,
                                 Some($<Token>2),
                                 Some($<Node>3),
                                 Some($<Token>4)
                             )?
-                        );
+                        )?);
                     }
                 | kNOT tLPAREN2 rparen
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.not_op(
                                 $<Token>1,
                                 Some($<Token>2),
                                 None,
                                 Some($<Token>3)
                             )?
-                        );
+                        )?);
                     }
                 | fcall brace_block
                     {
@@ -2860,7 +2868,7 @@
                         );
                         let BraceBlock { begin_t, args_type, body, end_t } = $<BraceBlock>2;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.block(
                                 method_call,
                                 begin_t,
@@ -2868,7 +2876,7 @@
                                 body,
                                 end_t
                             )?
-                        );
+                        )?);
                     }
                 | method_call
                     {
@@ -2877,7 +2885,7 @@
                 | method_call brace_block
                     {
                         let BraceBlock { begin_t, args_type, body, end_t } = $<BraceBlock>2;
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.block(
                                 $<Node>1,
                                 begin_t,
@@ -2885,7 +2893,7 @@
                                 body,
                                 end_t
                             )?
-                        );
+                        )?);
                     }
                 | lambda
                     {
@@ -2898,7 +2906,7 @@
                     {
                         let IfTail { keyword_t, body: else_body } = $<IfTail>5;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.condition(
                                 $<Token>1,
                                 $<Node>2,
@@ -2908,7 +2916,7 @@
                                 else_body,
                                 Some($<Token>6)
                             )
-                        );
+                        )?);
                     }
                 | k_unless expr_value then
                   compstmt
@@ -2917,7 +2925,7 @@
                     {
                         let (else_t, body) = $<OptElse>5.map(|else_| (Some(else_.else_t), else_.body)).unwrap_or_else(|| (None, None));
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.condition(
                                 $<Token>1,
                                 $<Node>2,
@@ -2927,14 +2935,14 @@
                                 $<MaybeNode>4,
                                 Some($<Token>6)
                             )
-                        );
+                        )?);
                     }
                 | k_while expr_value_do
                   compstmt
                   k_end
                     {
                         let ExprValueDo { value, do_t } = $<ExprValueDo>2;
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.loop_(
                                 LoopType::While,
                                 $<Token>1,
@@ -2943,14 +2951,14 @@
                                 $<MaybeNode>3,
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 | k_until expr_value_do
                   compstmt
                   k_end
                     {
                         let ExprValueDo { value, do_t } = $<ExprValueDo>2;
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.loop_(
                                 LoopType::Until,
                                 $<Token>1,
@@ -2959,7 +2967,7 @@
                                 $<MaybeNode>3,
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 | k_case expr_value opt_terms
                     {
@@ -2973,7 +2981,7 @@
                         let CaseBody { when_bodies, opt_else } = $<CaseBody>5;
                         let (else_t, else_body) = opt_else.map(|else_| (Some(else_.else_t), else_.body)).unwrap_or_else(|| (None, None));
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.case(
                                 $<Token>1,
                                 Some($<Node>2),
@@ -2982,7 +2990,7 @@
                                 else_body,
                                 $<Token>6
                             )
-                        );
+                        )?);
                     }
                 | k_case opt_terms
                     {
@@ -2996,7 +3004,7 @@
                         let CaseBody { when_bodies, opt_else } = $<CaseBody>4;
                         let (else_t, else_body) = opt_else.map(|else_| (Some(else_.else_t), else_.body)).unwrap_or_else(|| (None, None));
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.case(
                                 $<Token>1,
                                 None,
@@ -3005,7 +3013,7 @@
                                 else_body,
                                 $<Token>5
                             )
-                        );
+                        )?);
                     }
                 | k_case expr_value opt_terms
                   p_case_body
@@ -3014,7 +3022,7 @@
                         let PCaseBody { in_bodies, opt_else } = $<PCaseBody>4;
                         let (else_t, else_body) = opt_else.map(|else_| (Some(else_.else_t), else_.body)).unwrap_or_else(|| (None, None));
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.case_match(
                                 $<Token>1,
                                 $<Node>2,
@@ -3023,14 +3031,14 @@
                                 else_body,
                                 $<Token>5
                             )
-                        );
+                        )?);
                     }
                 | k_for for_var kIN expr_value_do
                   compstmt
                   k_end
                     {
                         let ExprValueDo { value, do_t } = $<ExprValueDo>4;
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.for_(
                                 $<Token>1,
                                 $<Node>2,
@@ -3040,7 +3048,7 @@
                                 $<MaybeNode>5,
                                 $<Token>6
                             )
-                        );
+                        )?);
                     }
                 | k_class cpath superclass
                     {
@@ -3059,7 +3067,7 @@
 
                         let Superclass { lt_t, value } = $<Superclass>3;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.def_class(
                                 $<Token>1,
                                 $<Node>2,
@@ -3068,7 +3076,7 @@
                                 $<MaybeNode>5,
                                 $<Token>6
                             )
-                        );
+                        )?);
 
                         self.yylexer.cmdarg.pop();
                         self.yylexer.cond.pop();
@@ -3087,7 +3095,7 @@
                   bodystmt
                   k_end
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.def_sclass(
                                 $<Token>1,
                                 $<Token>2,
@@ -3095,7 +3103,7 @@
                                 $<MaybeNode>6,
                                 $<Token>7
                             )
-                        );
+                        )?);
 
                         self.yylexer.cmdarg.pop();
                         self.yylexer.cond.pop();
@@ -3116,14 +3124,14 @@
                             return self.yyerror(&@1, DiagnosticMessage::ModuleDefinitionInMethodBody);
                         }
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.def_module(
                                 $<Token>1,
                                 $<Node>2,
                                 $<MaybeNode>4,
                                 $<Token>5
                             )
-                        );
+                        )?);
 
                         self.yylexer.cmdarg.pop();
                         self.static_env.unextend();
@@ -3136,7 +3144,7 @@
                     {
                         let DefnHead { def_t, name_t } = $<DefnHead>1;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.def_method(
                                 def_t,
                                 name_t,
@@ -3144,7 +3152,7 @@
                                 $<MaybeNode>3,
                                 $<Token>4
                             )?
-                        );
+                        )?);
 
                         self.yylexer.cmdarg.pop();
                         self.yylexer.cond.pop();
@@ -3159,7 +3167,7 @@
                     {
                         let DefsHead { def_t, definee, dot_t, name_t } = $<DefsHead>1;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.def_singleton(
                                 def_t,
                                 definee,
@@ -3169,7 +3177,7 @@
                                 $<MaybeNode>3,
                                 $<Token>4
                             )?
-                        );
+                        )?);
 
                         self.yylexer.cmdarg.pop();
                         self.yylexer.cond.pop();
@@ -3179,7 +3187,7 @@
                     }
                 | kBREAK
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Break,
                                 $<Token>1,
@@ -3187,11 +3195,11 @@
                                 vec![],
                                 None
                             )?
-                        );
+                        )?);
                     }
                 | kNEXT
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Next,
                                 $<Token>1,
@@ -3199,11 +3207,11 @@
                                 vec![],
                                 None
                             )?
-                        );
+                        )?);
                     }
                 | kREDO
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Redo,
                                 $<Token>1,
@@ -3211,11 +3219,11 @@
                                 vec![],
                                 None
                             )?
-                        );
+                        )?);
                     }
                 | kRETRY
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Retry,
                                 $<Token>1,
@@ -3223,7 +3231,7 @@
                                 vec![],
                                 None
                             )?
-                        );
+                        )?);
                     }
                 ;
 
@@ -3231,7 +3239,7 @@
                     {
                         let primary = $<Node>1;
                         self.value_expr(&primary)?;
-                        $$ = Value::Node(primary);
+                        $$ = Value::Node(self.check_depth(primary)?);
                     }
                 ;
 
@@ -3434,19 +3442,19 @@
 
           f_marg: f_norm_arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.arg($<Token>1)?
-                        );
+                        )?);
                     }
                 | tLPAREN f_margs rparen
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.multi_lhs(
                                 Some($<Token>1),
                                 $<NodeList>2,
                                 Some($<Token>3)
                             )
-                        );
+                        )?);
                     }
                 ;
 
@@ -3490,15 +3498,15 @@
 
      f_rest_marg: tSTAR f_norm_arg
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.restarg($<Token>1, Some($<Token>2))?
-                        );
+                        )?);
                     }
                 | tSTAR
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.restarg($<Token>1, None)?
-                        );
+                        )?);
                     }
                 ;
 
@@ -3702,9 +3710,9 @@ opt_block_args_tail:
                     {
                         let ident_t = $<Token>1;
                         self.static_env.declare(&clone_value(&ident_t));
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.shadowarg(ident_t)?
-                        );
+                        )?);
                     }
                 | f_bad_arg
                     {
@@ -3742,7 +3750,7 @@ opt_block_args_tail:
                         self.static_env.unextend();
                         self.yylexer.cmdarg.pop();
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.block(
                                 lambda_call,
                                 begin_t,
@@ -3750,7 +3758,7 @@ opt_block_args_tail:
                                 body,
                                 end_t
                             )?
-                        );
+                        )?);
                     }
                 ;
 
@@ -3834,7 +3842,7 @@ opt_block_args_tail:
       block_call: command do_block
                     {
                         let DoBlock { begin_t, args_type, body, end_t } = $<DoBlock>2;
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.block(
                                 $<Node>1,
                                 begin_t,
@@ -3842,12 +3850,12 @@ opt_block_args_tail:
                                 body,
                                 end_t
                             )?
-                        );
+                        )?);
                     }
                 | block_call call_op2 operation2 opt_paren_args
                     {
                         let OptParenArgs { begin_t, args, end_t } = $<OptParenArgs>4;
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.call_method(
                                 Some($<Node>1),
                                 Some($<Token>2),
@@ -3856,7 +3864,7 @@ opt_block_args_tail:
                                 args,
                                 end_t
                             )
-                        );
+                        )?);
                     }
                 | block_call call_op2 operation2 opt_paren_args brace_block
                     {
@@ -3871,7 +3879,7 @@ opt_block_args_tail:
                         );
 
                         let BraceBlock { begin_t, args_type, body, end_t } = $<BraceBlock>5;
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.block(
                                 method_call,
                                 begin_t,
@@ -3879,7 +3887,7 @@ opt_block_args_tail:
                                 body,
                                 end_t
                             )?
-                        );
+                        )?);
                     }
                 | block_call call_op2 operation2 command_args do_block
                     {
@@ -3893,7 +3901,7 @@ opt_block_args_tail:
                         );
 
                         let DoBlock { begin_t, args_type, body, end_t } = $<DoBlock>5;
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.block(
                                 method_call,
                                 begin_t,
@@ -3901,7 +3909,7 @@ opt_block_args_tail:
                                 body,
                                 end_t
                             )?
-                        );
+                        )?);
                     }
                 ;
 
@@ -3909,7 +3917,7 @@ opt_block_args_tail:
                     {
                         let ParenArgs { begin_t, args, end_t } = $<ParenArgs>2;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.call_method(
                                 None,
                                 None,
@@ -3918,13 +3926,13 @@ opt_block_args_tail:
                                 args,
                                 Some(end_t)
                             )
-                        );
+                        )?);
                     }
                 | primary_value call_op operation2 opt_paren_args
                     {
                         let OptParenArgs { begin_t, args, end_t } = $<OptParenArgs>4;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.call_method(
                                 Some($<Node>1),
                                 Some($<Token>2),
@@ -3933,13 +3941,13 @@ opt_block_args_tail:
                                 args,
                                 end_t
                             )
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 operation2 paren_args
                     {
                         let ParenArgs { begin_t, args, end_t } = $<ParenArgs>4;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.call_method(
                                 Some($<Node>1),
                                 Some($<Token>2),
@@ -3948,11 +3956,11 @@ opt_block_args_tail:
                                 args,
                                 Some(end_t)
                             )
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 operation3
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.call_method(
                                 Some($<Node>1),
                                 Some($<Token>2),
@@ -3961,13 +3969,13 @@ opt_block_args_tail:
                                 vec![],
                                 None
                             )
-                        );
+                        )?);
                     }
                 | primary_value call_op paren_args
                     {
                         let ParenArgs { begin_t, args, end_t } = $<ParenArgs>3;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.call_method(
                                 Some($<Node>1),
                                 Some($<Token>2),
@@ -3976,13 +3984,13 @@ opt_block_args_tail:
                                 args,
                                 Some(end_t)
                             )
-                        );
+                        )?);
                     }
                 | primary_value tCOLON2 paren_args
                     {
                         let ParenArgs { begin_t, args, end_t } = $<ParenArgs>3;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.call_method(
                                 Some($<Node>1),
                                 Some($<Token>2),
@@ -3991,13 +3999,13 @@ opt_block_args_tail:
                                 args,
                                 Some(end_t)
                             )
-                        );
+                        )?);
                     }
                 | kSUPER paren_args
                     {
                         let ParenArgs { begin_t, args, end_t } = $<ParenArgs>2;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Super,
                                 $<Token>1,
@@ -4005,11 +4013,11 @@ opt_block_args_tail:
                                 args,
                                 Some(end_t)
                             )?
-                        );
+                        )?);
                     }
                 | kSUPER
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Zsuper,
                                 $<Token>1,
@@ -4017,18 +4025,18 @@ opt_block_args_tail:
                                 vec![],
                                 None
                             )?
-                        );
+                        )?);
                     }
                 | primary_value tLBRACK2 opt_call_args rbracket
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.index(
                                 $<Node>1,
                                 $<Token>2,
                                 $<NodeList>3,
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 ;
 
@@ -4239,40 +4247,40 @@ opt_block_args_tail:
                     }
                 | p_expr tCOMMA
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.array_pattern(
                                 None,
                                 vec![ $<Node>1 ],
                                 Some($<Token>2),
                                 None
                             )
-                        );
+                        )?);
                     }
                 | p_expr tCOMMA p_args
                     {
                         let MatchPatternWithTrailingComma { elements, trailing_comma } = $<MatchPatternWithTrailingComma>3;
                         let elements = [ vec![$<Node>1], elements ].concat();
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.array_pattern(None, elements, trailing_comma, None)
-                        );
+                        )?);
                     }
                 | p_find
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.find_pattern(None, $<NodeList>1, None)
-                        );
+                        )?);
                     }
                 | p_args_tail
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.array_pattern(None, $<NodeList>1, None, None)
-                        );
+                        )?);
                     }
                 | p_kwargs
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.hash_pattern(None, $<NodeList>1, None)
-                        );
+                        )?);
                     }
                 ;
 
@@ -4284,13 +4292,13 @@ opt_block_args_tail:
 
             p_as: p_expr tASSOC p_variable
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.match_as(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )
-                        );
+                        )?);
                     }
                 | p_alt
                     {
@@ -4300,13 +4308,13 @@ opt_block_args_tail:
 
            p_alt: p_alt tPIPE p_expr_basic
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.match_alt(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )
-                        );
+                        )?);
                     }
                 | p_expr_basic
                     {
@@ -4337,141 +4345,141 @@ opt_block_args_tail:
                         self.pattern_hash_keys.pop();
                         let MatchPatternWithTrailingComma { elements, trailing_comma } = $<MatchPatternWithTrailingComma>3;
                         let pattern = self.builder.array_pattern(None, elements, trailing_comma, None);
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_pattern(
                                 $<Node>1,
                                 $<Token>2,
                                 pattern,
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 | p_const p_lparen p_find rparen
                     {
                         self.pattern_hash_keys.pop();
                         let pattern = self.builder.find_pattern(None, $<NodeList>3, None);
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_pattern(
                                 $<Node>1,
                                 $<Token>2,
                                 pattern,
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 | p_const p_lparen p_kwargs rparen
                     {
                         self.pattern_hash_keys.pop();
                         let pattern = self.builder.hash_pattern(None, $<NodeList>3, None);
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_pattern(
                                 $<Node>1,
                                 $<Token>2,
                                 pattern,
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 | p_const tLPAREN2 rparen
                     {
                         let lparen = $<Token>2;
                         let rparen = $<Token>3;
                         let pattern = self.builder.array_pattern(Some(lparen.clone()), vec![], None, Some(rparen.clone()));
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_pattern(
                                 $<Node>1,
                                 lparen,
                                 pattern,
                                 rparen
                             )
-                        );
+                        )?);
                     }
                 | p_const p_lbracket p_args rbracket
                     {
                         self.pattern_hash_keys.pop();
                         let MatchPatternWithTrailingComma { elements, trailing_comma } = $<MatchPatternWithTrailingComma>3;
                         let pattern = self.builder.array_pattern(None, elements, trailing_comma, None);
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_pattern(
                                 $<Node>1,
                                 $<Token>2,
                                 pattern,
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 | p_const p_lbracket p_find rbracket
                     {
                         self.pattern_hash_keys.pop();
                         let pattern = self.builder.find_pattern(None, $<NodeList>3, None);
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_pattern(
                                 $<Node>1,
                                 $<Token>2,
                                 pattern,
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 | p_const p_lbracket p_kwargs rbracket
                     {
                         self.pattern_hash_keys.pop();
                         let pattern = self.builder.hash_pattern(None, $<NodeList>3, None);
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_pattern(
                                 $<Node>1,
                                 $<Token>2,
                                 pattern,
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 | p_const tLBRACK2 rbracket
                     {
                         let lparen = $<Token>2;
                         let rparen = $<Token>3;
                         let pattern = self.builder.array_pattern(Some(lparen.clone()), vec![], None, Some(rparen.clone()));
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_pattern(
                                 $<Node>1,
                                 lparen,
                                 pattern,
                                 rparen
                             )
-                        );
+                        )?);
                     }
                 | tLBRACK p_args rbracket
                     {
                         let MatchPatternWithTrailingComma { elements, trailing_comma } = $<MatchPatternWithTrailingComma>2;
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.array_pattern(
                                 Some($<Token>1),
                                 elements,
                                 trailing_comma,
                                 Some($<Token>3)
                             )
-                        );
+                        )?);
                     }
                 | tLBRACK p_find rbracket
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.find_pattern(
                                 Some($<Token>1),
                                 $<NodeList>2,
                                 Some($<Token>3)
                             )
-                        );
+                        )?);
                     }
                 | tLBRACK rbracket
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.array_pattern(
                                 Some($<Token>1),
                                 vec![],
                                 None,
                                 Some($<Token>2)
                             )
-                        );
+                        )?);
                     }
                 | tLBRACE
                     {
@@ -4483,23 +4491,23 @@ opt_block_args_tail:
                     {
                         self.pattern_hash_keys.pop();
                         self.yylexer.in_kwarg = $<Bool>2;
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.hash_pattern(
                                 Some($<Token>1),
                                 $<NodeList>3,
                                 Some($<Token>4)
                             )
-                        );
+                        )?);
                     }
                 | tLBRACE rbrace
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.hash_pattern(
                                 Some($<Token>1),
                                 vec![],
                                 Some($<Token>2),
                             )
-                        );
+                        )?);
                     }
                 | tLPAREN
                     {
@@ -4509,13 +4517,13 @@ opt_block_args_tail:
                   p_expr rparen
                     {
                         self.pattern_hash_keys.pop();
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.begin(
                                 $<Token>1,
                                 Some($<Node>3),
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 ;
 
@@ -4639,15 +4647,15 @@ opt_block_args_tail:
 
           p_rest: tSTAR tIDENTIFIER
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.match_rest($<Token>1, Some($<Token>2))?
-                        );
+                        )?);
                     }
                 | tSTAR
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.match_rest($<Token>1, None)?
-                        );
+                        )?);
                     }
                 ;
 
@@ -4702,20 +4710,20 @@ opt_block_args_tail:
 
             p_kw: p_kw_label p_expr
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.match_pair(
                                 $<PKwLabel>1,
                                 $<Node>2
                             )?
-                        );
+                        )?);
                     }
                 | p_kw_label
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.match_label(
                                 $<PKwLabel>1,
                             )?
-                        );
+                        )?);
                     }
                 ;
 
@@ -4783,13 +4791,13 @@ opt_block_args_tail:
                         let right = $<Node>3;
                         self.value_expr(&right)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.range_inclusive(
                                 Some(left),
                                 $<Token>2,
                                 Some(right)
                             )
-                        );
+                        )?);
                     }
                 | p_primitive tDOT3 p_primitive
                     {
@@ -4799,39 +4807,39 @@ opt_block_args_tail:
                         let right = $<Node>3;
                         self.value_expr(&right)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.range_exclusive(
                                 Some(left),
                                 $<Token>2,
                                 Some(right)
                             )
-                        );
+                        )?);
                     }
                 | p_primitive tDOT2
                     {
                         let left = $<Node>1;
                         self.value_expr(&left)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.range_inclusive(
                                 Some(left),
                                 $<Token>2,
                                 None
                             )
-                        );
+                        )?);
                     }
                 | p_primitive tDOT3
                     {
                         let left = $<Node>1;
                         self.value_expr(&left)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.range_exclusive(
                                 Some(left),
                                 $<Token>2,
                                 None
                             )
-                        );
+                        )?);
                     }
                 | p_variable
                     {
@@ -4850,26 +4858,26 @@ opt_block_args_tail:
                         let right = $<Node>2;
                         self.value_expr(&right)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.range_inclusive(
                                 None,
                                 $<Token>1,
                                 Some(right)
                             )
-                        );
+                        )?);
                     }
                 | tBDOT3 p_primitive
                     {
                         let right = $<Node>2;
                         self.value_expr(&right)?;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.range_exclusive(
                                 None,
                                 $<Token>1,
                                 Some(right)
                             )
-                        );
+                        )?);
                     }
                 ;
 
@@ -4907,9 +4915,9 @@ opt_block_args_tail:
                     }
                 | keyword_variable
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.accessible($<Node>1)
-                        );
+                        )?);
                     }
                 | lambda
                     {
@@ -4919,9 +4927,9 @@ opt_block_args_tail:
 
       p_variable: tIDENTIFIER
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.match_var($<Token>1)?
-                        );
+                        )?);
                     }
                 ;
 
@@ -4935,31 +4943,31 @@ opt_block_args_tail:
                         }
 
                         let lvar = self.builder.accessible(self.builder.lvar(ident_t));
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.pin($<Token>1, lvar)
-                        );
+                        )?);
                     }
                 ;
 
          p_const: tCOLON3 cname
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_global($<Token>1, $<Token>2)
-                        );
+                        )?);
                     }
                 | p_const tCOLON2 cname
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_fetch(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Token>3,
                             )
-                        );
+                        )?);
                     }
                 | tCONSTANT
                     {
-                        $$ = Value::Node(self.builder.const_($<Token>1));
+                        $$ = Value::Node(self.check_depth(self.builder.const_($<Token>1))?);
                     }
                 ;
 
@@ -5044,13 +5052,13 @@ opt_block_args_tail:
 
          strings: string
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.string_compose(
                                 None,
                                 $<NodeList>1,
                                 None
                             )
-                        );
+                        )?);
                     }
                 ;
 
@@ -5080,7 +5088,7 @@ opt_block_args_tail:
                         let indent = self.yylexer.buffer.heredoc_indent;
                         self.yylexer.buffer.heredoc_indent = 0;
                         self.builder.heredoc_dedent(&mut string, indent);
-                        $$ = Value::Node(string);
+                        $$ = Value::Node(self.check_depth(string)?);
                     }
                 ;
 
@@ -5090,7 +5098,7 @@ opt_block_args_tail:
                         let indent = self.yylexer.buffer.heredoc_indent;
                         self.yylexer.buffer.heredoc_indent = 0;
                         self.builder.heredoc_dedent(&mut string, indent);
-                        $$ = Value::Node(string);
+                        $$ = Value::Node(self.check_depth(string)?);
                     }
                 ;
 
@@ -5098,26 +5106,26 @@ opt_block_args_tail:
                     {
                         let regexp_end = $<Token>3;
                         let opts = self.builder.regexp_options(regexp_end.clone());
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.regexp_compose(
                                 $<Token>1,
                                 $<NodeList>2,
                                 regexp_end,
                                 opts
                             )
-                        );
+                        )?);
                     }
                 ;
 
            words: tWORDS_BEG tSPACE word_list tSTRING_END
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.words_compose(
                                 $<Token>1,
                                 $<NodeList>3,
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 ;
 
@@ -5150,13 +5158,13 @@ opt_block_args_tail:
 
          symbols: tSYMBOLS_BEG tSPACE symbol_list tSTRING_END
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.symbols_compose(
                                 $<Token>1,
                                 $<NodeList>3,
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 ;
 
@@ -5176,25 +5184,25 @@ opt_block_args_tail:
 
           qwords: tQWORDS_BEG tSPACE qword_list tSTRING_END
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.words_compose(
                                 $<Token>1,
                                 $<NodeList>3,
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 ;
 
         qsymbols: tQSYMBOLS_BEG tSPACE qsym_list tSTRING_END
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.symbols_compose(
                                 $<Token>1,
                                 $<NodeList>3,
                                 $<Token>4
                             )
-                        );
+                        )?);
                     }
                 ;
 
@@ -5264,9 +5272,9 @@ xstring_contents: /* none */
 
   string_content: tSTRING_CONTENT
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.string_internal($<Token>1)
-                        );
+                        )?);
                     }
                 | tSTRING_DVAR
                     {
@@ -5309,28 +5317,28 @@ xstring_contents: /* none */
                         self.yylexer.buffer.heredoc_indent = $<Num>6;
                         self.yylexer.buffer.heredoc_line_indent = -1;
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.begin(
                                 $<Token>1,
                                 $<MaybeNode>7,
                                 $<Token>8
                             )
-                        );
+                        )?);
                     }
                 ;
 
      string_dvar: tGVAR
                     {
-                        $$ = Value::Node(self.builder.gvar($<Token>1));
+                        $$ = Value::Node(self.check_depth(self.builder.gvar($<Token>1))?);
                     }
                 | tIVAR
                     {
-                        $$ = Value::Node(self.builder.ivar($<Token>1));
+                        $$ = Value::Node(self.check_depth(self.builder.ivar($<Token>1))?);
 
                     }
                 | tCVAR
                     {
-                        $$ = Value::Node(self.builder.cvar($<Token>1));
+                        $$ = Value::Node(self.check_depth(self.builder.cvar($<Token>1))?);
                     }
                 | backref
                     {
@@ -5345,9 +5353,9 @@ xstring_contents: /* none */
             ssym: tSYMBEG sym
                     {
                         self.yylexer.lex_state.set(EXPR_END);
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.symbol($<Token>1, $<Token>2)
-                        );
+                        )?);
                     }
                 ;
 
@@ -5360,9 +5368,9 @@ xstring_contents: /* none */
             dsym: tSYMBEG string_contents tSTRING_END
                     {
                         self.yylexer.lex_state.set(EXPR_END);
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.symbol_compose($<Token>1, $<NodeList>2, $<Token>3)
-                        );
+                        )?);
                     }
                 ;
 
@@ -5372,114 +5380,114 @@ xstring_contents: /* none */
                     }
                 | tUMINUS_NUM simple_numeric   %prec tLOWEST
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.unary_num(
                                 $<Token>1,
                                 $<Node>2
                             )
-                        );
+                        )?);
                     }
                 ;
 
   simple_numeric: tINTEGER
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.integer($<Token>1)
-                        );
+                        )?);
                     }
                 | tFLOAT
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.float($<Token>1)
-                        );
+                        )?);
                     }
                 | tRATIONAL
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.rational($<Token>1)
-                        );
+                        )?);
                     }
                 | tIMAGINARY
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.complex($<Token>1)
-                        );
+                        )?);
                     }
                 ;
 
    user_variable: tIDENTIFIER
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.lvar($<Token>1)
-                        );
+                        )?);
                     }
                 | tIVAR
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.ivar($<Token>1)
-                        );
+                        )?);
                     }
                 | tGVAR
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.gvar($<Token>1)
-                        );
+                        )?);
                     }
                 | tCONSTANT
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.const_($<Token>1)
-                        );
+                        )?);
                     }
                 | tCVAR
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.cvar($<Token>1)
-                        );
+                        )?);
                     }
                 ;
 
 keyword_variable: kNIL
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.nil($<Token>1)
-                        );
+                        )?);
                     }
                 | kSELF
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.self_($<Token>1)
-                        );
+                        )?);
                     }
                 | kTRUE
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.true_($<Token>1)
-                        );
+                        )?);
                     }
                 | kFALSE
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.false_($<Token>1)
-                        );
+                        )?);
                     }
                 | k__FILE__
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.__file__($<Token>1)
-                        );
+                        )?);
                     }
                 | k__LINE__
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.__line__($<Token>1)
-                        );
+                        )?);
                     }
                 | k__ENCODING__
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.__encoding__($<Token>1)
-                        );
+                        )?);
                     }
                 ;
 
@@ -5537,43 +5545,43 @@ keyword_variable: kNIL
                             }
                         }
 
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.accessible(node)
-                        );
+                        )?);
                     }
                 | keyword_variable
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.accessible($<Node>1)
-                        );
+                        )?);
                     }
                 ;
 
          var_lhs: user_variable
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assignable($<Node>1)?
-                        );
+                        )?);
                     }
                 | keyword_variable
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.assignable($<Node>1)?
-                        );
+                        )?);
                     }
                 ;
 
          backref: tNTH_REF
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.nth_ref($<Token>1)
-                        );
+                        )?);
                     }
                 | tBACK_REF
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.back_ref($<Token>1)
-                        );
+                        )?);
                     }
                 ;
 
@@ -5815,19 +5823,19 @@ keyword_variable: kNIL
       f_arg_item: f_arg_asgn
                     {
                         self.current_arg_stack.set(None);
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.arg($<Token>1)?
-                        );
+                        )?);
                     }
                 | tLPAREN f_margs rparen
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.multi_lhs(
                                 Some($<Token>1),
                                 $<NodeList>2,
                                 Some($<Token>3)
                             )
-                        );
+                        )?);
                     }
                 ;
 
@@ -5863,30 +5871,30 @@ keyword_variable: kNIL
             f_kw: f_label arg_value
                     {
                         self.current_arg_stack.set(None);
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.kwoptarg($<Token>1, $<Node>2)?
-                        );
+                        )?);
                     }
                 | f_label
                     {
                         self.current_arg_stack.set(None);
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.kwarg($<Token>1)?
-                        );
+                        )?);
                     }
                 ;
 
       f_block_kw: f_label primary_value
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.kwoptarg($<Token>1, $<Node>2)?
-                        );
+                        )?);
                     }
                 | f_label
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.kwarg($<Token>1)?
-                        );
+                        )?);
                     }
                 ;
 
@@ -5958,26 +5966,26 @@ keyword_variable: kNIL
            f_opt: f_arg_asgn tEQL arg_value
                     {
                         self.current_arg_stack.set(None);
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.optarg(
                                 $<Token>1,
                                 $<Token>2,
                                 $<Node>3
                             )?
-                        );
+                        )?);
                     }
                 ;
 
      f_block_opt: f_arg_asgn tEQL primary_value
                     {
                         self.current_arg_stack.set(None);
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.optarg(
                                 $<Token>1,
                                 $<Token>2,
                                 $<Node>3
                             )?
-                        );
+                        )?);
                     }
                 ;
 
@@ -6050,9 +6058,9 @@ keyword_variable: kNIL
                     {
                         let ident_t = $<Token>2;
                         self.static_env.declare(&clone_value(&ident_t));
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.blockarg($<Token>1, ident_t)?
-                        );
+                        )?);
                     }
                 ;
 
@@ -6070,7 +6078,7 @@ keyword_variable: kNIL
                     {
                         let var_ref = $<Node>1;
                         self.value_expr(&var_ref)?;
-                        $$ = Value::Node(var_ref);
+                        $$ = Value::Node(self.check_depth(var_ref)?);
                     }
                 | tLPAREN2 { self.yylexer.lex_state.set(EXPR_BEG); $<None>$ = Value::None; } expr rparen
                     {
@@ -6098,7 +6106,7 @@ keyword_variable: kNIL
                                 self.value_expr(other)?
                             },
                         }
-                        $$ = Value::Node(expr);
+                        $$ = Value::Node(self.check_depth(expr)?);
                     }
                 ;
 
@@ -6126,39 +6134,39 @@ keyword_variable: kNIL
 
            assoc: arg_value tASSOC arg_value
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.pair(
                                 $<Node>1,
                                 $<Token>2,
                                 $<Node>3
                             )
-                        );
+                        )?);
                     }
                 | tLABEL arg_value
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.pair_keyword(
                                 $<Token>1,
                                 $<Node>2
                             )
-                        );
+                        )?);
                     }
                 | tSTRING_BEG string_contents tLABEL_END arg_value
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.pair_quoted(
                                 $<Token>1,
                                 $<NodeList>2,
                                 $<Token>3,
                                 $<Node>4
                             )
-                        );
+                        )?);
                     }
                 | tDSTAR arg_value
                     {
-                        $$ = Value::Node(
+                        $$ = Value::Node(self.check_depth(
                             self.builder.kwsplat($<Token>1, $<Node>2)
-                        );
+                        )?);
                     }
                 ;
 
@@ -6327,10 +6335,42 @@ impl Parser {
             buffer_name,
             debug,
             decoder,
+            record_tokens,
+            record_source_tokens,
+            record_comments,
+            interner,
+            max_depth,
+            max_tokens,
+            max_input_size,
+            cancelled,
+            deadline,
+            local_variables,
+            in_def,
+            forward_args,
+            ruby_version,
+            first_line,
+            byte_offset,
         } = options;
 
+        let limits = ParseLimits::new(max_depth, max_tokens, max_input_size, cancelled, deadline);
+        let input_size_error = limits.check_input_size(input.len()).err();
+        let input: &[u8] = if input_size_error.is_some() { &[] } else { input };
+
         let mut lexer = Lexer::new(input, &buffer_name, decoder);
         lexer.set_debug(debug);
+        lexer.record_comments = record_comments;
+        if let Some(interner) = interner {
+            lexer.static_env = StaticEnvironment::with_interner(interner);
+        }
+        for name in local_variables.iter() {
+            lexer.static_env.declare(name);
+        }
+        if forward_args {
+            lexer.static_env.declare_forward_args();
+        }
+        if in_def {
+            lexer.context.push_def();
+        }
 
         let current_arg_stack = CurrentArgStack::new();
         let max_numparam_stack = MaxNumparamStack::new();
@@ -6345,13 +6385,14 @@ impl Parser {
             pattern_variables.clone(),
             pattern_hash_keys.clone(),
             lexer.diagnostics.clone(),
+            ruby_version,
         );
 
-        let last_token = Token {
-            token_type: 0,
-            token_value: TokenValue::String("".to_owned()),
-            loc: Loc { begin: 0, end: 0 }
-        };
+        if let Some(message) = &input_size_error {
+            lexer.diagnostics.emit(
+                Diagnostic::new(ErrorLevel::Error, message.clone(), Range::new(0, 0))
+            );
+        }
 
         Self {
             yy_error_verbose: true,
@@ -6366,9 +6407,16 @@ impl Parser {
             pattern_variables,
             pattern_hash_keys,
             static_env: lexer.static_env.clone(),
-            last_token,
+            last_token_type: 0,
             tokens: vec![],
+            record_tokens,
+            source_tokens: vec![],
+            record_source_tokens,
+            limits,
+            limit_reached: input_size_error.is_some(),
             diagnostics: lexer.diagnostics.clone(),
+            first_line,
+            byte_offset,
             yylexer: lexer,
         }
     }
@@ -6382,14 +6430,26 @@ impl Parser {
     pub fn do_parse(mut self) -> ParserResult  {
         self.parse();
 
-        ParserResult {
+        let mut result = ParserResult {
             ast: self.result,
             tokens: std::mem::take(&mut self.tokens),
+            source_tokens: std::mem::take(&mut self.source_tokens),
             diagnostics: self.diagnostics.take(),
             comments: self.yylexer.comments,
             magic_comments: self.yylexer.magic_comments,
             input: self.yylexer.buffer.input,
-        }
+            interner: self.static_env.take_interner(),
+        };
+        result.move_to(self.first_line, self.byte_offset);
+        result
+    }
+
+    /// Same as `do_parse`, but returns the AST stored in an `Arena`.
+    ///
+    /// The parser builds an owned tree that is converted after parsing,
+    /// see `arena::ArenaParserResult`
+    pub fn do_parse_into_arena(self) -> crate::arena::ArenaParserResult {
+        self.do_parse().into()
     }
 
     /// Turns `self` and `yylexer` into debug mode
@@ -6410,13 +6470,61 @@ impl Parser {
     }
 
     fn next_token(&mut self) -> Token {
+        if self.limit_reached {
+            return Self::end_of_input(self.yylexer.buffer.input.len());
+        }
+
         let token = self.yylexer.yylex();
-        self.last_token = token.clone();
-        self.tokens.push(token.clone());
+        if let Err(message) = self.limits.check_token() {
+            self.limit_reached = true;
+            self.diagnostics.emit(
+                Diagnostic::new(
+                    ErrorLevel::Error,
+                    message,
+                    Range::new(token.loc.begin, token.loc.end)
+                )
+            );
+            return Self::end_of_input(token.loc.begin);
+        }
+
+        self.last_token_type = token.token_type;
+        if self.record_tokens {
+            self.tokens.push(token.clone());
+        }
+        if self.record_source_tokens {
+            self.source_tokens.push(SourceToken::new(&token, &self.yylexer.buffer.input));
+        }
 
         token
     }
 
+    // Called for every node that is built by a grammar rule,
+    // an error stops building the tree once it's too deep
+    fn check_depth(&mut self, node: Node) -> Result<Node, ()> {
+        if let Err(message) = self.limits.check_node(&node) {
+            if !self.limit_reached {
+                self.limit_reached = true;
+                self.diagnostics.emit(
+                    Diagnostic::new(
+                        ErrorLevel::Error,
+                        message,
+                        node.expression().clone()
+                    )
+                );
+            }
+            return Err(());
+        }
+        Ok(node)
+    }
+
+    fn end_of_input(pos: usize) -> Token {
+        Token {
+            token_type: Lexer::END_OF_INPUT,
+            token_value: TokenValue::String("".to_owned()),
+            loc: Loc { begin: pos, end: pos }
+        }
+    }
+
     fn check_kwarg_name(&self, ident_t: &Token) -> Result<(), ()> {
         let name = clone_value(&ident_t);
         let first_char = name.chars().next().expect("kwarg name can't be empty");
@@ -6470,6 +6578,13 @@ impl Parser {
 }
 
 impl TokenValue {
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::String(s) => s.as_bytes(),
+            Self::InvalidString(bytes) => bytes,
+        }
+    }
+
     pub fn into_string_lossy(self) -> String {
         match self {
             Self::String(s) => s,
@@ -1,4 +1,9 @@
 use crate::source::CustomDecoder;
+use crate::Interner;
+use crate::RubyVersion;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
 
 /// Configuration of the parser
 pub struct ParserOptions {
@@ -40,6 +45,138 @@ pub struct ParserOptions {
     /// assert_eq!(ast.unwrap().expression().source(&input).unwrap(), "decoded".to_owned())
     /// ```
     pub decoder: CustomDecoder,
+
+    /// Controls whether the parser should record tokens
+    /// into `ParserResult.tokens`.
+    ///
+    /// Disable it if you need only AST and diagnostics,
+    /// it saves a clone of every token.
+    /// Recorded tokens own their values, use `record_source_tokens`
+    /// to get tokens with values backed by ranges of the input.
+    pub record_tokens: bool,
+
+    /// Controls whether the parser should record tokens
+    /// into `ParserResult.source_tokens`.
+    ///
+    /// A `SourceToken` stores only a range of `ParserResult.input`
+    /// if its value is the same as the source code, and owns
+    /// the value only if it's different (e.g. a string with escapes).
+    /// Disable `record_tokens` to record only these tokens.
+    pub record_source_tokens: bool,
+
+    /// Controls whether the parser should record comments
+    /// and magic comments into `ParserResult.comments`
+    /// and `ParserResult.magic_comments`.
+    ///
+    /// Magic comments are still handled (e.g. `# encoding: ...`
+    /// changes encoding of the input) even if this option is disabled.
+    pub record_comments: bool,
+
+    /// String interner that is used to store names of local variables.
+    ///
+    /// Every parser returns its interner in `ParserResult.interner`,
+    /// pass it to the next parser to share identifiers between them
+    /// (e.g. when parsing a big codebase file by file).
+    /// If `None` every parser creates its own interner.
+    ///
+    /// Identifiers in AST nodes are owned `String`s,
+    /// `arena::Arena` stores them as `Symbol`s of this interner.
+    pub interner: Option<Interner>,
+
+    /// Maximum depth of the AST (`foo` has depth 1, `foo.bar` has depth 2).
+    /// Parsing stops with `DiagnosticMessage::NestingTooDeep`
+    /// as soon as a deeper node is built, and the node is discarded.
+    ///
+    /// Use it to protect recursive code that walks the AST
+    /// (including `Drop`) from a stack overflow on malicious inputs.
+    pub max_depth: Option<usize>,
+
+    /// Maximum number of tokens. Parsing stops with
+    /// `DiagnosticMessage::TooManyTokens` if it's exceeded.
+    pub max_tokens: Option<usize>,
+
+    /// Maximum size of the input in bytes. If the input is bigger
+    /// it's not parsed at all and `DiagnosticMessage::InputTooLarge`
+    /// is reported.
+    pub max_input_size: Option<usize>,
+
+    /// Cancellation flag, can be set from another thread.
+    /// Parsing stops with `DiagnosticMessage::ParsingCancelled`
+    /// once it's set to `true`.
+    pub cancelled: Option<Arc<AtomicBool>>,
+
+    /// Deadline of parsing. Parsing stops with
+    /// `DiagnosticMessage::ParsingTimeout` once it's reached.
+    /// It's checked once per 64 tokens.
+    pub deadline: Option<Instant>,
+
+    /// Local variables of the outer scope (like in `eval(code, binding)`),
+    /// they are declared before parsing begins.
+    ///
+    /// Identifiers with these names are parsed as local variables
+    /// instead of method calls. Numbered parameters of an outer block
+    /// (`_1`, `_2`, ...) can also be passed here.
+    ///
+    /// # Example
+    /// ```rust
+    /// use lib_ruby_parser::{Node, Parser, ParserOptions};
+    ///
+    /// let options = ParserOptions {
+    ///     local_variables: vec!["foo".to_owned()],
+    ///     ..Default::default()
+    /// };
+    /// let ast = Parser::new(b"foo", options).do_parse().ast.unwrap();
+    ///
+    /// assert!(matches!(ast, Node::Lvar(_)));
+    /// ```
+    pub local_variables: Vec<String>,
+
+    /// Controls whether the code is parsed as a part of a method body.
+    ///
+    /// Class, module and constant definitions are not allowed there.
+    pub in_def: bool,
+
+    /// Controls whether arguments forwarding (`foo(...)`) is allowed,
+    /// i.e. the code is a part of a method defined as `def m(...)`.
+    pub forward_args: bool,
+
+    /// Version of Ruby that the code is parsed as.
+    ///
+    /// Syntax that doesn't exist in this version (like endless methods
+    /// in Ruby 2.7) is reported as `DiagnosticMessage::UnsupportedInRubyVersion`.
+    pub ruby_version: RubyVersion,
+
+    /// 1-based number of the first line of the code in the enclosing
+    /// document, like `line` argument of `eval(code, binding, file, line)`.
+    ///
+    /// Lines returned by `ParserResult.input` (and so lines in rendered
+    /// diagnostics and `__LINE__` values in `mri_ast`) start from it.
+    pub first_line: usize,
+
+    /// Byte offset of the code in the enclosing document
+    /// (e.g. of a Markdown code block).
+    ///
+    /// It's added to all locations in `ParserResult`, and `ParserResult.input`
+    /// takes it into account, so `Range::source(&input)` still works.
+    /// Columns are counted from the beginning of the code,
+    /// so it should start at the beginning of a line.
+    /// Code with a prefix on every line (like YARD examples in `#   ` comments
+    /// or indented heredocs) can be parsed with `embedded::parse`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use lib_ruby_parser::{Parser, ParserOptions};
+    ///
+    /// let markdown = "# Example\n\n```ruby\nfoo(1, 2)\n```\n";
+    /// let byte_offset = markdown.find("foo").unwrap();
+    /// let options = ParserOptions { first_line: 4, byte_offset, ..Default::default() };
+    /// let result = Parser::new(b"foo(1, 2)\n", options).do_parse();
+    ///
+    /// let range = result.ast.unwrap().expression().clone();
+    /// assert_eq!(&markdown[range.to_range()], "foo(1, 2)");
+    /// assert_eq!(range.begin_line_col(&result.input), Some((3, 0)));
+    /// ```
+    pub byte_offset: usize,
 }
 
 const DEFAULT_BUFFER_NAME: &str = "(eval)";
@@ -50,6 +187,21 @@ impl Default for ParserOptions {
             buffer_name: DEFAULT_BUFFER_NAME.to_owned(),
             debug: false,
             decoder: CustomDecoder { f: None },
+            record_tokens: true,
+            record_source_tokens: false,
+            record_comments: true,
+            interner: None,
+            max_depth: None,
+            max_tokens: None,
+            max_input_size: None,
+            cancelled: None,
+            deadline: None,
+            local_variables: vec![],
+            in_def: false,
+            forward_args: false,
+            ruby_version: RubyVersion::default(),
+            first_line: 1,
+            byte_offset: 0,
         }
     }
 }
@@ -1,16 +1,93 @@
 use crate::source::buffer::Input;
 use crate::source::Comment;
 use crate::source::MagicComment;
+use crate::source::Range;
 use crate::Diagnostic;
+use crate::Interner;
 use crate::Node;
+use crate::SourceToken;
 use crate::Token;
 
+/// Combination of all data that `Parser` can give you
 #[derive(Debug)]
 pub struct ParserResult {
+    /// Abstract Syntax Tree that was constructed from your code.
+    /// Contains `None` if the code gives no AST nodes (e.g. empty file)
     pub ast: Option<Node>,
+
+    /// List of tokens returned by a Lexer and consumed by a Parser.
+    /// Empty if `ParserOptions.record_tokens` is `false`
     pub tokens: Vec<Token>,
+
+    /// List of tokens with values backed by ranges of `input`.
+    /// Empty if `ParserOptions.record_source_tokens` is `false`
+    pub source_tokens: Vec<SourceToken>,
+
+    /// List of all diagnostics (errors and warnings) that have been
+    /// recorded during lexing and parsing
     pub diagnostics: Vec<Diagnostic>,
+
+    /// List of comments extracted from the source code.
+    /// Empty if `ParserOptions.record_comments` is `false`
     pub comments: Vec<Comment>,
+
+    /// List of magic comments extracted from the source code.
+    /// Empty if `ParserOptions.record_comments` is `false`
     pub magic_comments: Vec<MagicComment>,
+
+    /// Input that was used for parsing.
+    ///
+    /// Note: this input is not necessarily the same byte array that
+    /// you passed to `Parser::new`. If encoding of the input is
+    /// not `UTF-8` or `ASCII-8BIT/BINARY` Parser invokes `decoder`
+    /// that usually produces a different sequence of bytes.
     pub input: Input,
+
+    /// Interner with names of local variables declared in the source.
+    /// Pass it to the next parser via `ParserOptions.interner`
+    /// to share identifiers between parsers
+    pub interner: Interner,
+}
+
+impl ParserResult {
+    /// Calls `f` with every location in the result:
+    /// of AST nodes, tokens, diagnostics, comments and magic comments
+    pub fn map_ranges<F: FnMut(&mut Range)>(&mut self, mut f: F) {
+        if let Some(ast) = &mut self.ast {
+            ast.map_ranges(&mut f);
+        }
+        for token in self.tokens.iter_mut() {
+            let mut range = Range::new(token.loc.begin, token.loc.end);
+            f(&mut range);
+            token.loc.begin = range.begin_pos;
+            token.loc.end = range.end_pos;
+        }
+        for token in self.source_tokens.iter_mut() {
+            token.map_ranges(&self.input, &mut f);
+        }
+        for diagnostic in self.diagnostics.iter_mut() {
+            f(&mut diagnostic.range);
+        }
+        for comment in self.comments.iter_mut() {
+            f(&mut comment.location);
+        }
+        for magic_comment in self.magic_comments.iter_mut() {
+            f(&mut magic_comment.key_l);
+            f(&mut magic_comment.value_l);
+        }
+    }
+
+    // Moves all locations to the position of the source in the enclosing document
+    // (see `ParserOptions::first_line` and `ParserOptions::byte_offset`).
+    // It's a plain shift, so the source is expected to start at column 0
+    // (`embedded::parse` strips per-line prefixes before calling it)
+    pub(crate) fn move_to(&mut self, first_line: usize, byte_offset: usize) {
+        if byte_offset != 0 {
+            self.map_ranges(|range| {
+                *range = Range::new(range.begin_pos + byte_offset, range.end_pos + byte_offset)
+            });
+        }
+        self.input.byte_offset = byte_offset;
+        self.input.line_offset = first_line.saturating_sub(1);
+    }
 }
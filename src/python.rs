@@ -0,0 +1,345 @@
+//! Python bindings of the parser, built as a wheel with [maturin](https://github.com/PyO3/maturin).
+//!
+//! ```python
+//! import lib_ruby_parser
+//!
+//! result = lib_ruby_parser.parse("foo(42)", "(eval)")
+//! node = result.ast
+//! node.type                    # => "send"
+//! node.method_name             # => "foo"
+//! node.args[0].value           # => "42"
+//! node.location["selector"]    # => Range(0, 3)
+//! node.source                  # => "foo(42)"
+//! ```
+//!
+//! Every field of a node is available as an attribute with the same name
+//! (`recv`, `args`, `body`, ...), locations (`*_l` fields) are collected
+//! in the `location` dict under names without the `_l` suffix.
+
+#![allow(unsafe_code)]
+
+use crate::nodes::FieldValue;
+use crate::parser::TokenValue;
+use crate::source::{Input, Range};
+use crate::{token_name, Node, Parser, ParserOptions, ParserResult};
+use pyo3::exceptions::{PyAttributeError, PyTypeError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyString};
+
+/// Byte range of the source, `line` (1-based) and `column` (0-based) point to its start
+#[pyclass(name = "Range", module = "lib_ruby_parser")]
+#[derive(Clone)]
+pub struct PyRange {
+    #[pyo3(get)]
+    begin: usize,
+    #[pyo3(get)]
+    end: usize,
+    #[pyo3(get)]
+    line: usize,
+    #[pyo3(get)]
+    column: usize,
+}
+
+#[pymethods]
+impl PyRange {
+    fn __repr__(&self) -> String {
+        format!("Range({}, {})", self.begin, self.end)
+    }
+}
+
+fn range_to_py(range: &Range, input: &Input) -> PyRange {
+    let (line, column) = range.begin_line_col(input).unwrap_or((0, 0));
+    PyRange {
+        begin: range.begin_pos,
+        end: range.end_pos,
+        line: line + 1,
+        column,
+    }
+}
+
+fn bytes_to_py(py: Python, bytes: &[u8]) -> PyObject {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.into_py(py),
+        Err(_) => PyBytes::new(py, bytes).into_py(py),
+    }
+}
+
+/// AST node, fields are accessible as attributes
+#[pyclass(name = "Node", module = "lib_ruby_parser")]
+pub struct PyNode {
+    node_type: &'static str,
+    fields: Vec<(&'static str, PyObject)>,
+    children: Vec<Py<PyNode>>,
+    location: Vec<(&'static str, Option<PyRange>)>,
+    source: String,
+}
+
+#[pymethods]
+impl PyNode {
+    #[getter(type)]
+    fn node_type(&self) -> &str {
+        self.node_type
+    }
+
+    /// Names of all fields except locations
+    #[getter]
+    fn fields(&self) -> Vec<&str> {
+        self.fields.iter().map(|(name, _)| *name).collect()
+    }
+
+    /// Direct child nodes in the order of fields
+    #[getter]
+    fn children(&self, py: Python) -> Vec<Py<PyNode>> {
+        self.children
+            .iter()
+            .map(|child| child.clone_ref(py))
+            .collect()
+    }
+
+    /// Dict of all locations of the node, absent locations are `None`
+    #[getter]
+    fn location(&self, py: Python) -> PyResult<PyObject> {
+        let dict = PyDict::new(py);
+        for (name, range) in self.location.iter() {
+            dict.set_item(*name, range.clone().into_py(py))?;
+        }
+        Ok(dict.into())
+    }
+
+    /// Source code of the whole node
+    #[getter]
+    fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn __getattr__(&self, py: Python, name: &str) -> PyResult<PyObject> {
+        self.fields
+            .iter()
+            .find(|(field_name, _)| *field_name == name)
+            .map(|(_, value)| value.clone_ref(py))
+            .ok_or_else(|| {
+                PyAttributeError::new_err(format!(
+                    "'{}' node has no field '{}'",
+                    self.node_type, name
+                ))
+            })
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Node {} {:?}>", self.node_type, self.source)
+    }
+}
+
+fn loc_name(field_name: &'static str) -> &'static str {
+    field_name.strip_suffix("_l").unwrap_or(field_name)
+}
+
+fn node_to_py(py: Python, node: &Node, input: &Input) -> PyResult<Py<PyNode>> {
+    let mut fields = vec![];
+    let mut children = vec![];
+    let mut location = vec![];
+
+    let child_to_py = |node: &Node, children: &mut Vec<Py<PyNode>>| -> PyResult<PyObject> {
+        let child = node_to_py(py, node, input)?;
+        children.push(child.clone_ref(py));
+        Ok(child.into_py(py))
+    };
+
+    for (name, value) in node.fields() {
+        let value = match value {
+            FieldValue::Node(node) => child_to_py(node, &mut children)?,
+            FieldValue::Nodes(nodes) => nodes
+                .iter()
+                .map(|node| child_to_py(node, &mut children))
+                .collect::<PyResult<Vec<_>>>()?
+                .into_py(py),
+            FieldValue::MaybeNode(Some(node)) => child_to_py(node, &mut children)?,
+            FieldValue::MaybeNode(None) => py.None(),
+            FieldValue::Range(range) => {
+                location.push((loc_name(name), Some(range_to_py(range, input))));
+                continue;
+            }
+            FieldValue::MaybeRange(range) => {
+                let range = range.map(|range| range_to_py(range, input));
+                location.push((loc_name(name), range));
+                continue;
+            }
+            FieldValue::Str(value) => value.into_py(py),
+            FieldValue::MaybeStr(value) => value.into_py(py),
+            FieldValue::Chars(chars) => chars.iter().collect::<String>().into_py(py),
+            FieldValue::StringValue(value) => bytes_to_py(py, value.as_bytes()),
+            FieldValue::U8(value) => value.into_py(py),
+            FieldValue::Usize(value) => value.into_py(py),
+        };
+        fields.push((name, value));
+    }
+
+    Py::new(
+        py,
+        PyNode {
+            node_type: node.str_type(),
+            fields,
+            children,
+            location,
+            source: node.expression().source(input).unwrap_or_default(),
+        },
+    )
+}
+
+/// Token produced by the lexer
+#[pyclass(name = "Token", module = "lib_ruby_parser")]
+pub struct PyToken {
+    token_type: i32,
+    #[pyo3(get)]
+    name: String,
+    #[pyo3(get)]
+    value: PyObject,
+    #[pyo3(get)]
+    location: PyRange,
+}
+
+#[pymethods]
+impl PyToken {
+    #[getter(type)]
+    fn token_type(&self) -> i32 {
+        self.token_type
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<Token {} {}...{}>",
+            self.name, self.location.begin, self.location.end
+        )
+    }
+}
+
+/// Error or warning produced by the parser
+#[pyclass(name = "Diagnostic", module = "lib_ruby_parser")]
+pub struct PyDiagnostic {
+    /// `"error"` or `"warning"`
+    #[pyo3(get)]
+    level: &'static str,
+    #[pyo3(get)]
+    message: String,
+    /// Message with a highlighted line of the source, like the one printed by `ruby -c`
+    #[pyo3(get)]
+    rendered: Option<String>,
+    #[pyo3(get)]
+    location: PyRange,
+}
+
+#[pymethods]
+impl PyDiagnostic {
+    fn __repr__(&self) -> String {
+        format!("<Diagnostic {}: {}>", self.level, self.message)
+    }
+}
+
+/// Result of `parse`
+#[pyclass(name = "ParseResult", module = "lib_ruby_parser")]
+pub struct PyParseResult {
+    #[pyo3(get)]
+    ast: Option<Py<PyNode>>,
+    #[pyo3(get)]
+    tokens: Vec<Py<PyToken>>,
+    #[pyo3(get)]
+    diagnostics: Vec<Py<PyDiagnostic>>,
+}
+
+fn result_to_py(py: Python, result: &ParserResult) -> PyResult<PyParseResult> {
+    let input = &result.input;
+
+    let ast = match &result.ast {
+        Some(node) => Some(node_to_py(py, node, input)?),
+        None => None,
+    };
+
+    let tokens = result
+        .tokens
+        .iter()
+        .map(|token| {
+            let value = match &token.token_value {
+                TokenValue::String(s) => s.into_py(py),
+                TokenValue::InvalidString(bytes) => bytes_to_py(py, bytes),
+            };
+            let range = Range::new(token.loc.begin, token.loc.end);
+            Py::new(
+                py,
+                PyToken {
+                    token_type: token.token_type,
+                    name: token_name(token.token_type),
+                    value,
+                    location: range_to_py(&range, input),
+                },
+            )
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let diagnostics = result
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let level = if diagnostic.is_error() {
+                "error"
+            } else {
+                "warning"
+            };
+            Py::new(
+                py,
+                PyDiagnostic {
+                    level,
+                    message: diagnostic.render_message(),
+                    rendered: diagnostic.render(input),
+                    location: range_to_py(&diagnostic.range, input),
+                },
+            )
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+
+    Ok(PyParseResult {
+        ast,
+        tokens,
+        diagnostics,
+    })
+}
+
+/// parse(source, filename=None)
+/// --
+///
+/// Parses `source` (`str` or `bytes`), `filename` is used in diagnostics
+#[pyfunction]
+#[text_signature = "(source, filename=None)"]
+fn parse(py: Python, source: &PyAny, filename: Option<&str>) -> PyResult<PyParseResult> {
+    let source = if let Ok(source) = source.downcast::<PyString>() {
+        source.to_str()?.as_bytes().to_vec()
+    } else if let Ok(source) = source.downcast::<PyBytes>() {
+        source.as_bytes().to_vec()
+    } else {
+        return Err(PyTypeError::new_err("source must be str or bytes"));
+    };
+
+    let buffer_name = filename.unwrap_or("(eval)").to_owned();
+
+    // `ParserOptions` isn't `Send` (custom decoder is a boxed closure),
+    // so they are built inside the closure that runs without the GIL
+    let result = py.allow_threads(move || {
+        let options = ParserOptions {
+            buffer_name,
+            record_tokens: true,
+            ..Default::default()
+        };
+        Parser::new(&source, options).do_parse()
+    });
+    result_to_py(py, &result)
+}
+
+#[pymodule]
+fn lib_ruby_parser(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyNode>()?;
+    m.add_class::<PyRange>()?;
+    m.add_class::<PyToken>()?;
+    m.add_class::<PyDiagnostic>()?;
+    m.add_class::<PyParseResult>()?;
+    m.add_function(wrap_pyfunction!(parse, m)?)?;
+    Ok(())
+}
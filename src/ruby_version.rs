@@ -0,0 +1,32 @@
+/// Version of Ruby that the code is parsed as
+///
+/// Syntax that doesn't exist in a given version is still parsed,
+/// but it's reported as an error (`DiagnosticMessage::UnsupportedInRubyVersion`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RubyVersion {
+    /// Ruby 2.7
+    Ruby27,
+
+    /// Ruby 3.0, this is the default version
+    Ruby30,
+}
+
+impl RubyVersion {
+    /// The latest supported version
+    pub const LATEST: Self = Self::Ruby30;
+}
+
+impl Default for RubyVersion {
+    fn default() -> Self {
+        Self::LATEST
+    }
+}
+
+impl std::fmt::Display for RubyVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Ruby27 => "2.7",
+            Self::Ruby30 => "3.0",
+        })
+    }
+}
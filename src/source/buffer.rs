@@ -1,13 +1,29 @@
 use crate::maybe_byte::*;
 use crate::source::SourceLine;
-use crate::source::{decode_input, CustomDecoder, InputError};
+use crate::source::{decode_input, CustomDecoder, InputError, PositionMap};
 use std::convert::TryFrom;
 
 #[derive(Debug, Default)]
 pub struct Input {
     pub name: String,
+
+    /// Bytes of the (decoded) source.
+    ///
+    /// Indexes in `bytes` are local to the source, while locations (`Range`)
+    /// include `ParserOptions::byte_offset`, so use `Range::source` or subtract
+    /// `byte_offset()` instead of indexing `bytes` with a location directly
     pub bytes: Vec<u8>,
+
+    /// Lines of the source, `start` and `end` are indexes in `bytes`
+    /// (without `byte_offset()`), the first line is `lines[0]`
+    /// regardless of `ParserOptions::first_line` (see `line_offset()`)
     pub lines: Vec<SourceLine>,
+
+    pub(crate) original_bytes: Option<Vec<u8>>,
+    pub(crate) position_map: Option<PositionMap>,
+
+    pub(crate) byte_offset: usize,
+    pub(crate) line_offset: usize,
 }
 
 impl Input {
@@ -48,6 +64,7 @@ impl Input {
     }
 
     pub(crate) fn substr_at(&self, start: usize, end: usize) -> Option<&[u8]> {
+        let (start, end) = (self.local_pos(start)?, self.local_pos(end)?);
         if start <= end && end <= self.bytes.len() {
             Some(&self.bytes[start..end])
         } else {
@@ -55,22 +72,40 @@ impl Input {
         }
     }
 
-    pub fn line_col_for_pos(&self, mut pos: usize) -> Option<(usize, usize)> {
-        if pos == self.len() {
-            // EOF loc
-            let last_line = self.lines.last()?;
-            return Some((self.lines.len() - 1, last_line.len()));
+    /// Returns 0-based line of a given byte offset
+    /// (`pos == len()` is a valid EOF position on the last line).
+    ///
+    /// Uses a binary search over line starts, so it's O(log n)
+    pub fn line_for_pos(&self, pos: usize) -> Option<usize> {
+        let pos = self.local_pos(pos)?;
+        if pos > self.len() {
+            return None;
         }
+        let line = match self.lines.binary_search_by(|line| line.start.cmp(&pos)) {
+            Ok(line) => line,
+            Err(line) => line.checked_sub(1)?,
+        };
+        Some(line + self.line_offset)
+    }
 
-        for (lineno, line) in self.lines.iter().enumerate() {
-            if line.len() > pos {
-                return Some((lineno, pos));
-            } else {
-                pos -= line.len()
-            }
-        }
+    /// Returns 0-based line and byte column of a given byte offset
+    pub fn line_col_for_pos(&self, pos: usize) -> Option<(usize, usize)> {
+        let line = self.line_for_pos(pos)?;
+        Some((line, pos - self.source_line(line)?.start))
+    }
 
-        None
+    /// Returns byte offset of a given 0-based line and byte column,
+    /// an inverse of `line_col_for_pos`.
+    ///
+    /// Returns `None` if there's no such line or if the column is after the end of the line
+    /// (the last valid column points to `\n` or to EOF)
+    pub fn pos_for_line_col(&self, line: usize, col: usize) -> Option<usize> {
+        let line = self.source_line(line)?;
+        if line.start + col <= line.line_end() {
+            Some(line.start + col)
+        } else {
+            None
+        }
     }
 
     pub fn len(&self) -> usize {
@@ -81,6 +116,95 @@ impl Input {
         self.bytes.is_empty()
     }
 
+    /// Returns bytes of the source before it was transcoded to UTF-8
+    /// by a decoder (or `bytes` if no decoding was needed)
+    pub fn original_bytes(&self) -> &[u8] {
+        self.original_bytes.as_ref().unwrap_or(&self.bytes)
+    }
+
+    /// Returns `true` if the source has been transcoded to UTF-8
+    /// because of the `# encoding: ...` magic comment
+    pub fn is_transcoded(&self) -> bool {
+        self.original_bytes.is_some()
+    }
+
+    /// Converts an offset in `bytes` to an offset in `original_bytes`.
+    ///
+    /// Offsets inside of a multibyte character
+    /// are rounded down to the beginning of the character.
+    pub fn original_pos(&self, pos: usize) -> usize {
+        match &self.position_map {
+            Some(map) => self.map_local_pos(pos, |pos| map.to_original(pos, false)),
+            None => pos,
+        }
+    }
+
+    /// Converts an offset in `original_bytes` to an offset in `bytes`.
+    ///
+    /// Offsets inside of a multibyte character
+    /// are rounded down to the beginning of the character.
+    pub fn decoded_pos(&self, original_pos: usize) -> usize {
+        match &self.position_map {
+            Some(map) => self.map_local_pos(original_pos, |pos| map.to_decoded(pos, false)),
+            None => original_pos,
+        }
+    }
+
+    pub(crate) fn original_range(&self, begin: usize, end: usize) -> (usize, usize) {
+        match &self.position_map {
+            Some(map) => (
+                self.map_local_pos(begin, |pos| map.to_original(pos, false)),
+                self.map_local_pos(end, |pos| map.to_original(pos, true)),
+            ),
+            None => (begin, end),
+        }
+    }
+
+    pub(crate) fn decoded_range(&self, begin: usize, end: usize) -> (usize, usize) {
+        match &self.position_map {
+            Some(map) => (
+                self.map_local_pos(begin, |pos| map.to_decoded(pos, false)),
+                self.map_local_pos(end, |pos| map.to_decoded(pos, true)),
+            ),
+            None => (begin, end),
+        }
+    }
+
+    /// Returns byte offset of the source in the enclosing document
+    /// (see `ParserOptions::byte_offset`), it's added to all locations
+    pub fn byte_offset(&self) -> usize {
+        self.byte_offset
+    }
+
+    /// Returns 0-based line of the enclosing document
+    /// where the source starts (see `ParserOptions::first_line`)
+    pub fn line_offset(&self) -> usize {
+        self.line_offset
+    }
+
+    /// Returns a 0-based line of the enclosing document,
+    /// `start` and `end` of the returned line are locations too
+    pub(crate) fn source_line(&self, line: usize) -> Option<SourceLine> {
+        let line = self.lines.get(line.checked_sub(self.line_offset)?)?;
+        Some(SourceLine {
+            start: line.start + self.byte_offset,
+            end: line.end + self.byte_offset,
+            ends_with_eof: line.ends_with_eof,
+        })
+    }
+
+    // Converts a location to an index in `bytes`
+    fn local_pos(&self, pos: usize) -> Option<usize> {
+        pos.checked_sub(self.byte_offset)
+    }
+
+    fn map_local_pos<F: Fn(usize) -> usize>(&self, pos: usize, f: F) -> usize {
+        match self.local_pos(pos) {
+            Some(local_pos) => f(local_pos) + self.byte_offset,
+            None => pos,
+        }
+    }
+
     // pub fn take_bytes
 }
 
@@ -91,6 +215,10 @@ impl Clone for Input {
             name: self.name.clone(),
             bytes: self.bytes.clone(),
             lines: self.lines.clone(),
+            original_bytes: self.original_bytes.clone(),
+            position_map: self.position_map.clone(),
+            byte_offset: self.byte_offset,
+            line_offset: self.line_offset,
         }
     }
 }
@@ -413,8 +541,27 @@ impl Buffer {
     }
 
     pub(crate) fn set_encoding(&mut self, encoding: &str) -> Result<(), InputError> {
-        let new_input = decode_input(&self.input.bytes, encoding, &self.decoder)?;
-        self.input.set_bytes(new_input);
+        // always decode the source as it was given,
+        // even if it has been transcoded by a previous magic comment
+        let original = self.input.original_bytes();
+        let decoded = decode_input(original, encoding, &self.decoder)?;
+        let position_map = if decoded == original {
+            None
+        } else {
+            Some(PositionMap::new(original, &decoded, |run| {
+                decode_input(run, encoding, &self.decoder).ok()
+            }))
+        };
+
+        let original = match self.input.original_bytes.take() {
+            Some(original) => original,
+            None => std::mem::take(&mut self.input.bytes),
+        };
+        if position_map.is_some() {
+            self.input.original_bytes = Some(original);
+        }
+        self.input.position_map = position_map;
+        self.input.set_bytes(decoded);
         Ok(())
     }
 }
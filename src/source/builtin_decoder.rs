@@ -0,0 +1,132 @@
+use crate::source::decoder::decode_us_ascii;
+use crate::source::{CustomDecoder, InputError, RecognizedEncoding};
+use encoding_rs::Encoding;
+
+fn encoding_for(encoding: &RecognizedEncoding) -> Option<&'static Encoding> {
+    let encoding = match encoding {
+        RecognizedEncoding::EUC_JP | RecognizedEncoding::EucJP_ms | RecognizedEncoding::CP51932 => {
+            encoding_rs::EUC_JP
+        }
+        RecognizedEncoding::Shift_JIS | RecognizedEncoding::Windows_31J => encoding_rs::SHIFT_JIS,
+        RecognizedEncoding::Big5 | RecognizedEncoding::Big5_HKSCS | RecognizedEncoding::CP950 => {
+            encoding_rs::BIG5
+        }
+        RecognizedEncoding::EUC_KR | RecognizedEncoding::CP949 => encoding_rs::EUC_KR,
+        RecognizedEncoding::GB18030 => encoding_rs::GB18030,
+        RecognizedEncoding::GBK | RecognizedEncoding::GB2312 => encoding_rs::GBK,
+        RecognizedEncoding::ISO_8859_2 => encoding_rs::ISO_8859_2,
+        RecognizedEncoding::ISO_8859_3 => encoding_rs::ISO_8859_3,
+        RecognizedEncoding::ISO_8859_4 => encoding_rs::ISO_8859_4,
+        RecognizedEncoding::ISO_8859_5 => encoding_rs::ISO_8859_5,
+        RecognizedEncoding::ISO_8859_6 => encoding_rs::ISO_8859_6,
+        RecognizedEncoding::ISO_8859_7 => encoding_rs::ISO_8859_7,
+        RecognizedEncoding::ISO_8859_8 => encoding_rs::ISO_8859_8,
+        RecognizedEncoding::ISO_8859_10 => encoding_rs::ISO_8859_10,
+        RecognizedEncoding::ISO_8859_13 => encoding_rs::ISO_8859_13,
+        RecognizedEncoding::ISO_8859_14 => encoding_rs::ISO_8859_14,
+        RecognizedEncoding::ISO_8859_15 => encoding_rs::ISO_8859_15,
+        RecognizedEncoding::ISO_8859_16 => encoding_rs::ISO_8859_16,
+        RecognizedEncoding::KOI8_R => encoding_rs::KOI8_R,
+        RecognizedEncoding::KOI8_U => encoding_rs::KOI8_U,
+        RecognizedEncoding::IBM866 => encoding_rs::IBM866,
+        RecognizedEncoding::MacRoman => encoding_rs::MACINTOSH,
+        RecognizedEncoding::MacCyrillic => encoding_rs::X_MAC_CYRILLIC,
+        RecognizedEncoding::Windows_874 => encoding_rs::WINDOWS_874,
+        RecognizedEncoding::Windows_1250 => encoding_rs::WINDOWS_1250,
+        RecognizedEncoding::Windows_1251 => encoding_rs::WINDOWS_1251,
+        RecognizedEncoding::Windows_1252 => encoding_rs::WINDOWS_1252,
+        RecognizedEncoding::Windows_1253 => encoding_rs::WINDOWS_1253,
+        RecognizedEncoding::Windows_1254 => encoding_rs::WINDOWS_1254,
+        RecognizedEncoding::Windows_1255 => encoding_rs::WINDOWS_1255,
+        RecognizedEncoding::Windows_1256 => encoding_rs::WINDOWS_1256,
+        RecognizedEncoding::Windows_1257 => encoding_rs::WINDOWS_1257,
+        RecognizedEncoding::Windows_1258 => encoding_rs::WINDOWS_1258,
+        _ => return None,
+    };
+    Some(encoding)
+}
+
+fn decode_iso_8859_1(input: &[u8]) -> Vec<u8> {
+    input
+        .iter()
+        .map(|byte| *byte as char)
+        .collect::<String>()
+        .into_bytes()
+}
+
+// ISO-8859-9 is ISO-8859-1 with 6 Icelandic letters replaced by Turkish ones
+fn decode_iso_8859_9(input: &[u8]) -> Vec<u8> {
+    input
+        .iter()
+        .map(|byte| match byte {
+            0xD0 => '\u{11E}',
+            0xDD => '\u{130}',
+            0xDE => '\u{15E}',
+            0xF0 => '\u{11F}',
+            0xFD => '\u{131}',
+            0xFE => '\u{15F}',
+            _ => *byte as char,
+        })
+        .collect::<String>()
+        .into_bytes()
+}
+
+// TIS-620 maps 0xA1..=0xDA and 0xDF..=0xFB to the Thai block (U+0E01..=U+0E5B),
+// ISO-8859-11 additionally has NBSP at 0xA0 and C1 controls at 0x80..=0x9F
+fn decode_iso_8859_11(encoding: RecognizedEncoding, input: &[u8]) -> Result<Vec<u8>, InputError> {
+    let mut output = String::with_capacity(input.len());
+    for byte in input.iter() {
+        let c = match byte {
+            0x00..=0x7F => *byte as char,
+            0x80..=0xA0 if encoding == RecognizedEncoding::ISO_8859_11 => *byte as char,
+            0xA1..=0xDA | 0xDF..=0xFB => {
+                std::char::from_u32(0x0E00 + (*byte as u32 - 0xA0)).unwrap()
+            }
+            _ => {
+                return Err(InputError::DecodingError(format!(
+                    "invalid byte sequence in {:?}: \\x{:02X}",
+                    encoding, byte
+                )))
+            }
+        };
+        output.push(c);
+    }
+    Ok(output.into_bytes())
+}
+
+/// Decodes `input` from a given `encoding` to UTF-8
+/// using a set of built-in decoders.
+///
+/// Supports US-ASCII, ISO-8859-*, Windows-125*, Windows-874, TIS-620,
+/// KOI8-*, IBM866, MacRoman, MacCyrillic
+/// and most common CJK encodings (Shift_JIS, EUC-JP, Big5, GBK, GB18030, EUC-KR).
+pub fn decode_builtin(encoding: RecognizedEncoding, input: &[u8]) -> Result<Vec<u8>, InputError> {
+    match encoding {
+        RecognizedEncoding::US_ASCII => return decode_us_ascii(input),
+        RecognizedEncoding::ISO_8859_1 => return Ok(decode_iso_8859_1(input)),
+        RecognizedEncoding::ISO_8859_9 => return Ok(decode_iso_8859_9(input)),
+        RecognizedEncoding::ISO_8859_11 | RecognizedEncoding::TIS_620 => {
+            return decode_iso_8859_11(encoding, input)
+        }
+        _ => {}
+    }
+
+    let decoder = encoding_for(&encoding).ok_or(InputError::NoDecoder(encoding))?;
+    let (output, had_errors) = decoder.decode_without_bom_handling(input);
+    if had_errors {
+        return Err(InputError::DecodingError(format!(
+            "invalid byte sequence in {}",
+            decoder.name()
+        )));
+    }
+    Ok(output.into_owned().into_bytes())
+}
+
+impl CustomDecoder {
+    /// Returns a decoder that uses built-in decoders (see `decode_builtin`)
+    pub fn builtin() -> Self {
+        Self {
+            f: Some(Box::new(decode_builtin)),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::source::buffer::Input;
+
+/// Conversions between byte offsets and columns
+/// counted in characters (for terminals and editors)
+/// or in UTF-16 code units (for LSP clients).
+///
+/// Lines and columns are 0-based, invalid UTF-8 bytes are counted as one character each.
+impl Input {
+    /// Returns line and column (in characters) of a given byte offset
+    pub fn line_char_col_for_pos(&self, pos: usize) -> Option<(usize, usize)> {
+        self.line_col_with(pos, |_| 1)
+    }
+
+    /// Returns line and column (in UTF-16 code units) of a given byte offset
+    pub fn line_utf16_col_for_pos(&self, pos: usize) -> Option<(usize, usize)> {
+        self.line_col_with(pos, char::len_utf16)
+    }
+
+    /// Returns byte offset of a given line and column (in characters).
+    ///
+    /// Columns after the end of the line point to the end of the line (excluding `\n`).
+    pub fn pos_for_line_char_col(&self, line: usize, col: usize) -> Option<usize> {
+        self.pos_for_line_col_with(line, col, |_| 1)
+    }
+
+    /// Returns byte offset of a given line and column (in UTF-16 code units).
+    ///
+    /// Columns after the end of the line point to the end of the line (excluding `\n`),
+    /// columns in the middle of a surrogate pair point to the next character.
+    pub fn pos_for_line_utf16_col(&self, line: usize, col: usize) -> Option<usize> {
+        self.pos_for_line_col_with(line, col, char::len_utf16)
+    }
+
+    /// Returns number of characters in `bytes[begin..end]`
+    pub fn char_count(&self, begin: usize, end: usize) -> usize {
+        self.substr_at(begin, end)
+            .map(|bytes| CharIndices::new(bytes).count())
+            .unwrap_or(0)
+    }
+
+    /// Returns number of UTF-16 code units in `bytes[begin..end]`
+    pub fn utf16_count(&self, begin: usize, end: usize) -> usize {
+        self.substr_at(begin, end)
+            .map(|bytes| CharIndices::new(bytes).map(|(_, c)| c.len_utf16()).sum())
+            .unwrap_or(0)
+    }
+
+    fn line_col_with(&self, pos: usize, width: fn(char) -> usize) -> Option<(usize, usize)> {
+        let (line, byte_col) = self.line_col_for_pos(pos)?;
+        let line_start = self.source_line(line)?.start;
+        let col = CharIndices::new(self.substr_at(line_start, line_start + byte_col)?)
+            .map(|(_, c)| width(c))
+            .sum();
+        Some((line, col))
+    }
+
+    fn pos_for_line_col_with(
+        &self,
+        line: usize,
+        col: usize,
+        width: fn(char) -> usize,
+    ) -> Option<usize> {
+        let line = self.source_line(line)?;
+        let line_end = line.line_end();
+
+        let mut current_col = 0;
+        for (offset, c) in CharIndices::new(self.substr_at(line.start, line_end)?) {
+            if current_col >= col {
+                return Some(line.start + offset);
+            }
+            current_col += width(c);
+        }
+        Some(line_end)
+    }
+}
+
+/// Iterator over characters of a UTF-8 byte string and their byte offsets,
+/// every invalid byte is returned as U+FFFD
+struct CharIndices<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> CharIndices<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+}
+
+impl Iterator for CharIndices<'_> {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.bytes.get(self.pos..).filter(|rest| !rest.is_empty())?;
+        let len = match rest[0] {
+            0x00..=0x7F => 1,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 1,
+        };
+        let c = rest
+            .get(..len)
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+            .and_then(|s| s.chars().next());
+
+        let offset = self.pos;
+        match c {
+            Some(c) => {
+                self.pos += len;
+                Some((offset, c))
+            }
+            None => {
+                self.pos += 1;
+                Some((offset, '\u{FFFD}'))
+            }
+        }
+    }
+}
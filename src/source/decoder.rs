@@ -53,10 +53,26 @@ pub fn decode_input(
             let enc = RecognizedEncoding::parse(&enc)
                 .ok_or(InputError::UnsupportdEncoding(enc.to_owned()))?;
             if let Some(f) = &decoder.f {
-                f(enc, input)
-            } else {
-                Err(InputError::NoDecoder(enc))
+                return f(enc, input);
+            }
+            match enc {
+                RecognizedEncoding::US_ASCII => decode_us_ascii(input),
+                #[cfg(feature = "builtin-decoders")]
+                _ => crate::source::decode_builtin(enc, input),
+                #[cfg(not(feature = "builtin-decoders"))]
+                _ => Err(InputError::NoDecoder(enc)),
             }
         }
     }
 }
+
+/// US-ASCII is a subset of UTF-8, so it only needs to be validated
+pub(crate) fn decode_us_ascii(input: &[u8]) -> Result<Vec<u8>, InputError> {
+    match input.iter().position(|byte| !byte.is_ascii()) {
+        None => Ok(input.to_vec()),
+        Some(pos) => Err(InputError::DecodingError(format!(
+            "invalid byte sequence in US-ASCII at {}: \\x{:02X}",
+            pos, input[pos]
+        ))),
+    }
+}
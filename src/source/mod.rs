@@ -1,16 +1,23 @@
 pub mod buffer;
+#[cfg(feature = "builtin-decoders")]
+mod builtin_decoder;
+mod columns;
 mod comment;
 mod decoder;
 mod magic_comment;
+mod position_map;
 mod range;
 mod recognized_encoding;
 mod source_line;
 
+#[cfg(feature = "builtin-decoders")]
+pub use builtin_decoder::decode_builtin;
 pub use comment::{Comment, CommentType};
 pub(crate) use decoder::decode_input;
 pub use decoder::CustomDecoder;
 pub use decoder::InputError;
 pub use magic_comment::{MagicComment, MagicCommentKind};
+pub(crate) use position_map::PositionMap;
 pub use range::Range;
 pub use recognized_encoding::RecognizedEncoding;
 pub(crate) use source_line::SourceLine;
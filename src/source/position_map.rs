@@ -0,0 +1,197 @@
+/// Maps offsets in the decoded (UTF-8) source to offsets in the original bytes and back.
+///
+/// All encodings supported by Ruby as source encodings are ASCII-compatible,
+/// so runs of ASCII characters are mapped byte-to-byte.
+/// Runs of non-ASCII characters are decoded with the same decoder
+/// and split into characters, so every character has its own entry.
+/// Only offsets inside of a single multibyte character are rounded
+/// to its boundaries.
+#[derive(Debug, Clone, Default)]
+pub struct PositionMap {
+    segments: Vec<Segment>,
+    decoded_len: usize,
+    original_len: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    decoded: usize,
+    original: usize,
+    exact: bool,
+}
+
+impl PositionMap {
+    pub(crate) fn new<F>(original: &[u8], decoded: &[u8], decode_run: F) -> Self
+    where
+        F: Fn(&[u8]) -> Option<Vec<u8>>,
+    {
+        let mut segments: Vec<Segment> = vec![];
+        let mut push = |decoded: usize, original: usize, exact: bool| match segments.last() {
+            Some(last) if last.exact && exact => {}
+            _ => segments.push(Segment {
+                decoded,
+                original,
+                exact,
+            }),
+        };
+
+        let (mut orig_pos, mut decoded_pos) = (0, 0);
+        while orig_pos < original.len() {
+            let run_end = if is_ascii_char(original, orig_pos) {
+                (orig_pos..original.len())
+                    .find(|idx| !is_ascii_char(original, *idx))
+                    .unwrap_or(original.len())
+            } else {
+                (orig_pos..original.len())
+                    .find(|idx| is_ascii_char(original, *idx))
+                    .unwrap_or(original.len())
+            };
+            let run = &original[orig_pos..run_end];
+
+            let decoded_run = if is_ascii_char(original, orig_pos) {
+                Some(run.to_vec())
+            } else {
+                decode_run(run)
+            };
+            match decoded_run {
+                Some(decoded_run)
+                    if !decoded_run.is_empty()
+                        && decoded[decoded_pos..].starts_with(&decoded_run) =>
+                {
+                    if run.is_ascii() {
+                        push(decoded_pos, orig_pos, true);
+                    } else {
+                        for (decoded_offset, orig_offset, exact) in
+                            split_run(run, &decoded_run, &decode_run)
+                        {
+                            push(decoded_pos + decoded_offset, orig_pos + orig_offset, exact);
+                        }
+                    }
+
+                    orig_pos = run_end;
+                    decoded_pos += decoded_run.len();
+                }
+                _ => {
+                    // the decoder is not stateless (or not a decoder at all),
+                    // so the rest of the input can't be aligned
+                    push(decoded_pos, orig_pos, false);
+                    decoded_pos = decoded.len();
+                    break;
+                }
+            }
+        }
+        if decoded_pos < decoded.len() {
+            push(decoded_pos, orig_pos, false);
+        }
+
+        Self {
+            segments,
+            decoded_len: decoded.len(),
+            original_len: original.len(),
+        }
+    }
+
+    /// Converts an offset in decoded bytes to an offset in original bytes
+    pub(crate) fn to_original(&self, pos: usize, round_up: bool) -> usize {
+        self.translate(
+            pos,
+            round_up,
+            |s| (s.decoded, s.original),
+            |m| (m.decoded_len, m.original_len),
+        )
+    }
+
+    /// Converts an offset in original bytes to an offset in decoded bytes
+    pub(crate) fn to_decoded(&self, pos: usize, round_up: bool) -> usize {
+        self.translate(
+            pos,
+            round_up,
+            |s| (s.original, s.decoded),
+            |m| (m.original_len, m.decoded_len),
+        )
+    }
+
+    fn translate(
+        &self,
+        pos: usize,
+        round_up: bool,
+        offsets: fn(&Segment) -> (usize, usize),
+        lens: fn(&Self) -> (usize, usize),
+    ) -> usize {
+        let (from_len, to_len) = lens(self);
+        if pos >= from_len {
+            return to_len;
+        }
+
+        let idx = match self
+            .segments
+            .binary_search_by(|segment| offsets(segment).0.cmp(&pos))
+        {
+            Ok(idx) => idx,
+            Err(idx) => idx - 1,
+        };
+        let (from, to) = offsets(&self.segments[idx]);
+        let to_end = self
+            .segments
+            .get(idx + 1)
+            .map(|segment| offsets(segment).1)
+            .unwrap_or(to_len);
+
+        if self.segments[idx].exact {
+            std::cmp::min(to + (pos - from), to_end)
+        } else if pos == from || !round_up {
+            to
+        } else {
+            to_end
+        }
+    }
+}
+
+// The longest character of Ruby source encodings (GB18030, EUC-TW) takes 4 bytes
+const MAX_CHAR_LEN: usize = 4;
+
+// Splits a decoded run of non-ASCII characters into characters,
+// returns offsets of every character in the decoded and the original run.
+//
+// Every character is the shortest prefix of the rest of the run that
+// is decoded to the same bytes as the beginning of the rest of `decoded_run`,
+// ASCII characters (like a closing quote after a non-ASCII string) are exact.
+// If some character can't be found this way the rest of the run is a single entry.
+fn split_run<F>(run: &[u8], decoded_run: &[u8], decode_run: F) -> Vec<(usize, usize, bool)>
+where
+    F: Fn(&[u8]) -> Option<Vec<u8>>,
+{
+    let mut chars = vec![];
+    let (mut orig_pos, mut decoded_pos) = (0, 0);
+    while orig_pos < run.len() {
+        let max_len = std::cmp::min(MAX_CHAR_LEN, run.len() - orig_pos);
+        let found = (1..=max_len).find_map(|len| {
+            let decoded_char = decode_run(&run[orig_pos..orig_pos + len])?;
+            if !decoded_char.is_empty() && decoded_run[decoded_pos..].starts_with(&decoded_char) {
+                Some((len, decoded_char))
+            } else {
+                None
+            }
+        });
+        match found {
+            Some((len, decoded_char)) => {
+                let exact = decoded_char == run[orig_pos..orig_pos + len];
+                chars.push((decoded_pos, orig_pos, exact));
+                orig_pos += len;
+                decoded_pos += decoded_char.len();
+            }
+            None => {
+                chars.push((decoded_pos, orig_pos, false));
+                break;
+            }
+        }
+    }
+    chars
+}
+
+// In ASCII-compatible encodings an ASCII byte is always a character
+// unless it's a trailing byte of a multibyte character (like 0x5C in Shift_JIS),
+// and trailing bytes always follow a non-ASCII byte
+fn is_ascii_char(bytes: &[u8], idx: usize) -> bool {
+    bytes[idx].is_ascii() && (idx == 0 || bytes[idx - 1].is_ascii())
+}
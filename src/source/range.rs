@@ -1,5 +1,4 @@
 use crate::source::buffer::Input;
-use std::convert::TryInto;
 
 #[derive(Clone)]
 pub struct Range {
@@ -49,7 +48,13 @@ impl Range {
     }
 
     pub fn size(&self) -> usize {
-        self.end_pos - self.begin_pos
+        debug_assert!(
+            self.begin_pos <= self.end_pos,
+            "begin_pos = {}, end_pos = {}",
+            self.begin_pos,
+            self.end_pos
+        );
+        self.end_pos.saturating_sub(self.begin_pos)
     }
 
     pub fn to_range(&self) -> std::ops::Range<usize> {
@@ -69,25 +74,11 @@ impl Range {
     }
 
     pub fn adjust_begin(&self, d: i32) -> Self {
-        let begin_pos: i32 = self
-            .begin_pos
-            .try_into()
-            .expect("failed to convert location to i32 (is it too big?)");
-        let begin_pos: usize = (begin_pos + d)
-            .try_into()
-            .expect("failed to convert location to usize (is it negative?)");
-        Self::new(begin_pos, self.end_pos)
+        Self::new(adjust_pos(self.begin_pos, d), self.end_pos)
     }
 
     pub fn adjust_end(&self, d: i32) -> Self {
-        let end_pos: i32 = self
-            .end_pos
-            .try_into()
-            .expect("failed to convert location to i32 (is it too big?)");
-        let end_pos: usize = (end_pos + d)
-            .try_into()
-            .expect("failed to convert location to usize (is it negative?)");
-        Self::new(self.begin_pos, end_pos)
+        Self::new(self.begin_pos, adjust_pos(self.end_pos, d))
     }
 
     pub fn resize(&self, new_size: usize) -> Self {
@@ -120,13 +111,62 @@ impl Range {
         input.line_col_for_pos(self.end_pos)
     }
 
+    /// Returns first and last 0-based lines of the range
+    /// (an empty range is located on a single line)
+    pub fn lines(&self, input: &Input) -> Option<(usize, usize)> {
+        let first = input.line_for_pos(self.begin_pos)?;
+        let last = if self.end_pos > self.begin_pos {
+            input.line_for_pos(self.end_pos - 1)?
+        } else {
+            first
+        };
+        Some((first, last))
+    }
+
+    /// Returns line and column (in characters) of the beginning of the range
+    pub fn begin_line_char_col(&self, input: &Input) -> Option<(usize, usize)> {
+        input.line_char_col_for_pos(self.begin_pos)
+    }
+
+    /// Returns line and column (in characters) of the end of the range
+    pub fn end_line_char_col(&self, input: &Input) -> Option<(usize, usize)> {
+        input.line_char_col_for_pos(self.end_pos)
+    }
+
+    /// Returns line and column (in UTF-16 code units) of the beginning of the range
+    pub fn begin_line_utf16_col(&self, input: &Input) -> Option<(usize, usize)> {
+        input.line_utf16_col_for_pos(self.begin_pos)
+    }
+
+    /// Returns line and column (in UTF-16 code units) of the end of the range
+    pub fn end_line_utf16_col(&self, input: &Input) -> Option<(usize, usize)> {
+        input.line_utf16_col_for_pos(self.end_pos)
+    }
+
     pub fn expand_to_line(&self, input: &Input) -> Option<(usize, Self)> {
         let (begin_line, _) = self.begin_line_col(input)?;
         let line_no = begin_line;
-        let line = &input.lines[line_no];
+        let line = input.source_line(line_no)?;
         Some((line_no, self.with(line.start, line.line_end())))
     }
 
+    /// Converts a range of decoded bytes to a range of original bytes
+    /// (see `Input::original_bytes`).
+    ///
+    /// If the range starts or ends inside of a run of non-ASCII characters
+    /// it's expanded to include the whole run.
+    pub fn to_original(&self, input: &Input) -> Self {
+        let (begin_pos, end_pos) = input.original_range(self.begin_pos, self.end_pos);
+        Self::new(begin_pos, end_pos)
+    }
+
+    /// Converts a range of original bytes to a range of decoded bytes,
+    /// an inverse of `to_original`
+    pub fn from_original(&self, input: &Input) -> Self {
+        let (begin_pos, end_pos) = input.decoded_range(self.begin_pos, self.end_pos);
+        Self::new(begin_pos, end_pos)
+    }
+
     pub fn source(&self, input: &Input) -> Option<String> {
         let bytes = input.substr_at(self.begin_pos, self.end_pos)?;
         Some(String::from_utf8_lossy(bytes).into_owned())
@@ -147,3 +187,14 @@ impl std::fmt::Debug for Range {
         f.write_str(&format!("{}...{}", self.begin_pos, self.end_pos))
     }
 }
+
+// Shifts `pos` by `d`, shifting out of `0..=usize::MAX` is a bug of the caller
+fn adjust_pos(pos: usize, d: i32) -> usize {
+    let adjusted = if d < 0 {
+        pos.checked_sub(d.unsigned_abs() as usize)
+    } else {
+        pos.checked_add(d as usize)
+    };
+    debug_assert!(adjusted.is_some(), "can't shift position {} by {}", pos, d);
+    adjusted.unwrap_or(if d < 0 { 0 } else { usize::MAX })
+}
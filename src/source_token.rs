@@ -0,0 +1,102 @@
+use crate::source::buffer::Input;
+use crate::source::Range;
+use crate::{Token, TokenValue};
+
+/// Value of the `SourceToken`
+#[derive(Debug, Clone)]
+pub enum SourceTokenValue {
+    /// The value is a part of `Input.bytes` in a given range
+    /// (identifiers, keywords, operators, string content without escapes, ...)
+    Range(Range),
+
+    /// The value is different from the source code
+    /// (e.g. string content with escape sequences)
+    Owned(TokenValue),
+}
+
+/// A token that doesn't own its value if it can be taken from the input.
+///
+/// Recorded into `ParserResult.source_tokens`
+/// if `ParserOptions.record_source_tokens` is `true`
+#[derive(Debug, Clone)]
+pub struct SourceToken {
+    /// Numeric type of the token, see `token_name`
+    pub token_type: i32,
+
+    /// Value of the token
+    pub value: SourceTokenValue,
+
+    /// Location of the token
+    pub loc: Range,
+}
+
+impl SourceToken {
+    pub(crate) fn new(token: &Token, input: &Input) -> Self {
+        let loc = Range::new(token.loc.begin, token.loc.end);
+        let source = input.substr_at(loc.begin_pos, loc.end_pos);
+        let value = if source == Some(token.token_value.as_bytes()) {
+            SourceTokenValue::Range(loc.clone())
+        } else {
+            SourceTokenValue::Owned(token.token_value.clone())
+        };
+        Self {
+            token_type: token.token_type,
+            value,
+            loc,
+        }
+    }
+
+    /// Returns bytes of the value,
+    /// `input` must be the input that was used for parsing (i.e. `ParserResult.input`)
+    pub fn value_bytes<'a>(&'a self, input: &'a Input) -> Option<&'a [u8]> {
+        match &self.value {
+            SourceTokenValue::Range(range) => input.substr_at(range.begin_pos, range.end_pos),
+            SourceTokenValue::Owned(value) => Some(value.as_bytes()),
+        }
+    }
+
+    /// Constructs an owned `Token`
+    pub fn to_token(&self, input: &Input) -> Option<Token> {
+        let token_value = match &self.value {
+            SourceTokenValue::Range(range) => {
+                token_value(input.substr_at(range.begin_pos, range.end_pos)?)
+            }
+            SourceTokenValue::Owned(value) => value.clone(),
+        };
+        Some(Token {
+            token_type: self.token_type,
+            token_value,
+            loc: crate::Loc {
+                begin: self.loc.begin_pos,
+                end: self.loc.end_pos,
+            },
+        })
+    }
+
+    // Calls `f` with every location of the token.
+    // `input` is the input that ranges currently refer to,
+    // a value that doesn't keep its size after `f` stops being
+    // a part of the input and becomes owned
+    pub(crate) fn map_ranges<F: FnMut(&mut Range)>(&mut self, input: &Input, f: &mut F) {
+        f(&mut self.loc);
+        if let SourceTokenValue::Range(range) = &self.value {
+            let mut mapped = range.clone();
+            f(&mut mapped);
+            self.value = if mapped.size() == range.size() {
+                SourceTokenValue::Range(mapped)
+            } else {
+                let bytes = input
+                    .substr_at(range.begin_pos, range.end_pos)
+                    .unwrap_or_default();
+                SourceTokenValue::Owned(token_value(bytes))
+            };
+        }
+    }
+}
+
+fn token_value(bytes: &[u8]) -> TokenValue {
+    match String::from_utf8(bytes.to_vec()) {
+        Ok(s) => TokenValue::String(s),
+        Err(err) => TokenValue::InvalidString(err.into_bytes()),
+    }
+}
@@ -1,23 +1,42 @@
-use std::cell::RefCell;
+use crate::{Interner, Symbol};
+use std::cell::{Ref, RefCell};
 use std::collections::HashSet;
 use std::rc::Rc;
 
 #[derive(Debug, Clone, Default)]
 pub struct StaticEnvironment {
-    variables: Rc<RefCell<HashSet<String>>>,
-    stack: Rc<RefCell<Vec<HashSet<String>>>>,
+    variables: Rc<RefCell<HashSet<Symbol>>>,
+    stack: Rc<RefCell<Vec<HashSet<Symbol>>>>,
+    interner: Rc<RefCell<Interner>>,
 }
 
 const FORWARD_ARGS: &str = "FORWARD_ARGS";
 
 impl StaticEnvironment {
     pub fn new() -> Self {
+        Self::with_interner(Interner::new())
+    }
+
+    /// Constructs a static environment that stores names in a given `interner`
+    pub fn with_interner(interner: Interner) -> Self {
         Self {
             variables: Rc::new(RefCell::new(HashSet::new())),
             stack: Rc::new(RefCell::new(vec![])),
+            interner: Rc::new(RefCell::new(interner)),
         }
     }
 
+    /// Returns an interner that is used to store names of variables
+    pub fn interner(&self) -> Ref<'_, Interner> {
+        self.interner.borrow()
+    }
+
+    // Moves the interner out (to return it in `ParserResult`),
+    // leaves an empty one in its place
+    pub(crate) fn take_interner(&self) -> Interner {
+        std::mem::take(&mut *self.interner.borrow_mut())
+    }
+
     #[allow(dead_code)]
     pub(crate) fn reset(&self) {
         self.variables.borrow_mut().clear();
@@ -44,11 +63,15 @@ impl StaticEnvironment {
     }
 
     pub fn declare(&self, name: &str) {
-        self.variables.borrow_mut().insert(name.to_owned());
+        let symbol = self.interner.borrow_mut().intern(name);
+        self.variables.borrow_mut().insert(symbol);
     }
 
     pub fn is_declared(&self, name: &str) -> bool {
-        self.variables.borrow().get(name).is_some()
+        match self.interner.borrow().lookup(name) {
+            Some(symbol) => self.variables.borrow().contains(&symbol),
+            None => false,
+        }
     }
 
     pub(crate) fn declare_forward_args(&self) {
@@ -129,6 +129,63 @@ impl PatternItem {
     }
 }
 
+impl std::fmt::Display for PatternItem {
+    /// Prints the item in the format accepted by `PatternItem::new`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Recv => "recv",
+            Self::Lhs => "lhs",
+            Self::Rhs => "rhs",
+            Self::Value => "value",
+            Self::Call => "call",
+            Self::Body => "body",
+            Self::Args => "args",
+            Self::Expr => "expr",
+            Self::ElseBody => "else_body",
+            Self::Scope => "scope",
+            Self::Name => "name",
+            Self::Superclass => "superclass",
+            Self::Const => "const",
+            Self::Definee => "definee",
+            Self::Iterator => "iterator",
+            Self::Iteratee => "iteratee",
+            Self::Pattern => "pattern",
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::IfTrue => "if_true",
+            Self::IfFalse => "if_false",
+            Self::Cond => "cond",
+            Self::Default => "default",
+            Self::Ensure => "ensure",
+            Self::Guard => "guard",
+            Self::As => "as",
+            Self::Re => "re",
+            Self::Key => "key",
+            Self::ExcList => "exc_list",
+            Self::ExcVar => "exc_var",
+            Self::Match => "match",
+            Self::Else => "else",
+            Self::Var => "var",
+            Self::Options => "options",
+            Self::To => "to",
+            Self::From => "from",
+            Self::Item(n) => return write!(f, "item[{}]", n),
+            Self::Arg(n) => return write!(f, "arg[{}]", n),
+            Self::Element(n) => return write!(f, "element[{}]", n),
+            Self::Stmt(n) => return write!(f, "stmt[{}]", n),
+            Self::WhenBody(n) => return write!(f, "when_body[{}]", n),
+            Self::InBody(n) => return write!(f, "in_body[{}]", n),
+            Self::Part(n) => return write!(f, "part[{}]", n),
+            Self::Index(n) => return write!(f, "index[{}]", n),
+            Self::Pair(n) => return write!(f, "pair[{}]", n),
+            Self::RescueBody(n) => return write!(f, "rescue_body[{}]", n),
+            Self::Root => "root",
+            Self::None => "none",
+        };
+        f.write_str(name)
+    }
+}
+
 #[derive(Debug)]
 pub struct PatternError {
     pub pattern: String,
@@ -143,6 +200,8 @@ impl std::fmt::Display for PatternError {
     }
 }
 
+impl std::error::Error for PatternError {}
+
 #[derive(Debug)]
 struct Pattern {
     parts: Vec<PatternItem>,
@@ -0,0 +1,453 @@
+use crate::source::Input;
+use crate::traverse::PatternItem;
+use crate::{Node, Token};
+
+/// The innermost node at some position and the way to it from the root
+#[derive(Debug)]
+pub struct LocatedNode<'a> {
+    /// Innermost node whose `expression()` contains the position
+    pub node: &'a Node,
+
+    /// Ancestors of `node`, starting from the root
+    pub ancestors: Vec<&'a Node>,
+
+    /// Fields of ancestors on the way to `node`
+    /// (`path[i]` is the field of `ancestors[i]` that contains the next node).
+    ///
+    /// `Find::run(&path.iter().map(|item| item.to_string()).collect::<Vec<_>>(), root)`
+    /// finds the same node.
+    pub path: Vec<PatternItem>,
+}
+
+/// Finds nodes and tokens at a given position,
+/// for example under the cursor in the editor
+pub struct Locate;
+
+impl Locate {
+    /// Returns the innermost node whose `expression()` contains byte `pos`
+    /// (i.e. `begin_pos <= pos < end_pos`).
+    ///
+    /// Nodes that are located outside of their parents (like heredoc bodies) are found too.
+    pub fn node_at(root: &Node, pos: usize) -> Option<LocatedNode<'_>> {
+        let mut search = Search {
+            pos,
+            ancestors: vec![],
+            path: vec![],
+            found: None,
+        };
+        search.visit(root);
+        search.found
+    }
+
+    /// Same as `node_at`, but takes 0-based line and byte column
+    pub fn node_at_line_col<'a>(
+        root: &'a Node,
+        input: &Input,
+        line: usize,
+        col: usize,
+    ) -> Option<LocatedNode<'a>> {
+        Self::node_at(root, pos_for_line_col(input, line, col)?)
+    }
+
+    /// Returns a token that contains byte `pos`
+    pub fn token_at(tokens: &[Token], pos: usize) -> Option<&Token> {
+        // tokens of heredocs go out of order, so they can't be searched by bisection
+        tokens
+            .iter()
+            .find(|token| token.loc.begin <= pos && pos < token.loc.end)
+    }
+
+    /// Same as `token_at`, but takes 0-based line and byte column
+    pub fn token_at_line_col<'a>(
+        tokens: &'a [Token],
+        input: &Input,
+        line: usize,
+        col: usize,
+    ) -> Option<&'a Token> {
+        Self::token_at(tokens, pos_for_line_col(input, line, col)?)
+    }
+}
+
+fn pos_for_line_col(input: &Input, line: usize, col: usize) -> Option<usize> {
+    let line = input.source_line(line)?;
+    if col < line.len() {
+        Some(line.start + col)
+    } else {
+        None
+    }
+}
+
+struct Search<'a> {
+    pos: usize,
+    ancestors: Vec<&'a Node>,
+    path: Vec<PatternItem>,
+    found: Option<LocatedNode<'a>>,
+}
+
+impl<'a> Search<'a> {
+    fn visit(&mut self, node: &'a Node) {
+        let range = node.expression();
+        let is_deeper = match &self.found {
+            Some(found) => self.ancestors.len() > found.ancestors.len(),
+            None => true,
+        };
+        if is_deeper && range.begin_pos <= self.pos && self.pos < range.end_pos {
+            self.found = Some(LocatedNode {
+                node,
+                ancestors: self.ancestors.clone(),
+                path: self.path.clone(),
+            });
+        }
+
+        self.ancestors.push(node);
+        for (item, child) in children(node).0 {
+            self.path.push(item);
+            self.visit(child);
+            self.path.pop();
+        }
+        self.ancestors.pop();
+    }
+}
+
+#[derive(Default)]
+struct Children<'a>(Vec<(PatternItem, &'a Node)>);
+
+impl<'a> Children<'a> {
+    fn push_node(&mut self, item: PatternItem, node: &'a Node) {
+        self.0.push((item, node))
+    }
+
+    fn push_maybe_node(&mut self, item: PatternItem, node: &'a Option<Node>) {
+        if let Some(node) = node {
+            self.push_node(item, node)
+        }
+    }
+
+    fn push_nodes(&mut self, item: fn(usize) -> PatternItem, nodes: &'a [Node]) {
+        for (idx, node) in nodes.iter().enumerate() {
+            self.push_node(item(idx), node)
+        }
+    }
+}
+
+/// Returns children of the node with their `Find` pattern items
+fn children(node: &Node) -> Children<'_> {
+    let mut children = Children::default();
+    match node {
+        Node::Alias(inner) => {
+            children.push_node(PatternItem::To, &inner.to);
+            children.push_node(PatternItem::From, &inner.from);
+        }
+        Node::And(inner) => {
+            children.push_node(PatternItem::Lhs, &inner.lhs);
+            children.push_node(PatternItem::Rhs, &inner.rhs);
+        }
+        Node::AndAsgn(inner) => {
+            children.push_node(PatternItem::Recv, &inner.recv);
+            children.push_node(PatternItem::Value, &inner.value);
+        }
+        Node::Args(inner) => {
+            children.push_nodes(PatternItem::Arg, &inner.args);
+        }
+        Node::Array(inner) => {
+            children.push_nodes(PatternItem::Element, &inner.elements);
+        }
+        Node::ArrayPattern(inner) => {
+            children.push_nodes(PatternItem::Element, &inner.elements);
+        }
+        Node::ArrayPatternWithTail(inner) => {
+            children.push_nodes(PatternItem::Element, &inner.elements);
+        }
+        Node::Begin(inner) => {
+            children.push_nodes(PatternItem::Stmt, &inner.statements);
+        }
+        Node::Block(inner) => {
+            children.push_node(PatternItem::Call, &inner.call);
+            children.push_maybe_node(PatternItem::Args, &inner.args);
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+        }
+        Node::BlockPass(inner) => {
+            children.push_node(PatternItem::Value, &inner.value);
+        }
+        Node::Break(inner) => {
+            children.push_nodes(PatternItem::Arg, &inner.args);
+        }
+        Node::Case(inner) => {
+            children.push_maybe_node(PatternItem::Expr, &inner.expr);
+            children.push_nodes(PatternItem::WhenBody, &inner.when_bodies);
+            children.push_maybe_node(PatternItem::ElseBody, &inner.else_body);
+        }
+        Node::CaseMatch(inner) => {
+            children.push_node(PatternItem::Expr, &inner.expr);
+            children.push_nodes(PatternItem::InBody, &inner.in_bodies);
+            children.push_maybe_node(PatternItem::ElseBody, &inner.else_body);
+        }
+        Node::Casgn(inner) => {
+            children.push_maybe_node(PatternItem::Scope, &inner.scope);
+            children.push_maybe_node(PatternItem::Value, &inner.value);
+        }
+        Node::Class(inner) => {
+            children.push_node(PatternItem::Name, &inner.name);
+            children.push_maybe_node(PatternItem::Superclass, &inner.superclass);
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+        }
+        Node::Const(inner) => {
+            children.push_maybe_node(PatternItem::Scope, &inner.scope);
+        }
+        Node::ConstPattern(inner) => {
+            children.push_node(PatternItem::Const, &inner.const_);
+            children.push_node(PatternItem::Pattern, &inner.pattern);
+        }
+        Node::CSend(inner) => {
+            children.push_node(PatternItem::Recv, &inner.recv);
+            children.push_nodes(PatternItem::Arg, &inner.args);
+        }
+        Node::Cvasgn(inner) => {
+            children.push_maybe_node(PatternItem::Value, &inner.value);
+        }
+        Node::Def(inner) => {
+            children.push_maybe_node(PatternItem::Args, &inner.args);
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+        }
+        Node::Defined(inner) => {
+            children.push_node(PatternItem::Value, &inner.value);
+        }
+        Node::Defs(inner) => {
+            children.push_node(PatternItem::Definee, &inner.definee);
+            children.push_maybe_node(PatternItem::Args, &inner.args);
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+        }
+        Node::Dstr(inner) => {
+            children.push_nodes(PatternItem::Part, &inner.parts);
+        }
+        Node::Dsym(inner) => {
+            children.push_nodes(PatternItem::Part, &inner.parts);
+        }
+        Node::EFlipFlop(inner) => {
+            children.push_maybe_node(PatternItem::Left, &inner.left);
+            children.push_maybe_node(PatternItem::Right, &inner.right);
+        }
+        Node::Ensure(inner) => {
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+            children.push_maybe_node(PatternItem::Ensure, &inner.ensure);
+        }
+        Node::Erange(inner) => {
+            children.push_maybe_node(PatternItem::Left, &inner.left);
+            children.push_maybe_node(PatternItem::Right, &inner.right);
+        }
+        Node::FindPattern(inner) => {
+            children.push_nodes(PatternItem::Element, &inner.elements);
+        }
+        Node::For(inner) => {
+            children.push_node(PatternItem::Iterator, &inner.iterator);
+            children.push_node(PatternItem::Iteratee, &inner.iteratee);
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+        }
+        Node::Gvasgn(inner) => {
+            children.push_maybe_node(PatternItem::Value, &inner.value);
+        }
+        Node::Hash(inner) => {
+            children.push_nodes(PatternItem::Pair, &inner.pairs);
+        }
+        Node::HashPattern(inner) => {
+            children.push_nodes(PatternItem::Element, &inner.elements);
+        }
+        Node::Heredoc(inner) => {
+            children.push_nodes(PatternItem::Part, &inner.parts);
+        }
+        Node::If(inner) => {
+            children.push_node(PatternItem::Cond, &inner.cond);
+            children.push_maybe_node(PatternItem::IfTrue, &inner.if_true);
+            children.push_maybe_node(PatternItem::IfFalse, &inner.if_false);
+        }
+        Node::IfGuard(inner) => {
+            children.push_node(PatternItem::Cond, &inner.cond);
+        }
+        Node::IFlipFlop(inner) => {
+            children.push_maybe_node(PatternItem::Left, &inner.left);
+            children.push_maybe_node(PatternItem::Right, &inner.right);
+        }
+        Node::IfMod(inner) => {
+            children.push_node(PatternItem::Cond, &inner.cond);
+            children.push_maybe_node(PatternItem::IfTrue, &inner.if_true);
+            children.push_maybe_node(PatternItem::IfFalse, &inner.if_false);
+        }
+        Node::IfTernary(inner) => {
+            children.push_node(PatternItem::Cond, &inner.cond);
+            children.push_node(PatternItem::IfTrue, &inner.if_true);
+            children.push_node(PatternItem::IfFalse, &inner.if_false);
+        }
+        Node::Index(inner) => {
+            children.push_node(PatternItem::Recv, &inner.recv);
+            children.push_nodes(PatternItem::Index, &inner.indexes);
+        }
+        Node::IndexAsgn(inner) => {
+            children.push_node(PatternItem::Recv, &inner.recv);
+            children.push_nodes(PatternItem::Index, &inner.indexes);
+            children.push_maybe_node(PatternItem::Value, &inner.value);
+        }
+        Node::InMatch(inner) => {
+            children.push_node(PatternItem::Value, &inner.value);
+            children.push_node(PatternItem::Pattern, &inner.pattern);
+        }
+        Node::InPattern(inner) => {
+            children.push_node(PatternItem::Pattern, &inner.pattern);
+            children.push_maybe_node(PatternItem::Guard, &inner.guard);
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+        }
+        Node::Irange(inner) => {
+            children.push_maybe_node(PatternItem::Left, &inner.left);
+            children.push_maybe_node(PatternItem::Right, &inner.right);
+        }
+        Node::Ivasgn(inner) => {
+            children.push_maybe_node(PatternItem::Value, &inner.value);
+        }
+        Node::KwBegin(inner) => {
+            children.push_nodes(PatternItem::Stmt, &inner.statements);
+        }
+        Node::Kwoptarg(inner) => {
+            children.push_node(PatternItem::Default, &inner.default);
+        }
+        Node::Kwsplat(inner) => {
+            children.push_node(PatternItem::Value, &inner.value);
+        }
+        Node::Lvasgn(inner) => {
+            children.push_maybe_node(PatternItem::Value, &inner.value);
+        }
+        Node::Masgn(inner) => {
+            children.push_node(PatternItem::Lhs, &inner.lhs);
+            children.push_node(PatternItem::Rhs, &inner.rhs);
+        }
+        Node::MatchAlt(inner) => {
+            children.push_node(PatternItem::Lhs, &inner.lhs);
+            children.push_node(PatternItem::Rhs, &inner.rhs);
+        }
+        Node::MatchAs(inner) => {
+            children.push_node(PatternItem::Value, &inner.value);
+            children.push_node(PatternItem::As, &inner.as_);
+        }
+        Node::MatchCurrentLine(inner) => {
+            children.push_node(PatternItem::Re, &inner.re);
+        }
+        Node::MatchRest(inner) => {
+            children.push_maybe_node(PatternItem::Name, &inner.name);
+        }
+        Node::MatchWithLvasgn(inner) => {
+            children.push_node(PatternItem::Re, &inner.re);
+            children.push_node(PatternItem::Value, &inner.value);
+        }
+        Node::Mlhs(inner) => {
+            children.push_nodes(PatternItem::Item, &inner.items);
+        }
+        Node::Module(inner) => {
+            children.push_node(PatternItem::Name, &inner.name);
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+        }
+        Node::Next(inner) => {
+            children.push_nodes(PatternItem::Arg, &inner.args);
+        }
+        Node::Numblock(inner) => {
+            children.push_node(PatternItem::Call, &inner.call);
+            children.push_node(PatternItem::Body, &inner.body);
+        }
+        Node::OpAsgn(inner) => {
+            children.push_node(PatternItem::Recv, &inner.recv);
+            children.push_node(PatternItem::Value, &inner.value);
+        }
+        Node::Optarg(inner) => {
+            children.push_node(PatternItem::Default, &inner.default);
+        }
+        Node::Or(inner) => {
+            children.push_node(PatternItem::Lhs, &inner.lhs);
+            children.push_node(PatternItem::Rhs, &inner.rhs);
+        }
+        Node::OrAsgn(inner) => {
+            children.push_node(PatternItem::Recv, &inner.recv);
+            children.push_node(PatternItem::Value, &inner.value);
+        }
+        Node::Pair(inner) => {
+            children.push_node(PatternItem::Key, &inner.key);
+            children.push_node(PatternItem::Value, &inner.value);
+        }
+        Node::Pin(inner) => {
+            children.push_node(PatternItem::Var, &inner.var);
+        }
+        Node::Postexe(inner) => {
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+        }
+        Node::Preexe(inner) => {
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+        }
+        Node::Procarg0(inner) => {
+            children.push_nodes(PatternItem::Arg, &inner.args);
+        }
+        Node::Regexp(inner) => {
+            children.push_nodes(PatternItem::Part, &inner.parts);
+            children.push_maybe_node(PatternItem::Options, &inner.options);
+        }
+        Node::Rescue(inner) => {
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+            children.push_nodes(PatternItem::RescueBody, &inner.rescue_bodies);
+            children.push_maybe_node(PatternItem::Else, &inner.else_);
+        }
+        Node::RescueBody(inner) => {
+            children.push_maybe_node(PatternItem::ExcList, &inner.exc_list);
+            children.push_maybe_node(PatternItem::ExcVar, &inner.exc_var);
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+        }
+        Node::Return(inner) => {
+            children.push_nodes(PatternItem::Arg, &inner.args);
+        }
+        Node::SClass(inner) => {
+            children.push_node(PatternItem::Expr, &inner.expr);
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+        }
+        Node::Send(inner) => {
+            children.push_maybe_node(PatternItem::Recv, &inner.recv);
+            children.push_nodes(PatternItem::Arg, &inner.args);
+        }
+        Node::Splat(inner) => {
+            children.push_maybe_node(PatternItem::Value, &inner.value);
+        }
+        Node::Super(inner) => {
+            children.push_nodes(PatternItem::Arg, &inner.args);
+        }
+        Node::Undef(inner) => {
+            children.push_nodes(PatternItem::Arg, &inner.names);
+        }
+        Node::UnlessGuard(inner) => {
+            children.push_node(PatternItem::Cond, &inner.cond);
+        }
+        Node::Until(inner) => {
+            children.push_node(PatternItem::Cond, &inner.cond);
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+        }
+        Node::UntilPost(inner) => {
+            children.push_node(PatternItem::Cond, &inner.cond);
+            children.push_node(PatternItem::Body, &inner.body);
+        }
+        Node::When(inner) => {
+            children.push_nodes(PatternItem::Arg, &inner.patterns);
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+        }
+        Node::While(inner) => {
+            children.push_node(PatternItem::Cond, &inner.cond);
+            children.push_maybe_node(PatternItem::Body, &inner.body);
+        }
+        Node::WhilePost(inner) => {
+            children.push_node(PatternItem::Cond, &inner.cond);
+            children.push_node(PatternItem::Body, &inner.body);
+        }
+        Node::XHeredoc(inner) => {
+            children.push_nodes(PatternItem::Part, &inner.parts);
+        }
+        Node::Xstr(inner) => {
+            children.push_nodes(PatternItem::Part, &inner.parts);
+        }
+        Node::Yield(inner) => {
+            children.push_nodes(PatternItem::Arg, &inner.args);
+        }
+        _ => {}
+    }
+    children
+}
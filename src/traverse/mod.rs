@@ -2,4 +2,7 @@ mod visitor;
 pub use visitor::Visitor;
 
 mod find;
-pub use find::Find;
+pub use find::{Find, PatternError, PatternItem};
+
+mod locate;
+pub use locate::{Locate, LocatedNode};
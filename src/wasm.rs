@@ -0,0 +1,253 @@
+//! WebAssembly bindings of the parser.
+//!
+//! Exposes a single `parse(source, options)` function that returns
+//! a plain JS object:
+//!
+//! ```js
+//! {
+//!   ast: { type: "send", children: [null, "foo", ...], location: { expression: { begin_pos: 0, end_pos: 7 }, ... } } | null,
+//!   tokens: [{ type: 307, name: "tIDENTIFIER", value: "foo", location: { begin_pos: 0, end_pos: 3 } }, ...],
+//!   diagnostics: [{ level: "error", message: "...", rendered: "...", location: { begin_pos: 0, end_pos: 3 } }, ...],
+//!   comments: [{ kind: "inline", location: { begin_pos: 8, end_pos: 17 } }, ...],
+//!   input: "foo(42) # comment\n",
+//! }
+//! ```
+//!
+//! `ast` has the same shape as `Node::to_json`.
+//!
+//! All locations are offsets in UTF-16 code units of `input`,
+//! so they can be passed to `String.prototype.slice` as is.
+//!
+//! `source` can be either a string or `Uint8Array`. Non-UTF-8 sources
+//! (with a magic `# encoding: ...` comment) are decoded with built-in decoders,
+//! then `input` is the decoded source.
+
+#![allow(unsafe_code)]
+
+use crate::parser::TokenValue;
+use crate::source::{CommentType, CustomDecoder, Range};
+use crate::{token_name, Parser, ParserOptions, ParserResult};
+use js_sys::{Array, Object, Reflect, Uint8Array, JSON};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+fn set(object: &Object, key: &str, value: impl Into<JsValue>) {
+    Reflect::set(object, &JsValue::from_str(key), &value.into())
+        .expect("setting a property of a plain object never fails");
+}
+
+fn get(object: &JsValue, key: &str) -> Option<JsValue> {
+    let value = Reflect::get(object, &JsValue::from_str(key)).ok()?;
+    if value.is_undefined() || value.is_null() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+fn get_bool(object: &JsValue, key: &str, default: bool) -> Result<bool, JsValue> {
+    match get(object, key) {
+        Some(value) => value
+            .as_bool()
+            .ok_or_else(|| JsValue::from_str(&format!("options.{} must be a boolean", key))),
+        None => Ok(default),
+    }
+}
+
+fn options_from_js(options: &JsValue) -> Result<ParserOptions, JsValue> {
+    let defaults = ParserOptions::default();
+
+    if options.is_undefined() || options.is_null() {
+        return Ok(ParserOptions {
+            decoder: CustomDecoder::builtin(),
+            ..defaults
+        });
+    }
+
+    let buffer_name = match get(options, "buffer_name") {
+        Some(value) => value
+            .as_string()
+            .ok_or_else(|| JsValue::from_str("options.buffer_name must be a string"))?,
+        None => defaults.buffer_name.clone(),
+    };
+
+    Ok(ParserOptions {
+        buffer_name,
+        decoder: CustomDecoder::builtin(),
+        record_tokens: get_bool(options, "record_tokens", defaults.record_tokens)?,
+        record_comments: get_bool(options, "record_comments", defaults.record_comments)?,
+        ..defaults
+    })
+}
+
+fn source_from_js(source: &JsValue) -> Result<Vec<u8>, JsValue> {
+    if let Some(source) = source.as_string() {
+        Ok(source.into_bytes())
+    } else if let Some(source) = source.dyn_ref::<Uint8Array>() {
+        Ok(source.to_vec())
+    } else {
+        Err(JsValue::from_str("source must be a string or Uint8Array"))
+    }
+}
+
+// Maps byte offsets in the input to offsets in UTF-16 code units
+// of the same input converted to a JS string
+struct Utf16Offsets {
+    offsets: Vec<usize>,
+}
+
+impl Utf16Offsets {
+    fn new(mut bytes: &[u8]) -> Self {
+        let mut result = Self {
+            offsets: Vec::with_capacity(bytes.len() + 1),
+        };
+        let mut utf16_offset = 0;
+
+        loop {
+            match std::str::from_utf8(bytes) {
+                Ok(s) => {
+                    result.push_str(s, &mut utf16_offset);
+                    break;
+                }
+                Err(err) => {
+                    let (valid, rest) = bytes.split_at(err.valid_up_to());
+                    if let Ok(s) = std::str::from_utf8(valid) {
+                        result.push_str(s, &mut utf16_offset);
+                    }
+                    // `String::from_utf8_lossy` replaces every invalid sequence
+                    // with a single U+FFFD
+                    let invalid_len = err.error_len().unwrap_or(rest.len());
+                    result.push(invalid_len, utf16_offset);
+                    utf16_offset += 1;
+                    bytes = &rest[invalid_len..];
+                }
+            }
+        }
+        result.push(1, utf16_offset);
+
+        result
+    }
+
+    fn push_str(&mut self, s: &str, utf16_offset: &mut usize) {
+        for c in s.chars() {
+            self.push(c.len_utf8(), *utf16_offset);
+            *utf16_offset += c.len_utf16();
+        }
+    }
+
+    // Maps next `len` bytes to `utf16_offset`
+    fn push(&mut self, len: usize, utf16_offset: usize) {
+        self.offsets
+            .extend(std::iter::repeat(utf16_offset).take(len));
+    }
+
+    fn get(&self, pos: usize) -> usize {
+        match self.offsets.get(pos) {
+            Some(offset) => *offset,
+            None => *self.offsets.last().unwrap_or(&0),
+        }
+    }
+
+    fn range(&self, range: &Range) -> Range {
+        Range::new(self.get(range.begin_pos), self.get(range.end_pos))
+    }
+}
+
+fn loc_to_js(range: &Range, offsets: &Utf16Offsets) -> Object {
+    let range = offsets.range(range);
+    let loc = Object::new();
+    set(&loc, "begin_pos", range.begin_pos as u32);
+    set(&loc, "end_pos", range.end_pos as u32);
+    loc
+}
+
+fn result_to_js(result: ParserResult) -> Object {
+    let input = String::from_utf8_lossy(&result.input.bytes).into_owned();
+    let offsets = Utf16Offsets::new(&result.input.bytes);
+
+    let ast = match result.ast {
+        Some(mut node) => {
+            node.map_ranges(|range| *range = offsets.range(range));
+            JSON::parse(&node.to_json()).expect("Node::to_json returns valid JSON")
+        }
+        None => JsValue::NULL,
+    };
+
+    let tokens = result
+        .tokens
+        .iter()
+        .map(|token| {
+            let value = match &token.token_value {
+                TokenValue::String(s) => s.clone(),
+                TokenValue::InvalidString(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+            };
+            let range = Range::new(token.loc.begin, token.loc.end);
+            let object = Object::new();
+            set(&object, "type", token.token_type);
+            set(&object, "name", token_name(token.token_type));
+            set(&object, "value", value);
+            set(&object, "location", loc_to_js(&range, &offsets));
+            object
+        })
+        .collect::<Array>();
+
+    let diagnostics = result
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let level = if diagnostic.is_error() {
+                "error"
+            } else {
+                "warning"
+            };
+            let object = Object::new();
+            set(&object, "level", level);
+            set(&object, "message", diagnostic.render_message());
+            if let Some(rendered) = diagnostic.render(&result.input) {
+                set(&object, "rendered", rendered);
+            }
+            set(&object, "location", loc_to_js(&diagnostic.range, &offsets));
+            object
+        })
+        .collect::<Array>();
+
+    let comments = result
+        .comments
+        .iter()
+        .map(|comment| {
+            let kind = match comment.kind {
+                CommentType::Inline => "inline",
+                CommentType::Document => "document",
+                CommentType::Unknown => "unknown",
+            };
+            let object = Object::new();
+            set(&object, "kind", kind);
+            set(&object, "location", loc_to_js(&comment.location, &offsets));
+            object
+        })
+        .collect::<Array>();
+
+    let object = Object::new();
+    set(&object, "ast", ast);
+    set(&object, "tokens", tokens);
+    set(&object, "diagnostics", diagnostics);
+    set(&object, "comments", comments);
+    set(&object, "input", input);
+    object
+}
+
+/// Parses given `source` (string or `Uint8Array`).
+///
+/// `options` is an optional object with the following optional fields:
+/// + `buffer_name` - string, name of the buffer used in diagnostics
+/// + `record_tokens` - boolean, `true` by default
+/// + `record_comments` - boolean, `true` by default
+///
+/// Throws if `source` or `options` have invalid types.
+#[wasm_bindgen]
+pub fn parse(source: JsValue, options: JsValue) -> Result<JsValue, JsValue> {
+    let source = source_from_js(&source)?;
+    let options = options_from_js(&options)?;
+    let result = Parser::new(&source, options).do_parse();
+    Ok(result_to_js(result).into())
+}
@@ -0,0 +1,103 @@
+use lib_ruby_parser::arena::{Arena, ArenaNode, ArenaParserResult};
+use lib_ruby_parser::{Parser, ParserOptions};
+
+mod files_under_dir;
+use files_under_dir::files_under_dir;
+
+fn fixture_input(path: &str) -> String {
+    let content = std::fs::read_to_string(path).unwrap();
+    content
+        .lines()
+        .skip_while(|line| *line != "--INPUT")
+        .skip(1)
+        .take_while(|line| !line.starts_with("--"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn parse(src: &str) -> ArenaParserResult {
+    let options = ParserOptions {
+        buffer_name: "(arena_test)".to_owned(),
+        ..Default::default()
+    };
+    Parser::new(src.as_bytes(), options).do_parse_into_arena()
+}
+
+#[test]
+fn test_arena_layout() {
+    let result = parse("foo(1, 2)");
+    let root = result.ast.expect("expected AST");
+
+    assert_eq!(result.arena.len(), 3);
+    let node = result.arena.get(root).unwrap();
+    assert_eq!(node.str_type(), "send");
+    assert_eq!(node.expression().to_range(), 0..9);
+}
+
+#[test]
+fn test_foreign_node_id() {
+    let big = parse("foo(1, 2)");
+    let small = parse("foo");
+    let id = big
+        .arena
+        .iter()
+        .map(|(id, _)| id)
+        .last()
+        .expect("expected nodes");
+
+    assert!(small.arena.get(id).is_none());
+    assert_eq!(small.arena.to_node(id), None);
+
+    // index of the root of `small` exists in `big`, but it's still foreign
+    let root = small.ast.expect("expected AST");
+    assert!(root.index() < big.arena.len());
+    assert!(big.arena.get(root).is_none());
+    assert_eq!(big.arena.to_node(root), None);
+}
+
+#[test]
+fn test_interned_names() {
+    let result = parse("foo = 1; foo + foo");
+    let names = result
+        .arena
+        .iter()
+        .filter_map(|(_, node)| match node {
+            ArenaNode::Lvar(lvar) => Some(lvar.name),
+            ArenaNode::Lvasgn(lvasgn) => Some(lvasgn.name),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(names.len(), 3);
+    assert!(names.iter().all(|name| *name == names[0]));
+    assert_eq!(result.arena.resolve(names[0]), Some("foo"));
+    assert_eq!(result.arena.interner().lookup("foo"), Some(names[0]));
+
+    let other = parse("foo");
+    assert_eq!(other.arena.resolve(names[0]), None);
+}
+
+#[test]
+fn test_empty_input() {
+    let result = parse("");
+
+    assert_eq!(result.ast, None);
+    assert!(result.arena.is_empty());
+}
+
+#[test]
+fn test_roundtrip() {
+    for path in files_under_dir("tests/fixtures/parser/gen") {
+        let src = fixture_input(&path);
+        let options = ParserOptions {
+            buffer_name: path.clone(),
+            ..Default::default()
+        };
+        let ast = Parser::new(src.as_bytes(), options).do_parse().ast;
+
+        let (arena, root) = Arena::from_ast(&ast);
+        let roundtrip = root.and_then(|root| arena.to_node(root));
+
+        assert_eq!(ast, roundtrip, "roundtrip failed for {}", path);
+    }
+}
@@ -0,0 +1,160 @@
+#![cfg(feature = "builtin-decoders")]
+
+use lib_ruby_parser::source::{decode_builtin, CustomDecoder, InputError, RecognizedEncoding};
+use lib_ruby_parser::{Parser, ParserOptions};
+
+#[test]
+fn test_decode_windows_1251() {
+    let decoded = decode_builtin(RecognizedEncoding::Windows_1251, &[0xEF, 0xF0, 0xE8]).unwrap();
+    assert_eq!(decoded, "при".as_bytes());
+}
+
+fn assert_decodes(cases: &[(&str, &[u8], &str)]) {
+    for (name, input, expected) in cases.iter() {
+        let encoding = RecognizedEncoding::parse(name).unwrap();
+        let decoded = decode_builtin(encoding, input)
+            .unwrap_or_else(|err| panic!("failed to decode {}: {}", name, err));
+        assert_eq!(
+            String::from_utf8(decoded).unwrap(),
+            *expected,
+            "wrong output for {}",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_decode_single_byte_encodings() {
+    assert_decodes(&[
+        ("ISO-8859-2", &[0xA3, 0xF3, 0x64, 0xBC], "Łódź"),
+        (
+            "ISO-8859-3",
+            &[0xA1, 0x61, 0x6D, 0x72, 0x75, 0x6E],
+            "Ħamrun",
+        ),
+        ("ISO-8859-4", &[0xD1, 0xFE, 0xF3], "Ņūķ"),
+        ("ISO-8859-5", &[0xDF, 0xE0, 0xD8], "при"),
+        ("ISO-8859-6", &[0xD9, 0xD1, 0xC8, 0xEA], "عربي"),
+        ("ISO-8859-7", &[0xE1, 0xE2, 0xE3], "αβγ"),
+        ("ISO-8859-8", &[0xF9, 0xEC, 0xE5, 0xED], "שלום"),
+        ("ISO-8859-9", &[0xDD, 0xFE, 0xF0, 0xE9], "İşğé"),
+        ("ISO-8859-10", &[0xAF, 0xBB], "Ŋŧ"),
+        ("ISO-8859-11", &[0xE4, 0xB7, 0xC2, 0xA0], "ไทย\u{a0}"),
+        ("ISO-8859-13", &[0xC0, 0xE8, 0xEB], "Ąčė"),
+        ("ISO-8859-14", &[0xD0, 0xFE], "Ŵŷ"),
+        ("ISO-8859-15", &[0xA4, 0xBD], "€œ"),
+        ("ISO-8859-16", &[0xAA, 0xFE], "Șț"),
+        ("KOI8-R", &[0xD0, 0xD2, 0xC9], "при"),
+        ("KOI8-U", &[0xA7, 0xD6, 0xC1, 0xCB], "їжак"),
+        ("IBM866", &[0xAF, 0xE0, 0xA8], "при"),
+        ("MacRoman", &[0x63, 0x61, 0x66, 0x8E], "café"),
+        ("MacCyrillic", &[0xEF, 0xF0, 0xE8], "при"),
+        ("TIS-620", &[0xE4, 0xB7, 0xC2], "ไทย"),
+        ("Windows-874", &[0xE4, 0xB7, 0xC2], "ไทย"),
+        ("Windows-1250", &[0xA3, 0xF3, 0x64, 0x9F], "Łódź"),
+        ("Windows-1251", &[0xEF, 0xF0, 0xE8], "при"),
+        ("Windows-1252", &[0x63, 0x61, 0x66, 0xE9], "café"),
+        ("Windows-1253", &[0xE1, 0xE2, 0xE3], "αβγ"),
+        ("Windows-1254", &[0xDD, 0xFE, 0xF0], "İşğ"),
+        ("Windows-1255", &[0xF9, 0xEC, 0xE5, 0xED], "שלום"),
+        ("Windows-1256", &[0xDA, 0xD1, 0xC8, 0xED], "عربي"),
+        ("Windows-1257", &[0xC0, 0xE8, 0xEB], "Ąčė"),
+        ("Windows-1258", &[0xD0, 0xF4, 0x6E, 0x67], "Đông"),
+    ]);
+}
+
+#[test]
+fn test_decode_cjk_encodings() {
+    assert_decodes(&[
+        ("Shift_JIS", &[0x93, 0xFA, 0x96, 0x7B, 0x8C, 0xEA], "日本語"),
+        (
+            "Windows-31J",
+            &[0x93, 0xFA, 0x96, 0x7B, 0x8C, 0xEA],
+            "日本語",
+        ),
+        ("EUC-JP", &[0xC6, 0xFC, 0xCB, 0xDC, 0xB8, 0xEC], "日本語"),
+        ("eucJP-ms", &[0xC6, 0xFC, 0xCB, 0xDC, 0xB8, 0xEC], "日本語"),
+        ("CP51932", &[0xC6, 0xFC, 0xCB, 0xDC, 0xB8, 0xEC], "日本語"),
+        ("Big5", &[0xA4, 0xA4, 0xA4, 0xE5], "中文"),
+        ("Big5-HKSCS", &[0xA4, 0xA4, 0xA4, 0xE5], "中文"),
+        ("CP950", &[0xA4, 0xA4, 0xA4, 0xE5], "中文"),
+        ("GBK", &[0xD6, 0xD0, 0xCE, 0xC4], "中文"),
+        ("GB2312", &[0xD6, 0xD0, 0xCE, 0xC4], "中文"),
+        ("GB18030", &[0xD6, 0xD0, 0xCE, 0xC4], "中文"),
+        ("EUC-KR", &[0xC7, 0xD1, 0xB1, 0xB9, 0xBE, 0xEE], "한국어"),
+        ("CP949", &[0xC7, 0xD1, 0xB1, 0xB9, 0xBE, 0xEE], "한국어"),
+    ]);
+}
+
+#[test]
+fn test_decode_invalid_byte_sequence() {
+    for (encoding, input) in vec![
+        (RecognizedEncoding::Shift_JIS, vec![0x93]),
+        (RecognizedEncoding::EUC_JP, vec![0xC6, 0x20]),
+        (RecognizedEncoding::TIS_620, vec![0xA0]),
+        (RecognizedEncoding::TIS_620, vec![0xFF]),
+        (RecognizedEncoding::ISO_8859_11, vec![0xDB]),
+    ] {
+        let name = format!("{:?}", encoding);
+        assert!(
+            matches!(
+                decode_builtin(encoding, &input),
+                Err(InputError::DecodingError(_))
+            ),
+            "{} decoded invalid input",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_decode_iso_8859_1() {
+    let decoded = decode_builtin(RecognizedEncoding::ISO_8859_1, &[0xE9, 0x80]).unwrap();
+    assert_eq!(decoded, "\u{e9}\u{80}".as_bytes());
+}
+
+#[test]
+fn test_decode_us_ascii() {
+    assert_eq!(
+        decode_builtin(RecognizedEncoding::US_ASCII, b"foo").unwrap(),
+        b"foo"
+    );
+    assert!(matches!(
+        decode_builtin(RecognizedEncoding::US_ASCII, &[0xFF]),
+        Err(InputError::DecodingError(_))
+    ));
+}
+
+#[test]
+fn test_unsupported_encoding() {
+    assert!(matches!(
+        decode_builtin(RecognizedEncoding::Emacs_Mule, b"foo"),
+        Err(InputError::NoDecoder(RecognizedEncoding::Emacs_Mule))
+    ));
+}
+
+#[test]
+fn test_parse_with_builtin_decoder() {
+    let mut input = b"# encoding: koi8-r\n".to_vec();
+    input.extend_from_slice(&[b'"', 0xD0, 0xD2, 0xC9, b'"']);
+
+    let options = ParserOptions {
+        decoder: CustomDecoder::builtin(),
+        ..Default::default()
+    };
+    let result = Parser::new(&input, options).do_parse();
+
+    assert!(result.diagnostics.is_empty());
+    assert_eq!(result.ast.unwrap().value(), Some("при".as_bytes()));
+}
+
+#[test]
+fn test_parse_falls_back_to_builtin_decoder() {
+    let mut input = b"# encoding: shift_jis\n".to_vec();
+    input.extend_from_slice(&[b'"', 0x93, 0xFA, 0x96, 0x7B, 0x8C, 0xEA, b'"']);
+
+    let result = Parser::new(&input, ParserOptions::default()).do_parse();
+
+    assert!(result.diagnostics.is_empty());
+    assert_eq!(result.ast.unwrap().value(), Some("日本語".as_bytes()));
+}
@@ -0,0 +1,203 @@
+#![cfg(feature = "cli")]
+
+use serde_json::Value;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+fn ruby_parse(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ruby-parse"))
+        .args(args)
+        .output()
+        .expect("failed to run ruby-parse")
+}
+
+fn ruby_parse_stdin(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ruby-parse"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run ruby-parse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn json_lines(output: &Output) -> Vec<Value> {
+    stdout(output)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+fn tmp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ruby-parse-test-{}", name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    std::fs::write(dir.join("valid.rb"), "def foo; end").unwrap();
+    std::fs::write(dir.join("nested").join("invalid.rb"), "def foo(").unwrap();
+    std::fs::write(dir.join("nested").join("ignored.txt"), "def foo(").unwrap();
+    dir
+}
+
+#[test]
+fn test_parse_sexp() {
+    let output = ruby_parse(&["parse", "-e", "42"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "s(:int, \"42\")\n");
+}
+
+#[test]
+fn test_parse_json() {
+    let output = ruby_parse(&["parse", "--format", "json", "-e", "42"]);
+    assert!(output.status.success());
+
+    let lines = json_lines(&output);
+    assert_eq!(lines.len(), 1);
+    let ast = &lines[0]["ast"];
+    assert_eq!(ast["type"], "int");
+    assert_eq!(ast["children"][0], "42");
+    assert_eq!(ast["location"]["expression"]["begin_pos"], 0);
+    assert_eq!(ast["location"]["expression"]["end_pos"], 2);
+    assert_eq!(ast["location"]["operator"], Value::Null);
+}
+
+#[test]
+fn test_parse_locations() {
+    let output = ruby_parse(&["parse", "--format", "locations", "-e", "foo(1)"]);
+    assert!(output.status.success());
+
+    let stdout = stdout(&output);
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines[..3],
+        ["foo(1)", "s(:send, nil, \"foo\",", "  s(:int, \"1\"))"]
+    );
+    for line in &[
+        "~~~~~~ expression",
+        "~~~ selector",
+        "   ~ begin",
+        "     ~ end",
+    ] {
+        assert!(lines.contains(line), "expected {:?} in {}", line, stdout);
+    }
+    assert_eq!(
+        lines[lines.len() - 2..],
+        ["s(:int, \"1\")", "    ~ expression"]
+    );
+}
+
+#[test]
+fn test_code_conflicts_with_paths() {
+    let dir = tmp_dir("code_conflicts_with_paths");
+
+    let output = ruby_parse(&["parse", "-e", "42", dir.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn test_closed_stdout() {
+    let dir = tmp_dir("closed_stdout");
+    let path = dir.join("large.rb");
+    std::fs::write(&path, "foo\n".repeat(100_000)).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ruby-parse"))
+        .args(&["tokens", path.to_str().unwrap()])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run ruby-parse");
+    drop(child.stdout.take());
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+}
+
+#[test]
+fn test_tokens_json() {
+    let output = ruby_parse_stdin(&["tokens", "--format", "json"], "foo");
+    assert!(output.status.success());
+
+    let lines = json_lines(&output);
+    assert_eq!(lines[0]["file"], "(stdin)");
+    assert_eq!(lines[0]["tokens"][0]["name"], "tIDENTIFIER");
+    assert_eq!(lines[0]["tokens"][0]["value"], "foo");
+}
+
+#[test]
+fn test_check_dir() {
+    let dir = tmp_dir("check_dir");
+
+    let output = ruby_parse(&["check", dir.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("invalid.rb:1:"));
+    assert!(!stdout(&output).contains("ignored.txt"));
+
+    let output = ruby_parse(&["check", dir.join("valid.rb").to_str().unwrap()]);
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_check_missing_files() {
+    let dir = tmp_dir("check_missing_files");
+
+    let missing = dir.join("typo.rb");
+    let output = ruby_parse(&["check", missing.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("typo.rb: no such file or directory"));
+
+    let pattern = format!("{}/**/*.rbx", dir.to_str().unwrap());
+    let output = ruby_parse(&["check", &pattern]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no files match"));
+}
+
+#[test]
+fn test_check_glob_json() {
+    let dir = tmp_dir("check_glob_json");
+    let pattern = format!("{}/**/*.rb", dir.to_str().unwrap());
+
+    let output = ruby_parse(&["check", "--format", "json", &pattern]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let lines = json_lines(&output);
+    assert_eq!(lines.len(), 2);
+    let invalid = lines
+        .iter()
+        .find(|line| line["file"].as_str().unwrap().ends_with("invalid.rb"))
+        .unwrap();
+    assert_eq!(invalid["diagnostics"][0]["level"], "error");
+}
+
+#[test]
+fn test_check_stdin() {
+    let output = ruby_parse_stdin(&["check"], "def foo; end");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn test_find() {
+    let src = "[1,2,3].each { |a| puts a + 1; 42 }";
+
+    let output = ruby_parse(&["find", "body stmt[0] arg[0]", "-e", src]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "(eval):1:24: a + 1\n");
+
+    let output = ruby_parse(&["find", "body stmt[5]", "-e", src]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = ruby_parse(&["find", "unknown", "-e", src]);
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,62 @@
+use lib_ruby_parser::source::buffer::{Buffer, Input};
+use lib_ruby_parser::source::{CustomDecoder, Range};
+
+fn input(source: &[u8]) -> Input {
+    Buffer::new("(columns)", source.to_vec(), CustomDecoder::default()).input
+}
+
+// "é" is 2 bytes/1 UTF-16 code unit, "😀" is 4 bytes/2 UTF-16 code units
+const SOURCE: &str = "foo\n\"é😀\" + bar\n";
+
+#[test]
+fn it_converts_byte_offsets_to_columns() {
+    let input = input(SOURCE.as_bytes());
+    let bar = SOURCE.find("bar").unwrap();
+
+    assert_eq!(input.line_col_for_pos(bar), Some((1, 11)));
+    assert_eq!(input.line_char_col_for_pos(bar), Some((1, 7)));
+    assert_eq!(input.line_utf16_col_for_pos(bar), Some((1, 8)));
+
+    assert_eq!(input.line_char_col_for_pos(2), Some((0, 2)));
+    assert_eq!(input.line_utf16_col_for_pos(SOURCE.len()), Some((2, 0)));
+    assert_eq!(input.line_char_col_for_pos(SOURCE.len() + 1), None);
+}
+
+#[test]
+fn it_converts_columns_to_byte_offsets() {
+    let input = input(SOURCE.as_bytes());
+    let bar = SOURCE.find("bar").unwrap();
+    let emoji = SOURCE.find('😀').unwrap();
+
+    assert_eq!(input.pos_for_line_char_col(1, 7), Some(bar));
+    assert_eq!(input.pos_for_line_utf16_col(1, 8), Some(bar));
+
+    assert_eq!(input.pos_for_line_char_col(1, 2), Some(emoji));
+    assert_eq!(input.pos_for_line_utf16_col(1, 2), Some(emoji));
+    // in the middle of a surrogate pair
+    assert_eq!(input.pos_for_line_utf16_col(1, 3), Some(emoji + 4));
+
+    // after the end of the line
+    assert_eq!(input.pos_for_line_char_col(0, 100), Some(3));
+    assert_eq!(input.pos_for_line_utf16_col(3, 0), None);
+}
+
+#[test]
+fn it_counts_characters_in_ranges() {
+    let input = input(SOURCE.as_bytes());
+    let range = Range::new(4, 4 + "\"é😀\"".len());
+
+    assert_eq!(input.char_count(range.begin_pos, range.end_pos), 4);
+    assert_eq!(input.utf16_count(range.begin_pos, range.end_pos), 5);
+    assert_eq!(range.begin_line_char_col(&input), Some((1, 0)));
+    assert_eq!(range.end_line_char_col(&input), Some((1, 4)));
+    assert_eq!(range.end_line_utf16_col(&input), Some((1, 5)));
+}
+
+#[test]
+fn it_counts_invalid_bytes_as_characters() {
+    let input = input(b"'\xFF\xFE' + a");
+
+    assert_eq!(input.line_char_col_for_pos(7), Some((0, 7)));
+    assert_eq!(input.pos_for_line_utf16_col(0, 3), Some(3));
+}
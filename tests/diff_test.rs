@@ -0,0 +1,58 @@
+use lib_ruby_parser::{diff, Parser, ParserOptions, ParserResult};
+use std::fs;
+
+mod files_under_dir;
+use files_under_dir::files_under_dir;
+
+fn parse(path: &str) -> ParserResult {
+    let options = ParserOptions {
+        buffer_name: path.to_owned(),
+        debug: false,
+        ..Default::default()
+    };
+    Parser::new(&fs::read(path).unwrap(), options).do_parse()
+}
+
+/// Every fixture is a directory with `old.rb`, `new.rb` and expected `changes` (one per line)
+fn test_fixture(dir: &str) {
+    let old = parse(&format!("{}/old.rb", dir));
+    let new = parse(&format!("{}/new.rb", dir));
+    let expected = fs::read_to_string(format!("{}/changes", dir)).unwrap();
+    let expected = expected.lines().collect::<Vec<_>>();
+
+    let changes = diff::diff(old.ast.as_ref(), new.ast.as_ref());
+    let changes = changes
+        .iter()
+        .map(|change| change.inspect())
+        .collect::<Vec<_>>();
+
+    assert_eq!(changes, expected, "fixture {}", dir);
+}
+
+#[test]
+fn test_diff_fixtures() {
+    for dir in files_under_dir("tests/fixtures/diff") {
+        test_fixture(&dir);
+    }
+}
+
+#[test]
+fn test_diff_with_empty_source() {
+    let result = Parser::new(b"foo", ParserOptions::default()).do_parse();
+
+    assert!(diff::diff(None, None).is_empty());
+    assert_eq!(
+        diff::diff(None, result.ast.as_ref())
+            .iter()
+            .map(|change| change.inspect())
+            .collect::<Vec<_>>(),
+        vec!["insert send 0...3"]
+    );
+    assert_eq!(
+        diff::diff(result.ast.as_ref(), None)
+            .iter()
+            .map(|change| change.inspect())
+            .collect::<Vec<_>>(),
+        vec!["remove send 0...3"]
+    );
+}
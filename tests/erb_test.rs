@@ -0,0 +1,217 @@
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::{erb, token_name, ParserOptions, ParserResult};
+
+fn compile(template: &str) -> String {
+    String::from_utf8(erb::compile(template.as_bytes()).ruby).unwrap()
+}
+
+fn parse(template: &str) -> ParserResult {
+    erb::parse(template.as_bytes(), ParserOptions::default())
+}
+
+fn source(result: &ParserResult, range: &Range) -> String {
+    range.source(&result.input).unwrap()
+}
+
+const LIST: &str = "<ul>
+<% users.each do |user| -%>
+  <li><%= user.name %></li>
+<% end -%>
+</ul>
+";
+
+#[test]
+fn test_compile() {
+    assert_eq!(
+        compile(LIST),
+        "_erbout << '<ul>\n'
+ users.each do |user| \n_erbout << '  <li>'
+_erbout << (( user.name \n)).to_s
+_erbout << '</li>\n'
+ end \n_erbout << '</ul>\n'
+"
+    );
+}
+
+#[test]
+fn test_trimming() {
+    assert_eq!(
+        compile("<div>\n  <%- if x -%>\n  y\n  <%- end -%>\n</div>"),
+        "_erbout << '<div>\n'
+ if x \n_erbout << '  y\n'
+ end \n_erbout << '</div>'
+"
+    );
+    // indentation is removed only if the tag starts a line
+    assert_eq!(compile("a  <%- x %>"), "_erbout << 'a  '\n x \n");
+}
+
+#[test]
+fn test_escaping() {
+    assert_eq!(compile("<%% x %>"), "_erbout << '<% x %>'\n");
+    assert_eq!(compile("it's a \\"), "_erbout << 'it\\'s a \\\\'\n");
+}
+
+#[test]
+fn test_comment_tags() {
+    assert_eq!(
+        compile("a<%# comment %>b"),
+        "_erbout << 'a'\n_erbout << 'b'\n"
+    );
+    assert_eq!(
+        compile("a<%# comment -%>\nb"),
+        "_erbout << 'a'\n_erbout << 'b'\n"
+    );
+}
+
+#[test]
+fn test_output_tags() {
+    assert_eq!(compile("<%= x %>"), "_erbout << (( x \n)).to_s\n");
+    assert_eq!(compile("<%== x %>"), "_erbout << (( x \n)).to_s\n");
+    assert_eq!(
+        compile("<%= x # comment %>"),
+        "_erbout << (( x # comment \n)).to_s\n"
+    );
+}
+
+#[test]
+fn test_block_output_tags() {
+    assert_eq!(
+        compile("<%= form_for(user) do |f| %><% end %>"),
+        "_erbout.append=  form_for(user) do |f| \n end \n"
+    );
+    assert_eq!(
+        compile("<%= link_to url do %><% end %>"),
+        "_erbout.append=  link_to url do \n end \n"
+    );
+    assert_eq!(
+        compile("<%= tag.div { %><% } %>"),
+        "_erbout.append=  tag.div { \n } \n"
+    );
+    assert_eq!(compile("<%= undo %>"), "_erbout << (( undo \n)).to_s\n");
+}
+
+#[test]
+fn test_parse() {
+    let result = parse(LIST);
+    assert!(result.diagnostics.is_empty());
+    assert_eq!(result.input.bytes, LIST.as_bytes());
+
+    let ast = result.ast.as_ref().unwrap();
+    assert_eq!(ast.expression(), &Range::new(0, LIST.len()));
+
+    let tokens = result
+        .tokens
+        .iter()
+        .map(|token| {
+            (
+                token_name(token.token_type),
+                source(&result, &Range::new(token.loc.begin, token.loc.end)),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(tokens[0], ("tIDENTIFIER", "users".to_owned()));
+    assert_eq!(tokens.last().unwrap(), &("kEND", "end".to_owned()));
+    assert!(tokens.iter().all(|(_, source)| !source.contains("_erbout")));
+}
+
+#[test]
+fn test_node_locations() {
+    let result = parse("<p><%= user.name %></p>");
+
+    let statements = result.ast.as_ref().unwrap().children();
+    let sources = statements
+        .iter()
+        .map(|node| source(&result, node.expression()))
+        .collect::<Vec<_>>();
+    assert_eq!(sources, vec!["<p>", "<%= user.name %>", "</p>"]);
+
+    // `_erbout << ((user.name)).to_s`
+    let value = statements[1].children()[1];
+    assert_eq!(source(&result, value.expression()), "<%= user.name %>");
+    let call = value.children()[0].children()[0].children()[0];
+    assert_eq!(source(&result, call.expression()), "user.name");
+}
+
+#[test]
+fn test_diagnostics() {
+    let template = "<p>\n<% def m(a, a); end %>\n</p>";
+    let result = parse(template);
+
+    let messages = result
+        .diagnostics
+        .iter()
+        .map(|d| (d.render_message(), source(&result, &d.range)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![("duplicated argument name".to_owned(), "a".to_owned())]
+    );
+    assert_eq!(
+        result.diagnostics[0].range.begin_line_col(&result.input),
+        Some((1, 12))
+    );
+    assert!(result.diagnostics[0]
+        .render(&result.input)
+        .unwrap()
+        .contains("<% def m(a, a); end %>"));
+}
+
+#[test]
+fn test_unterminated_tag() {
+    let result = parse("<p><%= foo");
+
+    let messages = result
+        .diagnostics
+        .iter()
+        .map(|d| (d.render_message(), source(&result, &d.range)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![(
+            "unterminated ERB tag meets end of file".to_owned(),
+            "<%=".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn test_comments() {
+    let result = parse("<p>\n<% # hello %>\n</p>");
+
+    assert_eq!(result.comments.len(), 1);
+    assert!(source(&result, &result.comments[0].location).starts_with("# hello"));
+}
+
+#[test]
+fn test_source_map() {
+    let template = "ab<%= foo %>cd";
+    let erb::Compiled {
+        ruby, source_map, ..
+    } = erb::compile(template.as_bytes());
+    let ruby = String::from_utf8(ruby).unwrap();
+    let pos = |code: &str| ruby.find(code).unwrap();
+
+    let foo = pos("foo");
+    assert_eq!(
+        source_map.template_range(&Range::new(foo, foo + 3)),
+        Range::new(6, 9)
+    );
+    assert!(source_map.is_template_code(foo));
+
+    let cd = pos("'cd'");
+    assert_eq!(
+        source_map.template_range(&Range::new(cd, cd + 4)),
+        Range::new(12, 14)
+    );
+    assert!(!source_map.is_template_code(cd));
+
+    // `_erbout << (( foo \n)).to_s` is the whole tag
+    let append = pos("_erbout << ((");
+    let to_s = pos(".to_s") + 5;
+    assert_eq!(
+        source_map.template_range(&Range::new(append, to_s)),
+        Range::new(2, 12)
+    );
+    assert_eq!(source_map.template_pos(ruby.len()), template.len());
+}
@@ -30,3 +30,32 @@ fn it_renders() {
         .join("\n")
     );
 }
+
+#[test]
+fn it_renders_non_ascii_source() {
+    let source = "# комментарий\nputs(\"привет\", 日本)\n";
+    let buffer = Buffer::new(
+        "(test_render)",
+        source.as_bytes().to_vec(),
+        CustomDecoder::default(),
+    );
+    let begin = source.find("日本").unwrap();
+
+    let error = Diagnostic::new(
+        ErrorLevel::Error,
+        DiagnosticMessage::ConstArgument,
+        Range::new(begin, begin + "日本".len()),
+    );
+
+    assert_eq!(
+        error
+            .render(&buffer.input)
+            .expect("failed to render diagnostic"),
+        vec![
+            "(test_render):2:15: error: formal argument cannot be a constant",
+            "(test_render):2: puts(\"привет\", 日本)",
+            "(test_render):2:                ^~"
+        ]
+        .join("\n")
+    );
+}
@@ -0,0 +1 @@
+if a; else b; end
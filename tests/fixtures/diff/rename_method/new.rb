@@ -0,0 +1,3 @@
+def baz
+  bar
+end
@@ -0,0 +1,3 @@
+def foo
+  bar
+end
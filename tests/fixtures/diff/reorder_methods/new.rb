@@ -0,0 +1,9 @@
+class Foo
+  def b
+    2
+  end
+
+  def a
+    1
+  end
+end
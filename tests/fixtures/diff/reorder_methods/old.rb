@@ -0,0 +1,9 @@
+class Foo
+  def a
+    1
+  end
+
+  def b
+    2
+  end
+end
@@ -0,0 +1,3 @@
+if cond
+  foo
+end
@@ -0,0 +1,5 @@
+foo
+foo.bar(1, 2)
+1 + 2
+foo(1, *a, 2)
+foo&.bar ||= 1
@@ -0,0 +1,13 @@
+if a then b elsif c then d else e end
+x while y
+case a
+when 1, 2 then b
+else c
+end
+begin
+  a
+rescue Foo => e
+  b
+ensure
+  c
+end
@@ -0,0 +1,4 @@
+def foo(a, b = 1, *c, d:, &e); end
+def self.bar(x) = x
+class Foo < Bar; end
+module Baz; end
@@ -0,0 +1,11 @@
+"a#{b}c"
+0x10
+1e20
+1.5r
+2i
+:"a b"
+/a/ix
+[1, *a]
+{ a: 1, "b" => 2, **c }
+1..2
+%w[a b]
@@ -0,0 +1,3 @@
+a = 1; a
+[1].each { |x| y = x }
+b, *c = 1, 2
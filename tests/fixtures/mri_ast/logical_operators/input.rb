@@ -0,0 +1,3 @@
+a && b && c
+a || b and c
+not a
@@ -0,0 +1,6 @@
+foo(
+  1
+)
+<<~HEREDOC
+  a #{b}
+HEREDOC
@@ -0,0 +1,26 @@
+# Writes the expected output of every `tests/fixtures/mri_ast/*/input.rb`
+# to `mri` next to it, using `RubyVM::AbstractSyntaxTree` of a real Ruby:
+#
+#   ruby tests/generate_mri_ast_fixtures.rb
+#
+# `mri_ast` follows Ruby 3.0, so fixtures must be generated by it.
+# `dump` prints the same text as `MriNode::inspect` (see src/mri_ast/mod.rs).
+
+unless RUBY_VERSION.start_with?('3.0.')
+  abort "Ruby 3.0 is required to generate mri_ast fixtures, got #{RUBY_VERSION}"
+end
+
+def dump(node)
+  case node
+  when RubyVM::AbstractSyntaxTree::Node
+    loc = "#{node.first_lineno}:#{node.first_column}-#{node.last_lineno}:#{node.last_column}"
+    ["(#{node.type}@#{loc}", *node.children.map { |c| dump(c) }].join(' ') + ')'
+  when Array then "[#{node.map { |c| dump(c) }.join(', ')}]"
+  else node.inspect
+  end
+end
+
+Dir[File.join(__dir__, 'fixtures', 'mri_ast', '*', 'input.rb')].sort.each do |input|
+  ast = RubyVM::AbstractSyntaxTree.parse(File.read(input))
+  File.write(File.join(File.dirname(input), 'mri'), dump(ast) + "\n")
+end
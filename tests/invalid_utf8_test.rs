@@ -0,0 +1,133 @@
+use lib_ruby_parser::{token_name, Parser, ParserOptions, ParserResult};
+
+fn parse(src: &[u8]) -> ParserResult {
+    Parser::new(src, ParserOptions::default()).do_parse()
+}
+
+fn error_messages(result: &ParserResult) -> Vec<String> {
+    result
+        .diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .map(|d| d.render_message())
+        .collect()
+}
+
+const INVALID_MULTIBYTE_CHAR: &str = "invalid multibyte char (UTF-8)";
+
+fn assert_invalid_token(src: &[u8], token_type: &str, value: &[u8]) {
+    let result = parse(src);
+
+    let token = result
+        .tokens
+        .iter()
+        .find(|token| token_name(token.token_type) == token_type)
+        .unwrap_or_else(|| panic!("no {} token in {:?}", token_type, result.tokens));
+    assert_eq!(token.clone().into_bytes(), value);
+    assert!(
+        error_messages(&result).contains(&INVALID_MULTIBYTE_CHAR.to_owned()),
+        "no error for {:?}: {:?}",
+        src,
+        error_messages(&result)
+    );
+}
+
+#[test]
+fn test_invalid_identifiers() {
+    assert_invalid_token(b"foo\xFF = 1", "tIDENTIFIER", b"foo\xFF");
+    assert_invalid_token(b"\xC3(1)", "tIDENTIFIER", b"\xC3");
+    assert_invalid_token(b"Foo\xE2\x82", "tCONSTANT", b"Foo\xE2\x82");
+    assert_invalid_token(b"@a\xFF", "tIVAR", b"@a\xFF");
+    assert_invalid_token(b"@@a\xFF", "tCVAR", b"@@a\xFF");
+    assert_invalid_token(b"$a\xFF", "tGVAR", b"$a\xFF");
+    assert_invalid_token(b"def m\xFF; end", "tIDENTIFIER", b"m\xFF");
+    assert_invalid_token(b"foo(a\xFF: 1)", "tLABEL", b"a\xFF");
+}
+
+#[test]
+fn test_invalid_heredoc_id() {
+    let result = parse(b"<<\"\xFF\"\nfoo\n\xFF\n");
+
+    let end = result
+        .tokens
+        .iter()
+        .find(|token| token_name(token.token_type) == "tSTRING_END")
+        .unwrap();
+    assert_eq!(end.clone().into_bytes(), b"\xFF");
+}
+
+#[test]
+fn test_invalid_magic_comment() {
+    parse(b"# \xFF: true\n1");
+
+    let result = parse(b"# encoding: \xFF\n1");
+    assert_eq!(
+        error_messages(&result),
+        vec!["encoding error: UnsupportdEncoding(\"\u{FFFD}\")"]
+    );
+}
+
+// A small deterministic xorshift generator, so failures are reproducible
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+const SNIPPETS: &[&str] = &[
+    "foo = bar.baz(1, *args, **kwargs, &blk)",
+    "def m(a, b = 1, *c, d:, e: 2, **f, &g); a + b; end",
+    "class Foo < Bar; @a = 1; @@b = 2; $c = 3; end",
+    "x = \"str #{interp} \\n\" + 'raw' + :sym.to_s + %w[a b].join",
+    "<<~HEREDOC\n  text #{1 + 2}\nHEREDOC",
+    "case x; in [1, *rest] then 1; in {a:} then 2; else 3; end",
+    "foo&.bar ||= ->(x) { x * 2 }.call(3) rescue nil",
+    "/re#{1}gex/mix =~ str; $~; $1; __FILE__",
+    "# encoding: utf-8\n=begin\ndoc\n=end\nBEGIN { } ; END { }",
+    "?a + ?\\u{1F600} + \"\\xFF\" + %q(x) + `cmd`",
+];
+
+#[test]
+fn test_random_invalid_bytes_dont_panic() {
+    let mut random = Random(0x2545_f491_4f6c_dd1d);
+
+    for _ in 0..2000 {
+        let mut src = SNIPPETS[random.below(SNIPPETS.len())].as_bytes().to_vec();
+        for _ in 0..=random.below(3) {
+            let pos = random.below(src.len() + 1);
+            // high bytes, incomplete sequences and stray continuation bytes
+            let byte = 0x80 + random.below(0x80) as u8;
+            src.insert(pos, byte);
+        }
+
+        let result = parse(&src);
+        for token in result.tokens.iter() {
+            assert!(token.loc.begin <= token.loc.end && token.loc.end <= src.len());
+        }
+        for diagnostic in result.diagnostics.iter() {
+            diagnostic.render(&result.input);
+        }
+    }
+}
+
+#[test]
+fn test_random_bytes_dont_panic() {
+    let mut random = Random(0x9e37_79b9_7f4a_7c15);
+
+    for _ in 0..2000 {
+        let len = random.below(40);
+        let src = (0..len)
+            .map(|_| random.below(256) as u8)
+            .collect::<Vec<_>>();
+        parse(&src);
+    }
+}
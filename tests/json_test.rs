@@ -0,0 +1,159 @@
+#![cfg(feature = "json")]
+
+use lib_ruby_parser::{Parser, ParserOptions};
+use serde_json::Value;
+use std::fs;
+
+mod files_under_dir;
+use files_under_dir::files_under_dir;
+
+mod loc_matcher;
+use loc_matcher::LocMatcher;
+
+/// Returns `--INPUT`, `--AST` and `--LOCATIONS` sections of the fixture
+fn read_fixture(path: &str) -> (String, Option<String>, Vec<String>) {
+    let content = fs::read_to_string(path).unwrap();
+
+    let mut section = "";
+    let mut input = vec![];
+    let mut ast = vec![];
+    let mut locs = vec![];
+
+    for line in content.lines() {
+        if line.starts_with("// ") {
+            continue;
+        }
+        if let "--INPUT" | "--AST" | "--LOCATIONS" | "--DIAGNOSTIC" = line {
+            section = line;
+            continue;
+        }
+        match section {
+            "--INPUT" => input.push(line),
+            "--AST" => ast.push(line),
+            "--LOCATIONS" => locs.push(line.to_owned()),
+            _ => {}
+        }
+    }
+
+    let ast = if ast.is_empty() {
+        None
+    } else {
+        Some(ast.join("\n"))
+    };
+    let locs = locs.into_iter().filter(|l| !l.is_empty()).collect();
+    (input.join("\n"), ast, locs)
+}
+
+/// Converts JSON back to the `Node::inspect` format
+fn json_to_sexp(json: &Value, indent: usize) -> String {
+    let mut sexp = format!(
+        "{}s(:{}",
+        "  ".repeat(indent),
+        json["type"].as_str().unwrap()
+    );
+    for child in json["children"].as_array().unwrap() {
+        match child {
+            Value::Object(_) => sexp.push_str(&format!(",\n{}", json_to_sexp(child, indent + 1))),
+            Value::Null => sexp.push_str(", nil"),
+            Value::String(s) => sexp.push_str(&format!(", {:?}", s)),
+            Value::Number(n) => sexp.push_str(&format!(", {}", n)),
+            other => panic!("unexpected child {:?}", other),
+        }
+    }
+    sexp.push(')');
+    sexp
+}
+
+fn test_file(path: &str) -> Result<(), String> {
+    let (input, expected_ast, locs) = read_fixture(path);
+    let expected_ast = match expected_ast {
+        Some(ast) if ast != "nil" => ast,
+        _ => return Ok(()),
+    };
+
+    let parser = Parser::new(input.as_bytes(), ParserOptions::default());
+    parser.static_env.declare("foo");
+    parser.static_env.declare("bar");
+    parser.static_env.declare("baz");
+    let ast = parser
+        .do_parse()
+        .ast
+        .ok_or_else(|| "expected AST".to_owned())?;
+
+    let json: Value = serde_json::from_str(&ast.to_json())
+        .map_err(|err| format!("invalid JSON {}: {}", ast.to_json(), err))?;
+
+    let actual_ast = json_to_sexp(&json, 0);
+    if actual_ast != expected_ast {
+        return Err(format!(
+            "AST diff:\nactual:\n{}\nexpected:\n{}\n",
+            actual_ast, expected_ast
+        ));
+    }
+
+    for loc in locs {
+        LocMatcher::new(&loc).test_json(&ast)?;
+    }
+
+    Ok(())
+}
+
+fn test_dir(dir: &str) {
+    let mut failed = 0;
+
+    for path in files_under_dir(dir) {
+        if let Err(err) = test_file(&path) {
+            eprintln!("test {} ... Err:\n{}\n", path, err);
+            failed += 1;
+        }
+    }
+
+    assert_eq!(
+        failed, 0,
+        "expected JSON tests to pass, got {} failures",
+        failed
+    );
+}
+
+#[test]
+fn test_gen() {
+    test_dir("tests/fixtures/parser/gen")
+}
+
+#[test]
+fn test_manual() {
+    test_dir("tests/fixtures/parser/manual")
+}
+
+#[test]
+fn test_json_structure() {
+    let ast = Parser::new(b"foo(\"a\\nb\")", ParserOptions::default())
+        .do_parse()
+        .ast
+        .unwrap();
+    let json: Value = serde_json::from_str(&ast.to_json()).unwrap();
+
+    assert_eq!(json["type"], "send");
+    assert_eq!(json["children"][0], Value::Null);
+    assert_eq!(json["children"][1], "foo");
+    assert_eq!(json["children"][2]["type"], "str");
+    assert_eq!(json["children"][2]["children"][0], "a\nb");
+
+    let location = &json["location"];
+    assert_eq!(location["expression"]["begin_pos"], 0);
+    assert_eq!(location["expression"]["end_pos"], 11);
+    assert_eq!(location["selector"]["end_pos"], 3);
+    assert_eq!(location["begin"]["begin_pos"], 3);
+    assert_eq!(location["operator"], Value::Null);
+}
+
+#[test]
+fn test_json_escaping() {
+    let ast = Parser::new(b"\"\\\"\\u0001\\u{1F600}\"", ParserOptions::default())
+        .do_parse()
+        .ast
+        .unwrap();
+    let json: Value = serde_json::from_str(&ast.to_json()).unwrap();
+
+    assert_eq!(json["children"][0], "\"\u{1}\u{1F600}");
+}
@@ -0,0 +1,86 @@
+use lib_ruby_parser::source::buffer::{Buffer, Input};
+use lib_ruby_parser::source::{CustomDecoder, Range};
+
+fn input(source: &str) -> Input {
+    Buffer::new(
+        "(lines)",
+        source.as_bytes().to_vec(),
+        CustomDecoder::default(),
+    )
+    .input
+}
+
+#[test]
+fn it_finds_lines_of_offsets() {
+    let input = input("foo\n\nbar\nbaz");
+
+    assert_eq!(input.line_for_pos(0), Some(0));
+    assert_eq!(input.line_for_pos(3), Some(0));
+    assert_eq!(input.line_for_pos(4), Some(1));
+    assert_eq!(input.line_for_pos(5), Some(2));
+    assert_eq!(input.line_for_pos(12), Some(3));
+    assert_eq!(input.line_for_pos(13), None);
+
+    assert_eq!(input.line_col_for_pos(7), Some((2, 2)));
+    assert_eq!(input.line_col_for_pos(12), Some((3, 3)));
+}
+
+#[test]
+fn it_finds_lines_of_offsets_in_empty_input() {
+    let input = input("");
+
+    assert_eq!(input.line_col_for_pos(0), Some((0, 0)));
+    assert_eq!(input.line_col_for_pos(1), None);
+    assert_eq!(Input::default().line_col_for_pos(0), None);
+}
+
+#[test]
+fn it_converts_line_col_to_offsets() {
+    let input = input("foo\n\nbar\n");
+
+    assert_eq!(input.pos_for_line_col(0, 0), Some(0));
+    assert_eq!(input.pos_for_line_col(0, 3), Some(3));
+    assert_eq!(input.pos_for_line_col(0, 4), None);
+    assert_eq!(input.pos_for_line_col(1, 0), Some(4));
+    assert_eq!(input.pos_for_line_col(2, 1), Some(6));
+    assert_eq!(input.pos_for_line_col(3, 0), Some(9));
+    assert_eq!(input.pos_for_line_col(4, 0), None);
+
+    for pos in 0..=input.len() {
+        let (line, col) = input.line_col_for_pos(pos).unwrap();
+        assert_eq!(input.pos_for_line_col(line, col), Some(pos));
+    }
+}
+
+#[test]
+fn it_returns_lines_of_ranges() {
+    let input = input("foo(\n  1,\n  2\n)\n");
+
+    assert_eq!(Range::new(0, 16).lines(&input), Some((0, 3)));
+    assert_eq!(Range::new(7, 8).lines(&input), Some((1, 1)));
+    // a range that ends with "\n" doesn't include the next line
+    assert_eq!(Range::new(5, 10).lines(&input), Some((1, 1)));
+    assert_eq!(Range::new(10, 10).lines(&input), Some((2, 2)));
+    assert_eq!(Range::new(0, 100).lines(&input), None);
+}
+
+#[test]
+fn it_matches_a_linear_search() {
+    let source = (0..1000)
+        .map(|i| "x".repeat(i % 7))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let input = input(&source);
+
+    let mut line = 0;
+    let mut col = 0;
+    for (pos, byte) in source.bytes().enumerate() {
+        assert_eq!(input.line_col_for_pos(pos), Some((line, col)));
+        if byte == b'\n' {
+            line += 1;
+            col = 0;
+        } else {
+            col += 1;
+        }
+    }
+}
@@ -293,6 +293,7 @@ pub struct LocMatcher {
     begin: usize,
     end: usize,
     name: LocName,
+    raw_name: String,
     pattern: Vec<String>,
 }
 
@@ -346,12 +347,14 @@ impl LocMatcher {
             .filter(|e| !e.is_empty())
             .map(|e| e.to_owned())
             .collect::<Vec<_>>();
-        let name = LocName::new(&name);
+        let raw_name = name;
+        let name = LocName::new(&raw_name);
 
         LocMatcher {
             begin,
             end,
             name,
+            raw_name,
             pattern,
         }
     }
@@ -384,4 +387,25 @@ impl LocMatcher {
             None => panic!("failed to find {:?} in {:?}", self.pattern, root.inspect(0)),
         }
     }
+
+    /// Same as `test`, but checks location map of the `Node::to_json` output
+    #[cfg(feature = "json")]
+    #[allow(dead_code)]
+    pub fn test_json(&self, root: &Node) -> Result<(), String> {
+        let node = Find::run(&self.pattern, root)
+            .unwrap()
+            .ok_or_else(|| format!("failed to find {:?}", self.pattern))?;
+        let json = node.to_json_value();
+        let range = &json["location"][&self.raw_name];
+        let expected = serde_json::json!({ "begin_pos": self.begin, "end_pos": self.end });
+
+        if range == &expected {
+            Ok(())
+        } else {
+            Err(format!(
+                "JSON location {:?} of {:?} doesn't match, expected {}, got {}",
+                self.raw_name, self.pattern, expected, range
+            ))
+        }
+    }
 }
@@ -0,0 +1,95 @@
+use lib_ruby_parser::traverse::{Find, Locate};
+use lib_ruby_parser::{token_name, Parser, ParserOptions, ParserResult};
+
+fn parse(src: &str) -> ParserResult {
+    let options = ParserOptions {
+        buffer_name: "(locate_test)".to_owned(),
+        debug: false,
+        ..Default::default()
+    };
+    Parser::new(src.as_bytes(), options).do_parse()
+}
+
+/// Returns source of the located node, types of its ancestors and the path
+fn locate(src: &str, pos: usize) -> Option<(String, Vec<&'static str>, Vec<String>)> {
+    let ParserResult { ast, input, .. } = parse(src);
+    let ast = ast?;
+    let located = Locate::node_at(&ast, pos)?;
+
+    let path = located
+        .path
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>();
+    let found = Find::run(&path, &ast).unwrap();
+    assert_eq!(found.as_ref(), Some(located.node), "path {:?}", path);
+
+    Some((
+        located.node.expression().source(&input).unwrap(),
+        located
+            .ancestors
+            .iter()
+            .map(|node| node.str_type())
+            .collect(),
+        path,
+    ))
+}
+
+#[test]
+fn it_locates_innermost_node() {
+    let src = "foo.bar(1, baz(2))";
+
+    assert_eq!(
+        locate(src, 15),
+        Some((
+            "2".to_owned(),
+            vec!["send", "send"],
+            vec!["arg[1]".to_owned(), "arg[0]".to_owned()]
+        ))
+    );
+    assert_eq!(
+        locate(src, 1),
+        Some(("foo".to_owned(), vec!["send"], vec!["recv".to_owned()]))
+    );
+    assert_eq!(locate(src, 4), Some((src.to_owned(), vec![], vec![])));
+}
+
+#[test]
+fn it_returns_none_outside_of_nodes() {
+    assert_eq!(locate("foo  ", 4), None);
+    assert_eq!(locate("foo", 3), None);
+}
+
+#[test]
+fn it_locates_by_line_and_column() {
+    let ParserResult { ast, input, .. } = parse("def m\n  x + 1\nend");
+    let ast = ast.unwrap();
+
+    let located = Locate::node_at_line_col(&ast, &input, 1, 6).unwrap();
+    assert_eq!(located.node.str_type(), "int");
+    assert_eq!(
+        located
+            .path
+            .iter()
+            .map(|item| item.to_string())
+            .collect::<Vec<_>>(),
+        vec!["body", "arg[0]"]
+    );
+
+    assert!(Locate::node_at_line_col(&ast, &input, 1, 100).is_none());
+    assert!(Locate::node_at_line_col(&ast, &input, 10, 0).is_none());
+}
+
+#[test]
+fn it_locates_tokens() {
+    let ParserResult { tokens, input, .. } = parse("foo.bar(1)\nbaz");
+
+    let token = Locate::token_at(&tokens, 5).unwrap();
+    assert_eq!(token_name(token.token_type), "tIDENTIFIER");
+    assert_eq!(token.clone().into_string_lossy(), "bar");
+
+    let token = Locate::token_at_line_col(&tokens, &input, 1, 2).unwrap();
+    assert_eq!(token.clone().into_string_lossy(), "baz");
+
+    assert!(Locate::token_at(&tokens, 100).is_none());
+}
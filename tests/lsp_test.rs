@@ -0,0 +1,312 @@
+#![cfg(feature = "lsp")]
+
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+const URI: &str = "file:///test.rb";
+
+const SRC: &str = "class Foo
+  def bar(a)
+    a + 1
+  end
+
+  X = <<~EOS
+    text
+  EOS
+end
+=begin
+doc
+=end
+";
+
+/// Scripted JSON-RPC session with the `lsp` binary
+struct Session {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+impl Session {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to start lsp");
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut session = Self {
+            child,
+            stdin,
+            stdout,
+            next_id: 1,
+        };
+        let response = session.request("initialize", json!({ "capabilities": {} }));
+        assert!(response["result"]["capabilities"].is_object());
+        session.notify("initialized", json!({}));
+        session
+    }
+
+    fn send(&mut self, message: Value) {
+        self.send_raw(&message.to_string());
+    }
+
+    fn send_raw(&mut self, content: &str) {
+        write!(
+            self.stdin,
+            "Content-Length: {}\r\n\r\n{}",
+            content.len(),
+            content
+        )
+        .unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    fn receive(&mut self) -> Value {
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            self.stdout.read_line(&mut header).unwrap();
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("Content-Length: ") {
+                content_length = value.parse().unwrap();
+            }
+        }
+        let mut content = vec![0; content_length];
+        self.stdout.read_exact(&mut content).unwrap();
+        serde_json::from_slice(&content).unwrap()
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+        let response = self.receive();
+        assert_eq!(response["id"], id);
+        response
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    fn open(&mut self, text: &str) -> Value {
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": { "uri": URI, "languageId": "ruby", "version": 1, "text": text }
+            }),
+        );
+        self.published_diagnostics()
+    }
+
+    fn published_diagnostics(&mut self) -> Value {
+        let notification = self.receive();
+        assert_eq!(notification["method"], "textDocument/publishDiagnostics");
+        assert_eq!(notification["params"]["uri"], URI);
+        notification["params"]["diagnostics"].clone()
+    }
+
+    fn document_request(&mut self, method: &str, extra: Value) -> Value {
+        let mut params = json!({ "textDocument": { "uri": URI } });
+        if let (Some(params), Some(extra)) = (params.as_object_mut(), extra.as_object()) {
+            params.extend(extra.clone());
+        }
+        self.request(method, params)["result"].clone()
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+fn range(start_line: u64, start_char: u64, end_line: u64, end_char: u64) -> Value {
+    json!({
+        "start": { "line": start_line, "character": start_char },
+        "end": { "line": end_line, "character": end_char },
+    })
+}
+
+#[test]
+fn test_diagnostics() {
+    let mut session = Session::start();
+
+    let diagnostics = session.open("def foo(");
+    assert!(!diagnostics.as_array().unwrap().is_empty());
+    assert_eq!(diagnostics[0]["severity"], 1);
+
+    session.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "def foo; end" }]
+        }),
+    );
+    assert_eq!(session.published_diagnostics(), json!([]));
+
+    session.notify(
+        "textDocument/didClose",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    assert_eq!(session.published_diagnostics(), json!([]));
+}
+
+#[test]
+fn test_document_symbols() {
+    let mut session = Session::start();
+    session.open(SRC);
+
+    let symbols = session.document_request("textDocument/documentSymbol", json!({}));
+
+    assert_eq!(symbols.as_array().unwrap().len(), 1);
+    let class = &symbols[0];
+    assert_eq!(class["name"], "Foo");
+    assert_eq!(class["kind"], 5);
+    assert_eq!(class["range"], range(0, 0, 8, 3));
+    assert_eq!(class["selectionRange"], range(0, 6, 0, 9));
+
+    let children = class["children"].as_array().unwrap();
+    assert_eq!(children.len(), 2);
+    assert_eq!(children[0]["name"], "bar");
+    assert_eq!(children[0]["kind"], 6);
+    assert_eq!(children[0]["selectionRange"], range(1, 6, 1, 9));
+    assert_eq!(children[1]["name"], "X");
+    assert_eq!(children[1]["kind"], 14);
+}
+
+#[test]
+fn test_folding_ranges() {
+    let mut session = Session::start();
+    session.open(SRC);
+
+    let ranges = session.document_request("textDocument/foldingRange", json!({}));
+
+    assert_eq!(
+        ranges,
+        json!([
+            { "startLine": 0, "endLine": 7 },
+            { "startLine": 1, "endLine": 2 },
+            { "startLine": 5, "endLine": 7 },
+            { "startLine": 9, "endLine": 11, "kind": "comment" },
+        ])
+    );
+}
+
+#[test]
+fn test_selection_ranges() {
+    let mut session = Session::start();
+    session.open(SRC);
+
+    let ranges = session.document_request(
+        "textDocument/selectionRange",
+        json!({ "positions": [{ "line": 2, "character": 4 }] }),
+    );
+
+    // a -> a + 1 -> def -> class body -> class
+    let lvar = &ranges[0];
+    assert_eq!(lvar["range"], range(2, 4, 2, 5));
+    let send = &lvar["parent"];
+    assert_eq!(send["range"], range(2, 4, 2, 9));
+    let def = &send["parent"];
+    assert_eq!(def["range"], range(1, 2, 3, 5));
+    let class = &def["parent"]["parent"];
+    assert_eq!(class["range"], range(0, 0, 8, 3));
+    assert!(class.get("parent").is_none());
+}
+
+#[test]
+fn test_semantic_tokens() {
+    let mut session = Session::start();
+    session.open("def foo(a) # comment\n  a\nend");
+
+    let data = session.document_request("textDocument/semanticTokens/full", json!({}))["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n.as_u64().unwrap())
+        .collect::<Vec<_>>();
+
+    // decode relative positions
+    let mut tokens = vec![];
+    let (mut line, mut col) = (0, 0);
+    for token in data.chunks(5) {
+        if token[0] > 0 {
+            col = 0;
+        }
+        line += token[0];
+        col += token[1];
+        tokens.push((line, col, token[2], token[3], token[4]));
+    }
+
+    assert_eq!(
+        tokens,
+        vec![
+            (0, 0, 3, 0, 0),  // def: keyword
+            (0, 4, 3, 7, 1),  // foo: method declaration
+            (0, 8, 1, 6, 1),  // a: parameter declaration
+            (0, 11, 9, 4, 0), // # comment
+            (1, 2, 1, 5, 0),  // a: variable
+            (2, 0, 3, 0, 0),  // end: keyword
+        ]
+    );
+}
+
+#[test]
+fn test_unknown_method() {
+    let mut session = Session::start();
+    let response = session.request("textDocument/hover", json!({}));
+    assert_eq!(response["error"]["code"], -32601);
+}
+
+#[test]
+fn test_invalid_json() {
+    let mut session = Session::start();
+
+    session.send_raw("{\"jsonrpc\": \"2.0\", \"id\": 1,");
+    let response = session.receive();
+    assert_eq!(response["id"], Value::Null);
+    assert_eq!(response["error"]["code"], -32700);
+
+    // the server keeps reading messages
+    assert_eq!(session.open("foo").as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn test_change_without_text() {
+    let mut session = Session::start();
+    session.open("def foo; end");
+
+    session.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "range": range(0, 0, 0, 0) }]
+        }),
+    );
+    let notification = session.receive();
+    assert_eq!(notification["method"], "window/logMessage");
+    assert_eq!(notification["params"]["type"], 1);
+
+    // the document hasn't been emptied
+    let symbols = session.document_request("textDocument/documentSymbol", json!({}));
+    assert_eq!(symbols[0]["name"], "foo");
+}
+
+#[test]
+fn test_shutdown_and_exit() {
+    let mut session = Session::start();
+
+    let response = session.request("shutdown", Value::Null);
+    assert_eq!(response["result"], Value::Null);
+    session.notify("exit", Value::Null);
+
+    let status = session.child.wait().unwrap();
+    assert!(status.success());
+}
@@ -0,0 +1,167 @@
+#![cfg(feature = "mri-ast")]
+
+use lib_ruby_parser::mri_ast::{self, MriNode, MriValue};
+use lib_ruby_parser::{Parser, ParserOptions, ParserResult};
+use std::fs;
+use std::path::Path;
+
+mod files_under_dir;
+use files_under_dir::files_under_dir;
+
+fn convert(src: &str) -> MriNode {
+    let options = ParserOptions {
+        buffer_name: "(mri_ast_test)".to_owned(),
+        debug: false,
+        ..Default::default()
+    };
+    let ParserResult { ast, input, .. } = Parser::new(src.as_bytes(), options).do_parse();
+    mri_ast::convert(ast.as_ref(), &input)
+}
+
+fn body(scope: &MriNode) -> &MriNode {
+    assert_eq!(scope.node_type, "SCOPE");
+    scope.children[2].as_node().expect("SCOPE has a body")
+}
+
+fn assert_converts(src: &str, expected: &str) {
+    assert_eq!(convert(src).inspect(), expected, "source: {:?}", src)
+}
+
+#[test]
+fn it_converts_calls() {
+    assert_converts("foo", "(SCOPE@1:0-1:3 [] nil (VCALL@1:0-1:3 :foo))");
+    assert_converts(
+        "foo.bar(1, 2)",
+        "(SCOPE@1:0-1:13 [] nil (CALL@1:0-1:13 (VCALL@1:0-1:3 :foo) :bar (LIST@1:8-1:12 (LIT@1:8-1:9 1) (LIT@1:11-1:12 2) nil)))",
+    );
+    assert_converts(
+        "1 + 2",
+        "(SCOPE@1:0-1:5 [] nil (OPCALL@1:0-1:5 (LIT@1:0-1:1 1) :+ (LIST@1:4-1:5 (LIT@1:4-1:5 2) nil)))",
+    );
+    assert_converts(
+        "foo(1, *a, 2)",
+        "(SCOPE@1:0-1:13 [] nil (FCALL@1:0-1:13 :foo (ARGSPUSH@1:4-1:12 (ARGSCAT@1:4-1:9 (LIST@1:4-1:5 (LIT@1:4-1:5 1) nil) (VCALL@1:8-1:9 :a)) (LIT@1:11-1:12 2))))",
+    );
+}
+
+#[test]
+fn it_tracks_local_variables() {
+    assert_converts(
+        "a = 1; a",
+        "(SCOPE@1:0-1:8 [:a] nil (BLOCK@1:0-1:8 (LASGN@1:0-1:5 :a (LIT@1:4-1:5 1)) (LVAR@1:7-1:8 :a)))",
+    );
+    assert_converts(
+        "[1].each { |x| y = x }",
+        "(SCOPE@1:0-1:22 [] nil (ITER@1:0-1:22 (CALL@1:0-1:8 (LIST@1:0-1:3 (LIT@1:1-1:2 1) nil) :each nil) (SCOPE@1:9-1:22 [:x, :y] (ARGS@1:12-1:13 1 nil nil nil 0 nil nil nil nil nil) (DASGN_CURR@1:15-1:20 :y (DVAR@1:19-1:20 :x)))))",
+    );
+}
+
+#[test]
+fn it_converts_definitions() {
+    let defn = convert("def foo(a, b = 1, *c, d:, &e); end");
+    let defn = body(&defn);
+    assert_eq!(defn.node_type, "DEFN");
+    assert_eq!(defn.children[0], MriValue::Symbol("foo".to_owned()));
+
+    let scope = defn.children[1].as_node().unwrap();
+    assert_eq!(scope.children[0].inspect(), "[:a, :b, :c, :d, nil, :e]");
+
+    let args = scope.children[1].as_node().unwrap();
+    assert_eq!(args.node_type, "ARGS");
+    assert_eq!(
+        args.children
+            .iter()
+            .map(|child| child.inspect())
+            .collect::<Vec<_>>(),
+        vec![
+            "1",
+            "nil",
+            "(OPT_ARG@1:11-1:16 (LASGN@1:11-1:16 :b (LIT@1:15-1:16 1)) nil)",
+            "nil",
+            "0",
+            "nil",
+            ":c",
+            "(KW_ARG@1:22-1:24 (LASGN@1:22-1:24 :d :NODE_SPECIAL_REQUIRED_KEYWORD) nil)",
+            "nil",
+            ":e",
+        ]
+    );
+
+    assert_converts(
+        "class Foo < Bar; end",
+        "(SCOPE@1:0-1:20 [] nil (CLASS@1:0-1:20 (COLON2@1:6-1:9 nil :Foo) (CONST@1:12-1:15 :Bar) (SCOPE@1:0-1:20 [] nil (BEGIN@1:15-1:15 nil))))",
+    );
+}
+
+#[test]
+fn it_nests_logical_operators_to_the_right() {
+    assert_converts(
+        "a && b && c",
+        "(SCOPE@1:0-1:11 [] nil (AND@1:0-1:11 (VCALL@1:0-1:1 :a) (AND@1:0-1:11 (VCALL@1:5-1:6 :b) (VCALL@1:10-1:11 :c))))",
+    );
+}
+
+#[test]
+fn it_converts_literals() {
+    assert_converts(
+        "\"a#{b}c\"",
+        "(SCOPE@1:0-1:8 [] nil (DSTR@1:0-1:8 \"a\" (EVSTR@1:2-1:6 (VCALL@1:4-1:5 :b)) (LIST@1:6-1:7 (STR@1:6-1:7 \"c\") nil)))",
+    );
+    assert_converts("0x10", "(SCOPE@1:0-1:4 [] nil (LIT@1:0-1:4 16))");
+    assert_converts("1e20", "(SCOPE@1:0-1:4 [] nil (LIT@1:0-1:4 1.0e+20))");
+    assert_converts("1.5r", "(SCOPE@1:0-1:4 [] nil (LIT@1:0-1:4 (3/2)))");
+    assert_converts("2i", "(SCOPE@1:0-1:2 [] nil (LIT@1:0-1:2 (0+2i)))");
+    assert_converts(":\"a b\"", "(SCOPE@1:0-1:6 [] nil (LIT@1:0-1:6 :\"a b\"))");
+    assert_converts("/a/ix", "(SCOPE@1:0-1:5 [] nil (LIT@1:0-1:5 /a/ix))");
+}
+
+#[test]
+fn it_reports_multiline_locations() {
+    let scope = convert("foo(\n  1\n)");
+    let fcall = body(&scope);
+    assert_eq!(
+        (
+            fcall.first_lineno,
+            fcall.first_column,
+            fcall.last_lineno,
+            fcall.last_column
+        ),
+        (1, 0, 3, 1)
+    );
+    assert_eq!(
+        fcall.children[1].inspect(),
+        "(LIST@2:2-2:3 (LIT@2:2-2:3 1) nil)"
+    );
+}
+
+#[test]
+fn it_converts_empty_input() {
+    assert_converts("", "(SCOPE@1:0-1:0 [] nil nil)");
+}
+
+/// Every fixture is a directory with `input.rb` and `mri`,
+/// the output of `RubyVM::AbstractSyntaxTree` written by `tests/generate_mri_ast_fixtures.rb`
+#[test]
+fn test_mri_ast_fixtures() {
+    let mut missing = vec![];
+    for dir in files_under_dir("tests/fixtures/mri_ast") {
+        let expected_path = format!("{}/mri", dir);
+        if !Path::new(&expected_path).exists() {
+            missing.push(dir);
+            continue;
+        }
+        let input = fs::read_to_string(format!("{}/input.rb", dir)).unwrap();
+        let expected = fs::read_to_string(&expected_path).unwrap();
+        assert_eq!(
+            convert(&input).inspect(),
+            expected.trim_end(),
+            "fixture {}",
+            dir
+        );
+    }
+    assert!(
+        missing.is_empty(),
+        "fixtures {:?} have no `mri`, run `ruby tests/generate_mri_ast_fixtures.rb` with Ruby 3.0",
+        missing
+    );
+}
@@ -0,0 +1,127 @@
+use lib_ruby_parser::{Node, Parser, ParserOptions, ParserResult};
+use std::fs;
+
+mod files_under_dir;
+use files_under_dir::files_under_dir;
+
+fn parse(src: &[u8]) -> ParserResult {
+    Parser::new(src, ParserOptions::default()).do_parse()
+}
+
+fn error_messages(src: &[u8]) -> Vec<String> {
+    parse(src)
+        .diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .map(|d| d.render_message())
+        .collect()
+}
+
+#[test]
+fn test_non_ascii_argument_names() {
+    assert_eq!(
+        error_messages("def m(é, é); end".as_bytes()),
+        vec!["duplicated argument name"]
+    );
+    assert_eq!(
+        error_messages(b"def m(a\xFF, a\xFF); end"),
+        vec![
+            "invalid multibyte char (UTF-8)",
+            "invalid multibyte char (UTF-8)",
+            "duplicated argument name"
+        ]
+    );
+}
+
+#[test]
+fn test_non_ascii_pattern_keys() {
+    assert_eq!(
+        error_messages("case 1; in {\"é\": 1}; end".as_bytes()),
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        error_messages("case 1; in {\"é!\": 1}; end".as_bytes()),
+        vec!["key must be valid as local variables"]
+    );
+    assert_eq!(
+        error_messages(b"case 1; in {\"\": 1}; end"),
+        vec!["key must be valid as local variables"]
+    );
+}
+
+#[test]
+fn test_invalid_codepoints() {
+    assert_eq!(
+        error_messages(b"\"\\uD800\""),
+        vec!["invalid Unicode codepoint"]
+    );
+    assert_eq!(
+        error_messages(b"\"\\u{110000}\""),
+        vec!["invalid Unicode codepoint (too large)"]
+    );
+}
+
+#[test]
+fn test_safe_navigation_call_without_method_name() {
+    let result = parse(b"foo&.()");
+    assert!(result.diagnostics.is_empty());
+    match result.ast {
+        Some(Node::CSend(csend)) => {
+            assert_eq!(csend.method_name, "call");
+            assert_eq!(csend.dot_l.to_range(), 3..5);
+            assert_eq!(csend.selector_l.to_range(), 5..5);
+        }
+        other => panic!("expected csend, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_squiggly_heredoc_with_variables() {
+    let result = parse(b"<<~HEREDOC\n  a #@b #$c #@@d\n    e\nHEREDOC\n");
+    assert!(result.diagnostics.is_empty());
+    match result.ast {
+        Some(Node::Heredoc(heredoc)) => assert_eq!(
+            heredoc
+                .parts
+                .iter()
+                .map(|part| part.str_type())
+                .filter(|str_type| *str_type != "str")
+                .collect::<Vec<_>>(),
+            vec!["ivar", "gvar", "cvar"]
+        ),
+        other => panic!("expected heredoc, got {:?}", other),
+    }
+}
+
+fn fixture_input(path: &str) -> Vec<u8> {
+    let content = fs::read(path).unwrap_or_else(|_| panic!("failed to read file {:?}", path));
+    let mut input = vec![];
+    let mut in_input = false;
+
+    for line in content.split(|byte| *byte == b'\n') {
+        if line.starts_with(b"--") && line[2..].iter().all(|c| c.is_ascii_uppercase()) {
+            in_input = line == b"--INPUT";
+            continue;
+        }
+        if in_input {
+            input.extend_from_slice(line);
+            input.push(b'\n');
+        }
+    }
+    input.pop();
+    input
+}
+
+// Unterminated strings, heredocs, blocks etc. are the most common
+// inputs of editors, so every prefix of every fixture must be handled
+#[test]
+fn test_fixture_prefixes_dont_panic() {
+    for dir in &["tests/fixtures/parser/gen", "tests/fixtures/parser/manual"] {
+        for path in files_under_dir(dir) {
+            let input = fixture_input(&path);
+            for len in 0..input.len() {
+                parse(&input[..len]);
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use lib_ruby_parser::source::{CustomDecoder, InputError, Range, RecognizedEncoding};
+use lib_ruby_parser::{Parser, ParserOptions, ParserResult};
+
+fn decode_latin1(encoding: RecognizedEncoding, input: &[u8]) -> Result<Vec<u8>, InputError> {
+    match encoding {
+        RecognizedEncoding::ISO_8859_1 => Ok(input
+            .iter()
+            .map(|byte| *byte as char)
+            .collect::<String>()
+            .into_bytes()),
+        _ => Err(InputError::NoDecoder(encoding)),
+    }
+}
+
+fn parse(src: &[u8], decoder: CustomDecoder) -> ParserResult {
+    let options = ParserOptions {
+        decoder,
+        ..Default::default()
+    };
+    Parser::new(src, options).do_parse()
+}
+
+fn find(bytes: &[u8], needle: &[u8]) -> Range {
+    let begin = bytes
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .unwrap();
+    Range::new(begin, begin + needle.len())
+}
+
+const LATIN1_SRC: &[u8] = b"# encoding: iso-8859-1\nfoo(\"caf\xE9\", bar)\n";
+
+#[test]
+fn test_not_transcoded() {
+    let result = parse(b"foo(bar)", CustomDecoder::default());
+    let input = &result.input;
+
+    assert!(!input.is_transcoded());
+    assert_eq!(input.original_bytes(), b"foo(bar)");
+    assert_eq!(input.original_pos(4), 4);
+    assert_eq!(input.decoded_pos(4), 4);
+    assert_eq!(Range::new(4, 7).to_original(input), Range::new(4, 7));
+}
+
+#[test]
+fn test_transcoded_keeps_original_bytes() {
+    let result = parse(
+        LATIN1_SRC,
+        CustomDecoder {
+            f: Some(Box::new(decode_latin1)),
+        },
+    );
+    let input = &result.input;
+
+    assert!(result.diagnostics.is_empty());
+    assert!(input.is_transcoded());
+    assert_eq!(input.original_bytes(), LATIN1_SRC);
+    assert_eq!(input.len(), LATIN1_SRC.len() + 1);
+}
+
+#[test]
+fn test_ranges_to_original() {
+    let result = parse(
+        LATIN1_SRC,
+        CustomDecoder {
+            f: Some(Box::new(decode_latin1)),
+        },
+    );
+    let input = &result.input;
+
+    let decoded_bar = find(&input.bytes, b"bar");
+    let original_bar = find(LATIN1_SRC, b"bar");
+    assert_eq!(decoded_bar.to_original(input), original_bar);
+    assert_eq!(original_bar.from_original(input), decoded_bar);
+
+    let decoded_str = find(&input.bytes, "\"café\"".as_bytes());
+    let original_str = find(LATIN1_SRC, b"\"caf\xE9\"");
+    assert_eq!(decoded_str.to_original(input), original_str);
+    assert_eq!(original_str.from_original(input), decoded_str);
+
+    let eof = Range::new(input.len(), input.len());
+    assert_eq!(
+        eof.to_original(input),
+        Range::new(LATIN1_SRC.len(), LATIN1_SRC.len())
+    );
+}
+
+#[test]
+fn test_offsets_inside_non_ascii_character() {
+    let result = parse(
+        LATIN1_SRC,
+        CustomDecoder {
+            f: Some(Box::new(decode_latin1)),
+        },
+    );
+    let input = &result.input;
+
+    // "é" is 2 bytes in UTF-8 and 1 byte in Latin-1
+    let e_acute = find(&input.bytes, "é".as_bytes());
+    let original_e_acute = find(LATIN1_SRC, b"\xE9");
+    assert_eq!(
+        input.original_pos(e_acute.begin_pos + 1),
+        original_e_acute.begin_pos
+    );
+    assert_eq!(
+        Range::new(e_acute.begin_pos + 1, e_acute.end_pos).to_original(input),
+        original_e_acute
+    );
+}
+
+// Decodes "あ" (0xA4 0xA2) and "い" (0xA4 0xA4) of EUC-JP
+fn decode_euc_jp(encoding: RecognizedEncoding, input: &[u8]) -> Result<Vec<u8>, InputError> {
+    if encoding != RecognizedEncoding::EUC_JP {
+        return Err(InputError::NoDecoder(encoding));
+    }
+    let mut output = String::new();
+    let mut bytes = input.iter();
+    while let Some(byte) = bytes.next() {
+        match (byte, bytes.as_slice().first()) {
+            (byte, _) if byte.is_ascii() => output.push(*byte as char),
+            (0xA4, Some(0xA2)) => output.push('あ'),
+            (0xA4, Some(0xA4)) => output.push('い'),
+            _ => return Err(InputError::DecodingError("invalid EUC-JP".to_owned())),
+        }
+        if !byte.is_ascii() {
+            bytes.next();
+        }
+    }
+    Ok(output.into_bytes())
+}
+
+#[test]
+fn test_offsets_inside_non_ascii_run() {
+    let src = b"# encoding: iso-8859-1\n'\xE9\xE8\xEA'\n";
+    let result = parse(
+        src,
+        CustomDecoder {
+            f: Some(Box::new(decode_latin1)),
+        },
+    );
+    let input = &result.input;
+
+    let e_grave = find(&input.bytes, "è".as_bytes());
+    assert_eq!(e_grave.to_original(input), find(src, b"\xE8"));
+    assert_eq!(
+        find(src, b"\xEA").from_original(input),
+        find(&input.bytes, "ê".as_bytes())
+    );
+
+    let src = b"# encoding: euc-jp\nfoo('\xA4\xA2\xA4\xA4\xA4\xA2', bar)\n";
+    let result = parse(
+        src,
+        CustomDecoder {
+            f: Some(Box::new(decode_euc_jp)),
+        },
+    );
+    let input = &result.input;
+    assert!(result.diagnostics.is_empty());
+
+    // "い" is in the middle of the run, 3 bytes in UTF-8 and 2 bytes in EUC-JP
+    let i = find(&input.bytes, "い".as_bytes());
+    let original_i = find(src, b"\xA4\xA4");
+    assert_eq!(i.to_original(input), original_i);
+    assert_eq!(original_i.from_original(input), i);
+    assert_eq!(input.original_pos(i.begin_pos + 1), original_i.begin_pos);
+    assert_eq!(input.decoded_pos(original_i.begin_pos + 1), i.begin_pos);
+}
+
+#[test]
+fn test_decoder_that_rewrites_input() {
+    let src = b"# encoding: us-ascii\n3 + 3";
+    let result = parse(
+        src,
+        CustomDecoder {
+            f: Some(Box::new(|_, _| Ok(b"# encoding: us-ascii\n2 + 2".to_vec()))),
+        },
+    );
+    let input = &result.input;
+
+    assert!(input.is_transcoded());
+    assert_eq!(input.original_pos(2), 2);
+    for pos in 0..=input.len() {
+        assert!(input.original_pos(pos) <= src.len());
+    }
+    assert_eq!(
+        Range::new(0, input.len()).to_original(input),
+        Range::new(0, src.len())
+    );
+}
@@ -0,0 +1,331 @@
+use lib_ruby_parser::{Parser, ParserOptions, ParserResult, SourceTokenValue};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
+
+fn parse(src: &str, options: ParserOptions) -> ParserResult {
+    Parser::new(src.as_bytes(), options).do_parse()
+}
+
+const SRC: &str = "# encoding: utf-8\n# comment\nfoo + 1 # trailing\n";
+
+#[test]
+fn test_records_tokens_and_comments_by_default() {
+    let result = parse(SRC, ParserOptions::default());
+
+    assert!(!result.tokens.is_empty());
+    assert_eq!(result.comments.len(), 3);
+    assert_eq!(result.magic_comments.len(), 1);
+}
+
+#[test]
+fn test_record_tokens_disabled() {
+    let options = ParserOptions {
+        record_tokens: false,
+        ..Default::default()
+    };
+    let result = parse(SRC, options);
+
+    assert!(result.tokens.is_empty());
+    assert!(result.ast.is_some());
+    assert_eq!(result.comments.len(), 3);
+}
+
+#[test]
+fn test_record_comments_disabled() {
+    let options = ParserOptions {
+        record_comments: false,
+        ..Default::default()
+    };
+    let result = parse(SRC, options);
+
+    assert!(result.comments.is_empty());
+    assert!(result.magic_comments.is_empty());
+    assert!(!result.tokens.is_empty());
+    assert!(result.ast.is_some());
+}
+
+#[test]
+fn test_record_source_tokens() {
+    let options = ParserOptions {
+        record_source_tokens: true,
+        ..Default::default()
+    };
+    let result = parse("foo = \"a\\tb\"\nbar(foo)\n", options);
+
+    assert_eq!(result.source_tokens.len(), result.tokens.len());
+    for (source_token, token) in result.source_tokens.iter().zip(result.tokens.iter()) {
+        assert_eq!(source_token.token_type, token.token_type);
+        assert_eq!(source_token.loc.to_range(), token.loc.begin..token.loc.end);
+        assert_eq!(
+            source_token.value_bytes(&result.input),
+            Some(token.token_value.as_bytes())
+        );
+    }
+
+    let value_of = |bytes: &[u8]| {
+        result
+            .source_tokens
+            .iter()
+            .find(|token| token.value_bytes(&result.input) == Some(bytes))
+            .map(|token| &token.value)
+    };
+    assert!(matches!(value_of(b"foo"), Some(SourceTokenValue::Range(_))));
+    assert!(matches!(
+        value_of(b"a\tb"),
+        Some(SourceTokenValue::Owned(_))
+    ));
+}
+
+#[test]
+fn test_record_only_source_tokens() {
+    let options = ParserOptions {
+        record_tokens: false,
+        record_source_tokens: true,
+        ..Default::default()
+    };
+    let result = parse(SRC, options);
+
+    assert!(result.tokens.is_empty());
+    assert!(!result.source_tokens.is_empty());
+    assert!(result.ast.is_some());
+}
+
+fn error_messages(result: &ParserResult) -> Vec<String> {
+    result
+        .diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .map(|d| d.render_message())
+        .collect()
+}
+
+#[test]
+fn test_max_input_size() {
+    let options = ParserOptions {
+        max_input_size: Some(4),
+        ..Default::default()
+    };
+    let result = parse("1 + 2 + 3", options);
+
+    assert!(result.ast.is_none());
+    assert_eq!(
+        error_messages(&result),
+        vec!["input is too large (more than 4 bytes)"]
+    );
+}
+
+#[test]
+fn test_max_tokens() {
+    let options = ParserOptions {
+        max_tokens: Some(3),
+        ..Default::default()
+    };
+    let result = parse("1 + 2 + 3", options);
+
+    assert!(error_messages(&result).contains(&"too many tokens (more than 3)".to_owned()));
+}
+
+#[test]
+fn test_max_depth() {
+    let src = format!("{}{}", "[".repeat(100), "]".repeat(100));
+
+    let options = ParserOptions {
+        max_depth: Some(10),
+        ..Default::default()
+    };
+    let result = parse(&src, options);
+    assert!(
+        error_messages(&result).contains(&"nesting is too deep (more than 10 levels)".to_owned())
+    );
+
+    let options = ParserOptions {
+        max_depth: Some(100),
+        ..Default::default()
+    };
+    let result = parse(&src, options);
+    assert!(error_messages(&result).is_empty());
+}
+
+fn assert_too_deep(src: &str) {
+    let options = ParserOptions {
+        max_depth: Some(1000),
+        ..Default::default()
+    };
+    let result = parse(src, options);
+    assert!(
+        error_messages(&result).contains(&"nesting is too deep (more than 1000 levels)".to_owned()),
+        "expected {:?}... to be too deep",
+        &src[..20]
+    );
+}
+
+const DEEP: usize = 100_000;
+
+#[test]
+fn test_max_depth_without_brackets() {
+    // right-recursive rules
+    assert_too_deep(&format!("{}x", "!".repeat(DEEP)));
+    assert_too_deep(&format!("{}x{}", "-(".repeat(DEEP), ")".repeat(DEEP)));
+    assert_too_deep(&format!("{}x", "p ".repeat(DEEP)));
+
+    // left-recursive rules
+    assert_too_deep(&format!("a{}", ".b".repeat(DEEP)));
+    assert_too_deep(&format!("1{}", "+1".repeat(DEEP)));
+}
+
+#[test]
+fn test_max_depth_nested_defs() {
+    let src = format!("{}{}", "def m\n".repeat(DEEP), "end\n".repeat(DEEP));
+    assert_too_deep(&src);
+}
+
+fn assert_depth(src: &str, depth: usize) {
+    for (max_depth, too_deep) in &[(depth, false), (depth - 1, true)] {
+        let options = ParserOptions {
+            max_depth: Some(*max_depth),
+            ..Default::default()
+        };
+        let result = parse(src, options);
+        assert_eq!(
+            !error_messages(&result).is_empty(),
+            *too_deep,
+            "depth of {:?} with max_depth = {}",
+            src,
+            max_depth
+        );
+    }
+}
+
+#[test]
+fn test_max_depth_is_exact() {
+    assert_depth("a.b.c", 3);
+    assert_depth("[[1]]", 3);
+    assert_depth("x = [1, [2]]", 4);
+    assert_depth("def m(a, (b, c)); end", 4);
+    assert_depth("foo(<<~A)\n  #{[[1]]}\nA\n", 6);
+    assert_depth("a = 1\nb = [[a]]\nc = a", 5);
+}
+
+#[test]
+fn test_cancelled() {
+    let options = ParserOptions {
+        cancelled: Some(Arc::new(AtomicBool::new(true))),
+        ..Default::default()
+    };
+    let result = parse("1 + 2", options);
+
+    assert!(error_messages(&result).contains(&"parsing has been cancelled".to_owned()));
+}
+
+#[test]
+fn test_deadline() {
+    let options = ParserOptions {
+        deadline: Some(Instant::now()),
+        ..Default::default()
+    };
+    let result = parse("1 + 2", options);
+
+    assert!(error_messages(&result).contains(&"parsing has exceeded its deadline".to_owned()));
+}
+
+#[test]
+fn test_us_ascii_source() {
+    let result = parse("# encoding: us-ascii\n'foo'", ParserOptions::default());
+
+    assert!(error_messages(&result).is_empty());
+    assert_eq!(result.ast.unwrap().value(), Some(&b"foo"[..]));
+}
+
+#[test]
+fn test_us_ascii_source_with_high_bytes() {
+    let result = parse("# encoding: us-ascii\n'при'", ParserOptions::default());
+
+    assert_eq!(
+        error_messages(&result),
+        vec!["encoding error: DecodingError(\"invalid byte sequence in US-ASCII at 22: \\\\xD0\")"]
+    );
+}
+
+fn ast(result: ParserResult) -> String {
+    result.ast.unwrap().inspect(0)
+}
+
+#[test]
+fn test_local_variables() {
+    let options = ParserOptions {
+        local_variables: vec!["foo".to_owned()],
+        ..Default::default()
+    };
+    let result = parse("foo [1]", options);
+
+    assert!(error_messages(&result).is_empty());
+    assert_eq!(
+        ast(result),
+        "s(:index,\n  s(:lvar, \"foo\"),\n  s(:int, \"1\"))"
+    );
+
+    let result = parse("foo [1]", ParserOptions::default());
+    assert_eq!(
+        ast(result),
+        "s(:send, nil, \"foo\",\n  s(:array,\n    s(:int, \"1\")))"
+    );
+}
+
+#[test]
+fn test_local_variables_in_blocks_and_methods() {
+    let options = ParserOptions {
+        local_variables: vec!["foo".to_owned()],
+        ..Default::default()
+    };
+    let result = parse("bar { foo }; def m; foo; end", options);
+
+    assert_eq!(
+        ast(result),
+        "s(:begin,\n  s(:block,\n    s(:send, nil, \"bar\"), nil,\n    s(:lvar, \"foo\")),\n  s(:def, \"m\", nil,\n    s(:send, nil, \"foo\")))"
+    );
+}
+
+#[test]
+fn test_outer_numbered_parameters() {
+    let options = ParserOptions {
+        local_variables: vec!["_1".to_owned()],
+        ..Default::default()
+    };
+    let result = parse("_1 + 1", options);
+
+    assert!(error_messages(&result).is_empty());
+    assert_eq!(
+        ast(result),
+        "s(:send,\n  s(:lvar, \"_1\"), \"+\",\n  s(:int, \"1\"))"
+    );
+}
+
+#[test]
+fn test_in_def() {
+    let in_def = || ParserOptions {
+        in_def: true,
+        ..Default::default()
+    };
+
+    assert!(error_messages(&parse("class A; end", ParserOptions::default())).is_empty());
+    assert!(error_messages(&parse("class A; end", in_def()))
+        .contains(&"class definition in method body".to_owned()));
+    assert!(error_messages(&parse("A = 1", in_def()))
+        .contains(&"dynamic constant assignment".to_owned()));
+}
+
+#[test]
+fn test_forward_args() {
+    assert!(error_messages(&parse("foo(...)", ParserOptions::default()))
+        .contains(&"unexpected tBDOT3".to_owned()));
+
+    let options = ParserOptions {
+        forward_args: true,
+        ..Default::default()
+    };
+    let result = parse("foo(...)", options);
+
+    assert!(error_messages(&result).is_empty());
+    assert_eq!(ast(result), "s(:send, nil, \"foo\",\n  s(:forwarded_args))");
+}
@@ -0,0 +1,56 @@
+import unittest
+
+import lib_ruby_parser
+
+
+class TestParse(unittest.TestCase):
+    def test_node_fields(self):
+        node = lib_ruby_parser.parse("foo(42)", "(test)").ast
+
+        self.assertEqual(node.type, "send")
+        self.assertEqual(node.fields, ["recv", "method_name", "args"])
+        self.assertIsNone(node.recv)
+        self.assertEqual(node.method_name, "foo")
+        self.assertEqual(node.args[0].type, "int")
+        self.assertEqual(node.args[0].value, "42")
+        self.assertEqual(node.children[0].source, "42")
+
+        with self.assertRaises(AttributeError):
+            node.unknown
+
+    def test_node_location(self):
+        node = lib_ruby_parser.parse("a\n  .b", "(test)").ast
+
+        self.assertEqual(node.source, "a\n  .b")
+        selector = node.location["selector"]
+        self.assertEqual((selector.begin, selector.end), (5, 6))
+        self.assertEqual((selector.line, selector.column), (2, 3))
+        self.assertIsNone(node.location["begin"])
+
+    def test_tokens(self):
+        tokens = lib_ruby_parser.parse("foo", "(test)").tokens
+
+        self.assertEqual(tokens[0].name, "tIDENTIFIER")
+        self.assertEqual(tokens[0].value, "foo")
+        self.assertEqual((tokens[0].location.begin, tokens[0].location.end), (0, 3))
+
+    def test_diagnostics(self):
+        result = lib_ruby_parser.parse("foo(", "(test)")
+
+        self.assertIsNone(result.ast)
+        self.assertEqual(result.diagnostics[0].level, "error")
+        self.assertIn("(test):1:", result.diagnostics[0].rendered)
+
+    def test_bytes_source(self):
+        node = lib_ruby_parser.parse(b"'\xff'").ast
+
+        self.assertEqual(node.type, "str")
+        self.assertEqual(node.value, b"\xff")
+
+    def test_invalid_source(self):
+        with self.assertRaises(TypeError):
+            lib_ruby_parser.parse(42)
+
+
+if __name__ == "__main__":
+    unittest.main()
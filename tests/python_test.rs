@@ -0,0 +1,46 @@
+// Python test loads the cdylib as an extension module,
+// so it's built together with the library by cargo.
+#![cfg(feature = "python")]
+
+use std::path::PathBuf;
+use std::process::Command;
+
+fn target_dir() -> PathBuf {
+    let mut path = std::env::current_exe().expect("failed to get current exe");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path
+}
+
+#[test]
+fn test_python_bindings() {
+    let target_dir = target_dir();
+    let dylib = if cfg!(target_os = "macos") {
+        "liblib_ruby_parser.dylib"
+    } else {
+        "liblib_ruby_parser.so"
+    };
+
+    // Python looks for `<module name>.so`
+    let module_dir = target_dir.join("python_test");
+    std::fs::create_dir_all(&module_dir).expect("failed to create python_test dir");
+    std::fs::copy(
+        target_dir.join(dylib),
+        module_dir.join("lib_ruby_parser.so"),
+    )
+    .expect("failed to copy python module");
+
+    let python = std::env::var("PYTHON").unwrap_or_else(|_| "python3".to_owned());
+    let output = Command::new(python)
+        .arg("tests/python/test_lib_ruby_parser.py")
+        .env("PYTHONPATH", &module_dir)
+        .output()
+        .expect("failed to run python");
+    assert!(
+        output.status.success(),
+        "Python test failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,75 @@
+use lib_ruby_parser::{Parser, ParserOptions, RubyVersion};
+
+fn error_messages(src: &str, ruby_version: RubyVersion) -> Vec<String> {
+    let options = ParserOptions {
+        ruby_version,
+        ..Default::default()
+    };
+    Parser::new(src.as_bytes(), options)
+        .do_parse()
+        .diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .map(|d| d.render_message())
+        .collect()
+}
+
+fn assert_requires_ruby_30(src: &str, feature: &str) {
+    assert_eq!(
+        error_messages(src, RubyVersion::Ruby27),
+        vec![format!("{} is not supported in Ruby 2.7", feature)],
+        "{:?}",
+        src
+    );
+    assert_eq!(
+        error_messages(src, RubyVersion::Ruby30),
+        Vec::<String>::new(),
+        "{:?}",
+        src
+    );
+}
+
+#[test]
+fn test_latest_version_by_default() {
+    assert_eq!(RubyVersion::default(), RubyVersion::LATEST);
+    assert_eq!(ParserOptions::default().ruby_version, RubyVersion::Ruby30);
+    assert!(RubyVersion::Ruby27 < RubyVersion::Ruby30);
+    assert_eq!(RubyVersion::Ruby27.to_string(), "2.7");
+}
+
+#[test]
+fn test_endless_methods() {
+    assert_requires_ruby_30("def foo() = 42", "endless method definition");
+    assert_requires_ruby_30("def foo(a) = a rescue nil", "endless method definition");
+    assert_requires_ruby_30("def self.foo() = 42", "endless method definition");
+}
+
+#[test]
+fn test_rightward_assignment() {
+    assert_requires_ruby_30("42 => a", "rightward assignment");
+    assert_requires_ruby_30("[1, 2] => a, b", "rightward assignment");
+}
+
+#[test]
+fn test_find_pattern() {
+    assert_requires_ruby_30("case foo; in [*, 42, *]; end", "find pattern");
+    assert_requires_ruby_30("case foo; in Foo(*, 42, *post); end", "find pattern");
+}
+
+#[test]
+fn test_syntax_of_ruby_27() {
+    for src in &[
+        "1 in Integer",
+        "case foo; in [1, *rest] then rest; end",
+        "foo { _1 }",
+        "def foo(...); bar(...); end",
+        "(..1)",
+    ] {
+        assert_eq!(
+            error_messages(src, RubyVersion::Ruby27),
+            Vec::<String>::new(),
+            "{:?}",
+            src
+        );
+    }
+}
@@ -0,0 +1,221 @@
+#[cfg(feature = "mri-ast")]
+use lib_ruby_parser::mri_ast;
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::{embedded, erb, Node, Parser, ParserOptions, ParserResult};
+
+// A Markdown document with a Ruby code block on lines 4-5 (1-based)
+const DOCUMENT: &str = "# Example
+
+```ruby
+foo(1) # comment
+def m(a, a); end
+```
+";
+
+fn code_block() -> (&'static str, usize) {
+    let begin = DOCUMENT.find("foo").unwrap();
+    let end = DOCUMENT.rfind("```").unwrap();
+    (&DOCUMENT[begin..end], begin)
+}
+
+fn parse(code: &str, first_line: usize, byte_offset: usize) -> ParserResult {
+    let options = ParserOptions {
+        buffer_name: "README.md".to_owned(),
+        first_line,
+        byte_offset,
+        ..Default::default()
+    };
+    Parser::new(code.as_bytes(), options).do_parse()
+}
+
+fn document_source(range: &Range) -> &'static str {
+    &DOCUMENT[range.to_range()]
+}
+
+#[test]
+fn test_defaults() {
+    let options = ParserOptions::default();
+    assert_eq!(options.first_line, 1);
+    assert_eq!(options.byte_offset, 0);
+
+    let result = parse("foo", 1, 0);
+    assert_eq!(result.input.byte_offset(), 0);
+    assert_eq!(result.input.line_offset(), 0);
+    assert_eq!(result.ast.unwrap().expression(), &Range::new(0, 3));
+}
+
+#[test]
+fn test_locations() {
+    let (code, byte_offset) = code_block();
+    let result = parse(code, 4, byte_offset);
+
+    let ast = result.ast.as_ref().unwrap();
+    assert_eq!(document_source(ast.expression()), code.trim_end());
+    let statements = ast.children();
+    assert_eq!(document_source(statements[0].expression()), "foo(1)");
+    assert_eq!(
+        statements[0].expression().source(&result.input).unwrap(),
+        "foo(1)"
+    );
+
+    for token in result.tokens.iter() {
+        let range = Range::new(token.loc.begin, token.loc.end);
+        assert_eq!(
+            range.source(&result.input).unwrap(),
+            document_source(&range)
+        );
+    }
+    assert_eq!(result.tokens[0].loc.begin, byte_offset);
+
+    assert_eq!(result.comments.len(), 1);
+    assert_eq!(document_source(&result.comments[0].location), "# comment");
+}
+
+#[test]
+fn test_lines() {
+    let (code, byte_offset) = code_block();
+    let result = parse(code, 4, byte_offset);
+    let input = &result.input;
+
+    let def = &result.ast.as_ref().unwrap().children()[1];
+    assert_eq!(def.expression().begin_line_col(input), Some((4, 0)));
+    assert_eq!(def.expression().lines(input), Some((4, 4)));
+    assert_eq!(input.line_for_pos(byte_offset), Some(3));
+    assert_eq!(input.line_for_pos(byte_offset - 1), None);
+    assert_eq!(
+        input.pos_for_line_col(4, 0),
+        Some(def.expression().begin_pos)
+    );
+    assert_eq!(input.pos_for_line_col(2, 0), None);
+}
+
+#[test]
+fn test_rendered_diagnostics() {
+    let (code, byte_offset) = code_block();
+    let result = parse(code, 4, byte_offset);
+
+    assert_eq!(result.diagnostics.len(), 1);
+    let diagnostic = &result.diagnostics[0];
+    assert_eq!(document_source(&diagnostic.range), "a");
+    assert_eq!(
+        diagnostic.render(&result.input).unwrap(),
+        "README.md:5:9: error: duplicated argument name
+README.md:5: def m(a, a); end
+README.md:5:          ^"
+    );
+}
+
+#[cfg(feature = "mri-ast")]
+#[test]
+fn test_line_constant() {
+    let result = parse("\n__LINE__", 10, 0);
+    assert_eq!(
+        mri_ast::convert(result.ast.as_ref(), &result.input).inspect(),
+        "(SCOPE@11:0-11:8 [] nil (LIT@11:0-11:8 11))"
+    );
+}
+
+#[test]
+fn test_erb_template() {
+    let document = "---\ntitle: Users\n---\n<p><%= user.name %></p>\n";
+    let byte_offset = document.find("<p>").unwrap();
+    let options = ParserOptions {
+        first_line: 4,
+        byte_offset,
+        ..Default::default()
+    };
+    let result = erb::parse(document[byte_offset..].as_bytes(), options);
+
+    let output = &result.ast.as_ref().unwrap().children()[1];
+    let range = output.expression();
+    assert_eq!(&document[range.to_range()], "<%= user.name %>");
+    assert_eq!(range.source(&result.input).unwrap(), "<%= user.name %>");
+    assert_eq!(range.begin_line_col(&result.input), Some((3, 3)));
+}
+
+// A YARD example inside of an indented class body, lines 3-5 (1-based)
+const YARD: &str = "class User
+  # @example
+  #   def m(a, a); end
+  #
+  #   user.save
+  def rename; end
+end
+";
+
+#[test]
+fn test_embedded_with_line_prefix() {
+    let begin = YARD.find("  #   def").unwrap();
+    let end = YARD.find("  def").unwrap();
+    let options = ParserOptions {
+        buffer_name: "user.rb".to_owned(),
+        first_line: 3,
+        byte_offset: begin,
+        ..Default::default()
+    };
+    let result = embedded::parse(YARD[begin..end].as_bytes(), b"  #   ", options);
+    let input = &result.input;
+
+    let statements = result.ast.as_ref().unwrap().children();
+    let save = statements[1].expression();
+    assert_eq!(&YARD[save.to_range()], "user.save");
+    assert_eq!(save.begin_line_col(input), Some((4, 6)));
+    assert_eq!(save.source(input).unwrap(), "user.save");
+
+    for token in result.tokens.iter() {
+        let range = Range::new(token.loc.begin, token.loc.end);
+        assert_eq!(range.source(input).unwrap(), &YARD[range.to_range()]);
+    }
+
+    assert_eq!(
+        result.diagnostics[0].render(input).unwrap(),
+        "user.rb:3:15: error: duplicated argument name
+user.rb:3:   #   def m(a, a); end
+user.rb:3:                ^"
+    );
+}
+
+#[test]
+fn test_embedded_source_tokens() {
+    let document = "# @example\n#   x = \"a\n#   b\"\n#   foo(x)\n";
+    let begin = document.find("#   x").unwrap();
+    let options = ParserOptions {
+        first_line: 2,
+        byte_offset: begin,
+        record_source_tokens: true,
+        ..Default::default()
+    };
+    let result = embedded::parse(document[begin..].as_bytes(), b"#   ", options);
+
+    assert_eq!(result.source_tokens.len(), result.tokens.len());
+    for (source_token, token) in result.source_tokens.iter().zip(result.tokens.iter()) {
+        assert_eq!(
+            source_token.value_bytes(&result.input),
+            Some(token.token_value.as_bytes())
+        );
+    }
+}
+
+#[test]
+fn test_embedded_heredoc() {
+    let document = "def query\n  eval <<~RUBY\n    foo(1)\n      .bar\n  RUBY\nend\n";
+    let begin = document.find("    foo").unwrap();
+    let end = document.find("  RUBY\nend").unwrap();
+    let code = &document.as_bytes()[begin..end];
+    assert_eq!(embedded::common_indent(code), b"    ");
+
+    let options = ParserOptions {
+        first_line: 3,
+        byte_offset: begin,
+        ..Default::default()
+    };
+    let result = embedded::parse(code, embedded::common_indent(code), options);
+
+    let bar = result.ast.unwrap();
+    let selector = match &bar {
+        Node::Send(send) => send.selector_l.clone().unwrap(),
+        _ => panic!("expected a method call"),
+    };
+    assert_eq!(&document[bar.expression().to_range()], "foo(1)\n      .bar");
+    assert_eq!(selector.begin_line_col(&result.input), Some((3, 7)));
+}
@@ -1,4 +1,4 @@
-use lib_ruby_parser::StaticEnvironment;
+use lib_ruby_parser::{Interner, Parser, ParserOptions, StaticEnvironment};
 
 #[test]
 fn test_declare() {
@@ -45,3 +45,60 @@ fn test_unextend() {
     assert!(env.is_declared("foo"));
     assert!(!env.is_declared("bar"));
 }
+
+#[test]
+fn test_interner() {
+    let mut interner = Interner::new();
+
+    let foo = interner.intern("foo");
+    assert_eq!(interner.intern("foo"), foo);
+    assert_eq!(interner.lookup("foo"), Some(foo));
+    assert_eq!(interner.lookup("bar"), None);
+    assert_eq!(interner.resolve(foo), Some("foo"));
+    assert_eq!(interner.len(), 1);
+}
+
+#[test]
+fn test_foreign_symbol() {
+    let mut interner = Interner::new();
+    let mut other = Interner::new();
+
+    let foo = interner.intern("foo");
+    other.intern("bar");
+
+    assert_eq!(other.resolve(foo), None);
+    assert_ne!(other.lookup("bar"), interner.lookup("bar"));
+}
+
+#[test]
+fn test_env_interner() {
+    let mut interner = Interner::new();
+    interner.intern("foo");
+
+    let env = StaticEnvironment::with_interner(interner);
+    env.declare("foo");
+    env.declare("bar");
+
+    assert_eq!(env.interner().len(), 2);
+    assert!(env.is_declared("bar"));
+}
+
+#[test]
+fn test_interner_between_parsers() {
+    let result = Parser::new(b"foo = 1; bar = 2", ParserOptions::default()).do_parse();
+    let foo = result
+        .interner
+        .lookup("foo")
+        .expect("expected foo to be interned");
+
+    let options = ParserOptions {
+        interner: Some(result.interner),
+        ..Default::default()
+    };
+    let result = Parser::new(b"foo = 3; baz = 4", options).do_parse();
+
+    assert_eq!(result.interner.lookup("foo"), Some(foo));
+    assert_eq!(result.interner.resolve(foo), Some("foo"));
+    assert!(result.interner.lookup("baz").is_some());
+    assert_eq!(result.interner.len(), 3);
+}
@@ -0,0 +1,77 @@
+use lib_ruby_parser::nodes::StructureOptions;
+use lib_ruby_parser::{Node, Parser, ParserOptions};
+
+fn parse(src: &str) -> Node {
+    Parser::new(src.as_bytes(), ParserOptions::default())
+        .do_parse()
+        .ast
+        .expect("expected AST")
+}
+
+/// Returns statements of a `begin` node
+fn stmts(src: &str) -> Vec<Node> {
+    match parse(src) {
+        Node::Begin(inner) => inner.statements,
+        other => panic!("expected begin, got {}", other.str_type()),
+    }
+}
+
+#[test]
+fn test_same_code_at_different_places() {
+    let stmts = stmts("foo(1, [2, :x])\n\n  foo(1,   [2, :x])");
+
+    assert_ne!(stmts[0], stmts[1]);
+    assert!(stmts[0].structurally_eq(&stmts[1]));
+    assert_eq!(stmts[0].structural_hash(), stmts[1].structural_hash());
+}
+
+#[test]
+fn test_different_code() {
+    for (lhs, rhs) in &[
+        ("foo(1)", "bar(1)"),
+        ("foo(1)", "foo(2)"),
+        ("foo(1)", "foo(1, 2)"),
+        ("a.b", "a&.b"),
+        ("'a'", ":a"),
+        ("def m(*a); end", "def m(*); end"),
+        ("/a/i", "/a/m"),
+    ] {
+        let (lhs, rhs) = (parse(lhs), parse(rhs));
+        assert!(!lhs.structurally_eq(&rhs), "{:?} == {:?}", lhs, rhs);
+        assert_ne!(lhs.structural_hash(), rhs.structural_hash());
+    }
+}
+
+#[test]
+fn test_ignore_literal_values() {
+    let options = StructureOptions {
+        ignore_literal_values: true,
+    };
+
+    for (lhs, rhs) in &[
+        ("foo(1, 'a')", "foo(2, 'b')"),
+        ("x = 1.5", "x = 2.0"),
+        ("[:a, 3r]", "[:b, 4r]"),
+    ] {
+        let (lhs, rhs) = (parse(lhs), parse(rhs));
+        assert!(!lhs.structurally_eq(&rhs));
+        assert!(lhs.structurally_eq_with(&rhs, &options));
+        assert_eq!(
+            lhs.structural_hash_with(&options),
+            rhs.structural_hash_with(&options)
+        );
+    }
+
+    // names are not literals
+    let (lhs, rhs) = (parse("x = 1"), parse("y = 1"));
+    assert!(!lhs.structurally_eq_with(&rhs, &options));
+    // and neither are types of literals
+    let (lhs, rhs) = (parse("1"), parse("1.0"));
+    assert!(!lhs.structurally_eq_with(&rhs, &options));
+}
+
+#[test]
+fn test_structural_hash_is_stable() {
+    // FNV-1a of the node type, must not change between releases
+    assert_eq!(parse("nil").structural_hash(), 0xcc32_e6d2_5895_7c9d);
+}
@@ -0,0 +1,132 @@
+// Run with `wasm-pack test --node --features wasm -- --test wasm_test`
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use js_sys::{Array, Reflect, Uint8Array};
+use lib_ruby_parser::wasm::parse;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn get(value: &JsValue, key: &str) -> JsValue {
+    Reflect::get(value, &JsValue::from_str(key)).unwrap()
+}
+
+fn get_at(value: &JsValue, idx: u32) -> JsValue {
+    Array::from(value).get(idx)
+}
+
+fn length(value: &JsValue) -> u32 {
+    Array::from(value).length()
+}
+
+#[wasm_bindgen_test]
+fn test_ast() {
+    let result = parse(JsValue::from_str("foo(42)"), JsValue::UNDEFINED).unwrap();
+
+    let ast = get(&result, "ast");
+    assert_eq!(get(&ast, "type").as_string().unwrap(), "send");
+
+    // same as Node::to_json
+    let children = get(&ast, "children");
+    assert_eq!(length(&children), 3);
+    assert!(get_at(&children, 0).is_null());
+    assert_eq!(get_at(&children, 1).as_string().unwrap(), "foo");
+
+    let arg = get_at(&children, 2);
+    assert_eq!(get(&arg, "type").as_string().unwrap(), "int");
+    assert_eq!(get_at(&get(&arg, "children"), 0).as_string().unwrap(), "42");
+    let expression = get(&get(&arg, "location"), "expression");
+    assert_eq!(get(&expression, "begin_pos").as_f64().unwrap(), 4.0);
+    assert_eq!(get(&expression, "end_pos").as_f64().unwrap(), 6.0);
+}
+
+fn begin_end(location: &JsValue) -> (f64, f64) {
+    (
+        get(location, "begin_pos").as_f64().unwrap(),
+        get(location, "end_pos").as_f64().unwrap(),
+    )
+}
+
+#[wasm_bindgen_test]
+fn test_utf16_locations() {
+    // "ы" is 2 bytes and 1 UTF-16 code unit, "😀" is 4 bytes and 2 code units
+    let result = parse(JsValue::from_str("'ы😀' + bar # ok"), JsValue::UNDEFINED).unwrap();
+
+    let ast = get(&result, "ast");
+    assert_eq!(
+        begin_end(&get(&get(&ast, "location"), "expression")),
+        (0.0, 11.0)
+    );
+    let arg = get_at(&get(&ast, "children"), 2);
+    assert_eq!(
+        begin_end(&get(&get(&arg, "location"), "expression")),
+        (8.0, 11.0)
+    );
+
+    let token = get_at(&get(&result, "tokens"), 1);
+    assert_eq!(get(&token, "value").as_string().unwrap(), "ы😀");
+    assert_eq!(begin_end(&get(&token, "location")), (1.0, 4.0));
+
+    let comment = get_at(&get(&result, "comments"), 0);
+    assert_eq!(begin_end(&get(&comment, "location")), (12.0, 16.0));
+}
+
+#[wasm_bindgen_test]
+fn test_tokens_and_comments() {
+    let result = parse(JsValue::from_str("foo # comment"), JsValue::UNDEFINED).unwrap();
+
+    let token = get_at(&get(&result, "tokens"), 0);
+    assert_eq!(get(&token, "name").as_string().unwrap(), "tIDENTIFIER");
+    assert_eq!(get(&token, "value").as_string().unwrap(), "foo");
+
+    let comments = get(&result, "comments");
+    assert_eq!(length(&comments), 1);
+    assert_eq!(
+        get(&get_at(&comments, 0), "kind").as_string().unwrap(),
+        "inline"
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_options() {
+    let options = js_sys::Object::new();
+    Reflect::set(&options, &"record_tokens".into(), &JsValue::FALSE).unwrap();
+    Reflect::set(&options, &"buffer_name".into(), &"(test)".into()).unwrap();
+
+    let result = parse(JsValue::from_str("foo("), options.into()).unwrap();
+    assert_eq!(length(&get(&result, "tokens")), 0);
+    assert!(get(&result, "ast").is_null());
+
+    let diagnostic = get_at(&get(&result, "diagnostics"), 0);
+    assert_eq!(get(&diagnostic, "level").as_string().unwrap(), "error");
+    assert!(get(&diagnostic, "rendered")
+        .as_string()
+        .unwrap()
+        .starts_with("(test):1:"));
+}
+
+#[wasm_bindgen_test]
+fn test_invalid_options() {
+    let options = js_sys::Object::new();
+    Reflect::set(&options, &"record_tokens".into(), &"yes".into()).unwrap();
+
+    assert!(parse(JsValue::from_str("42"), options.into()).is_err());
+    assert!(parse(JsValue::from_f64(42.0), JsValue::UNDEFINED).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_builtin_decoder() {
+    // "# encoding: windows-1251\n'Привет'" encoded in windows-1251
+    let mut bytes = b"# encoding: windows-1251\n'".to_vec();
+    bytes.extend_from_slice(&[0xCF, 0xF0, 0xE8, 0xE2, 0xE5, 0xF2]);
+    bytes.push(b'\'');
+    let source = Uint8Array::from(&bytes[..]);
+
+    let result = parse(source.into(), JsValue::UNDEFINED).unwrap();
+    assert_eq!(length(&get(&result, "diagnostics")), 0);
+    let ast = get(&result, "ast");
+    assert_eq!(get(&ast, "type").as_string().unwrap(), "str");
+    assert_eq!(
+        get_at(&get(&ast, "children"), 0).as_string().unwrap(),
+        "Привет"
+    );
+}